@@ -0,0 +1,76 @@
+// Build script: embeds the MAC vendor (OUI) database
+// src/utils/oui.txt is the in-tree database; RUST_RECON_OUI names a registry (IEEE, nmap or
+// Wireshark format) whose assignments are added on top. Nothing else on the build machine is read,
+// so the same sources always produce the same binary
+use std::env;
+use std::fs;
+use std::path::Path;
+
+const BUNDLED: &str = "src/utils/oui.txt";
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", BUNDLED);
+    println!("cargo:rerun-if-env-changed=RUST_RECON_OUI");
+
+    let mut database = fs::read_to_string(BUNDLED).expect("bundled OUI list");
+    let registry = env::var("RUST_RECON_OUI").ok().map(|path| {
+        println!("cargo:rerun-if-changed={}", path);
+        fs::read_to_string(&path).unwrap_or_else(|e| panic!("Cannot read RUST_RECON_OUI {}: {}", path, e))
+    });
+
+    // Later lines win when the database is loaded, so the registry overrides the bundled names
+    if let Some(registry) = registry {
+        let entries: Vec<String> = registry.lines()
+            .filter_map(parse_assignment)
+            .map(|(prefix, vendor)| format!("{} {}", prefix, vendor))
+            .collect();
+        database.push_str("# Added from RUST_RECON_OUI\n");
+        database.push_str(&entries.join("\n"));
+        database.push('\n');
+    }
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR");
+    fs::write(Path::new(&out_dir).join("oui.txt"), database).expect("write OUI database");
+}
+
+// One 24-bit assignment from any of the supported formats:
+//   IEEE CSV:      MA-L,00000C,"Cisco Systems, Inc",...
+//   IEEE text:     00-00-0C   (hex)		Cisco Systems, Inc
+//   nmap:          00000C Cisco Systems
+//   Wireshark:     00:00:0C	Cisco	Cisco Systems, Inc
+fn parse_assignment(line: &str) -> Option<(String, String)> {
+    let line = line.trim();
+    // The IEEE text list repeats every assignment as a "(base 16)" line
+    if line.is_empty() || line.starts_with('#') || line.contains("(base 16)") {
+        return None;
+    }
+
+    let (prefix, vendor) = if let Some(rest) = line.strip_prefix("MA-L,") {
+        let (prefix, rest) = rest.split_once(',')?;
+        let vendor = match rest.strip_prefix('"') {
+            Some(quoted) => quoted.split_once('"')?.0,
+            None => rest.split(',').next()?,
+        };
+        (prefix.to_string(), vendor)
+    } else if let Some((prefix, vendor)) = line.split_once("(hex)") {
+        (prefix.trim().replace('-', ""), vendor)
+    } else if line.contains('\t') && line.contains(':') {
+        // Wireshark also lists 28 and 36-bit blocks (prefix/mask); only whole OUIs are looked up
+        let mut fields = line.split('\t').filter(|f| !f.is_empty());
+        let prefix = fields.next()?;
+        if prefix.contains('/') {
+            return None;
+        }
+        let short = fields.next()?;
+        (prefix.replace(':', ""), fields.next().unwrap_or(short))
+    } else {
+        let (prefix, vendor) = line.split_once(char::is_whitespace)?;
+        (prefix.to_string(), vendor)
+    };
+
+    let vendor = vendor.trim();
+    if prefix.len() != 6 || !prefix.chars().all(|c| c.is_ascii_hexdigit()) || vendor.is_empty() {
+        return None;
+    }
+    Some((prefix.to_uppercase(), vendor.to_string()))
+}
@@ -0,0 +1,224 @@
+use console::style;
+use indicatif::ProgressBar;
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+use std::net::IpAddr;
+use std::time::Duration;
+use serde_json::Value;
+
+use super::client::{api_base_url, api_key, ApiAuth, ApiClient, ApiResponse, ApiSettings};
+use crate::models::{AbuseIpdbInfo, AbuseReport, Finding, Severity};
+
+// Overridable with ABUSEIPDB_API_URL, e.g. to point at a local mock server
+const DEFAULT_API_URL: &str = "https://api.abuseipdb.com/api/v2";
+
+const REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+
+// Reporting window checked, and the most recent reports kept for the summary
+const MAX_AGE_DAYS: u32 = 90;
+const MAX_REPORTS: usize = 10;
+
+// Parsed AbuseIPDB data and the findings derived from it
+pub struct AbuseIpdbResults {
+    pub abuseipdb: AbuseIpdbInfo,
+    pub findings: Vec<Finding>,
+}
+
+// The "data" of an AbuseIPDB answer
+fn abuseipdb_data(response: ApiResponse) -> Result<Value, Box<dyn Error>> {
+    if !response.is_success() {
+        // Errors come as {"errors": [{"detail": ..., "status": ...}]}
+        return Err(match (response.status, response.body["errors"][0]["detail"].as_str()) {
+            (401, _) => "AbuseIPDB API key rejected (401 Unauthorized)".into(),
+            (429, _) => "AbuseIPDB daily rate limit exceeded (429 Too Many Requests)".into(),
+            (status, Some(detail)) => format!("AbuseIPDB API error: {}: {}", status, detail).into(),
+            (status, None) => format!("AbuseIPDB API error: {}", status).into(),
+        });
+    }
+    Ok(response.body["data"].clone())
+}
+
+// Perform AbuseIPDB check with spinner: abuse confidence and recent reports for an IP
+pub fn perform_abuseipdb_check(spinner: &ProgressBar, target: &str, api: &ApiSettings, base_filename: &str, scan_dir: &str) -> Result<AbuseIpdbResults, Box<dyn Error>> {
+    spinner.set_message(format!("Connecting to AbuseIPDB API for {}...", style(target).cyan()));
+    if target.parse::<IpAddr>().is_err() {
+        return Err("AbuseIPDB checks need an IP address".into());
+    }
+    let api_key = api_key(spinner, api, "AbuseIPDB", "ABUSEIPDB_API_KEY")?;
+    let client = ApiClient::new("AbuseIPDB", ApiAuth::Header("Key", api_key), REQUEST_INTERVAL, api)?;
+    let url = format!("{}/check", api_base_url(api, "ABUSEIPDB_API_URL", DEFAULT_API_URL));
+
+    spinner.set_message(format!("Checking {} on AbuseIPDB...", style(target).cyan()));
+    let max_age = MAX_AGE_DAYS.to_string();
+    let data = abuseipdb_data(client.get(&url, &[("ipAddress", target), ("maxAgeInDays", max_age.as_str()), ("verbose", "")])?)?;
+
+    let mut file = File::create(format!("{}/{}_abuseipdb.json", scan_dir, base_filename))?;
+    file.write_all(serde_json::to_string_pretty(&data)?.as_bytes())?;
+
+    let mut reports: Vec<AbuseReport> = data["reports"].as_array()
+        .map(|reports| reports.iter().filter_map(|report| Some(AbuseReport {
+            reported_at: string(report, "reportedAt")?,
+            categories: report["categories"].as_array()
+                .map(|ids| ids.iter().filter_map(|id| id.as_u64()).map(category_name).collect())
+                .unwrap_or_default(),
+            reporter_country: string(report, "reporterCountryCode"),
+            comment: string(report, "comment").map(|c| c.trim().to_string()),
+        })).collect())
+        .unwrap_or_default();
+    reports.sort_by(|a, b| b.reported_at.cmp(&a.reported_at));
+    reports.truncate(MAX_REPORTS);
+
+    let info = AbuseIpdbInfo {
+        ip: target.to_string(),
+        max_age_days: MAX_AGE_DAYS,
+        confidence: data["abuseConfidenceScore"].as_u64().unwrap_or(0).min(100) as u8,
+        total_reports: data["totalReports"].as_u64().unwrap_or(0),
+        distinct_reporters: data["numDistinctUsers"].as_u64().unwrap_or(0),
+        last_reported: string(&data, "lastReportedAt"),
+        country: string(&data, "countryCode"),
+        usage_type: string(&data, "usageType"),
+        isp: string(&data, "isp"),
+        domain: string(&data, "domain"),
+        hostnames: data["hostnames"].as_array()
+            .map(|names| names.iter().filter_map(|n| n.as_str().map(|s| s.to_string())).collect())
+            .unwrap_or_default(),
+        tor: data["isTor"].as_bool().unwrap_or(false),
+        whitelisted: data["isWhitelisted"].as_bool().unwrap_or(false),
+        reports,
+    };
+    let findings = abuseipdb_findings(&info);
+    Ok(AbuseIpdbResults { abuseipdb: info, findings })
+}
+
+// Addresses other AbuseIPDB users reported for abuse
+pub fn abuseipdb_findings(abuseipdb: &AbuseIpdbInfo) -> Vec<Finding> {
+    if abuseipdb.total_reports == 0 {
+        return Vec::new();
+    }
+    let severity = match abuseipdb.confidence {
+        75.. => Severity::Medium,
+        25.. => Severity::Low,
+        _ => Severity::Info,
+    };
+    let categories: Vec<String> = abuseipdb.top_categories().into_iter().take(3).map(|(name, _)| name).collect();
+    vec![Finding {
+        source: "AbuseIPDB".to_string(),
+        id: Some("abuseipdb-reported".to_string()),
+        severity,
+        location: abuseipdb.ip.clone(),
+        method: None,
+        description: format!("Reported {} times by {} users in the last {} days (abuse confidence {}%){}",
+            abuseipdb.total_reports, abuseipdb.distinct_reporters, abuseipdb.max_age_days, abuseipdb.confidence,
+            if categories.is_empty() { String::new() } else { format!(", mostly for {}", categories.join(", ")) }),
+        references: vec![format!("https://www.abuseipdb.com/check/{}", abuseipdb.ip)],
+    }]
+}
+
+// AbuseIPDB report category IDs (https://www.abuseipdb.com/categories)
+fn category_name(id: u64) -> String {
+    let name = match id {
+        1 => "DNS Compromise",
+        2 => "DNS Poisoning",
+        3 => "Fraud Orders",
+        4 => "DDoS Attack",
+        5 => "FTP Brute-Force",
+        6 => "Ping of Death",
+        7 => "Phishing",
+        8 => "Fraud VoIP",
+        9 => "Open Proxy",
+        10 => "Web Spam",
+        11 => "Email Spam",
+        12 => "Blog Spam",
+        13 => "VPN IP",
+        14 => "Port Scan",
+        15 => "Hacking",
+        16 => "SQL Injection",
+        17 => "Spoofing",
+        18 => "Brute-Force",
+        19 => "Bad Web Bot",
+        20 => "Exploited Host",
+        21 => "Web App Attack",
+        22 => "SSH",
+        23 => "IoT Targeted",
+        _ => return format!("Category {}", id),
+    };
+    name.to_string()
+}
+
+fn string(value: &Value, key: &str) -> Option<String> {
+    value[key].as_str().filter(|s| !s.is_empty()).map(|s| s.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::mock;
+
+    fn respond(target: &str) -> (u16, String) {
+        let body = if target.contains("ipAddress=198.51.100.7&") {
+            r#"{"data": {"ipAddress": "198.51.100.7", "abuseConfidenceScore": 100, "countryCode": "NL", "usageType": "Data Center/Web Hosting/Transit",
+                "isp": "Example Hosting", "domain": "example.net", "hostnames": ["scanner.example.net"], "isTor": false, "isWhitelisted": false,
+                "totalReports": 3, "numDistinctUsers": 2, "lastReportedAt": "2026-10-18T09:00:00+00:00",
+                "reports": [
+                    {"reportedAt": "2026-10-01T12:00:00+00:00", "comment": "port scan", "categories": [14], "reporterCountryCode": "DE"},
+                    {"reportedAt": "2026-10-18T09:00:00+00:00", "comment": " sshd brute force \n", "categories": [18, 22], "reporterCountryCode": "US"},
+                    {"reportedAt": "2026-10-10T08:00:00+00:00", "categories": [18, 99]}
+                ]}}"#
+        } else if target.contains("ipAddress=198.51.100.8&") {
+            r#"{"data": {"ipAddress": "198.51.100.8", "abuseConfidenceScore": 0, "totalReports": 0, "numDistinctUsers": 0, "reports": []}}"#
+        } else {
+            return (422, r#"{"errors": [{"detail": "The ip address must be a valid IPv4 or IPv6 address.", "status": 422}]}"#.to_string());
+        };
+        (200, body.to_string())
+    }
+
+    #[test]
+    fn check_against_mock_server() {
+        let (base_url, requests) = mock::serve(respond);
+        let overrides = [("ABUSEIPDB_API_URL", base_url), ("ABUSEIPDB_API_KEY", "testkey".to_string())]
+            .into_iter().map(|(name, value)| (name.to_string(), value)).collect();
+        let api = ApiSettings { overrides, ..Default::default() };
+        let scan_dir = mock::scan_dir("abuseipdb");
+
+        let results = perform_abuseipdb_check(&ProgressBar::hidden(), "198.51.100.7", &api, "test", &scan_dir).unwrap();
+        let info = &results.abuseipdb;
+        assert_eq!((info.confidence, info.total_reports, info.distinct_reporters), (100, 3, 2));
+        assert_eq!(info.hostnames, vec!["scanner.example.net"]);
+        // Newest report first, comments trimmed and categories named
+        let reports: Vec<(&str, Vec<String>, Option<&str>)> = info.reports.iter()
+            .map(|r| (r.reported_at.as_str(), r.categories.clone(), r.comment.as_deref()))
+            .collect();
+        assert_eq!(reports, vec![
+            ("2026-10-18T09:00:00+00:00", vec!["Brute-Force".to_string(), "SSH".to_string()], Some("sshd brute force")),
+            ("2026-10-10T08:00:00+00:00", vec!["Brute-Force".to_string(), "Category 99".to_string()], None),
+            ("2026-10-01T12:00:00+00:00", vec!["Port Scan".to_string()], Some("port scan")),
+        ]);
+        let finding = &results.findings[0];
+        assert_eq!((finding.id.as_deref(), finding.severity), (Some("abuseipdb-reported"), Severity::Medium));
+        assert_eq!(finding.description, "Reported 3 times by 2 users in the last 90 days (abuse confidence 100%), mostly for Brute-Force, Category 99, Port Scan");
+
+        let clean = perform_abuseipdb_check(&ProgressBar::hidden(), "198.51.100.8", &api, "test", &scan_dir).unwrap();
+        assert!(clean.findings.is_empty());
+        let error = perform_abuseipdb_check(&ProgressBar::hidden(), "::ffff:0:0", &api, "test", &scan_dir).err().unwrap();
+        assert_eq!(error.to_string(), "AbuseIPDB API error: 422: The ip address must be a valid IPv4 or IPv6 address.");
+
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].target.contains("maxAgeInDays=90"));
+        assert!(requests.iter().all(|r| r.headers.iter().any(|h| h.eq_ignore_ascii_case("key: testkey"))));
+    }
+
+    #[test]
+    fn hostnames_are_rejected_before_asking_for_a_key() {
+        let error = perform_abuseipdb_check(&ProgressBar::hidden(), "www.example.com", &ApiSettings::default(), "test", "/nonexistent").err().unwrap();
+        assert_eq!(error.to_string(), "AbuseIPDB checks need an IP address");
+    }
+
+    #[test]
+    fn severity_follows_the_confidence_score() {
+        let info = |confidence: u8, total_reports: u64| AbuseIpdbInfo { ip: "198.51.100.7".to_string(), confidence, total_reports, ..Default::default() };
+        let severity = |confidence: u8| abuseipdb_findings(&info(confidence, 1))[0].severity;
+        assert_eq!((severity(75), severity(74), severity(25), severity(24)), (Severity::Medium, Severity::Low, Severity::Low, Severity::Info));
+        assert!(abuseipdb_findings(&info(0, 0)).is_empty());
+    }
+}
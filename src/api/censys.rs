@@ -0,0 +1,305 @@
+use console::style;
+use indicatif::ProgressBar;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::Write;
+use std::env;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+use serde_json::Value;
+
+use super::client::{api_base_url, ApiAuth, ApiClient, ApiResponse, ApiSettings};
+use crate::models::{CensysCertificate, CensysHost, CensysInfo, CensysService, CensysSoftware, TlsInfo};
+
+// Overridable with CENSYS_API_URL, e.g. to point at a local mock server
+const DEFAULT_API_URL: &str = "https://search.censys.io/api";
+
+// The free tier allows 0.4 actions per second
+const REQUEST_INTERVAL: Duration = Duration::from_millis(2500);
+
+// Hosts read from one search page, and leaf certificates looked up for an IP target
+const SEARCH_PAGE_SIZE: &str = "50";
+const MAX_CERTIFICATES: usize = 10;
+
+struct CensysClient {
+    api: ApiClient,
+    base_url: String,
+}
+
+impl CensysClient {
+    fn get(&self, path: &str, params: &[(&str, &str)]) -> Result<Option<Value>, Box<dyn Error>> {
+        censys_result(self.api.get(&format!("{}{}", self.base_url, path), params)?)
+    }
+}
+
+// The "result" of a Censys answer; None when Censys has no data (404)
+fn censys_result(response: ApiResponse) -> Result<Option<Value>, Box<dyn Error>> {
+    if response.status == 404 {
+        return Ok(None);
+    }
+    if !response.is_success() {
+        // Errors come as {"code": ..., "status": ..., "error": ...}
+        return Err(match (response.status, response.body["error"].as_str()) {
+            (401, _) => "Censys API credentials rejected (401 Unauthorized)".into(),
+            (429, _) => "Censys API rate limit exceeded (429 Too Many Requests)".into(),
+            (status, Some(detail)) => format!("Censys API error: {}: {}", status, detail).into(),
+            (status, None) => format!("Censys API error: {}", status).into(),
+        });
+    }
+    Ok(Some(response.body["result"].clone()))
+}
+
+// API ID and secret from CENSYS_API_ID/CENSYS_API_SECRET, or the censys CLI configuration file
+fn censys_credentials(api: &ApiSettings) -> Option<(String, String)> {
+    if let (Some(id), Some(secret)) = (api.variable("CENSYS_API_ID"), api.variable("CENSYS_API_SECRET")) {
+        return Some((id, secret));
+    }
+    let config_dir = env::var("XDG_CONFIG_HOME").ok().filter(|d| !d.is_empty()).map(PathBuf::from)
+        .or_else(|| env::var("HOME").ok().map(|home| PathBuf::from(home).join(".config")))?;
+    let config = fs::read_to_string(config_dir.join("censys").join("censys.cfg")).ok()?;
+    let value = |key: &str| config.lines()
+        .filter_map(|line| line.split_once('='))
+        .find(|(name, _)| name.trim() == key)
+        .map(|(_, value)| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    Some((value("api_id")?, value("api_secret")?))
+}
+
+// Perform Censys lookup with spinner: the host view for an IP, a host search for domains and ranges,
+// plus the certificates seen on those hosts or issued for the domain
+pub fn perform_censys_lookup(spinner: &ProgressBar, target: &str, api: &ApiSettings, base_filename: &str, scan_dir: &str) -> Result<CensysInfo, Box<dyn Error>> {
+    spinner.set_message(format!("Connecting to Censys API for {}...", style(target).cyan()));
+    let Some((api_id, api_secret)) = censys_credentials(api) else {
+        spinner.suspend(|| {
+            println!("⚠️ Censys credentials not found");
+            println!("⚠️ Please set them with: export CENSYS_API_ID=your_api_id CENSYS_API_SECRET=your_api_secret");
+            println!("⚠️ (or run 'censys config' to create ~/.config/censys/censys.cfg)");
+        });
+        return Err("Censys API credentials not found in environment variables or censys.cfg".into());
+    };
+    let censys = CensysClient {
+        api: ApiClient::new("Censys", ApiAuth::Basic(api_id, api_secret), REQUEST_INTERVAL, api)?,
+        base_url: api_base_url(api, "CENSYS_API_URL", DEFAULT_API_URL),
+    };
+    let mut info = CensysInfo { target: target.to_string(), ..Default::default() };
+    let output_file = format!("{}/{}_censys.json", scan_dir, base_filename);
+
+    if target.parse::<IpAddr>().is_ok() {
+        spinner.set_message(format!("Reading Censys host view of {}...", style(target).cyan()));
+        if let Some(host) = censys.get(&format!("/v2/hosts/{}", target), &[])? {
+            let mut file = File::create(&output_file)?;
+            file.write_all(serde_json::to_string_pretty(&host)?.as_bytes())?;
+            info.hosts.push(parse_host(&host));
+        }
+    } else {
+        // Hosts answering for the domain, or every indexed host in the range
+        let query = if target.contains('/') {
+            format!("ip: {}", target)
+        } else {
+            format!("dns.names: {}", target)
+        };
+        spinner.set_message(format!("Searching Censys for {}...", style(&query).cyan()));
+        if let Some(search) = censys.get("/v2/hosts/search", &[("q", query.as_str()), ("per_page", SEARCH_PAGE_SIZE)])? {
+            let mut file = File::create(&output_file)?;
+            file.write_all(serde_json::to_string_pretty(&search)?.as_bytes())?;
+            info.total = search["total"].as_u64();
+            info.hosts = search["hits"].as_array()
+                .map(|hits| hits.iter().map(parse_host).collect())
+                .unwrap_or_default();
+        }
+        info.query = Some(query);
+    }
+
+    spinner.set_message(format!("Reading Censys certificates for {}...", style(target).cyan()));
+    if target.parse::<IpAddr>().is_err() && !target.contains('/') {
+        // Certificates issued for the domain or its subdomains
+        let query = format!("names: {}", target);
+        if let Some(search) = censys.get("/v2/certificates/search", &[("q", query.as_str()), ("per_page", SEARCH_PAGE_SIZE)])? {
+            let mut file = File::create(format!("{}/{}_censys_certificates.json", scan_dir, base_filename))?;
+            file.write_all(serde_json::to_string_pretty(&search)?.as_bytes())?;
+            info.certificates = search["hits"].as_array()
+                .map(|hits| hits.iter().map(parse_certificate).collect())
+                .unwrap_or_default();
+        }
+    } else {
+        // Leaf certificates the hosts present
+        let mut fingerprints: Vec<String> = info.hosts.iter()
+            .flat_map(|h| h.services.iter().filter_map(|s| s.certificate.clone()))
+            .collect();
+        fingerprints.sort();
+        fingerprints.dedup();
+        for fingerprint in fingerprints.iter().take(MAX_CERTIFICATES) {
+            if let Some(certificate) = censys.get(&format!("/v2/certificates/{}", fingerprint), &[])? {
+                info.certificates.push(parse_certificate(&certificate));
+            }
+        }
+
+        // Host views leave validity out of the leaf data
+        let now = chrono::Utc::now();
+        for service in info.hosts.iter_mut().flat_map(|h| h.services.iter_mut()) {
+            let certificate = info.certificates.iter().find(|c| Some(&c.fingerprint) == service.certificate.as_ref());
+            if let (Some(tls), Some(not_after)) = (service.tls.as_mut(), certificate.and_then(|c| c.not_after.clone())) {
+                tls.expired = chrono::DateTime::parse_from_rfc3339(&not_after).is_ok_and(|expiry| expiry < now);
+                tls.expires = Some(not_after);
+            }
+        }
+    }
+
+    Ok(info)
+}
+
+fn string(value: &Value, key: &str) -> Option<String> {
+    value[key].as_str().filter(|s| !s.is_empty()).map(|s| s.to_string())
+}
+
+fn strings(value: &Value) -> Vec<String> {
+    value.as_array()
+        .map(|items| items.iter().filter_map(|i| i.as_str().map(|s| s.to_string())).collect())
+        .unwrap_or_default()
+}
+
+// "CN=www.example.com, O=Example" -> the value of one attribute
+fn dn_attribute(dn: &str, attribute: &str) -> Option<String> {
+    dn.split(',')
+        .filter_map(|part| part.trim().split_once('='))
+        .find(|(name, _)| name.eq_ignore_ascii_case(attribute))
+        .map(|(_, value)| value.trim().to_string())
+}
+
+// Host view and search hits share their layout; hits just carry fewer fields
+fn parse_host(json: &Value) -> CensysHost {
+    let mut dns_names = strings(&json["dns"]["names"]);
+    dns_names.extend(strings(&json["dns"]["reverse_dns"]["names"]));
+    dns_names.sort();
+    dns_names.dedup();
+    let os = &json["operating_system"];
+    CensysHost {
+        ip: string(json, "ip").unwrap_or_default(),
+        asn: json["autonomous_system"]["asn"].as_u64(),
+        as_name: string(&json["autonomous_system"], "name"),
+        country: string(&json["location"], "country"),
+        city: string(&json["location"], "city"),
+        os: match (string(os, "vendor"), string(os, "product"), string(os, "version")) {
+            (_, None, _) => None,
+            (vendor, Some(product), version) => Some([vendor, Some(product), version].into_iter().flatten().collect::<Vec<_>>().join(" ")),
+        },
+        dns_names,
+        labels: strings(&json["labels"]),
+        last_updated: string(json, "last_updated_at"),
+        services: json["services"].as_array()
+            .map(|services| services.iter().map(parse_service).collect())
+            .unwrap_or_default(),
+    }
+}
+
+fn parse_service(json: &Value) -> CensysService {
+    let leaf = &json["tls"]["certificates"]["leaf_data"];
+    let certificate = string(&json["tls"]["certificates"], "leaf_fp_sha_256")
+        .or_else(|| string(leaf, "fingerprint"));
+    let tls = json["tls"].is_object().then(|| TlsInfo {
+        subject: string(leaf, "subject_dn").and_then(|dn| dn_attribute(&dn, "CN")),
+        issuer: string(leaf, "issuer_dn").and_then(|dn| dn_attribute(&dn, "CN").or_else(|| dn_attribute(&dn, "O"))),
+        expires: None,
+        expired: false,
+        versions: string(&json["tls"], "version_selected").into_iter().collect(),
+    });
+    CensysService {
+        port: json["port"].as_u64().unwrap_or(0) as u16,
+        transport: string(json, "transport_protocol").unwrap_or_else(|| "tcp".to_string()).to_lowercase(),
+        service_name: string(json, "service_name").unwrap_or_else(|| "UNKNOWN".to_string()),
+        extended_service_name: string(json, "extended_service_name").filter(|name| Some(name) != string(json, "service_name").as_ref()),
+        software: json["software"].as_array()
+            .map(|software| software.iter().filter_map(|s| Some(CensysSoftware {
+                vendor: string(s, "vendor"),
+                product: string(s, "product")?,
+                version: string(s, "version"),
+            })).collect())
+            .unwrap_or_default(),
+        labels: strings(&json["labels"]),
+        banner: string(json, "banner").map(|b| b.trim().to_string()).filter(|b| !b.is_empty()),
+        certificate,
+        tls,
+        observed_at: string(json, "observed_at"),
+    }
+}
+
+fn parse_certificate(json: &Value) -> CensysCertificate {
+    let parsed = &json["parsed"];
+    CensysCertificate {
+        fingerprint: string(json, "fingerprint_sha256").unwrap_or_default(),
+        subject_dn: string(parsed, "subject_dn"),
+        issuer_dn: string(parsed, "issuer_dn"),
+        names: strings(&json["names"]),
+        not_before: string(&parsed["validity_period"], "not_before"),
+        not_after: string(&parsed["validity_period"], "not_after"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::mock;
+
+    const FINGERPRINT: &str = "ab12";
+
+    fn respond(target: &str) -> (u16, String) {
+        let body = match target.split('?').next().unwrap_or(target) {
+            "/v2/hosts/198.51.100.7" => r#"{"code": 200, "status": "OK", "result": {
+                "ip": "198.51.100.7", "autonomous_system": {"asn": 64500, "name": "EXAMPLE-AS"},
+                "location": {"country": "Netherlands", "city": "Amsterdam"},
+                "operating_system": {"vendor": "Canonical", "product": "Linux"},
+                "dns": {"names": ["www.example.com"], "reverse_dns": {"names": ["host7.example.net"]}},
+                "last_updated_at": "2026-10-01T00:00:00Z",
+                "services": [
+                    {"port": 443, "transport_protocol": "TCP", "service_name": "HTTP", "extended_service_name": "HTTPS",
+                     "software": [{"vendor": "nginx", "product": "nginx", "version": "1.18.0"}, {"vendor": "x"}],
+                     "tls": {"version_selected": "TLSv1_3", "certificates": {"leaf_fp_sha_256": "ab12",
+                        "leaf_data": {"subject_dn": "CN=www.example.com", "issuer_dn": "C=US, O=Let's Encrypt, CN=R3"}}}},
+                    {"port": 22, "service_name": "SSH", "banner": "  SSH-2.0-OpenSSH_8.9  "}
+                ]}}"#,
+            "/v2/certificates/ab12" => r#"{"code": 200, "status": "OK", "result": {
+                "fingerprint_sha256": "ab12", "names": ["www.example.com"],
+                "parsed": {"subject_dn": "CN=www.example.com", "validity_period": {"not_before": "2020-01-01T00:00:00Z", "not_after": "2020-04-01T00:00:00Z"}}}}"#,
+            _ => return (404, r#"{"code": 404, "status": "Not Found", "error": "not found"}"#.to_string()),
+        };
+        (200, body.to_string())
+    }
+
+    #[test]
+    fn host_lookup_against_mock_server() {
+        let (base_url, requests) = mock::serve(respond);
+        let overrides = [("CENSYS_API_URL", base_url.as_str()), ("CENSYS_API_ID", "id"), ("CENSYS_API_SECRET", "secret")]
+            .into_iter().map(|(name, value)| (name.to_string(), value.to_string())).collect();
+        let api = ApiSettings { overrides, ..Default::default() };
+        let info = perform_censys_lookup(&ProgressBar::hidden(), "198.51.100.7", &api, "198_51_100_7", &mock::scan_dir("censys")).unwrap();
+
+        let host = &info.hosts[0];
+        assert_eq!(host.asn, Some(64500));
+        assert_eq!(host.os.as_deref(), Some("Canonical Linux"));
+        assert_eq!(host.dns_names, vec!["host7.example.net", "www.example.com"]);
+
+        let https = &host.services[0];
+        assert_eq!((https.port, https.transport.as_str(), https.extended_service_name.as_deref()), (443, "tcp", Some("HTTPS")));
+        assert_eq!(https.software.len(), 1);
+        let tls = https.tls.as_ref().unwrap();
+        assert_eq!((tls.subject.as_deref(), tls.issuer.as_deref()), (Some("www.example.com"), Some("R3")));
+        // Validity comes from the certificate lookup
+        assert_eq!(tls.expires.as_deref(), Some("2020-04-01T00:00:00Z"));
+        assert!(tls.expired);
+        assert_eq!(host.services[1].banner.as_deref(), Some("SSH-2.0-OpenSSH_8.9"));
+        assert_eq!(info.certificates[0].fingerprint, FINGERPRINT);
+
+        // id:secret as HTTP basic authentication
+        assert!(requests.lock().unwrap().iter().all(|r| r.headers.iter().any(|h| h == "authorization: Basic aWQ6c2VjcmV0")));
+    }
+
+    #[test]
+    fn error_answers_are_explained() {
+        let response = |status: u16, body: Value| ApiResponse { status, body };
+        assert!(censys_result(response(404, Value::Null)).unwrap().is_none());
+        assert!(censys_result(response(429, Value::Null)).unwrap_err().to_string().contains("rate limit"));
+        let error = censys_result(response(403, serde_json::json!({"error": "Forbidden"}))).unwrap_err();
+        assert_eq!(error.to_string(), "Censys API error: 403: Forbidden");
+    }
+}
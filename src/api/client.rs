@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use indicatif::ProgressBar;
+use serde_json::{json, Value};
+use reqwest;
+
+// Attempts for a request answered with 429 or a server error, and the backoff ceiling between them
+const MAX_ATTEMPTS: u32 = 4;
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+// Cache and retry behaviour shared by every intelligence API
+#[derive(Debug, Clone, Default)]
+pub struct ApiSettings {
+    pub cache_dir: Option<PathBuf>,          // None disables the cache
+    pub cache_ttl: Duration,
+    pub overrides: HashMap<String, String>,  // API keys and *_API_URL values used instead of the environment
+}
+
+impl ApiSettings {
+    // A provider variable: the override when one is set, the environment otherwise
+    pub fn variable(&self, name: &str) -> Option<String> {
+        self.overrides.get(name).cloned().or_else(|| env::var(name).ok()).filter(|value| !value.is_empty())
+    }
+
+    // $XDG_CACHE_HOME/rust_recon/api, falling back to ~/.cache/rust_recon/api
+    pub fn default_cache_dir() -> Option<PathBuf> {
+        env::var("XDG_CACHE_HOME").ok().filter(|d| !d.is_empty()).map(PathBuf::from)
+            .or_else(|| env::var("HOME").ok().map(|home| PathBuf::from(home).join(".cache")))
+            .map(|dir| dir.join("rust_recon").join("api"))
+    }
+}
+
+// API key from its override or environment variable, with a hint on the console when it is missing
+pub fn api_key(spinner: &ProgressBar, api: &ApiSettings, provider: &str, variable: &str) -> Result<String, Box<dyn Error>> {
+    match api.variable(variable) {
+        Some(key) => Ok(key),
+        None => {
+            spinner.suspend(|| {
+                println!("⚠️ {} environment variable not set", variable);
+                println!("⚠️ Please set your {} API key with: export {}=your_api_key", provider, variable);
+            });
+            Err(format!("{} API key not found in environment variables", provider).into())
+        }
+    }
+}
+
+// Base URL of a provider, overridable through an environment variable (e.g. to point at a local mock server)
+pub fn api_base_url(api: &ApiSettings, variable: &str, default: &str) -> String {
+    api.variable(variable).unwrap_or_else(|| default.to_string()).trim_end_matches('/').to_string()
+}
+
+// How a provider expects its credentials; never part of the cache key
+pub enum ApiAuth {
+    None,
+    Query(&'static str, String),
+    Header(&'static str, String),
+    Basic(String, String),
+}
+
+pub struct ApiResponse {
+    pub status: u16,
+    pub body: Value,                         // Parsed JSON, or the raw text as a string
+}
+
+impl ApiResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+// Earliest time each provider may be called again, shared by every client in the process
+fn next_slots() -> &'static Mutex<HashMap<&'static str, Instant>> {
+    static SLOTS: OnceLock<Mutex<HashMap<&'static str, Instant>>> = OnceLock::new();
+    SLOTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+// HTTP client for one provider: cached GETs, spaced at least `interval` apart, retried with backoff
+pub struct ApiClient {
+    provider: &'static str,
+    client: reqwest::blocking::Client,
+    auth: ApiAuth,
+    interval: Duration,
+    settings: ApiSettings,
+}
+
+impl ApiClient {
+    pub fn new(provider: &'static str, auth: ApiAuth, interval: Duration, settings: &ApiSettings) -> Result<Self, Box<dyn Error>> {
+        Ok(ApiClient {
+            provider,
+            client: reqwest::blocking::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .user_agent(concat!("rust_recon/", env!("CARGO_PKG_VERSION")))
+                .build()?,
+            auth,
+            interval,
+            settings: settings.clone(),
+        })
+    }
+
+    // GET a JSON endpoint; successful and 404 answers are cached, failures never are
+    pub fn get(&self, url: &str, params: &[(&str, &str)]) -> Result<ApiResponse, Box<dyn Error>> {
+        self.fetch(url, params, self.cache_file(url, params))
+    }
+
+    // GET without the cache, for answers tied to the key rather than the target (plan, credits)
+    pub fn get_uncached(&self, url: &str, params: &[(&str, &str)]) -> Result<ApiResponse, Box<dyn Error>> {
+        self.fetch(url, params, None)
+    }
+
+    fn fetch(&self, url: &str, params: &[(&str, &str)], cache_file: Option<PathBuf>) -> Result<ApiResponse, Box<dyn Error>> {
+        if let Some(cached) = cache_file.as_ref().and_then(|file| self.read_cache(file)) {
+            return Ok(cached);
+        }
+
+        let mut attempt = 1;
+        let response = loop {
+            self.wait_for_slot();
+            let mut request = self.client.get(url).query(params);
+            request = match &self.auth {
+                ApiAuth::None => request,
+                ApiAuth::Query(name, value) => request.query(&[(name, value)]),
+                ApiAuth::Header(name, value) => request.header(*name, value),
+                ApiAuth::Basic(user, password) => request.basic_auth(user, Some(password)),
+            };
+            let response = request.send()
+                .map_err(|e| format!("Error connecting to {} API: {}", self.provider, e))?;
+            let status = response.status();
+            let retry = status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error();
+            if !retry || attempt >= MAX_ATTEMPTS {
+                break response;
+            }
+
+            // Retry-After (in seconds) when the provider sends it, exponential backoff otherwise
+            let delay = response.headers().get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .map(Duration::from_secs)
+                .unwrap_or_else(|| self.interval.max(Duration::from_secs(1)) * 2u32.pow(attempt))
+                .min(MAX_BACKOFF);
+            thread::sleep(delay);
+            attempt += 1;
+        };
+
+        let status = response.status().as_u16();
+        let text = response.text()?;
+        let body = serde_json::from_str(&text).unwrap_or(Value::String(text));
+        let response = ApiResponse { status, body };
+        if let Some(file) = cache_file
+            && (response.is_success() || status == 404)
+        {
+            self.write_cache(&file, &response);
+        }
+        Ok(response)
+    }
+
+    // Reserve the next free slot for this provider and sleep until it comes
+    fn wait_for_slot(&self) {
+        let now = Instant::now();
+        let start = {
+            let mut slots = next_slots().lock().unwrap_or_else(|e| e.into_inner());
+            let start = slots.get(self.provider).copied().filter(|slot| *slot > now).unwrap_or(now);
+            slots.insert(self.provider, start + self.interval);
+            start
+        };
+        if start > now {
+            thread::sleep(start - now);
+        }
+    }
+
+    // provider/sha256(url and sorted parameters).json
+    fn cache_file(&self, url: &str, params: &[(&str, &str)]) -> Option<PathBuf> {
+        let dir = self.settings.cache_dir.as_ref().filter(|_| !self.settings.cache_ttl.is_zero())?;
+        let mut params: Vec<String> = params.iter().map(|(name, value)| format!("{}={}", name, value)).collect();
+        params.sort();
+        let key = format!("{}?{}", url, params.join("&"));
+        let digest = ring::digest::digest(&ring::digest::SHA256, key.as_bytes());
+        let name: String = digest.as_ref().iter().map(|b| format!("{:02x}", b)).collect();
+        Some(dir.join(self.provider.to_lowercase()).join(format!("{}.json", name)))
+    }
+
+    fn read_cache(&self, file: &PathBuf) -> Option<ApiResponse> {
+        let entry: Value = serde_json::from_str(&fs::read_to_string(file).ok()?).ok()?;
+        let age = chrono::Utc::now().timestamp() - entry["fetched"].as_i64()?;
+        if age < 0 || age as u64 >= self.settings.cache_ttl.as_secs() {
+            return None;
+        }
+        Some(ApiResponse {
+            status: entry["status"].as_u64()? as u16,
+            body: entry["body"].clone(),
+        })
+    }
+
+    // Best effort: a read-only or full disk only costs the caching
+    fn write_cache(&self, file: &PathBuf, response: &ApiResponse) {
+        let entry = json!({
+            "provider": self.provider,
+            "fetched": chrono::Utc::now().timestamp(),
+            "status": response.status,
+            "body": response.body,
+        });
+        if let Some(dir) = file.parent()
+            && fs::create_dir_all(dir).is_ok()
+        {
+            let _ = fs::write(file, entry.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::mock;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn respond(target: &str) -> (u16, &'static str, String) {
+        match target.split('?').next().unwrap_or(target) {
+            "/ok" => (200, "", r#"{"answer": 42}"#.to_string()),
+            "/missing" => (404, "", r#"{"error": "not found"}"#.to_string()),
+            "/broken" => (503, "Retry-After: 0\r\n", r#"{"error": "unavailable"}"#.to_string()),
+            "/limited" => (429, "Retry-After: 0\r\n", r#"{"error": "slow down"}"#.to_string()),
+            // Rate limited once, with a Retry-After shorter than the 2 s backoff
+            "/flaky" => {
+                static CALLS: AtomicUsize = AtomicUsize::new(0);
+                if CALLS.fetch_add(1, Ordering::Relaxed) == 0 {
+                    (429, "Retry-After: 1\r\n", String::new())
+                } else {
+                    (200, "", r#"{"answer": 43}"#.to_string())
+                }
+            },
+            _ => (500, "", String::new()),
+        }
+    }
+
+    fn cached_client(provider: &'static str, ttl: Duration) -> ApiClient {
+        let cache_dir = PathBuf::from(mock::scan_dir(provider));
+        let _ = fs::remove_dir_all(&cache_dir);
+        let settings = ApiSettings { cache_dir: Some(cache_dir), cache_ttl: ttl, ..Default::default() };
+        ApiClient::new(provider, ApiAuth::Query("key", "secret".to_string()), Duration::ZERO, &settings).unwrap()
+    }
+
+    #[test]
+    fn answers_are_cached_until_the_ttl_expires() {
+        let (base_url, requests) = mock::serve_with_headers(respond);
+        let client = cached_client("cache-ttl", Duration::from_secs(3600));
+        let url = format!("{}/ok", base_url);
+
+        assert_eq!(client.get(&url, &[("q", "a")]).unwrap().body["answer"], 42);
+        assert_eq!(client.get(&url, &[("q", "a")]).unwrap().body["answer"], 42);
+        assert_eq!(requests.lock().unwrap().len(), 1);
+        // 404 is an answer too; other parameters are another entry; the key never is part of it
+        client.get(&format!("{}/missing", base_url), &[]).unwrap();
+        client.get(&format!("{}/missing", base_url), &[]).unwrap();
+        client.get(&url, &[("q", "b")]).unwrap();
+        assert_eq!(requests.lock().unwrap().len(), 3);
+        client.get_uncached(&url, &[("q", "a")]).unwrap();
+        assert_eq!(requests.lock().unwrap().len(), 4);
+
+        // Age the entry past the TTL
+        let file = client.cache_file(&url, &[("q", "a")]).unwrap();
+        let mut entry: Value = serde_json::from_str(&fs::read_to_string(&file).unwrap()).unwrap();
+        assert!(!entry.to_string().contains("secret"));
+        entry["fetched"] = json!(chrono::Utc::now().timestamp() - 3600);
+        fs::write(&file, entry.to_string()).unwrap();
+        client.get(&url, &[("q", "a")]).unwrap();
+        assert_eq!(requests.lock().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn failures_are_retried_up_to_max_attempts_and_never_cached() {
+        let (base_url, requests) = mock::serve_with_headers(respond);
+        let client = cached_client("cache-failures", Duration::from_secs(3600));
+
+        for path in ["/broken", "/limited"] {
+            let url = format!("{}{}", base_url, path);
+            assert_eq!(client.get(&url, &[]).unwrap().status, if path == "/broken" { 503 } else { 429 });
+            assert!(client.cache_file(&url, &[]).is_some_and(|file| !file.exists()));
+        }
+        assert_eq!(requests.lock().unwrap().len(), 2 * MAX_ATTEMPTS as usize);
+
+        // A second lookup goes back to the provider
+        client.get(&format!("{}/broken", base_url), &[]).unwrap();
+        assert_eq!(requests.lock().unwrap().len(), 3 * MAX_ATTEMPTS as usize);
+    }
+
+    #[test]
+    fn retry_after_is_honoured() {
+        let (base_url, requests) = mock::serve_with_headers(respond);
+        let client = cached_client("retry-after", Duration::ZERO);
+
+        let started = Instant::now();
+        let response = client.get(&format!("{}/flaky", base_url), &[]).unwrap();
+        let elapsed = started.elapsed();
+        assert_eq!(response.body["answer"], 43);
+        assert_eq!(requests.lock().unwrap().len(), 2);
+        assert!(elapsed >= Duration::from_secs(1) && elapsed < Duration::from_secs(2), "{:?}", elapsed);
+    }
+}
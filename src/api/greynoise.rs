@@ -0,0 +1,207 @@
+use console::style;
+use indicatif::ProgressBar;
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+use std::net::IpAddr;
+use std::time::Duration;
+use serde_json::{json, Value};
+
+use super::client::{api_base_url, api_key, ApiAuth, ApiClient, ApiResponse, ApiSettings};
+use crate::models::{Finding, GreyNoiseInfo, Severity};
+
+// Overridable with GREYNOISE_API_URL, e.g. to point at a local mock server
+const DEFAULT_API_URL: &str = "https://api.greynoise.io";
+
+const REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+
+// Parsed GreyNoise data and the findings derived from it
+pub struct GreyNoiseResults {
+    pub greynoise: GreyNoiseInfo,
+    pub findings: Vec<Finding>,
+}
+
+struct GreyNoiseClient {
+    api: ApiClient,
+    base_url: String,
+}
+
+impl GreyNoiseClient {
+    fn get(&self, path: &str) -> Result<Value, Box<dyn Error>> {
+        greynoise_body(self.api.get(&format!("{}{}", self.base_url, path), &[])?)
+    }
+}
+
+// The body of a GreyNoise answer; a 404 still describes the address (not observed)
+fn greynoise_body(response: ApiResponse) -> Result<Value, Box<dyn Error>> {
+    if response.is_success() || response.status == 404 {
+        return Ok(response.body);
+    }
+    Err(match (response.status, response.body["message"].as_str()) {
+        (401, _) => "GreyNoise API key rejected (401 Unauthorized)".into(),
+        (429, _) => "GreyNoise API rate limit exceeded (429 Too Many Requests)".into(),
+        (status, Some(message)) => format!("GreyNoise API error: {}: {}", status, message).into(),
+        (status, None) => format!("GreyNoise API error: {}", status).into(),
+    })
+}
+
+// Perform GreyNoise lookup with spinner: the community summary, or the full context
+// and RIOT (known business service) records with an enterprise key
+pub fn perform_greynoise_lookup(spinner: &ProgressBar, target: &str, plan: &str, api: &ApiSettings, base_filename: &str, scan_dir: &str) -> Result<GreyNoiseResults, Box<dyn Error>> {
+    spinner.set_message(format!("Connecting to GreyNoise API for {}...", style(target).cyan()));
+    if target.parse::<IpAddr>().is_err() {
+        return Err("GreyNoise lookups need an IP address".into());
+    }
+    let api_key = api_key(spinner, api, "GreyNoise", "GREYNOISE_API_KEY")?;
+    let greynoise = GreyNoiseClient {
+        api: ApiClient::new("GreyNoise", ApiAuth::Header("key", api_key), REQUEST_INTERVAL, api)?,
+        base_url: api_base_url(api, "GREYNOISE_API_URL", DEFAULT_API_URL),
+    };
+    let mut info = GreyNoiseInfo { ip: target.to_string(), plan: plan.to_string(), ..Default::default() };
+
+    let raw = if plan == "enterprise" {
+        spinner.set_message(format!("Reading GreyNoise context for {}...", style(target).cyan()));
+        let context = greynoise.get(&format!("/v2/noise/context/{}", target))?;
+        spinner.set_message(format!("Reading GreyNoise RIOT record for {}...", style(target).cyan()));
+        let riot = greynoise.get(&format!("/v2/riot/{}", target))?;
+
+        let metadata = &context["metadata"];
+        info.noise = context["seen"].as_bool().unwrap_or(false);
+        info.riot = riot["riot"].as_bool().unwrap_or(false);
+        info.classification = string(&context, "classification");
+        info.name = string(&context, "actor").filter(|a| a != "unknown").or_else(|| string(&riot, "name"));
+        info.category = string(&riot, "category");
+        info.trust_level = string(&riot, "trust_level");
+        info.tags = strings(&context["tags"]);
+        info.cves = strings(&context["cve"]);
+        info.first_seen = string(&context, "first_seen");
+        info.last_seen = string(&context, "last_seen");
+        info.country = string(metadata, "country");
+        info.organization = string(metadata, "organization");
+        info.tor = metadata["tor"].as_bool().unwrap_or(false);
+        info.vpn_service = string(metadata, "vpn_service").filter(|_| metadata["vpn"].as_bool().unwrap_or(false));
+        json!({ "context": context, "riot": riot })
+    } else {
+        spinner.set_message(format!("Reading GreyNoise community data for {}...", style(target).cyan()));
+        let community = greynoise.get(&format!("/v3/community/{}", target))?;
+        info.noise = community["noise"].as_bool().unwrap_or(false);
+        info.riot = community["riot"].as_bool().unwrap_or(false);
+        info.classification = string(&community, "classification");
+        info.name = string(&community, "name").filter(|n| n != "unknown");
+        info.last_seen = string(&community, "last_seen");
+        info.link = string(&community, "link");
+        community
+    };
+
+    let mut file = File::create(format!("{}/{}_greynoise.json", scan_dir, base_filename))?;
+    file.write_all(serde_json::to_string_pretty(&raw)?.as_bytes())?;
+
+    let findings = greynoise_findings(&info);
+    Ok(GreyNoiseResults { greynoise: info, findings })
+}
+
+// Addresses GreyNoise classifies as malicious or suspicious scanners
+pub fn greynoise_findings(greynoise: &GreyNoiseInfo) -> Vec<Finding> {
+    let severity = match greynoise.classification.as_deref() {
+        Some("malicious") => Severity::Medium,
+        Some("suspicious") => Severity::Low,
+        _ => return Vec::new(),
+    };
+    vec![Finding {
+        source: "GreyNoise".to_string(),
+        id: Some(format!("greynoise-{}", greynoise.classification.as_deref().unwrap_or_default())),
+        severity,
+        location: greynoise.ip.clone(),
+        method: None,
+        description: format!("GreyNoise classifies this address as a {} internet scanner{}{}",
+            greynoise.classification.as_deref().unwrap_or_default(),
+            greynoise.name.as_deref().map(|n| format!(" ({})", n)).unwrap_or_default(),
+            greynoise.last_seen.as_deref().map(|d| format!(", last seen {}", d)).unwrap_or_default()),
+        references: vec![greynoise.link.clone().unwrap_or_else(|| format!("https://viz.greynoise.io/ip/{}", greynoise.ip))],
+    }]
+}
+
+fn string(value: &Value, key: &str) -> Option<String> {
+    value[key].as_str().filter(|s| !s.is_empty()).map(|s| s.to_string())
+}
+
+fn strings(value: &Value) -> Vec<String> {
+    value.as_array()
+        .map(|items| items.iter().filter_map(|i| i.as_str().map(|s| s.to_string())).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::mock;
+
+    fn respond(target: &str) -> (u16, String) {
+        let body = match target {
+            "/v3/community/198.51.100.7" => r#"{"ip": "198.51.100.7", "noise": true, "riot": false, "classification": "malicious",
+                "name": "unknown", "link": "https://viz.greynoise.io/ip/198.51.100.7", "last_seen": "2026-10-18"}"#,
+            "/v3/community/198.51.100.8" => return (404, r#"{"ip": "198.51.100.8", "noise": false, "riot": false,
+                "message": "IP not observed scanning the internet or contained in RIOT data set."}"#.to_string()),
+            "/v2/noise/context/198.51.100.9" => r#"{"ip": "198.51.100.9", "seen": true, "classification": "suspicious", "actor": "unknown",
+                "tags": ["SSH Scanner", "Telnet Bruteforcer"], "cve": ["CVE-2018-10562"], "first_seen": "2026-01-02", "last_seen": "2026-10-17",
+                "metadata": {"country": "Netherlands", "organization": "Example Hosting", "tor": false, "vpn": true, "vpn_service": "EXAMPLE_VPN"}}"#,
+            "/v2/riot/198.51.100.9" => r#"{"ip": "198.51.100.9", "riot": false}"#,
+            _ => return (500, r#"{"message": "unexpected request"}"#.to_string()),
+        };
+        (200, body.to_string())
+    }
+
+    #[test]
+    fn community_and_enterprise_lookups_against_mock_server() {
+        let (base_url, requests) = mock::serve(respond);
+        let overrides = [("GREYNOISE_API_URL", base_url), ("GREYNOISE_API_KEY", "testkey".to_string())]
+            .into_iter().map(|(name, value)| (name.to_string(), value)).collect();
+        let api = ApiSettings { overrides, ..Default::default() };
+        let scan_dir = mock::scan_dir("greynoise");
+        let lookup = |target: &str, plan: &str| perform_greynoise_lookup(&ProgressBar::hidden(), target, plan, &api, "test", &scan_dir).unwrap();
+
+        let malicious = lookup("198.51.100.7", "community");
+        assert!(malicious.greynoise.noise && !malicious.greynoise.riot);
+        assert_eq!(malicious.greynoise.name, None);
+        let finding = &malicious.findings[0];
+        assert_eq!((finding.id.as_deref(), finding.severity, finding.location.as_str()), (Some("greynoise-malicious"), Severity::Medium, "198.51.100.7"));
+        assert_eq!(finding.description, "GreyNoise classifies this address as a malicious internet scanner, last seen 2026-10-18");
+
+        // Not observed: GreyNoise answers 404 with a body that still describes the address
+        let quiet = lookup("198.51.100.8", "community");
+        assert!(!quiet.greynoise.noise && quiet.greynoise.classification.is_none() && quiet.findings.is_empty());
+
+        let enterprise = lookup("198.51.100.9", "enterprise").greynoise;
+        assert_eq!(enterprise.classification.as_deref(), Some("suspicious"));
+        assert_eq!(enterprise.tags, vec!["SSH Scanner", "Telnet Bruteforcer"]);
+        assert_eq!(enterprise.cves, vec!["CVE-2018-10562"]);
+        assert_eq!((enterprise.first_seen.as_deref(), enterprise.country.as_deref()), (Some("2026-01-02"), Some("Netherlands")));
+        assert_eq!(enterprise.vpn_service.as_deref(), Some("EXAMPLE_VPN"));
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 4);
+        assert!(requests.iter().all(|r| r.headers.iter().any(|h| h.eq_ignore_ascii_case("key: testkey"))));
+    }
+
+    #[test]
+    fn hostnames_are_rejected_before_asking_for_a_key() {
+        let error = perform_greynoise_lookup(&ProgressBar::hidden(), "www.example.com", "community", &ApiSettings::default(), "test", "/nonexistent")
+            .err().unwrap();
+        assert_eq!(error.to_string(), "GreyNoise lookups need an IP address");
+    }
+
+    #[test]
+    fn only_malicious_and_suspicious_scanners_are_findings() {
+        let info = |classification: &str| GreyNoiseInfo { ip: "198.51.100.7".to_string(), classification: Some(classification.to_string()), ..Default::default() };
+        let suspicious = greynoise_findings(&GreyNoiseInfo { name: Some("Shadowserver".to_string()), ..info("suspicious") });
+        assert_eq!((suspicious[0].id.as_deref(), suspicious[0].severity), (Some("greynoise-suspicious"), Severity::Low));
+        assert_eq!(suspicious[0].description, "GreyNoise classifies this address as a suspicious internet scanner (Shadowserver)");
+        assert_eq!(suspicious[0].references, vec!["https://viz.greynoise.io/ip/198.51.100.7"]);
+        assert!(greynoise_findings(&info("benign")).is_empty());
+        assert!(greynoise_findings(&info("unknown")).is_empty());
+
+        let response = |status: u16, body: Value| ApiResponse { status, body };
+        assert!(greynoise_body(response(401, Value::Null)).unwrap_err().to_string().contains("key rejected"));
+        assert_eq!(greynoise_body(response(400, json!({"message": "invalid IP"}))).unwrap_err().to_string(), "GreyNoise API error: 400: invalid IP");
+    }
+}
@@ -0,0 +1,59 @@
+// Local HTTP server standing in for a provider in tests, reached through its *_API_URL variable
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+// A request the mock answered: the request target (path and query) and its headers
+pub struct MockRequest {
+    pub target: String,
+    pub headers: Vec<String>,
+}
+
+// Serve `respond(target) -> (status, JSON body)` on a local port; returns the base URL and the requests seen
+pub fn serve(respond: fn(&str) -> (u16, String)) -> (String, Arc<Mutex<Vec<MockRequest>>>) {
+    serve_with_headers(move |target| {
+        let (status, body) = respond(target);
+        (status, "", body)
+    })
+}
+
+// As serve, with extra header lines (e.g. "Retry-After: 1\r\n") in each answer
+pub fn serve_with_headers(respond: impl Fn(&str) -> (u16, &'static str, String) + Send + 'static) -> (String, Arc<Mutex<Vec<MockRequest>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&requests);
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            if reader.read_line(&mut request_line).is_err() {
+                continue;
+            }
+            let mut headers = Vec::new();
+            let mut line = String::new();
+            while reader.read_line(&mut line).is_ok_and(|n| n > 0) && line.trim_end() != "" {
+                headers.push(line.trim_end().to_string());
+                line.clear();
+            }
+
+            let target = request_line.split_whitespace().nth(1).unwrap_or("/").to_string();
+            let (status, extra_headers, body) = respond(&target);
+            seen.lock().unwrap().push(MockRequest { target, headers });
+            let _ = write!(stream, "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+                status, body.len(), extra_headers, body);
+        }
+    });
+
+    (base_url, requests)
+}
+
+// A fresh directory for the files a lookup writes
+pub fn scan_dir(name: &str) -> String {
+    let dir = std::env::temp_dir().join(format!("rust_recon_{}_{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.to_string_lossy().to_string()
+}
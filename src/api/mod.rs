@@ -1,3 +1,18 @@
+pub mod abuseipdb;
+pub mod censys;
+pub mod client;
+pub mod greynoise;
+#[cfg(test)]
+mod mock;
+pub mod passive_dns;
+pub mod securitytrails;
 pub mod shodan;
+pub mod virustotal;
 
-pub use shodan::*;
\ No newline at end of file
+pub use abuseipdb::*;
+pub use censys::*;
+pub use client::*;
+pub use greynoise::*;
+pub use passive_dns::*;
+pub use shodan::*;
+pub use virustotal::*;
\ No newline at end of file
@@ -0,0 +1,134 @@
+use console::style;
+use indicatif::ProgressBar;
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+use std::net::IpAddr;
+use serde_json::json;
+
+use super::client::ApiSettings;
+use super::securitytrails::SecurityTrailsProvider;
+use crate::models::{normalise_name, PassiveDnsRecord};
+
+// Providers accepted by --passive-dns
+pub const PASSIVE_DNS_PROVIDERS: &[&str] = &["securitytrails"];
+
+// A source of historical DNS data; each provider maps its own API onto these three queries
+pub trait PassiveDnsProvider {
+    fn name(&self) -> &'static str;
+
+    // A records the domain has resolved to, with the period each was seen
+    fn a_history(&self, domain: &str) -> Result<Vec<PassiveDnsRecord>, Box<dyn Error>>;
+
+    // Fully qualified names under the domain, including ones that no longer resolve
+    fn subdomains(&self, domain: &str) -> Result<Vec<String>, Box<dyn Error>>;
+
+    // Other domains sharing the owner, registrant or infrastructure
+    fn associated_domains(&self, domain: &str) -> Result<Vec<String>, Box<dyn Error>>;
+}
+
+// Build the provider named on the command line
+pub fn passive_dns_provider(spinner: &ProgressBar, name: &str, api: &ApiSettings) -> Result<Box<dyn PassiveDnsProvider>, Box<dyn Error>> {
+    match name {
+        "securitytrails" => Ok(Box::new(SecurityTrailsProvider::new(spinner, api)?)),
+        _ => Err(format!("Unknown passive DNS provider {} (supported: {})", name, PASSIVE_DNS_PROVIDERS.join(", ")).into()),
+    }
+}
+
+// Historical resolutions, subdomains and associated domains from one provider
+pub struct PassiveDnsResults {
+    pub provider: String,
+    pub records: Vec<PassiveDnsRecord>,
+    pub subdomains: Vec<String>,
+    pub associated_domains: Vec<String>,
+    pub associated_error: Option<String>,  // Often a paid feature; the rest of the lookup still counts
+}
+
+// Perform passive DNS lookup with spinner
+pub fn perform_passive_dns(spinner: &ProgressBar, domain: &str, provider: &dyn PassiveDnsProvider, base_filename: &str, scan_dir: &str) -> Result<PassiveDnsResults, Box<dyn Error>> {
+    if domain.parse::<IpAddr>().is_ok() || domain.contains('/') {
+        return Err("Passive DNS lookups need a domain".into());
+    }
+    let domain = normalise_name(domain);
+
+    spinner.set_message(format!("Reading {} A record history for {}...", provider.name(), style(&domain).cyan()));
+    let records = provider.a_history(&domain)?;
+
+    spinner.set_message(format!("Reading {} subdomains of {}...", provider.name(), style(&domain).cyan()));
+    let mut subdomains: Vec<String> = provider.subdomains(&domain)?.iter().map(|name| normalise_name(name)).collect();
+    subdomains.sort();
+    subdomains.dedup();
+
+    spinner.set_message(format!("Reading {} associated domains of {}...", provider.name(), style(&domain).cyan()));
+    let (mut associated_domains, associated_error) = match provider.associated_domains(&domain) {
+        Ok(names) => (names.iter().map(|name| normalise_name(name)).filter(|name| *name != domain).collect(), None),
+        Err(e) => (Vec::new(), Some(e.to_string())),
+    };
+    associated_domains.sort();
+    associated_domains.dedup();
+
+    let mut file = File::create(format!("{}/{}_passive_dns.json", scan_dir, base_filename))?;
+    file.write_all(serde_json::to_string_pretty(&json!({
+        "provider": provider.name(),
+        "domain": domain,
+        "a_history": records,
+        "subdomains": subdomains,
+        "associated_domains": associated_domains,
+        "associated_error": associated_error,
+    }))?.as_bytes())?;
+
+    Ok(PassiveDnsResults { provider: provider.name().to_string(), records, subdomains, associated_domains, associated_error })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::mock;
+
+    // Canned answers standing in for a provider API
+    struct StubProvider;
+
+    impl PassiveDnsProvider for StubProvider {
+        fn name(&self) -> &'static str {
+            "Stub"
+        }
+
+        fn a_history(&self, domain: &str) -> Result<Vec<PassiveDnsRecord>, Box<dyn Error>> {
+            Ok(vec![PassiveDnsRecord { source: "Stub".to_string(), name: domain.to_string(), record_type: "A".to_string(),
+                value: "198.51.100.7".to_string(), organization: None, first_seen: None, last_seen: None }])
+        }
+
+        fn subdomains(&self, domain: &str) -> Result<Vec<String>, Box<dyn Error>> {
+            Ok(vec![format!("WWW.{}.", domain), format!("www.{}", domain), format!("api.{}", domain)])
+        }
+
+        fn associated_domains(&self, domain: &str) -> Result<Vec<String>, Box<dyn Error>> {
+            Ok(vec!["Example.net".to_string(), domain.to_uppercase(), "example.net.".to_string()])
+        }
+    }
+
+    #[test]
+    fn names_are_normalised_and_deduplicated() {
+        let scan_dir = mock::scan_dir("passive_dns");
+        let results = perform_passive_dns(&ProgressBar::hidden(), "Example.COM", &StubProvider, "test", &scan_dir).unwrap();
+
+        assert_eq!(results.provider, "Stub");
+        assert_eq!(results.records[0].name, "example.com");
+        assert_eq!(results.subdomains, vec!["api.example.com", "www.example.com"]);
+        // The domain itself is not associated with itself
+        assert_eq!(results.associated_domains, vec!["example.net"]);
+        assert!(results.associated_error.is_none());
+
+        let saved: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(format!("{}/test_passive_dns.json", scan_dir)).unwrap()).unwrap();
+        assert_eq!(saved["domain"], "example.com");
+        assert_eq!(saved["a_history"][0]["value"], "198.51.100.7");
+    }
+
+    #[test]
+    fn addresses_and_ranges_are_rejected() {
+        for target in ["198.51.100.7", "198.51.100.0/24"] {
+            let error = perform_passive_dns(&ProgressBar::hidden(), target, &StubProvider, "test", "/nonexistent").err().unwrap();
+            assert_eq!(error.to_string(), "Passive DNS lookups need a domain");
+        }
+    }
+}
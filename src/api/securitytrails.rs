@@ -0,0 +1,167 @@
+use indicatif::ProgressBar;
+use std::error::Error;
+use std::time::Duration;
+use serde_json::Value;
+
+use super::client::{api_base_url, api_key, ApiAuth, ApiClient, ApiResponse, ApiSettings};
+use super::passive_dns::PassiveDnsProvider;
+use crate::models::PassiveDnsRecord;
+
+// Overridable with SECURITYTRAILS_API_URL, e.g. to point at a local mock server
+const DEFAULT_API_URL: &str = "https://api.securitytrails.com/v1";
+
+// Free accounts are limited to one request per second
+const REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+
+// History pages read per domain (100 records each); every page counts against the monthly quota
+const MAX_HISTORY_PAGES: u64 = 10;
+
+pub struct SecurityTrailsProvider {
+    api: ApiClient,
+    base_url: String,
+}
+
+impl SecurityTrailsProvider {
+    pub fn new(spinner: &ProgressBar, api: &ApiSettings) -> Result<Self, Box<dyn Error>> {
+        let api_key = api_key(spinner, api, "SecurityTrails", "SECURITYTRAILS_API_KEY")?;
+        Ok(SecurityTrailsProvider {
+            api: ApiClient::new("SecurityTrails", ApiAuth::Header("APIKEY", api_key), REQUEST_INTERVAL, api)?,
+            base_url: api_base_url(api, "SECURITYTRAILS_API_URL", DEFAULT_API_URL),
+        })
+    }
+
+    fn get(&self, path: &str, params: &[(&str, &str)]) -> Result<Value, Box<dyn Error>> {
+        securitytrails_body(self.api.get(&format!("{}{}", self.base_url, path), params)?)
+    }
+}
+
+impl PassiveDnsProvider for SecurityTrailsProvider {
+    fn name(&self) -> &'static str {
+        "SecurityTrails"
+    }
+
+    // /history/{domain}/dns/a, page by page: each record is a set of addresses seen together
+    fn a_history(&self, domain: &str) -> Result<Vec<PassiveDnsRecord>, Box<dyn Error>> {
+        let mut records = Vec::new();
+        let mut page = 1;
+        loop {
+            let body = self.get(&format!("/history/{}/dns/a", domain), &[("page", page.to_string().as_str())])?;
+            for record in body["records"].as_array().into_iter().flatten() {
+                let organization = record["organizations"].as_array()
+                    .and_then(|o| o.first())
+                    .and_then(|o| o.as_str())
+                    .map(|o| o.to_string());
+                for value in record["values"].as_array().into_iter().flatten() {
+                    let Some(ip) = value["ip"].as_str() else { continue };
+                    records.push(PassiveDnsRecord {
+                        source: self.name().to_string(),
+                        name: domain.to_string(),
+                        record_type: "A".to_string(),
+                        value: ip.to_string(),
+                        organization: organization.clone(),
+                        first_seen: string(record, "first_seen"),
+                        last_seen: string(record, "last_seen"),
+                    });
+                }
+            }
+            if page >= body["pages"].as_u64().unwrap_or(1).min(MAX_HISTORY_PAGES) {
+                break;
+            }
+            page += 1;
+        }
+        Ok(records)
+    }
+
+    // Subdomains come back as labels relative to the domain
+    fn subdomains(&self, domain: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        let body = self.get(&format!("/domain/{}/subdomains", domain), &[("children_only", "false"), ("include_inactive", "true")])?;
+        Ok(body["subdomains"].as_array().into_iter().flatten()
+            .filter_map(|label| label.as_str())
+            .map(|label| format!("{}.{}", label, domain))
+            .collect())
+    }
+
+    fn associated_domains(&self, domain: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        let body = self.get(&format!("/domain/{}/associated", domain), &[])?;
+        Ok(body["records"].as_array().into_iter().flatten()
+            .filter_map(|record| string(record, "hostname"))
+            .collect())
+    }
+}
+
+// Errors come as {"message": ...}
+fn securitytrails_body(response: ApiResponse) -> Result<Value, Box<dyn Error>> {
+    if !response.is_success() {
+        return Err(match (response.status, response.body["message"].as_str()) {
+            (401 | 403, _) => format!("SecurityTrails API key rejected or endpoint not in plan ({})", response.status).into(),
+            (429, _) => "SecurityTrails API quota exceeded (429 Too Many Requests)".into(),
+            (status, Some(message)) => format!("SecurityTrails API error: {}: {}", status, message).into(),
+            (status, None) => format!("SecurityTrails API error: {}", status).into(),
+        });
+    }
+    Ok(response.body)
+}
+
+fn string(value: &Value, key: &str) -> Option<String> {
+    value[key].as_str().filter(|s| !s.is_empty()).map(|s| s.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::mock;
+    use crate::api::passive_dns::perform_passive_dns;
+
+    fn respond(target: &str) -> (u16, String) {
+        let body = match target {
+            "/history/example.com/dns/a?page=1" => r#"{"type": "a/history", "pages": 2, "records": [
+                {"first_seen": "2024-03-01", "last_seen": "2026-10-18", "organizations": ["Example Hosting"],
+                 "values": [{"ip": "198.51.100.7", "ip_count": 12}, {"ip": "198.51.100.8", "ip_count": 3}]}]}"#,
+            "/history/example.com/dns/a?page=2" => r#"{"type": "a/history", "pages": 2, "records": [
+                {"first_seen": "2019-06-10", "last_seen": "2024-02-28", "organizations": [], "values": [{"ip": "203.0.113.20"}, {"ipv6": "2001:db8::1"}]}]}"#,
+            "/domain/example.com/subdomains?children_only=false&include_inactive=true" =>
+                r#"{"endpoint": "/v1/domain/example.com/subdomains", "subdomains": ["www", "mail", "WWW", "old.vpn"], "subdomain_count": 4}"#,
+            "/domain/example.com/associated" => return (403, r#"{"message": "You've exceeded the usage limits for your account."}"#.to_string()),
+            _ => return (404, r#"{"message": "Not found"}"#.to_string()),
+        };
+        (200, body.to_string())
+    }
+
+    #[test]
+    fn passive_dns_against_mock_server() {
+        let (base_url, requests) = mock::serve(respond);
+        let overrides = [("SECURITYTRAILS_API_URL", base_url), ("SECURITYTRAILS_API_KEY", "testkey".to_string())]
+            .into_iter().map(|(name, value)| (name.to_string(), value)).collect();
+        let provider = SecurityTrailsProvider::new(&ProgressBar::hidden(), &ApiSettings { overrides, ..Default::default() }).unwrap();
+
+        let results = perform_passive_dns(&ProgressBar::hidden(), "Example.com.", &provider, "test", &mock::scan_dir("securitytrails")).unwrap();
+
+        // Both history pages are read; values without an IPv4 address are skipped
+        let history: Vec<String> = results.records.iter()
+            .map(|r| format!("{} {} {}..{} {}", r.name, r.value, r.first_seen.as_deref().unwrap_or("-"), r.last_seen.as_deref().unwrap_or("-"),
+                r.organization.as_deref().unwrap_or("-")))
+            .collect();
+        assert_eq!(history, vec![
+            "example.com 198.51.100.7 2024-03-01..2026-10-18 Example Hosting",
+            "example.com 198.51.100.8 2024-03-01..2026-10-18 Example Hosting",
+            "example.com 203.0.113.20 2019-06-10..2024-02-28 -",
+        ]);
+        assert_eq!(results.subdomains, vec!["mail.example.com", "old.vpn.example.com", "www.example.com"]);
+
+        // Associated domains are a paid feature; the rest of the lookup still succeeds
+        assert!(results.associated_domains.is_empty());
+        assert_eq!(results.associated_error.as_deref(), Some("SecurityTrails API key rejected or endpoint not in plan (403)"));
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 4);
+        assert!(requests.iter().all(|r| r.headers.iter().any(|h| h.eq_ignore_ascii_case("apikey: testkey"))));
+    }
+
+    #[test]
+    fn error_answers_are_explained() {
+        let response = |status: u16, body: Value| ApiResponse { status, body };
+        assert!(securitytrails_body(response(429, Value::Null)).unwrap_err().to_string().contains("quota exceeded"));
+        assert_eq!(securitytrails_body(response(400, serde_json::json!({"message": "Invalid hostname"}))).unwrap_err().to_string(),
+            "SecurityTrails API error: 400: Invalid hostname");
+    }
+}
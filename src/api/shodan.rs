@@ -3,48 +3,415 @@ use indicatif::ProgressBar;
 use std::error::Error;
 use std::fs::File;
 use std::io::Write;
-use std::env;
+use std::net::IpAddr;
+use std::time::Duration;
 use serde_json::Value;
-use reqwest;
 
-// Perform Shodan lookup with spinner
-pub fn perform_shodan_lookup(spinner: &ProgressBar, target: &str, base_filename: &str, scan_dir: &str) -> Result<(), Box<dyn Error>> {
+use super::client::{api_base_url, api_key, ApiAuth, ApiClient, ApiResponse, ApiSettings};
+use crate::models::{Finding, Severity, ShodanAccount, ShodanDnsRecord, ShodanDomain, ShodanFacetValue, ShodanHost, ShodanInfo, ShodanMatch, ShodanSearch, ShodanService, ShodanVuln, TlsInfo};
+
+// Overridable with SHODAN_API_URL, e.g. to point at a local mock server
+const DEFAULT_API_URL: &str = "https://api.shodan.io";
+
+// Search query and facets, beyond the host/domain lookups that always run
+pub struct ShodanOptions {
+    pub query: Option<String>,
+    pub facets: Option<String>,
+    pub api: ApiSettings,
+}
+
+// Parsed Shodan data and the findings derived from it
+pub struct ShodanResults {
+    pub shodan: ShodanInfo,
+    pub findings: Vec<Finding>,
+}
+
+// Shodan allows one request per second on every plan
+const REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+
+struct ShodanClient {
+    api: ApiClient,
+    base_url: String,
+}
+
+impl ShodanClient {
+    fn get(&self, path: &str, params: &[(&str, &str)]) -> Result<Option<Value>, Box<dyn Error>> {
+        shodan_body(self.api.get(&format!("{}{}", self.base_url, path), params)?)
+    }
+
+    fn get_uncached(&self, path: &str) -> Result<Option<Value>, Box<dyn Error>> {
+        shodan_body(self.api.get_uncached(&format!("{}{}", self.base_url, path), &[])?)
+    }
+}
+
+// The body of a Shodan answer; None when Shodan has no data (404)
+fn shodan_body(response: ApiResponse) -> Result<Option<Value>, Box<dyn Error>> {
+    if response.status == 404 {
+        return Ok(None);
+    }
+    if !response.is_success() {
+        // Shodan explains most failures in an {"error": ...} body
+        return Err(match (response.status, response.body["error"].as_str()) {
+            (401, _) => "Shodan API key rejected (401 Unauthorized)".into(),
+            (429, _) => "Shodan API rate limit exceeded (429 Too Many Requests)".into(),
+            (status, Some(detail)) => format!("Shodan API error: {}: {}", status, detail).into(),
+            (status, None) => format!("Shodan API error: {}", status).into(),
+        });
+    }
+    Ok(Some(response.body))
+}
+
+// Perform Shodan lookup with spinner: account, host, honeyscore, domain DNS and optional search
+pub fn perform_shodan_lookup(spinner: &ProgressBar, target: &str, options: &ShodanOptions, base_filename: &str, scan_dir: &str) -> Result<ShodanResults, Box<dyn Error>> {
     let output_file = format!("{}/{}_shodan.json", scan_dir, base_filename);
-    
+
     // Update spinner message
     spinner.set_message(format!("Connecting to Shodan API for {}...", style(target).cyan()));
-    
+
     // Check for Shodan API key
-    let api_key = match env::var("SHODAN_API_KEY") {
-        Ok(key) => key,
-        Err(_) => {
-            spinner.suspend(|| {
-                println!("⚠️ SHODAN_API_KEY environment variable not set");
-                println!("⚠️ Please set your Shodan API key with: export SHODAN_API_KEY=your_api_key");
-            });
-            return Err("Shodan API key not found in environment variables".into());
-        }
+    let api_key = api_key(spinner, &options.api, "Shodan", "SHODAN_API_KEY")?;
+    let shodan = ShodanClient {
+        api: ApiClient::new("Shodan", ApiAuth::Query("key", api_key), REQUEST_INTERVAL, &options.api)?,
+        base_url: api_base_url(&options.api, "SHODAN_API_URL", DEFAULT_API_URL),
     };
-    
-    // Make API request to Shodan
-    spinner.set_message(format!("Querying Shodan database for {}...", style(target).cyan()));
-    let client = reqwest::blocking::Client::new();
-    let url = format!("https://api.shodan.io/shodan/host/{}?key={}", target, api_key);
-    
-    match client.get(&url).send() {
-        Ok(response) => {
-            if response.status().is_success() {
-                spinner.set_message(format!("Processing Shodan data for {}...", style(target).cyan()));
-                let json: Value = response.json()?;
-                let mut file = File::create(output_file)?;
-                file.write_all(serde_json::to_string_pretty(&json)?.as_bytes())?;
-                Ok(())
-            } else {
-                Err(format!("Shodan API error: {}", response.status()).into())
+    let mut info = ShodanInfo { target: target.to_string(), ..Default::default() };
+
+    // Plan and credits, always fresh; this also fails fast on a bad key
+    if let Some(account) = shodan.get_uncached("/api-info")? {
+        info.account = Some(parse_account(&account));
+    }
+
+    // Domains are resolved through Shodan and their DNS data read; ranges can only be searched
+    let range = target.contains('/');
+    let ip = if range {
+        None
+    } else if target.parse::<IpAddr>().is_ok() {
+        Some(target.to_string())
+    } else {
+        spinner.set_message(format!("Reading Shodan DNS data for {}...", style(target).cyan()));
+        match shodan.get(&format!("/dns/domain/{}", target), &[]) {
+            Ok(Some(domain)) => {
+                let mut file = File::create(format!("{}/{}_shodan_dns.json", scan_dir, base_filename))?;
+                file.write_all(serde_json::to_string_pretty(&domain)?.as_bytes())?;
+                info.domain = Some(parse_domain(target, &domain));
+            },
+            Ok(None) => {},
+            Err(e) => warn(spinner, &format!("Shodan DNS lookup failed: {}", e)),
+        }
+        if let Some(Value::Object(resolved)) = shodan.get("/dns/resolve", &[("hostnames", target)])? {
+            for (hostname, address) in resolved {
+                if let Some(address) = address.as_str() {
+                    info.resolved.insert(hostname, address.to_string());
+                }
             }
-        },
-        Err(e) => {
-            Err(format!("Error connecting to Shodan API: {}", e).into())
         }
+        info.resolved.get(target).cloned()
+    };
+
+    if let Some(ip) = &ip {
+        // Make API request to Shodan
+        spinner.set_message(format!("Querying Shodan database for {}...", style(ip).cyan()));
+        if let Some(json) = shodan.get(&format!("/shodan/host/{}", ip), &[])? {
+            spinner.set_message(format!("Processing Shodan data for {}...", style(ip).cyan()));
+            let mut file = File::create(output_file)?;
+            file.write_all(serde_json::to_string_pretty(&json)?.as_bytes())?;
+            info.host = Some(parse_host(&json));
+        }
+
+        // The honeyscore is a labs endpoint that not every plan can use
+        spinner.set_message(format!("Checking Shodan honeyscore for {}...", style(ip).cyan()));
+        match shodan.get(&format!("/labs/honeyscore/{}", ip), &[]) {
+            Ok(score) => info.honeyscore = score.and_then(|s| s.as_f64()),
+            Err(e) => warn(spinner, &format!("Shodan honeyscore failed: {}", e)),
+        }
+    }
+
+    // Explicit query, or everything Shodan indexed in a range target
+    let query = options.query.clone().or_else(|| range.then(|| format!("net:{}", target)));
+    if let Some(query) = query {
+        spinner.set_message(format!("Searching Shodan for {}...", style(&query).cyan()));
+        let mut params = vec![("query", query.as_str())];
+        if let Some(facets) = &options.facets {
+            params.push(("facets", facets.as_str()));
+        }
+        if let Some(search) = shodan.get("/shodan/host/search", &params)? {
+            let mut file = File::create(format!("{}/{}_shodan_search.json", scan_dir, base_filename))?;
+            file.write_all(serde_json::to_string_pretty(&search)?.as_bytes())?;
+            info.search = Some(parse_search(&query, &search));
+        }
+    }
+
+    let findings = shodan_findings(&info);
+    Ok(ShodanResults { shodan: info, findings })
+}
+
+// Vulnerabilities Shodan associates with the host, one finding per CVE and port
+pub fn shodan_findings(shodan: &ShodanInfo) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let Some(host) = &shodan.host else {
+        return findings;
+    };
+    let finding = |vuln: &ShodanVuln, location: String, context: String| Finding {
+        source: "Shodan".to_string(),
+        id: Some(vuln.cve.clone()),
+        severity: cvss_severity(vuln.cvss),
+        location,
+        method: None,
+        description: format!("{}{}{}",
+            context,
+            if vuln.verified { "" } else { " (inferred from the version, not verified)" },
+            vuln.summary.as_deref().map(|s| format!(": {}", s)).unwrap_or_default()),
+        references: vec![vuln.cve.clone(), format!("https://nvd.nist.gov/vuln/detail/{}", vuln.cve)],
+    };
+
+    for service in &host.services {
+        let product: Vec<&str> = [service.product.as_deref(), service.version.as_deref()].into_iter().flatten().collect();
+        for vuln in &service.vulns {
+            findings.push(finding(vuln,
+                format!("{}:{}/{}", host.ip, service.port, service.transport),
+                format!("Shodan lists {} for {}{}",
+                    vuln.cve,
+                    if product.is_empty() { "this service".to_string() } else { product.join(" ") },
+                    service.timestamp.as_deref().map(|t| format!(" as seen on {}", t.split('T').next().unwrap_or(t))).unwrap_or_default())));
+        }
+    }
+
+    // CVEs listed for the host without a banner to pin them to
+    for cve in &host.vulns {
+        if !host.services.iter().any(|s| s.vulns.iter().any(|v| &v.cve == cve)) {
+            let vuln = ShodanVuln { cve: cve.clone(), cvss: None, summary: None, verified: false };
+            findings.push(finding(&vuln, host.ip.clone(), format!("Shodan lists {} for this host", cve)));
+        }
+    }
+    findings
+}
+
+// CVSS base score bands; unscored CVEs are treated as Medium
+fn cvss_severity(cvss: Option<f64>) -> Severity {
+    match cvss {
+        Some(score) if score >= 9.0 => Severity::Critical,
+        Some(score) if score >= 7.0 => Severity::High,
+        Some(score) if score >= 4.0 => Severity::Medium,
+        Some(score) if score > 0.0 => Severity::Low,
+        Some(_) => Severity::Info,
+        None => Severity::Medium,
+    }
+}
+
+fn warn(spinner: &ProgressBar, message: &str) {
+    spinner.suspend(|| println!("⚠️ {}", message));
+}
+
+// String field; numbers are accepted too since Shodan is not consistent about e.g. versions
+fn string(value: &Value, key: &str) -> Option<String> {
+    match &value[key] {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn strings(value: &Value, key: &str) -> Vec<String> {
+    value[key].as_array()
+        .map(|items| items.iter().filter_map(|i| i.as_str().map(|s| s.to_string())).collect())
+        .unwrap_or_default()
+}
+
+// Vulnerabilities come as a list of CVEs on the host and as an object keyed by CVE on banners
+fn vulns(value: &Value) -> Vec<ShodanVuln> {
+    let mut vulns: Vec<ShodanVuln> = match &value["vulns"] {
+        Value::Array(items) => items.iter()
+            .filter_map(|i| i.as_str())
+            .map(|cve| ShodanVuln { cve: cve.to_string(), cvss: None, summary: None, verified: false })
+            .collect(),
+        Value::Object(items) => items.iter().map(|(cve, detail)| ShodanVuln {
+            cve: cve.clone(),
+            cvss: detail["cvss"].as_f64().or_else(|| detail["cvss"].as_str().and_then(|c| c.parse().ok())),
+            summary: string(detail, "summary"),
+            verified: detail["verified"].as_bool().unwrap_or(false),
+        }).collect(),
+        _ => Vec::new(),
+    };
+    vulns.sort_by(|a, b| a.cve.cmp(&b.cve));
+    vulns.dedup_by(|a, b| a.cve == b.cve);
+    vulns
+}
+
+fn parse_account(json: &Value) -> ShodanAccount {
+    ShodanAccount {
+        plan: string(json, "plan"),
+        query_credits: json["query_credits"].as_i64(),
+        scan_credits: json["scan_credits"].as_i64(),
+        monitored_ips: json["monitored_ips"].as_i64(),
+    }
+}
+
+fn parse_host(json: &Value) -> ShodanHost {
+    let services: Vec<ShodanService> = json["data"].as_array()
+        .map(|banners| banners.iter().map(parse_service).collect())
+        .unwrap_or_default();
+
+    // Older responses only carry vulns per banner
+    let mut host_vulns: Vec<String> = vulns(json).into_iter().map(|v| v.cve).collect();
+    host_vulns.extend(services.iter().flat_map(|s| s.vulns.iter().map(|v| v.cve.clone())));
+    host_vulns.sort();
+    host_vulns.dedup();
+
+    let mut ports: Vec<u16> = json["ports"].as_array()
+        .map(|ports| ports.iter().filter_map(|p| p.as_u64()).map(|p| p as u16).collect())
+        .unwrap_or_default();
+    ports.extend(services.iter().map(|s| s.port));
+    ports.sort_unstable();
+    ports.dedup();
+
+    ShodanHost {
+        ip: string(json, "ip_str").unwrap_or_default(),
+        hostnames: strings(json, "hostnames"),
+        domains: strings(json, "domains"),
+        org: string(json, "org"),
+        isp: string(json, "isp"),
+        asn: string(json, "asn"),
+        os: string(json, "os"),
+        country: string(json, "country_name"),
+        city: string(json, "city"),
+        tags: strings(json, "tags"),
+        last_update: string(json, "last_update"),
+        ports,
+        services,
+        vulns: host_vulns,
+    }
+}
+
+fn parse_service(banner: &Value) -> ShodanService {
+    let mut cpe = strings(banner, "cpe23");
+    if cpe.is_empty() {
+        cpe = strings(banner, "cpe");
+    }
+    let ssl = banner["ssl"].is_object().then(|| {
+        let cert = &banner["ssl"]["cert"];
+        TlsInfo {
+            subject: string(&cert["subject"], "CN"),
+            issuer: string(&cert["issuer"], "CN").or_else(|| string(&cert["issuer"], "O")),
+            expires: string(cert, "expires"),
+            expired: cert["expired"].as_bool().unwrap_or(false),
+            versions: strings(&banner["ssl"], "versions"),
+        }
+    });
+    ShodanService {
+        port: banner["port"].as_u64().unwrap_or(0) as u16,
+        transport: string(banner, "transport").unwrap_or_else(|| "tcp".to_string()),
+        module: string(&banner["_shodan"], "module"),
+        product: string(banner, "product"),
+        version: string(banner, "version"),
+        banner: string(banner, "data").map(|d| d.trim().to_string()).filter(|d| !d.is_empty()),
+        cpe,
+        ssl,
+        vulns: vulns(banner),
+        timestamp: string(banner, "timestamp"),
+    }
+}
+
+fn parse_domain(domain: &str, json: &Value) -> ShodanDomain {
+    ShodanDomain {
+        domain: string(json, "domain").unwrap_or_else(|| domain.to_string()),
+        tags: strings(json, "tags"),
+        subdomains: strings(json, "subdomains"),
+        records: json["data"].as_array()
+            .map(|records| records.iter().filter_map(|r| Some(ShodanDnsRecord {
+                subdomain: string(r, "subdomain").unwrap_or_default(),
+                record_type: string(r, "type")?,
+                value: string(r, "value")?,
+                last_seen: string(r, "last_seen"),
+            })).collect())
+            .unwrap_or_default(),
+    }
+}
+
+fn parse_search(query: &str, json: &Value) -> ShodanSearch {
+    ShodanSearch {
+        query: query.to_string(),
+        total: json["total"].as_u64().unwrap_or(0),
+        matches: json["matches"].as_array()
+            .map(|matches| matches.iter().map(|m| ShodanMatch {
+                ip: string(m, "ip_str").unwrap_or_default(),
+                port: m["port"].as_u64().unwrap_or(0) as u16,
+                transport: string(m, "transport").unwrap_or_else(|| "tcp".to_string()),
+                product: string(m, "product"),
+                org: string(m, "org"),
+                hostnames: strings(m, "hostnames"),
+            }).collect())
+            .unwrap_or_default(),
+        facets: json["facets"].as_object()
+            .map(|facets| facets.iter().map(|(name, values)| (name.clone(), values.as_array()
+                .map(|values| values.iter().filter_map(|v| Some(ShodanFacetValue {
+                    value: string(v, "value")?,
+                    count: v["count"].as_u64().unwrap_or(0),
+                })).collect())
+                .unwrap_or_default())).collect())
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::mock;
+
+    fn respond(target: &str) -> (u16, String) {
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        if !query.split('&').any(|p| p == "key=testkey") {
+            return (401, r#"{"error": "Please provide a valid API key"}"#.to_string());
+        }
+        let body = match path {
+            "/api-info" => r#"{"plan": "dev", "query_credits": 99, "scan_credits": 100, "monitored_ips": 0}"#,
+            "/shodan/host/203.0.113.10" => r#"{"ip_str": "203.0.113.10", "hostnames": ["www.example.com"], "org": "Example Corp",
+                "ports": [22, 8443], "vulns": ["CVE-2023-38408", "CVE-2021-44228"],
+                "data": [
+                    {"port": 22, "transport": "tcp", "product": "OpenSSH", "version": 89, "_shodan": {"module": "ssh"},
+                     "timestamp": "2026-09-30T10:00:00.000000", "vulns": {"CVE-2023-38408": {"cvss": 9.8, "verified": true}}},
+                    {"port": 8443, "transport": "tcp", "data": "HTTP/1.1 401 Unauthorized"}
+                ]}"#,
+            "/labs/honeyscore/203.0.113.10" => "0.3",
+            _ => return (404, r#"{"error": "No information available for that IP."}"#.to_string()),
+        };
+        (200, body.to_string())
+    }
+
+    #[test]
+    fn host_lookup_against_mock_server() {
+        let (base_url, requests) = mock::serve(respond);
+        let overrides = [("SHODAN_API_URL", base_url), ("SHODAN_API_KEY", "testkey".to_string())]
+            .into_iter().map(|(name, value)| (name.to_string(), value)).collect();
+        let options = ShodanOptions { query: None, facets: None, api: ApiSettings { overrides, ..Default::default() } };
+        let results = perform_shodan_lookup(&ProgressBar::hidden(), "203.0.113.10", &options, "203_0_113_10", &mock::scan_dir("shodan")).unwrap();
+
+        let info = results.shodan;
+        assert_eq!(info.account.and_then(|a| a.plan).as_deref(), Some("dev"));
+        assert_eq!(info.honeyscore, Some(0.3));
+        let host = info.host.as_ref().unwrap();
+        assert_eq!(host.org.as_deref(), Some("Example Corp"));
+        assert_eq!(host.services.iter().map(|s| s.port).collect::<Vec<_>>(), vec![22, 8443]);
+        assert_eq!(host.services[0].version.as_deref(), Some("89"));
+
+        // The banner CVE is pinned to its port; the other one only to the host
+        let findings: Vec<(&str, &str, Severity)> = results.findings.iter()
+            .map(|f| (f.id.as_deref().unwrap(), f.location.as_str(), f.severity))
+            .collect();
+        assert_eq!(findings, vec![
+            ("CVE-2023-38408", "203.0.113.10:22/tcp", Severity::Critical),
+            ("CVE-2021-44228", "203.0.113.10", Severity::Medium),
+        ]);
+        // The key travels in the query string, never as a header
+        assert!(requests.lock().unwrap().iter().all(|r| r.target.contains("key=testkey")
+            && !r.headers.iter().any(|h| h.to_lowercase().starts_with("authorization"))));
+    }
+
+    #[test]
+    fn error_answers_are_explained() {
+        let response = |status: u16, body: Value| ApiResponse { status, body };
+        assert!(shodan_body(response(404, Value::Null)).unwrap().is_none());
+        assert!(shodan_body(response(401, Value::Null)).unwrap_err().to_string().contains("key rejected"));
+        let error = shodan_body(response(403, serde_json::json!({"error": "Access denied"}))).unwrap_err();
+        assert_eq!(error.to_string(), "Shodan API error: 403: Access denied");
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,288 @@
+use console::style;
+use indicatif::ProgressBar;
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+use std::net::IpAddr;
+use std::time::Duration;
+use serde_json::{json, Value};
+
+use super::client::{api_base_url, api_key, ApiAuth, ApiClient, ApiResponse, ApiSettings};
+use crate::models::{DetectionStats, Finding, Severity, VirusTotalInfo, VtFile, VtResolution};
+
+// Overridable with VIRUSTOTAL_API_URL, e.g. to point at a local mock server
+const DEFAULT_API_URL: &str = "https://www.virustotal.com/api/v3";
+
+// The public API allows four requests per minute (tests talk to a local mock server)
+const REQUEST_INTERVAL: Duration = if cfg!(test) { Duration::ZERO } else { Duration::from_secs(15) };
+
+// Relationship items read per lookup (one page each)
+const RESOLUTION_LIMIT: &str = "40";
+const SUBDOMAIN_LIMIT: &str = "40";
+const FILE_LIMIT: &str = "20";
+
+// Parsed VirusTotal data and the findings derived from it
+pub struct VirusTotalResults {
+    pub virustotal: VirusTotalInfo,
+    pub findings: Vec<Finding>,
+}
+
+struct VirusTotalClient {
+    api: ApiClient,
+    base_url: String,
+}
+
+impl VirusTotalClient {
+    fn get(&self, path: &str, params: &[(&str, &str)]) -> Result<Option<Value>, Box<dyn Error>> {
+        virustotal_body(self.api.get(&format!("{}{}", self.base_url, path), params)?)
+    }
+}
+
+// The body of a VirusTotal answer; None when the object is unknown (404)
+fn virustotal_body(response: ApiResponse) -> Result<Option<Value>, Box<dyn Error>> {
+    if response.status == 404 {
+        return Ok(None);
+    }
+    if !response.is_success() {
+        // Errors come as {"error": {"code": ..., "message": ...}}
+        return Err(match (response.status, response.body["error"]["message"].as_str()) {
+            (401, _) => "VirusTotal API key rejected (401 Unauthorized)".into(),
+            (429, _) => "VirusTotal API quota exceeded (429 Too Many Requests)".into(),
+            (status, Some(message)) => format!("VirusTotal API error: {}: {}", status, message).into(),
+            (status, None) => format!("VirusTotal API error: {}", status).into(),
+        });
+    }
+    Ok(Some(response.body))
+}
+
+// Perform VirusTotal lookup with spinner: the IP or domain report, then its passive DNS resolutions,
+// subdomains (domains only) and communicating files
+pub fn perform_virustotal_lookup(spinner: &ProgressBar, target: &str, api: &ApiSettings, base_filename: &str, scan_dir: &str) -> Result<VirusTotalResults, Box<dyn Error>> {
+    spinner.set_message(format!("Connecting to VirusTotal API for {}...", style(target).cyan()));
+    let api_key = api_key(spinner, api, "VirusTotal", "VIRUSTOTAL_API_KEY")?;
+    if target.contains('/') {
+        return Err("VirusTotal lookups need an IP address or a domain, not a range".into());
+    }
+    let virustotal = VirusTotalClient {
+        api: ApiClient::new("VirusTotal", ApiAuth::Header("x-apikey", api_key), REQUEST_INTERVAL, api)?,
+        base_url: api_base_url(api, "VIRUSTOTAL_API_URL", DEFAULT_API_URL),
+    };
+    let is_domain = target.parse::<IpAddr>().is_err();
+    let path = if is_domain {
+        format!("/domains/{}", target.to_lowercase())
+    } else {
+        format!("/ip_addresses/{}", target)
+    };
+    let mut info = VirusTotalInfo {
+        target: target.to_string(),
+        kind: if is_domain { "domain" } else { "ip_address" }.to_string(),
+        ..Default::default()
+    };
+
+    spinner.set_message(format!("Reading VirusTotal report for {}...", style(target).cyan()));
+    let Some(report) = virustotal.get(&path, &[])? else {
+        return Ok(VirusTotalResults { virustotal: info, findings: Vec::new() });
+    };
+    let attributes = &report["data"]["attributes"];
+    info.reputation = attributes["reputation"].as_i64();
+    info.stats = attributes["last_analysis_stats"].is_object().then(|| parse_stats(&attributes["last_analysis_stats"]));
+    info.last_analysis = attributes["last_analysis_date"].as_i64().and_then(format_date);
+    info.as_owner = string(attributes, "as_owner");
+    info.country = string(attributes, "country");
+    info.registrar = string(attributes, "registrar");
+    info.tags = strings(&attributes["tags"]);
+    if let Some(categories) = attributes["categories"].as_object() {
+        info.categories = categories.values().filter_map(|c| c.as_str().map(|s| s.to_string())).collect();
+        info.categories.sort();
+        info.categories.dedup();
+    }
+
+    spinner.set_message(format!("Reading VirusTotal passive DNS for {}...", style(target).cyan()));
+    let resolutions = virustotal.get(&format!("{}/resolutions", path), &[("limit", RESOLUTION_LIMIT)])?;
+    info.resolutions = items(resolutions.as_ref()).iter().filter_map(|item| {
+        let attributes = &item["attributes"];
+        Some(VtResolution {
+            host_name: string(attributes, "host_name")?,
+            ip_address: string(attributes, "ip_address")?,
+            date: attributes["date"].as_i64().and_then(format_date),
+        })
+    }).collect();
+
+    let subdomains = if is_domain {
+        spinner.set_message(format!("Reading VirusTotal subdomains of {}...", style(target).cyan()));
+        virustotal.get(&format!("{}/subdomains", path), &[("limit", SUBDOMAIN_LIMIT)])?
+    } else {
+        None
+    };
+    info.subdomains = items(subdomains.as_ref()).iter().filter_map(|item| string(item, "id")).collect();
+
+    spinner.set_message(format!("Reading VirusTotal communicating files for {}...", style(target).cyan()));
+    let files = virustotal.get(&format!("{}/communicating_files", path), &[("limit", FILE_LIMIT)])?;
+    info.communicating_files = items(files.as_ref()).iter().filter_map(|item| {
+        let attributes = &item["attributes"];
+        Some(VtFile {
+            sha256: string(attributes, "sha256").or_else(|| string(item, "id"))?,
+            name: string(attributes, "meaningful_name"),
+            file_type: string(attributes, "type_description"),
+            stats: parse_stats(&attributes["last_analysis_stats"]),
+        })
+    }).collect();
+
+    // Everything VirusTotal returned, as one document
+    let mut file = File::create(format!("{}/{}_virustotal.json", scan_dir, base_filename))?;
+    file.write_all(serde_json::to_string_pretty(&json!({
+        "report": report,
+        "resolutions": resolutions,
+        "subdomains": subdomains,
+        "communicating_files": files,
+    }))?.as_bytes())?;
+
+    let findings = virustotal_findings(&info);
+    Ok(VirusTotalResults { virustotal: info, findings })
+}
+
+// Engines flagging the target, and malware known to contact it
+pub fn virustotal_findings(virustotal: &VirusTotalInfo) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let reference = format!("https://www.virustotal.com/gui/{}/{}",
+        if virustotal.kind == "domain" { "domain" } else { "ip-address" }, virustotal.target);
+
+    if let Some(stats) = virustotal.stats.as_ref().filter(|s| s.malicious + s.suspicious > 0) {
+        findings.push(Finding {
+            source: "VirusTotal".to_string(),
+            id: Some("virustotal-detections".to_string()),
+            severity: if stats.malicious > 0 { Severity::Medium } else { Severity::Low },
+            location: virustotal.target.clone(),
+            method: None,
+            description: format!("{} of {} VirusTotal engines flag this {} as malicious and {} as suspicious{}",
+                stats.malicious, stats.total(),
+                if virustotal.kind == "domain" { "domain" } else { "address" },
+                stats.suspicious,
+                virustotal.last_analysis.as_deref().map(|d| format!(" (last analysed {})", d)).unwrap_or_default()),
+            references: vec![reference.clone()],
+        });
+    }
+
+    let malware: Vec<&VtFile> = virustotal.communicating_files.iter().filter(|f| f.stats.malicious > 0).collect();
+    if !malware.is_empty() {
+        findings.push(Finding {
+            source: "VirusTotal".to_string(),
+            id: Some("virustotal-communicating-malware".to_string()),
+            severity: Severity::Medium,
+            location: virustotal.target.clone(),
+            method: None,
+            description: format!("{} file(s) detected as malicious contact this target when executed, e.g. {}",
+                malware.len(),
+                malware.iter().take(3).map(|f| f.name.clone().unwrap_or_else(|| f.sha256.clone())).collect::<Vec<_>>().join(", ")),
+            references: vec![reference],
+        });
+    }
+    findings
+}
+
+fn string(value: &Value, key: &str) -> Option<String> {
+    value[key].as_str().filter(|s| !s.is_empty()).map(|s| s.to_string())
+}
+
+fn strings(value: &Value) -> Vec<String> {
+    value.as_array()
+        .map(|items| items.iter().filter_map(|i| i.as_str().map(|s| s.to_string())).collect())
+        .unwrap_or_default()
+}
+
+// Objects of a relationship answer ({"data": [...]})
+fn items(value: Option<&Value>) -> Vec<Value> {
+    value.and_then(|v| v["data"].as_array()).cloned().unwrap_or_default()
+}
+
+fn parse_stats(stats: &Value) -> DetectionStats {
+    let count = |key: &str| stats[key].as_u64().unwrap_or(0);
+    DetectionStats {
+        malicious: count("malicious"),
+        suspicious: count("suspicious"),
+        harmless: count("harmless"),
+        undetected: count("undetected"),
+    }
+}
+
+// VirusTotal dates are Unix timestamps
+fn format_date(timestamp: i64) -> Option<String> {
+    chrono::DateTime::from_timestamp(timestamp, 0).map(|d| d.format("%Y-%m-%d").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::mock;
+    use crate::models::ScanResults;
+
+    fn respond(target: &str) -> (u16, String) {
+        let body = match target {
+            "/domains/example.com" => r#"{"data": {"id": "example.com", "type": "domain", "attributes": {
+                "reputation": -12, "registrar": "Example Registrar, Inc.", "tags": ["dga"],
+                "last_analysis_date": 1760000000,
+                "last_analysis_stats": {"malicious": 2, "suspicious": 1, "harmless": 60, "undetected": 10, "timeout": 0},
+                "categories": {"Forcepoint ThreatSeeker": "phishing", "Sophos": "phishing", "BitDefender": "malware"}}}}"#,
+            "/domains/example.com/resolutions?limit=40" => r#"{"data": [
+                {"id": "198.51.100.7example.com", "attributes": {"host_name": "example.com", "ip_address": "198.51.100.7", "date": 1759000000}},
+                {"id": "broken", "attributes": {"host_name": "example.com"}}]}"#,
+            "/domains/example.com/subdomains?limit=40" => r#"{"data": [
+                {"id": "www.example.com", "type": "domain"}, {"id": "Mail.Example.com.", "type": "domain"},
+                {"id": "example.com.attacker.test", "type": "domain"}, {"id": "", "type": "domain"}]}"#,
+            "/domains/example.com/communicating_files?limit=20" => r#"{"data": [
+                {"id": "a1b2", "attributes": {"sha256": "a1b2", "meaningful_name": "invoice.exe", "type_description": "Win32 EXE",
+                    "last_analysis_stats": {"malicious": 48, "suspicious": 0, "harmless": 0, "undetected": 20}}},
+                {"id": "c3d4", "attributes": {"last_analysis_stats": {"malicious": 0, "undetected": 70}}}]}"#,
+            _ => return (404, r#"{"error": {"code": "NotFoundError", "message": "not found"}}"#.to_string()),
+        };
+        (200, body.to_string())
+    }
+
+    #[test]
+    fn domain_lookup_against_mock_server() {
+        let (base_url, requests) = mock::serve(respond);
+        let overrides = [("VIRUSTOTAL_API_URL", base_url), ("VIRUSTOTAL_API_KEY", "testkey".to_string())]
+            .into_iter().map(|(name, value)| (name.to_string(), value)).collect();
+        let api = ApiSettings { overrides, ..Default::default() };
+        let scan_dir = mock::scan_dir("virustotal");
+
+        let results = perform_virustotal_lookup(&ProgressBar::hidden(), "Example.com", &api, "test", &scan_dir).unwrap();
+        let info = &results.virustotal;
+        assert_eq!((info.kind.as_str(), info.reputation), ("domain", Some(-12)));
+        assert_eq!(info.stats.as_ref().map(|s| (s.malicious, s.suspicious, s.total())), Some((2, 1, 73)));
+        assert_eq!(info.last_analysis.as_deref(), Some("2025-10-09"));
+        assert_eq!(info.categories, vec!["malware", "phishing"]);
+        // Relationship items missing a required field are skipped
+        assert_eq!(info.resolutions.len(), 1);
+        assert_eq!((info.resolutions[0].ip_address.as_str(), info.resolutions[0].date.as_deref()), ("198.51.100.7", Some("2025-09-27")));
+        assert_eq!(info.subdomains, vec!["www.example.com", "Mail.Example.com.", "example.com.attacker.test"]);
+        let files: Vec<(&str, Option<&str>, u64)> = info.communicating_files.iter()
+            .map(|f| (f.sha256.as_str(), f.name.as_deref(), f.stats.malicious))
+            .collect();
+        assert_eq!(files, vec![("a1b2", Some("invoice.exe"), 48), ("c3d4", None, 0)]);
+
+        let findings: Vec<(&str, Severity)> = results.findings.iter().map(|f| (f.id.as_deref().unwrap(), f.severity)).collect();
+        assert_eq!(findings, vec![("virustotal-detections", Severity::Medium), ("virustotal-communicating-malware", Severity::Medium)]);
+        assert_eq!(results.findings[1].description, "1 file(s) detected as malicious contact this target when executed, e.g. invoice.exe");
+        assert!(requests.lock().unwrap().iter().all(|r| r.headers.iter().any(|h| h.eq_ignore_ascii_case("x-apikey: testkey"))));
+
+        // Only names inside the looked-up domain reach the DNS inventory
+        let mut scan = ScanResults::default();
+        scan.merge_virustotal(results.virustotal);
+        assert_eq!(scan.dns.subdomains.keys().collect::<Vec<_>>(), vec!["mail.example.com", "www.example.com"]);
+        assert!(scan.dns.subdomains.values().all(|s| s.sources.contains("VirusTotal")));
+    }
+
+    #[test]
+    fn unknown_addresses_stop_after_the_report() {
+        let (base_url, requests) = mock::serve(respond);
+        let overrides = [("VIRUSTOTAL_API_URL", base_url), ("VIRUSTOTAL_API_KEY", "testkey".to_string())]
+            .into_iter().map(|(name, value)| (name.to_string(), value)).collect();
+        let api = ApiSettings { overrides, ..Default::default() };
+
+        let results = perform_virustotal_lookup(&ProgressBar::hidden(), "198.51.100.7", &api, "test", &mock::scan_dir("virustotal_ip")).unwrap();
+        assert_eq!(results.virustotal.kind, "ip_address");
+        assert!(results.virustotal.stats.is_none() && results.findings.is_empty());
+        assert_eq!(requests.lock().unwrap().iter().map(|r| r.target.as_str()).collect::<Vec<_>>(), vec!["/ip_addresses/198.51.100.7"]);
+    }
+}
@@ -1,8 +1,10 @@
 use clap::{Arg, App, ArgMatches};
 
+use crate::api::PASSIVE_DNS_PROVIDERS;
+
 pub fn parse_args() -> ArgMatches<'static> {
     // Define command-line arguments for normal operation
-    let matches = App::new("RustRecon")
+    App::new("RustRecon")
         .version("1.8")
         .author("Ethical Hacker")
         .about("Network reconnaissance tool combining NMAP, Shodan, WHOIS, and more")
@@ -29,7 +31,49 @@ pub fn parse_args() -> ArgMatches<'static> {
             .help("Perform WHOIS lookup to get domain registration information"))
         .arg(Arg::with_name("shodan")
             .long("shodan")
-            .help("Query Shodan API for target information (requires SHODAN_API_KEY env variable)"))
+            .help("Query Shodan API for target information (requires SHODAN_API_KEY env variable; SHODAN_API_URL overrides the API base URL)"))
+        .arg(Arg::with_name("shodan-query")
+            .long("shodan-query")
+            .value_name("QUERY")
+            .help("Also run a Shodan search, e.g. 'org:\"Example Corp\" port:3389' (ranges are searched with net:<range> by default)")
+            .requires("shodan")
+            .takes_value(true))
+        .arg(Arg::with_name("shodan-facets")
+            .long("shodan-facets")
+            .value_name("FACETS")
+            .help("Comma-separated facets to summarize Shodan search results by (e.g. 'port,product,org:5')")
+            .requires("shodan")
+            .takes_value(true))
+        .arg(Arg::with_name("censys")
+            .long("censys")
+            .help("Query the Censys Search API for hosts, services and certificates (requires CENSYS_API_ID and CENSYS_API_SECRET, or ~/.config/censys/censys.cfg; CENSYS_API_URL overrides the API base URL)"))
+        .arg(Arg::with_name("virustotal")
+            .long("virustotal")
+            .help("Query VirusTotal for detection stats, passive DNS, subdomains and communicating files of an IP or domain (requires VIRUSTOTAL_API_KEY; VIRUSTOTAL_API_URL overrides the API base URL)"))
+        .arg(Arg::with_name("greynoise")
+            .long("greynoise")
+            .help("Ask GreyNoise whether an IP is an internet scanner or a known business service (requires GREYNOISE_API_KEY; GREYNOISE_API_URL overrides the API base URL)"))
+        .arg(Arg::with_name("greynoise-plan")
+            .long("greynoise-plan")
+            .value_name("PLAN")
+            .help("GreyNoise API to use: community (classification only, the default) or enterprise (context, tags, CVEs and RIOT)")
+            .possible_values(&["community", "enterprise"])
+            .requires("greynoise")
+            .takes_value(true))
+        .arg(Arg::with_name("abuseipdb")
+            .long("abuseipdb")
+            .help("Check an IP's abuse confidence score and recent reports on AbuseIPDB (requires ABUSEIPDB_API_KEY; ABUSEIPDB_API_URL overrides the API base URL)"))
+        .arg(Arg::with_name("api-cache-ttl")
+            .long("api-cache-ttl")
+            .value_name("HOURS")
+            .help("Reuse Shodan and other intelligence API answers for this many hours (0 disables the cache)")
+            .default_value("24")
+            .takes_value(true))
+        .arg(Arg::with_name("api-cache-dir")
+            .long("api-cache-dir")
+            .value_name("DIR")
+            .help("Directory for cached API answers (default: ~/.cache/rust_recon/api)")
+            .takes_value(true))
         .arg(Arg::with_name("nslookup")
             .long("nslookup")
             .help("Perform DNS lookup using nslookup tool"))
@@ -79,12 +123,123 @@ pub fn parse_args() -> ArgMatches<'static> {
         .arg(Arg::with_name("enum4linux")
             .long("enum4linux")
             .help("Enumerate Windows/Samba hosts using enum4linux"))
+        .arg(Arg::with_name("enum4linux-ng")
+            .long("enum4linux-ng")
+            .help("Enumerate Windows/Samba hosts using enum4linux-ng (parsed from its JSON export)"))
+        .arg(Arg::with_name("smb")
+            .long("smb")
+            .help("Enumerate SMB natively: dialects, signing, SMBv1, NTLM host info and anonymous shares"))
+        .arg(Arg::with_name("snmp")
+            .long("snmp")
+            .help("Enumerate SNMP: community and SNMPv3 user guessing, then walk system, interface, route, process and software MIBs (runs automatically when NMAP finds UDP/161 open)"))
+        .arg(Arg::with_name("snmp-communities")
+            .long("snmp-communities")
+            .value_name("FILE")
+            .help("Community strings to try, one per line (default: bundled list of common communities)")
+            .takes_value(true))
+        .arg(Arg::with_name("snmp-users")
+            .long("snmp-users")
+            .value_name("FILE")
+            .help("SNMPv3 user names to try, one per line (default: bundled list)")
+            .takes_value(true))
+        .arg(Arg::with_name("snmp-timeout")
+            .long("snmp-timeout")
+            .value_name("SECONDS")
+            .help("Seconds to wait for SNMP replies")
+            .default_value("2")
+            .takes_value(true))
+        .arg(Arg::with_name("ldap")
+            .long("ldap")
+            .help("Enumerate LDAP/Active Directory: RootDSE, anonymous bind, users, groups, computers and password policy (runs automatically when NMAP finds 389/636/3268 open)"))
+        .arg(Arg::with_name("ldap-user")
+            .long("ldap-user")
+            .value_name("USER")
+            .help("Bind as this user for --ldap (DN, user@domain or DOMAIN\\user)")
+            .requires("ldap-password")
+            .takes_value(true))
+        .arg(Arg::with_name("ldap-password")
+            .long("ldap-password")
+            .value_name("PASSWORD")
+            .help("Password for --ldap-user")
+            .requires("ldap-user")
+            .takes_value(true))
         .arg(Arg::with_name("whatweb")
             .long("whatweb")
             .help("Identify web technologies using WhatWeb"))
+        .arg(Arg::with_name("fingerprint")
+            .long("fingerprint")
+            .help("Identify web technologies with the built-in fingerprinting engine (no WhatWeb required)"))
+        .arg(Arg::with_name("signatures")
+            .long("signatures")
+            .value_name("FILE")
+            .help("Wappalyzer-style signature file for --fingerprint (defaults to the bundled web_fingerprints.json)")
+            .takes_value(true))
         .arg(Arg::with_name("dnsrecon")
             .long("dnsrecon")
             .help("Perform DNS enumeration using dnsrecon"))
+        .arg(Arg::with_name("axfr")
+            .long("axfr")
+            .help("Test every authoritative name server for AXFR/IXFR zone transfers"))
+        .arg(Arg::with_name("axfr-servers")
+            .long("axfr-servers")
+            .value_name("SERVERS")
+            .help("Comma-separated name servers for --axfr instead of the domain's NS records (e.g. 192.0.2.1,192.0.2.2:5353)")
+            .takes_value(true))
+        .arg(Arg::with_name("email-security")
+            .long("email-security")
+            .help("Analyse SPF, DMARC, DKIM, MTA-STS and TLS-RPT records for email spoofing weaknesses"))
+        .arg(Arg::with_name("dnssec")
+            .long("dnssec")
+            .help("Validate the DNSSEC chain of trust and check for expiring signatures and NSEC zone walking"))
+        .arg(Arg::with_name("rdns")
+            .long("rdns")
+            .help("Reverse DNS (PTR) sweep of the hosts found by NMAP, or of every address in a CIDR target"))
+        .arg(Arg::with_name("passive-dns")
+            .long("passive-dns")
+            .value_name("PROVIDER")
+            .help("Historical A records, subdomains and associated domains from a passive DNS provider (needs SECURITYTRAILS_API_KEY)")
+            .possible_values(PASSIVE_DNS_PROVIDERS)
+            .takes_value(true))
+        .arg(Arg::with_name("ct")
+            .long("ct")
+            .help("Discover subdomains passively from Certificate Transparency logs (crt.sh, or the endpoint in CT_API_URL)"))
+        .arg(Arg::with_name("subdomains")
+            .long("subdomains")
+            .help("Brute-force subdomains natively with wildcard detection and permutations"))
+        .arg(Arg::with_name("subdomain-wordlist")
+            .long("subdomain-wordlist")
+            .value_name("WORDLIST")
+            .help("Wordlist for --subdomains (defaults to the bundled subdomains.txt)")
+            .takes_value(true))
+        .arg(Arg::with_name("no-permutations")
+            .long("no-permutations")
+            .help("Skip permutation generation during --subdomains"))
+        .arg(Arg::with_name("resolvers")
+            .long("resolvers")
+            .value_name("RESOLVERS")
+            .help("Comma-separated DNS resolvers for the native DNS modules (e.g., '1.1.1.1,8.8.8.8,127.0.0.1:5353')")
+            .takes_value(true))
+        .arg(Arg::with_name("dns-rate")
+            .long("dns-rate")
+            .value_name("QPS")
+            .help("Maximum DNS queries per second for the native DNS modules (0 = unlimited)")
+            .default_value("100")
+            .takes_value(true))
+        .arg(Arg::with_name("dns-threads")
+            .long("dns-threads")
+            .value_name("THREADS")
+            .help("Number of concurrent DNS workers for the native DNS modules")
+            .default_value("20")
+            .takes_value(true))
+        .arg(Arg::with_name("geoip")
+            .long("geoip")
+            .help("Annotate hosts and traceroute hops with country, city, ASN and organisation from local GeoLite2/IP2ASN databases (no network calls)"))
+        .arg(Arg::with_name("geoip-db")
+            .long("geoip-db")
+            .value_name("FILES")
+            .help("Comma-separated .mmdb (GeoLite2 City/Country/ASN) or IP2ASN .tsv files (default: search ~/.local/share/GeoIP, /usr/share/GeoIP and /var/lib/GeoIP)")
+            .requires("geoip")
+            .takes_value(true))
         .arg(Arg::with_name("traceroute")
             .long("traceroute")
             .help("Perform network path discovery using traceroute"))
@@ -93,8 +248,35 @@ pub fn parse_args() -> ArgMatches<'static> {
             .help("Check SSL/TLS configuration using OpenSSL"))
         .arg(Arg::with_name("netdiscover")
             .long("netdiscover")
-            .help("Discover active hosts on network using netdiscover (requires target in CIDR format e.g., 192.168.1.0/24)"))
-        .get_matches();
-    
-    matches
+            .help("Discover active hosts on network using netdiscover (target in CIDR format e.g., 192.168.1.0/24, or an IP on a directly connected network)"))
+        .arg(Arg::with_name("pipeline")
+            .long("pipeline")
+            .help("Discover live hosts in the target range first, then run the selected tools against each host in its own directory"))
+        .arg(Arg::with_name("discover")
+            .long("discover")
+            .help("Built-in host discovery of the target IP/CIDR (ARP on local segments, ICMP echo, TCP ping) with MAC vendor lookup"))
+        .arg(Arg::with_name("discovery-methods")
+            .long("discovery-methods")
+            .value_name("METHODS")
+            .help("Comma separated discovery methods: arp, icmp, tcp")
+            .default_value("arp,icmp,tcp")
+            .takes_value(true))
+        .arg(Arg::with_name("discovery-ports")
+            .long("discovery-ports")
+            .value_name("PORTS")
+            .help("Comma separated TCP ports used for the TCP ping sweep")
+            .default_value("22,80,443,445,3389")
+            .takes_value(true))
+        .arg(Arg::with_name("discovery-threads")
+            .long("discovery-threads")
+            .value_name("THREADS")
+            .help("Number of concurrent workers for the TCP ping sweep")
+            .default_value("64")
+            .takes_value(true))
+        .arg(Arg::with_name("oui-db")
+            .long("oui-db")
+            .value_name("FILE")
+            .help("MAC vendor database in nmap-mac-prefixes format, added to the embedded list")
+            .takes_value(true))
+        .get_matches()
 }
\ No newline at end of file
@@ -20,6 +20,16 @@ pub fn print_examples() {
     println!("\n5️⃣  Full Reconnaissance with All Tools:");
     println!("    ./rust_recon -i 8.8.8.8 --comprehensive --scripts \"http,vuln\" --whois --shodan --nslookup --dig");
     println!("    Complete scan with HTTP and vulnerability scripts, plus all external tools");
+    println!("    ./rust_recon -i example.com --shodan --shodan-query 'ssl.cert.subject.cn:example.com' --shodan-facets 'port,product'");
+    println!("    Shodan DNS data, host services and CVEs for the resolved address, plus a faceted search");
+    println!("    ./rust_recon -i 203.0.113.10 --quick --shodan");
+    println!("    Compare what Shodan indexed with what NMAP sees now: confirmed, no longer open, newly open and not scanned ports");
+    println!("    ./rust_recon -i example.com --shodan --censys");
+    println!("    Combine Shodan and Censys: hosts behind the domain, their services and software, and its certificates");
+    println!("    ./rust_recon -i example.com --virustotal");
+    println!("    VirusTotal reputation, passive DNS, subdomains (added to the DNS asset inventory) and communicating files");
+    println!("    ./rust_recon -i 198.51.100.7 --greynoise --greynoise-plan enterprise --abuseipdb");
+    println!("    Check whether an address is a noisy internet scanner, a known business service or a reported abuser");
     
     println!("\n6️⃣  Proxied Scan through Tor:");
     println!("    ./rust_recon -i target.com --noisey --proxy \"socks5://127.0.0.1:9050\"");
@@ -29,13 +39,52 @@ pub fn print_examples() {
     println!("    ./rust_recon -i example.com --quick --gobuster --gobuster-wordlist \"/usr/share/wordlists/dirb/common.txt\" --nikto --whatweb");
     println!("    Web-focused scan that identifies technologies, directories, and vulnerabilities");
     
+    println!("\n    Without WhatWeb installed, use the built-in fingerprinting engine instead:");
+    println!("    ./rust_recon -i example.com --fingerprint --signatures ./web_fingerprints.json");
+    
     println!("\n8️⃣  Windows/SMB Enumeration:");
     println!("    ./rust_recon -i 192.168.1.100 --quick --enum4linux");
     println!("    Targeted scan for Windows/Samba hosts to enumerate users, shares, and policies");
+    println!("    ./rust_recon -i 192.168.1.100 --enum4linux-ng");
+    println!("    Same enumeration with enum4linux-ng; anonymous share access and weak policies are reported as findings");
+    println!("    ./rust_recon -i 192.168.1.100 --smb");
+    println!("    Built-in SMB client (no external tools): dialects, signing, SMBv1, NTLM host/domain names and anonymous shares");
+    println!("    ./rust_recon -i 192.168.1.1 --quick --quick-options \"-sU -sS -p U:161,T:22,80,443\"");
+    println!("    SNMP is enumerated automatically when NMAP finds UDP/161 open; --snmp forces it");
+    println!("    ./rust_recon -i 192.168.1.1 --snmp --snmp-communities communities.txt --snmp-users users.txt");
+    println!("    Guess communities and SNMPv3 users from custom lists, then walk system, interfaces, routes, processes and software");
+    println!("    ./rust_recon -i 10.0.0.10 --ldap");
+    println!("    Read the RootDSE, test anonymous bind and dump users, groups, computers and the domain password policy");
+    println!("    ./rust_recon -i 10.0.0.10 --ldap --ldap-user 'CORP\\jdoe' --ldap-password 'Summer2024!'");
+    println!("    Authenticated directory dump; flags AS-REP roastable, Kerberoastable and passwordless accounts");
     
     println!("\n9️⃣  Domain Reconnaissance:");
     println!("    ./rust_recon -i example.com --dnsrecon --dig --ssl-check");
     println!("    DNS and certificate analysis for a domain");
+    println!("    ./rust_recon -i example.com --subdomains --resolvers \"1.1.1.1,8.8.8.8\" --dns-rate 50");
+    println!("    Native subdomain brute force with wildcard filtering (no dnsrecon needed)");
+    println!("    ./rust_recon -i example.com --ct --subdomains");
+    println!("    Seed the brute force with names from Certificate Transparency logs (first/last seen from the certificates)");
+    println!("    ./rust_recon -i example.com --passive-dns securitytrails --subdomains");
+    println!("    Historical A records, old subdomains and associated domains from SecurityTrails, then brute force around them");
+    println!("    ./rust_recon -i example.com --axfr --subdomains");
+    println!("    Test every name server for AXFR/IXFR and seed permutations with any leaked zone");
+    println!("    ./rust_recon -i example.com --email-security");
+    println!("    Check SPF (with include lookup counting), DMARC, DKIM, MTA-STS and TLS-RPT for spoofing risks");
+    println!("    ./rust_recon -i example.com --dnssec");
+    println!("    Validate the DNSSEC chain of trust from the root and test whether NSEC allows zone walking");
+    println!("    ./rust_recon -i 192.168.1.0/24 --discover --rdns --resolvers 192.168.1.1");
+    println!("    Find live hosts with ARP/ICMP/TCP ping (no netdiscover needed) and name them via the internal DNS server");
+    println!("    ./rust_recon -i 192.168.1.0/24 --pipeline --quick --fingerprint --ssl-check");
+    println!("    Discover live hosts, then scan each one into its own directory with a network summary linking them");
+    println!("    ./rust_recon -i 10.0.0.0/24 --discover --discovery-methods tcp --discovery-ports 22,443,3389");
+    println!("    TCP-only discovery for networks that drop ICMP and aren't on the local segment");
+    println!("    ./rust_recon -i 192.168.1.0/24 --quick --rdns --resolvers 192.168.1.1");
+    println!("    Name every live host found by NMAP using the internal DNS server");
+    println!("    ./rust_recon -i example.com --traceroute");
+    println!("    Trace the path hop by hop (addresses, names, AS numbers, RTTs) and export it as a topology graph");
+    println!("    ./rust_recon -i example.com --traceroute --geoip --geoip-db GeoLite2-City.mmdb,GeoLite2-ASN.mmdb");
+    println!("    Add country, city, ASN and organisation to every host and hop from local databases (no network calls)");
     
     println!("\n🔟  Complete Scan with Organization:");
     println!("    ./rust_recon -i target.com --comprehensive --scripts \"default,safe\" --shodan --whois --whatweb --nikto --ssl-check");
@@ -44,7 +93,15 @@ pub fn print_examples() {
     println!("\n📝 NOTES:");
     println!("  - Each scan is automatically saved in its own timestamped directory");
     println!("  - A SCAN_SUMMARY.md file is created in each scan directory with details");
+    println!("  - Parsed results and findings are written to SCAN_RESULTS.json and REPORT.html");
+    println!("  - Traceroute topology is exported to TOPOLOGY.dot (and TOPOLOGY.svg when Graphviz is installed)");
     println!("  - For Shodan lookups, set your API key: export SHODAN_API_KEY=your_api_key");
+    println!("  - SHODAN_API_URL points Shodan lookups at another base URL (e.g. a local mock server)");
+    println!("  - For Censys lookups, set CENSYS_API_ID and CENSYS_API_SECRET (or use ~/.config/censys/censys.cfg)");
+    println!("  - For VirusTotal lookups, set your API key: export VIRUSTOTAL_API_KEY=your_api_key");
+    println!("  - For SecurityTrails passive DNS, set your API key: export SECURITYTRAILS_API_KEY=your_api_key");
+    println!("  - For GreyNoise and AbuseIPDB, set GREYNOISE_API_KEY and ABUSEIPDB_API_KEY");
+    println!("  - API answers are cached for 24 hours in ~/.cache/rust_recon/api; --api-cache-ttl 0 forces fresh lookups");
     println!("  - HTML reports are generated automatically when xsltproc is available");
     println!("  - You can combine any scan types and tools based on your needs");
 }
\ No newline at end of file
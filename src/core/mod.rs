@@ -1,3 +1,4 @@
 pub mod orchestrator;
+pub mod pipeline;
 
 pub use orchestrator::*;
\ No newline at end of file
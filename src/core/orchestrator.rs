@@ -3,18 +3,34 @@ use console::style;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use std::error::Error;
 use std::fs;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::time::Duration;
 
 use crate::ui::progress::create_spinner;
-use crate::scanners::nmap::{run_nmap_scan, convert_nmap_to_html};
-use crate::scanners::dns::{perform_whois, perform_nslookup, perform_dig, perform_dnsrecon};
-use crate::scanners::web::{perform_gobuster, perform_nikto, perform_whatweb, perform_ssl_check};
-use crate::scanners::host::perform_enum4linux;
-use crate::scanners::network::{perform_traceroute, perform_netdiscover};
-use crate::api::perform_shodan_lookup;
-use crate::report::create_scan_summary;
+use crate::scanners::nmap::{run_nmap_scan, convert_nmap_to_html, parse_nmap_xml};
+use crate::scanners::dns::{perform_whois, perform_nslookup, perform_dig, perform_dnsrecon, perform_subdomain_enum, perform_zone_transfer, perform_email_security, perform_dnssec, perform_reverse_dns, perform_ct_lookup, SubdomainOptions};
+use crate::scanners::dns::resolver::parse_resolvers;
+use crate::scanners::web::{perform_gobuster, perform_nikto, perform_whatweb, perform_ssl_check, perform_fingerprint};
+use crate::scanners::host::{perform_enum4linux, perform_enum4linux_ng, perform_smb_enum, perform_snmp_enum, perform_ldap_enum, SnmpOptions, LdapOptions, LDAP_PORTS};
+use crate::scanners::network::{perform_traceroute, perform_netdiscover, perform_discovery, parse_discovery_methods, DiscoveryOptions};
+use crate::api::{perform_shodan_lookup, perform_censys_lookup, perform_virustotal_lookup, perform_greynoise_lookup, perform_abuseipdb_check, perform_passive_dns, passive_dns_provider, ApiSettings, ShodanOptions};
+use crate::models::ScanResults;
+use crate::utils::geoip::GeoDatabase;
+use crate::utils::net::{expand_targets, local_networks};
+use crate::report::{create_scan_summary, write_consolidated_report, write_html_report, write_topology_graph, TopologyFiles};
+use crate::core::pipeline::run_pipeline;
 
 // Core function to orchestrate and run all selected scans
 pub fn run_scans(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    // Network ranges can be discovered first and each live host scanned on its own
+    if matches.is_present("pipeline") {
+        return run_pipeline(matches);
+    }
+    
+    // Parse every flag value up front so a typo fails before any tool has run
+    let options = scan_options(matches)?;
+    
     // Create output directory if it doesn't exist
     let output_dir = matches.value_of("output-dir").unwrap_or("./recon_results");
     fs::create_dir_all(output_dir)?;
@@ -54,18 +70,61 @@ pub fn run_scans(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
     // Base filename (without path) - replace problematic characters
     let base_filename = target_ip.replace(".", "_").replace("/", "_");
     
+    // Parsed results collected from every tool
+    let mut results = ScanResults::new(target_ip);
+    run_target_scans(&mp, matches, &options, target_ip, &base_filename, &scan_dir, &mut results)?;
+    
+    // Complete the overall progress
+    overall_spinner.finish_with_message(format!("{} All reconnaissance tasks completed!", style("✓").green().bold()));
+    
+    // Write consolidated report and scan summary
+    let topology = write_reports(matches, target_ip, &scan_dir, &results)?;
+    
+    println!("\n{} All reconnaissance tasks completed!", style("✓").green().bold());
+    println!("📁 Results saved in: {}", style(&scan_dir).green());
+    println!("📝 Scan summary created: {}/SCAN_SUMMARY.md", style(&scan_dir).green());
+    println!("📊 Consolidated report created: {}/SCAN_RESULTS.json", style(&scan_dir).green());
+    println!("🌐 HTML report created: {}/REPORT.html", style(&scan_dir).green());
+    if let Some(topology) = &topology {
+        println!("🗺️  Topology graph created: {}", style(topology.svg.as_ref().unwrap_or(&topology.dot)).green());
+    }
+    
+    Ok(())
+}
+
+// Run every selected tool against one target, collecting parsed output into `results`
+pub fn run_target_scans(mp: &MultiProgress, matches: &ArgMatches, options: &ScanOptions, target_ip: &str, base_filename: &str, scan_dir: &str, results: &mut ScanResults) -> Result<(), Box<dyn Error>> {
+    // Built-in host discovery
+    if options.network_tools && matches.is_present("discover") {
+        let discovery_spinner = mp.add(create_spinner(&format!("Discovering live hosts in {}...", style(target_ip).cyan())));
+        let discovery = expand_targets(target_ip)
+            .and_then(|targets| perform_discovery(&discovery_spinner, &targets, &options.discovery, base_filename, scan_dir));
+        match discovery {
+            Ok(hosts) => {
+                discovery_spinner.finish_with_message(format!("{} Host discovery completed ({} hosts up)", style("✓").green(), hosts.len()));
+                for host in hosts {
+                    results.merge_host(host);
+                }
+                results.record_raw_output("Host Discovery", &format!("{}_discovery.txt", base_filename));
+            },
+            Err(e) => {
+                discovery_spinner.finish_with_message(format!("{} Host discovery failed: {}", style("⚠").yellow(), e));
+            }
+        }
+    }
+    
     // NMAP scanning
     if matches.is_present("comprehensive") || matches.is_present("quick") || 
        matches.is_present("noisey") || matches.is_present("stealthy") || 
        matches.is_present("firewall-bypass") {
         
         // Run the appropriate NMAP scan
-        let nmap_result = run_nmap_scan(&mp, &matches, target_ip, &base_filename, &scan_dir)?;
+        let nmap_result = run_nmap_scan(mp, matches, target_ip, base_filename, scan_dir)?;
         
         // Convert NMAP XML to HTML if scan was successful
         if nmap_result.status.success() {
-            let html_spinner = mp.add(create_spinner(&format!("Converting NMAP results to HTML...")));
-            match convert_nmap_to_html(&base_filename, &scan_dir) {
+            let html_spinner = mp.add(create_spinner("Converting NMAP results to HTML..."));
+            match convert_nmap_to_html(base_filename, scan_dir) {
                 Ok(_) => {
                     html_spinner.finish_with_message(format!("{} NMAP results converted to HTML", style("✓").green()));
                 },
@@ -74,12 +133,27 @@ pub fn run_scans(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
                 }
             }
         }
+        
+        // Parse hosts, ports and services into the scan model
+        let parse_spinner = mp.add(create_spinner("Parsing NMAP results..."));
+        match parse_nmap_xml(&format!("{}/{}_nmap.xml", scan_dir, base_filename)) {
+            Ok(hosts) => {
+                parse_spinner.finish_with_message(format!("{} NMAP results parsed ({} hosts)", style("✓").green(), hosts.len()));
+                for host in hosts {
+                    results.merge_host(host);
+                }
+                results.record_raw_output("Nmap", &format!("{}_nmap.xml", base_filename));
+            },
+            Err(e) => {
+                parse_spinner.finish_with_message(format!("{} Parsing NMAP results failed: {}", style("⚠").yellow(), e));
+            }
+        }
     }
     
     // WHOIS lookup
     if matches.is_present("whois") {
         let whois_spinner = mp.add(create_spinner(&format!("Performing WHOIS lookup on {}...", style(target_ip).cyan())));
-        match perform_whois(&whois_spinner, target_ip, &base_filename, &scan_dir) {
+        match perform_whois(&whois_spinner, target_ip, base_filename, scan_dir) {
             Ok(_) => {
                 whois_spinner.finish_with_message(format!("{} WHOIS lookup completed", style("✓").green()));
             },
@@ -92,9 +166,29 @@ pub fn run_scans(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
     // Shodan lookup
     if matches.is_present("shodan") {
         let shodan_spinner = mp.add(create_spinner(&format!("Querying Shodan for {}...", style(target_ip).cyan())));
-        match perform_shodan_lookup(&shodan_spinner, target_ip, &base_filename, &scan_dir) {
-            Ok(_) => {
-                shodan_spinner.finish_with_message(format!("{} Shodan query completed", style("✓").green()));
+        let options = ShodanOptions {
+            query: matches.value_of("shodan-query").map(|q| q.to_string()),
+            facets: matches.value_of("shodan-facets").map(|f| f.to_string()),
+            api: options.api.clone(),
+        };
+        match perform_shodan_lookup(&shodan_spinner, target_ip, &options, base_filename, scan_dir) {
+            Ok(lookup) => {
+                let shodan = lookup.shodan;
+                let (services, vulns) = shodan.host.as_ref().map(|h| (h.services.len(), h.vulns.len())).unwrap_or((0, 0));
+                shodan_spinner.finish_with_message(format!("{} Shodan query completed ({} services, {} vulnerabilities{})",
+                    style("✓").green(), services, vulns,
+                    shodan.search.as_ref().map(|s| format!(", {} search results", s.total)).unwrap_or_default()));
+                if shodan.host.is_some() {
+                    results.record_raw_output("Shodan", &format!("{}_shodan.json", base_filename));
+                }
+                if shodan.domain.is_some() {
+                    results.record_raw_output("Shodan DNS", &format!("{}_shodan_dns.json", base_filename));
+                }
+                if shodan.search.is_some() {
+                    results.record_raw_output("Shodan Search", &format!("{}_shodan_search.json", base_filename));
+                }
+                results.merge_shodan(shodan);
+                results.add_findings(lookup.findings);
             },
             Err(e) => {
                 shodan_spinner.finish_with_message(format!("{} Shodan query failed: {}", style("⚠").yellow(), e));
@@ -102,10 +196,97 @@ pub fn run_scans(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
         }
     }
     
+    // Censys lookup
+    if matches.is_present("censys") {
+        let censys_spinner = mp.add(create_spinner(&format!("Querying Censys for {}...", style(target_ip).cyan())));
+        match perform_censys_lookup(&censys_spinner, target_ip, &options.api, base_filename, scan_dir) {
+            Ok(censys) => {
+                censys_spinner.finish_with_message(format!("{} Censys query completed ({} hosts, {} services, {} certificates)",
+                    style("✓").green(), censys.hosts.len(),
+                    censys.hosts.iter().map(|h| h.services.len()).sum::<usize>(),
+                    censys.certificates.len()));
+                if !censys.hosts.is_empty() {
+                    results.record_raw_output("Censys", &format!("{}_censys.json", base_filename));
+                }
+                if censys.query.is_some() && !censys.certificates.is_empty() {
+                    results.record_raw_output("Censys Certificates", &format!("{}_censys_certificates.json", base_filename));
+                }
+                results.merge_censys(censys);
+            },
+            Err(e) => {
+                censys_spinner.finish_with_message(format!("{} Censys query failed: {}", style("⚠").yellow(), e));
+            }
+        }
+    }
+    
+    // VirusTotal lookup
+    if matches.is_present("virustotal") {
+        let virustotal_spinner = mp.add(create_spinner(&format!("Querying VirusTotal for {}...", style(target_ip).cyan())));
+        match perform_virustotal_lookup(&virustotal_spinner, target_ip, &options.api, base_filename, scan_dir) {
+            Ok(lookup) => {
+                let virustotal = lookup.virustotal;
+                virustotal_spinner.finish_with_message(format!("{} VirusTotal query completed ({} malicious detections, {} resolutions, {} subdomains, {} communicating files)",
+                    style("✓").green(),
+                    virustotal.stats.as_ref().map(|s| s.malicious).unwrap_or(0),
+                    virustotal.resolutions.len(), virustotal.subdomains.len(), virustotal.communicating_files.len()));
+                if virustotal.stats.is_some() {
+                    results.record_raw_output("VirusTotal", &format!("{}_virustotal.json", base_filename));
+                }
+                results.merge_virustotal(virustotal);
+                results.add_findings(lookup.findings);
+            },
+            Err(e) => {
+                virustotal_spinner.finish_with_message(format!("{} VirusTotal query failed: {}", style("⚠").yellow(), e));
+            }
+        }
+    }
+    
+    // GreyNoise lookup
+    if matches.is_present("greynoise") {
+        let greynoise_spinner = mp.add(create_spinner(&format!("Querying GreyNoise for {}...", style(target_ip).cyan())));
+        let plan = matches.value_of("greynoise-plan").unwrap_or("community");
+        match perform_greynoise_lookup(&greynoise_spinner, target_ip, plan, &options.api, base_filename, scan_dir) {
+            Ok(lookup) => {
+                let greynoise = lookup.greynoise;
+                greynoise_spinner.finish_with_message(format!("{} GreyNoise query completed ({})",
+                    style("✓").green(),
+                    match (greynoise.noise, greynoise.riot) {
+                        (_, true) => "known business service".to_string(),
+                        (true, _) => format!("{} scanner", greynoise.classification.as_deref().unwrap_or("unknown")),
+                        _ => "not observed scanning".to_string(),
+                    }));
+                results.record_raw_output("GreyNoise", &format!("{}_greynoise.json", base_filename));
+                results.greynoise.push(greynoise);
+                results.add_findings(lookup.findings);
+            },
+            Err(e) => {
+                greynoise_spinner.finish_with_message(format!("{} GreyNoise query failed: {}", style("⚠").yellow(), e));
+            }
+        }
+    }
+    
+    // AbuseIPDB check
+    if matches.is_present("abuseipdb") {
+        let abuseipdb_spinner = mp.add(create_spinner(&format!("Checking {} on AbuseIPDB...", style(target_ip).cyan())));
+        match perform_abuseipdb_check(&abuseipdb_spinner, target_ip, &options.api, base_filename, scan_dir) {
+            Ok(check) => {
+                let abuseipdb = check.abuseipdb;
+                abuseipdb_spinner.finish_with_message(format!("{} AbuseIPDB check completed (abuse confidence {}%, {} reports)",
+                    style("✓").green(), abuseipdb.confidence, abuseipdb.total_reports));
+                results.record_raw_output("AbuseIPDB", &format!("{}_abuseipdb.json", base_filename));
+                results.abuseipdb.push(abuseipdb);
+                results.add_findings(check.findings);
+            },
+            Err(e) => {
+                abuseipdb_spinner.finish_with_message(format!("{} AbuseIPDB check failed: {}", style("⚠").yellow(), e));
+            }
+        }
+    }
+    
     // NSLookup
     if matches.is_present("nslookup") {
         let nslookup_spinner = mp.add(create_spinner(&format!("Performing NSLookup on {}...", style(target_ip).cyan())));
-        match perform_nslookup(&nslookup_spinner, target_ip, &base_filename, &scan_dir) {
+        match perform_nslookup(&nslookup_spinner, target_ip, base_filename, scan_dir) {
             Ok(_) => {
                 nslookup_spinner.finish_with_message(format!("{} NSLookup completed", style("✓").green()));
             },
@@ -118,7 +299,7 @@ pub fn run_scans(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
     // DIG lookup
     if matches.is_present("dig") {
         let dig_spinner = mp.add(create_spinner(&format!("Performing DIG lookup on {}...", style(target_ip).cyan())));
-        match perform_dig(&dig_spinner, target_ip, &base_filename, &scan_dir) {
+        match perform_dig(&dig_spinner, target_ip, base_filename, scan_dir) {
             Ok(_) => {
                 dig_spinner.finish_with_message(format!("{} DIG lookup completed", style("✓").green()));
             },
@@ -137,7 +318,7 @@ pub fn run_scans(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
             style(wordlist).yellow())
         ));
         
-        match perform_gobuster(&gobuster_spinner, target_ip, wordlist, &base_filename, &scan_dir) {
+        match perform_gobuster(&gobuster_spinner, target_ip, wordlist, base_filename, scan_dir) {
             Ok(_) => {
                 gobuster_spinner.finish_with_message(format!("{} Gobuster directory enumeration completed", style("✓").green()));
             },
@@ -154,9 +335,11 @@ pub fn run_scans(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
             style(target_ip).cyan())
         ));
         
-        match perform_nikto(&nikto_spinner, target_ip, &base_filename, &scan_dir) {
-            Ok(_) => {
-                nikto_spinner.finish_with_message(format!("{} Nikto web vulnerability scan completed", style("✓").green()));
+        match perform_nikto(&nikto_spinner, target_ip, base_filename, scan_dir) {
+            Ok(findings) => {
+                nikto_spinner.finish_with_message(format!("{} Nikto web vulnerability scan completed ({} findings)", style("✓").green(), findings.len()));
+                results.add_findings(findings);
+                results.record_raw_output("Nikto", &format!("{}_nikto.xml", base_filename));
             },
             Err(e) => {
                 nikto_spinner.finish_with_message(format!("{} Nikto scan failed: {}", style("⚠").yellow(), e));
@@ -171,9 +354,13 @@ pub fn run_scans(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
             style(target_ip).cyan())
         ));
         
-        match perform_enum4linux(&enum4linux_spinner, target_ip, &base_filename, &scan_dir) {
-            Ok(_) => {
-                enum4linux_spinner.finish_with_message(format!("{} Enum4linux completed", style("✓").green()));
+        match perform_enum4linux(&enum4linux_spinner, target_ip, base_filename, scan_dir) {
+            Ok(enumeration) => {
+                enum4linux_spinner.finish_with_message(format!("{} Enum4linux completed ({} users, {} shares)",
+                    style("✓").green(), enumeration.smb.users.len(), enumeration.smb.shares.len()));
+                results.merge_smb(enumeration.smb);
+                results.add_findings(enumeration.findings);
+                results.record_raw_output("Enum4linux", &format!("{}_enum4linux.txt", base_filename));
             },
             Err(e) => {
                 enum4linux_spinner.finish_with_message(format!("{} Enum4linux failed: {}", style("⚠").yellow(), e));
@@ -181,6 +368,149 @@ pub fn run_scans(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
         }
     }
     
+    // Enum4linux-ng Windows/Samba enumeration
+    if matches.is_present("enum4linux-ng") {
+        let enum4linux_ng_spinner = mp.add(create_spinner(
+            &format!("Running Enum4linux-ng Windows/Samba enumeration on {}...", 
+            style(target_ip).cyan())
+        ));
+        
+        match perform_enum4linux_ng(&enum4linux_ng_spinner, target_ip, base_filename, scan_dir) {
+            Ok(enumeration) => {
+                enum4linux_ng_spinner.finish_with_message(format!("{} Enum4linux-ng completed ({} users, {} shares)",
+                    style("✓").green(), enumeration.smb.users.len(), enumeration.smb.shares.len()));
+                results.merge_smb(enumeration.smb);
+                results.add_findings(enumeration.findings);
+                results.record_raw_output("Enum4linux-ng", &format!("{}_enum4linux_ng.json", base_filename));
+            },
+            Err(e) => {
+                enum4linux_ng_spinner.finish_with_message(format!("{} Enum4linux-ng failed: {}", style("⚠").yellow(), e));
+            }
+        }
+    }
+    
+    // Native SMB enumeration
+    if matches.is_present("smb") {
+        let smb_spinner = mp.add(create_spinner(
+            &format!("Running native SMB enumeration on {}...", 
+            style(target_ip).cyan())
+        ));
+        
+        match perform_smb_enum(&smb_spinner, target_ip, base_filename, scan_dir) {
+            Ok(enumeration) => {
+                smb_spinner.finish_with_message(format!("{} SMB enumeration completed ({} dialects, signing {}, {} shares)",
+                    style("✓").green(), enumeration.smb.dialects.len(),
+                    if enumeration.smb.signing_required == Some(true) { "required" } else { "not required" },
+                    enumeration.smb.shares.len()));
+                results.merge_smb(enumeration.smb);
+                results.add_findings(enumeration.findings);
+                results.record_raw_output("SMB", &format!("{}_smb.txt", base_filename));
+            },
+            Err(e) => {
+                smb_spinner.finish_with_message(format!("{} SMB enumeration failed: {}", style("⚠").yellow(), e));
+            }
+        }
+    }
+    
+    // SNMP enumeration, also started for every host NMAP found with UDP/161 open
+    let snmp_hosts: Vec<String> = results.hosts.values()
+        .filter(|h| h.open_ports().any(|p| p.protocol == "udp" && p.port == 161))
+        .map(|h| h.address.clone())
+        .collect();
+    if matches.is_present("snmp") || !snmp_hosts.is_empty() {
+        // Without a UDP scan, fall back to the live hosts already known, then to a single-address target;
+        // probing every address of a range one after the other would take minutes per dead host
+        let live_hosts: Vec<String> = results.hosts.values()
+            .filter(|h| h.status.as_deref() != Some("down"))
+            .map(|h| h.address.clone())
+            .collect();
+        let targets: Vec<String> = if !snmp_hosts.is_empty() {
+            snmp_hosts
+        } else if !live_hosts.is_empty() {
+            live_hosts
+        } else {
+            let fallback = expand_targets(target_ip).and_then(|addresses| match addresses.as_slice() {
+                [address] => Ok(vec![address.to_string()]),
+                _ => Err(format!("no live hosts known among the {} addresses of {}; add --discover or --pipeline to find them first", addresses.len(), target_ip).into()),
+            });
+            match fallback {
+                Ok(addresses) => addresses,
+                Err(e) => {
+                    let snmp_spinner = mp.add(create_spinner("Running SNMP enumeration..."));
+                    snmp_spinner.finish_with_message(format!("{} SNMP enumeration failed: {}", style("⚠").yellow(), e));
+                    Vec::new()
+                }
+            }
+        };
+        let snmp_options = SnmpOptions {
+            communities: matches.value_of("snmp-communities").map(|f| f.to_string()),
+            users: matches.value_of("snmp-users").map(|f| f.to_string()),
+            timeout: options.snmp_timeout,
+        };
+        
+        for target in targets {
+            let snmp_spinner = mp.add(create_spinner(
+                &format!("Running SNMP enumeration on {}...", 
+                style(&target).cyan())
+            ));
+            
+            match perform_snmp_enum(&snmp_spinner, &target, &snmp_options, base_filename, scan_dir) {
+                Ok(enumeration) => {
+                    snmp_spinner.finish_with_message(format!("{} SNMP enumeration of {} completed ({} communities, {} v3 users, {} interfaces, {} processes)",
+                        style("✓").green(), target, enumeration.snmp.communities.len(), enumeration.snmp.users.len(),
+                        enumeration.snmp.interfaces.len(), enumeration.snmp.processes.len()));
+                    results.merge_snmp(enumeration.snmp);
+                    results.add_findings(enumeration.findings);
+                    results.record_raw_output("SNMP", &format!("{}_snmp.txt", base_filename));
+                },
+                Err(e) => {
+                    snmp_spinner.finish_with_message(format!("{} SNMP enumeration of {} failed: {}", style("⚠").yellow(), target, e));
+                }
+            }
+        }
+    }
+    
+    // LDAP enumeration, also started for every host NMAP found with a directory port open
+    let ldap_hosts: Vec<(String, Vec<u16>)> = results.hosts.values()
+        .map(|h| (h.address.clone(), h.open_ports()
+            .filter(|p| p.protocol == "tcp" && matches!(p.port, 389 | 636 | 3268))
+            .map(|p| p.port)
+            .collect::<Vec<u16>>()))
+        .filter(|(_, ports)| !ports.is_empty())
+        .collect();
+    if matches.is_present("ldap") || !ldap_hosts.is_empty() {
+        let targets = if ldap_hosts.is_empty() {
+            vec![(target_ip.to_string(), LDAP_PORTS.to_vec())]
+        } else {
+            ldap_hosts
+        };
+        let options = LdapOptions {
+            bind_user: matches.value_of("ldap-user").map(|u| u.to_string()),
+            bind_password: matches.value_of("ldap-password").map(|p| p.to_string()),
+        };
+        
+        for (target, ports) in targets {
+            let ldap_spinner = mp.add(create_spinner(
+                &format!("Running LDAP enumeration on {}...", 
+                style(&target).cyan())
+            ));
+            
+            match perform_ldap_enum(&ldap_spinner, &target, &ports, &options, base_filename, scan_dir) {
+                Ok(enumeration) => {
+                    ldap_spinner.finish_with_message(format!("{} LDAP enumeration of {}:{} completed ({} users, {} groups, {} computers)",
+                        style("✓").green(), target, enumeration.ldap.port, enumeration.ldap.users.len(),
+                        enumeration.ldap.groups.len(), enumeration.ldap.computers.len()));
+                    results.merge_ldap(enumeration.ldap);
+                    results.add_findings(enumeration.findings);
+                    results.record_raw_output("LDAP", &format!("{}_ldap.txt", base_filename));
+                },
+                Err(e) => {
+                    ldap_spinner.finish_with_message(format!("{} LDAP enumeration of {} failed: {}", style("⚠").yellow(), target, e));
+                }
+            }
+        }
+    }
+    
     // WhatWeb technology identification
     if matches.is_present("whatweb") {
         let whatweb_spinner = mp.add(create_spinner(
@@ -188,9 +518,11 @@ pub fn run_scans(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
             style(target_ip).cyan())
         ));
         
-        match perform_whatweb(&whatweb_spinner, target_ip, &base_filename, &scan_dir) {
-            Ok(_) => {
-                whatweb_spinner.finish_with_message(format!("{} WhatWeb technology identification completed", style("✓").green()));
+        match perform_whatweb(&whatweb_spinner, target_ip, base_filename, scan_dir) {
+            Ok(technologies) => {
+                whatweb_spinner.finish_with_message(format!("{} WhatWeb technology identification completed ({} technologies)", style("✓").green(), technologies.len()));
+                results.technologies.extend(technologies);
+                results.record_raw_output("WhatWeb", &format!("{}_whatweb.json", base_filename));
             },
             Err(e) => {
                 whatweb_spinner.finish_with_message(format!("{} WhatWeb failed: {}", style("⚠").yellow(), e));
@@ -198,6 +530,25 @@ pub fn run_scans(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
         }
     }
     
+    // Built-in web technology fingerprinting
+    if matches.is_present("fingerprint") {
+        let fingerprint_spinner = mp.add(create_spinner(
+            &format!("Fingerprinting web technologies on {}...", 
+            style(target_ip).cyan())
+        ));
+        
+        match perform_fingerprint(&fingerprint_spinner, target_ip, matches.value_of("signatures"), base_filename, scan_dir) {
+            Ok(technologies) => {
+                fingerprint_spinner.finish_with_message(format!("{} Web fingerprinting completed ({} technologies)", style("✓").green(), technologies.len()));
+                results.technologies.extend(technologies);
+                results.record_raw_output("Fingerprint", &format!("{}_fingerprint.json", base_filename));
+            },
+            Err(e) => {
+                fingerprint_spinner.finish_with_message(format!("{} Web fingerprinting failed: {}", style("⚠").yellow(), e));
+            }
+        }
+    }
+    
     // DNSrecon domain enumeration
     if matches.is_present("dnsrecon") {
         let dnsrecon_spinner = mp.add(create_spinner(
@@ -205,9 +556,14 @@ pub fn run_scans(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
             style(target_ip).cyan())
         ));
         
-        match perform_dnsrecon(&dnsrecon_spinner, target_ip, &base_filename, &scan_dir) {
-            Ok(_) => {
-                dnsrecon_spinner.finish_with_message(format!("{} DNSrecon domain enumeration completed", style("✓").green()));
+        match perform_dnsrecon(&dnsrecon_spinner, target_ip, base_filename, scan_dir) {
+            Ok(dnsrecon) => {
+                dnsrecon_spinner.finish_with_message(format!("{} DNSrecon domain enumeration completed ({} records)", style("✓").green(), dnsrecon.records.len()));
+                for record in dnsrecon.records {
+                    results.dns.add_record(target_ip, record);
+                }
+                results.add_findings(dnsrecon.findings);
+                results.record_raw_output("DNSrecon", &format!("{}_dnsrecon.csv", base_filename));
             },
             Err(e) => {
                 dnsrecon_spinner.finish_with_message(format!("{} DNSrecon failed: {}", style("⚠").yellow(), e));
@@ -215,6 +571,193 @@ pub fn run_scans(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
         }
     }
     
+    // Native zone transfer checks
+    if matches.is_present("axfr") {
+        let axfr_spinner = mp.add(create_spinner(
+            &format!("Testing zone transfers for {}...", 
+            style(target_ip).cyan())
+        ));
+        
+        match perform_zone_transfer(&axfr_spinner, target_ip, options.resolvers.clone(), options.axfr_servers.clone(), base_filename, scan_dir) {
+            Ok(transfers) => {
+                let open = transfers.attempts.iter().filter(|a| a.zone_file.is_some()).count();
+                axfr_spinner.finish_with_message(format!("{} Zone transfer checks completed ({} of {} name servers allow AXFR)", style("✓").green(), open, transfers.attempts.len()));
+                for record in transfers.records {
+                    results.dns.add_record(target_ip, record);
+                }
+                results.add_findings(transfers.findings);
+                for attempt in &transfers.attempts {
+                    if let Some(zone_file) = &attempt.zone_file {
+                        results.record_raw_output(&format!("AXFR {}", attempt.address), zone_file);
+                    }
+                }
+                results.dns.zone_transfers.extend(transfers.attempts);
+            },
+            Err(e) => {
+                axfr_spinner.finish_with_message(format!("{} Zone transfer checks failed: {}", style("⚠").yellow(), e));
+            }
+        }
+    }
+    
+    // Email security posture (SPF, DMARC, DKIM, MTA-STS, TLS-RPT)
+    if matches.is_present("email-security") {
+        let email_spinner = mp.add(create_spinner(
+            &format!("Analysing email security for {}...", 
+            style(target_ip).cyan())
+        ));
+        
+        match perform_email_security(&email_spinner, target_ip, options.resolvers.clone(), options.dns_rate, base_filename, scan_dir) {
+            Ok(email) => {
+                email_spinner.finish_with_message(format!("{} Email security analysis completed ({} issues)", style("✓").green(), email.findings.len()));
+                results.add_findings(email.findings);
+                results.email.push(email.posture);
+                results.record_raw_output("Email security", &format!("{}_email_security.txt", base_filename));
+            },
+            Err(e) => {
+                email_spinner.finish_with_message(format!("{} Email security analysis failed: {}", style("⚠").yellow(), e));
+            }
+        }
+    }
+    
+    // DNSSEC chain of trust and zone walking checks
+    if matches.is_present("dnssec") {
+        let dnssec_spinner = mp.add(create_spinner(
+            &format!("Validating DNSSEC for {}...", 
+            style(target_ip).cyan())
+        ));
+        
+        match perform_dnssec(&dnssec_spinner, target_ip, options.resolvers.clone(), options.dns_rate, base_filename, scan_dir) {
+            Ok(dnssec) => {
+                let status = dnssec.report.chain.last().map(|z| z.status.to_string()).unwrap_or_default();
+                dnssec_spinner.finish_with_message(format!("{} DNSSEC validation completed ({})", style("✓").green(), status));
+                for record in dnssec.records {
+                    results.dns.add_record(target_ip, record);
+                }
+                results.add_findings(dnssec.findings);
+                results.dns.dnssec = Some(dnssec.report);
+                results.record_raw_output("DNSSEC", &format!("{}_dnssec.txt", base_filename));
+            },
+            Err(e) => {
+                dnssec_spinner.finish_with_message(format!("{} DNSSEC validation failed: {}", style("⚠").yellow(), e));
+            }
+        }
+    }
+    
+    // Reverse DNS sweep of discovered hosts (or the whole CIDR range)
+    if matches.is_present("rdns") {
+        let rdns_spinner = mp.add(create_spinner(
+            &format!("Running reverse DNS sweep for {}...", 
+            style(target_ip).cyan())
+        ));
+        
+        let (resolvers, rate_limit, threads) = (options.resolvers.clone(), options.dns_rate, options.dns_threads);
+        let live_hosts: Vec<std::net::IpAddr> = results.hosts.values()
+            .filter(|h| h.status.as_deref() != Some("down"))
+            .filter_map(|h| h.address.parse().ok())
+            .collect();
+        
+        let sweep = if live_hosts.is_empty() {
+            expand_targets(target_ip).and_then(|targets| perform_reverse_dns(&rdns_spinner, &targets, resolvers, rate_limit, threads, base_filename, scan_dir))
+        } else {
+            perform_reverse_dns(&rdns_spinner, &live_hosts, resolvers, rate_limit, threads, base_filename, scan_dir)
+        };
+        match sweep {
+            Ok(records) => {
+                rdns_spinner.finish_with_message(format!("{} Reverse DNS sweep completed ({} names)", style("✓").green(), records.len()));
+                for record in records {
+                    // Name the hosts already found; a range sweep must not create a host per PTR record
+                    if let Some(host) = record.address.as_ref().and_then(|address| results.hosts.get_mut(address)) {
+                        host.hostnames.insert(record.name.clone());
+                    }
+                    results.dns.add_record(target_ip, record);
+                }
+                results.record_raw_output("Reverse DNS", &format!("{}_rdns.txt", base_filename));
+            },
+            Err(e) => {
+                rdns_spinner.finish_with_message(format!("{} Reverse DNS sweep failed: {}", style("⚠").yellow(), e));
+            }
+        }
+    }
+    
+    // Passive DNS history
+    if let Some(provider) = matches.value_of("passive-dns") {
+        let passive_spinner = mp.add(create_spinner(&format!("Reading passive DNS history for {}...", style(target_ip).cyan())));
+        match passive_dns_provider(&passive_spinner, provider, &options.api)
+            .and_then(|provider| perform_passive_dns(&passive_spinner, target_ip, provider.as_ref(), base_filename, scan_dir)) {
+            Ok(passive) => {
+                let associated = match &passive.associated_error {
+                    Some(e) => format!("associated domains unavailable: {}", e),
+                    None => format!("{} associated domains", passive.associated_domains.len()),
+                };
+                passive_spinner.finish_with_message(format!("{} Passive DNS lookup completed ({} historical A records, {} subdomains, {})",
+                    style("✓").green(), passive.records.len(), passive.subdomains.len(), associated));
+                for record in passive.records {
+                    results.dns.add_history(target_ip, record);
+                }
+                for name in &passive.subdomains {
+                    results.dns.add_subdomain(name, &passive.provider);
+                }
+                for name in &passive.associated_domains {
+                    results.dns.add_associated_domain(name, &passive.provider);
+                }
+                results.record_raw_output("Passive DNS", &format!("{}_passive_dns.json", base_filename));
+            },
+            Err(e) => {
+                passive_spinner.finish_with_message(format!("{} Passive DNS lookup failed: {}", style("⚠").yellow(), e));
+            }
+        }
+    }
+    
+    // Certificate Transparency subdomain discovery
+    if matches.is_present("ct") {
+        let ct_spinner = mp.add(create_spinner(&format!("Searching Certificate Transparency logs for {}...", style(target_ip).cyan())));
+        match perform_ct_lookup(&ct_spinner, target_ip, &options.api, base_filename, scan_dir) {
+            Ok(ct) => {
+                ct_spinner.finish_with_message(format!("{} Certificate Transparency search completed ({} names from {} certificates)",
+                    style("✓").green(), ct.names.len(), ct.certificates));
+                for name in &ct.names {
+                    results.dns.add_ct_name(name, &ct.source);
+                }
+                results.record_raw_output("Certificate Transparency", &format!("{}_ct.json", base_filename));
+            },
+            Err(e) => {
+                ct_spinner.finish_with_message(format!("{} Certificate Transparency search failed: {}", style("⚠").yellow(), e));
+            }
+        }
+    }
+    
+    // Native subdomain enumeration
+    if matches.is_present("subdomains") {
+        let subdomain_spinner = mp.add(create_spinner(
+            &format!("Enumerating subdomains of {}...", 
+            style(target_ip).cyan())
+        ));
+        
+        let subdomain_options = SubdomainOptions {
+            wordlist: matches.value_of("subdomain-wordlist").map(|w| w.to_string()),
+            resolvers: options.resolvers.clone(),
+            rate_limit: options.dns_rate,
+            threads: options.dns_threads,
+            permutations: !matches.is_present("no-permutations"),
+        };
+        let known_names: Vec<String> = results.dns.subdomains.keys().cloned().collect();
+        
+        match perform_subdomain_enum(&subdomain_spinner, target_ip, &subdomain_options, &known_names, base_filename, scan_dir) {
+            Ok(subdomains) => {
+                let found: std::collections::BTreeSet<&str> = subdomains.records.iter().map(|r| r.name.as_str()).collect();
+                subdomain_spinner.finish_with_message(format!("{} Subdomain enumeration completed ({} names)", style("✓").green(), found.len()));
+                for record in subdomains.records {
+                    results.dns.add_record(target_ip, record);
+                }
+                results.add_findings(subdomains.findings);
+                results.record_raw_output("Subdomain brute-force", &format!("{}_subdomains.txt", base_filename));
+            },
+            Err(e) => {
+                subdomain_spinner.finish_with_message(format!("{} Subdomain enumeration failed: {}", style("⚠").yellow(), e));
+            }
+        }
+    }
+    
     // Traceroute network path discovery
     if matches.is_present("traceroute") {
         let traceroute_spinner = mp.add(create_spinner(
@@ -222,9 +765,14 @@ pub fn run_scans(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
             style(target_ip).cyan())
         ));
         
-        match perform_traceroute(&traceroute_spinner, target_ip, &base_filename, &scan_dir) {
-            Ok(_) => {
-                traceroute_spinner.finish_with_message(format!("{} Traceroute completed", style("✓").green()));
+        match perform_traceroute(&traceroute_spinner, target_ip, base_filename, scan_dir) {
+            Ok(trace) => {
+                traceroute_spinner.finish_with_message(format!("{} Traceroute completed ({} hops{})",
+                    style("✓").green(),
+                    trace.hops.iter().map(|h| h.ttl).max().unwrap_or(0),
+                    if trace.reached { "" } else { ", target not reached" }));
+                results.traces.push(trace);
+                results.record_raw_output("Traceroute", &format!("{}_traceroute.txt", base_filename));
             },
             Err(e) => {
                 traceroute_spinner.finish_with_message(format!("{} Traceroute failed: {}", style("⚠").yellow(), e));
@@ -239,7 +787,7 @@ pub fn run_scans(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
             style(target_ip).cyan())
         ));
         
-        match perform_ssl_check(&ssl_spinner, target_ip, &base_filename, &scan_dir) {
+        match perform_ssl_check(&ssl_spinner, target_ip, base_filename, scan_dir) {
             Ok(_) => {
                 ssl_spinner.finish_with_message(format!("{} SSL/TLS configuration check completed", style("✓").green()));
             },
@@ -250,28 +798,32 @@ pub fn run_scans(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
     }
     
     // Netdiscover network scan
-    if matches.is_present("netdiscover") {
-        // Verify the input is in CIDR format
+    if options.network_tools && matches.is_present("netdiscover") {
+        // A single IP is widened to the directly connected network it sits on, never guessed
         let network_target = if target_ip.contains("/") {
-            target_ip.to_string()
+            Ok(target_ip.to_string())
         } else {
-            // If not in CIDR, assume it's a single IP and use /24 for that subnet
-            let ip_parts: Vec<&str> = target_ip.split('.').collect();
-            if ip_parts.len() == 4 {
-                format!("{}.{}.{}.0/24", ip_parts[0], ip_parts[1], ip_parts[2])
-            } else {
-                target_ip.to_string() // Just use as-is if we can't parse it
-            }
+            target_ip.parse::<std::net::Ipv4Addr>().ok()
+                .and_then(|ip| local_networks().into_iter().find(|n| n.contains(ip)))
+                .map(|n| format!("{}/{}", n.network, n.prefix))
+                .ok_or_else(|| format!("{} is not on a directly connected network; pass a CIDR range", target_ip))
         };
         
         let netdiscover_spinner = mp.add(create_spinner(
             &format!("Running Netdiscover on network {}...", 
-            style(&network_target).cyan())
+            style(network_target.as_deref().unwrap_or(target_ip)).cyan())
         ));
         
-        match perform_netdiscover(&netdiscover_spinner, &network_target, &base_filename, &scan_dir) {
-            Ok(_) => {
-                netdiscover_spinner.finish_with_message(format!("{} Netdiscover completed", style("✓").green()));
+        let netdiscover = network_target
+            .map_err(|e| e.into())
+            .and_then(|network| perform_netdiscover(&netdiscover_spinner, &network, base_filename, scan_dir));
+        match netdiscover {
+            Ok(hosts) => {
+                netdiscover_spinner.finish_with_message(format!("{} Netdiscover completed ({} hosts)", style("✓").green(), hosts.len()));
+                for host in hosts {
+                    results.merge_host(host);
+                }
+                results.record_raw_output("Netdiscover", &format!("{}_netdiscover.txt", base_filename));
             },
             Err(e) => {
                 netdiscover_spinner.finish_with_message(format!("{} Netdiscover failed: {}", style("⚠").yellow(), e));
@@ -279,15 +831,91 @@ pub fn run_scans(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
         }
     }
     
-    // Complete the overall progress
-    overall_spinner.finish_with_message(format!("{} All reconnaissance tasks completed!", style("✓").green().bold()));
-    
-    // Create scan summary
-    create_scan_summary(matches, target_ip, &scan_dir)?;
-    
-    println!("\n{} All reconnaissance tasks completed!", style("✓").green().bold());
-    println!("📁 Results saved in: {}", style(&scan_dir).green());
-    println!("📝 Scan summary created: {}/SCAN_SUMMARY.md", style(&scan_dir).green());
+    // Offline GeoIP/ASN enrichment of everything discovered above
+    if matches.is_present("geoip") {
+        let geoip_spinner = mp.add(create_spinner("Looking up locations and ASNs in local GeoIP databases..."));
+        let files: Vec<std::path::PathBuf> = matches.value_of("geoip-db")
+            .map(|files| files.split(',').map(|f| f.trim()).filter(|f| !f.is_empty()).map(std::path::PathBuf::from).collect())
+            .unwrap_or_default();
+        match GeoDatabase::shared(&files) {
+            Ok(database) => {
+                let found = results.annotate_geo(|address| database.lookup(address));
+                geoip_spinner.finish_with_message(format!("{} GeoIP enrichment completed ({} addresses from {} databases)",
+                    style("✓").green(), found, database.files.len()));
+            },
+            Err(e) => {
+                geoip_spinner.finish_with_message(format!("{} GeoIP enrichment failed: {}", style("⚠").yellow(), e));
+            }
+        }
+    }
     
     Ok(())
+}
+
+// Write the consolidated JSON, topology graph, HTML report and markdown summary for a scan
+pub fn write_reports(matches: &ArgMatches, target_ip: &str, scan_dir: &str, results: &ScanResults) -> Result<Option<TopologyFiles>, Box<dyn Error>> {
+    write_consolidated_report(results, scan_dir)?;
+    let topology = if results.traces.is_empty() {
+        None
+    } else {
+        Some(write_topology_graph(results, scan_dir)?)
+    };
+    write_html_report(results, scan_dir, topology.as_ref())?;
+    create_scan_summary(matches, target_ip, scan_dir, results)?;
+    Ok(topology)
+}
+
+// Flag values shared by several tools, parsed once before any scan starts
+pub struct ScanOptions {
+    pub api: ApiSettings,
+    pub discovery: DiscoveryOptions,
+    pub resolvers: Vec<SocketAddr>,
+    pub axfr_servers: Vec<SocketAddr>,
+    pub dns_rate: u32,
+    pub dns_threads: usize,
+    pub snmp_timeout: Duration,
+    pub network_tools: bool,        // Range-wide discovery tools, which per-host pipeline scans skip
+}
+
+pub fn scan_options(matches: &ArgMatches) -> Result<ScanOptions, Box<dyn Error>> {
+    Ok(ScanOptions {
+        api: api_settings(matches)?,
+        discovery: discovery_options(matches)?,
+        resolvers: parse_resolvers(matches.value_of("resolvers").unwrap_or(""))?,
+        axfr_servers: parse_resolvers(matches.value_of("axfr-servers").unwrap_or(""))?,
+        dns_rate: number(matches, "dns-rate", "100")?,
+        dns_threads: number(matches, "dns-threads", "20")?,
+        snmp_timeout: Duration::from_secs(number(matches, "snmp-timeout", "2")?),
+        network_tools: true,
+    })
+}
+
+// A numeric flag, naming the flag when the value does not parse
+fn number<T: FromStr>(matches: &ArgMatches, flag: &str, default: &str) -> Result<T, Box<dyn Error>> {
+    let value = matches.value_of(flag).unwrap_or(default);
+    value.parse().map_err(|_| format!("Invalid --{}: {} is not a number", flag, value).into())
+}
+
+// Cache settings for the intelligence APIs
+fn api_settings(matches: &ArgMatches) -> Result<ApiSettings, Box<dyn Error>> {
+    let hours: u64 = matches.value_of("api-cache-ttl").unwrap_or("24").parse()
+        .map_err(|_| "Invalid --api-cache-ttl: expected a number of hours")?;
+    Ok(ApiSettings {
+        cache_dir: matches.value_of("api-cache-dir").map(std::path::PathBuf::from).or_else(ApiSettings::default_cache_dir),
+        cache_ttl: std::time::Duration::from_secs(hours * 3600),
+        overrides: std::collections::HashMap::new(),
+    })
+}
+
+// Host discovery settings from the command line
+fn discovery_options(matches: &ArgMatches) -> Result<DiscoveryOptions, Box<dyn Error>> {
+    Ok(DiscoveryOptions {
+        methods: parse_discovery_methods(matches.value_of("discovery-methods").unwrap_or("arp,icmp,tcp"))?,
+        tcp_ports: matches.value_of("discovery-ports").unwrap_or("22,80,443,445,3389")
+            .split(',')
+            .map(|p| p.trim().parse::<u16>().map_err(|_| format!("Invalid discovery port: {}", p)))
+            .collect::<Result<Vec<u16>, String>>()?,
+        threads: number(matches, "discovery-threads", "64")?,
+        oui_file: matches.value_of("oui-db").map(|f| f.to_string()),
+    })
 }
\ No newline at end of file
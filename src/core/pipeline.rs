@@ -0,0 +1,113 @@
+use clap::ArgMatches;
+use console::style;
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use std::error::Error;
+use std::fs;
+
+use crate::core::orchestrator::{run_target_scans, scan_options, write_reports, ScanOptions};
+use crate::models::ScanResults;
+use crate::report::{create_network_summary, write_consolidated_report, write_html_report, write_topology_graph};
+use crate::scanners::network::perform_discovery;
+use crate::ui::progress::create_spinner;
+use crate::utils::net::expand_targets;
+
+// Discover live hosts in the target range, then scan each one into its own directory
+pub fn run_pipeline(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    // Parse every flag value up front so a typo fails before any host is scanned
+    let options = ScanOptions { network_tools: false, ..scan_options(matches)? };
+    let output_dir = matches.value_of("output-dir").unwrap_or("./recon_results");
+    fs::create_dir_all(output_dir)?;
+    
+    let target = matches.value_of("ip").unwrap();
+    let mp = MultiProgress::new();
+    
+    let overall_spinner = mp.add(ProgressBar::new_spinner());
+    overall_spinner.set_style(
+        ProgressStyle::with_template("{spinner:.blue} {msg}")
+            .unwrap()
+            .tick_strings(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"])
+    );
+    overall_spinner.set_message(format!("Starting pipeline on {}...", style(target).cyan()));
+    overall_spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+    
+    overall_spinner.suspend(|| {
+        println!("🎯 Network: {}", style(target).cyan().bold());
+    });
+    
+    // Network directory holding the discovery results and one directory per live host
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
+    let base_filename = target.replace(".", "_").replace("/", "_");
+    let network_dir = format!("{}/{}_{}", output_dir, base_filename, timestamp);
+    fs::create_dir_all(&network_dir)?;
+    overall_spinner.suspend(|| {
+        println!("📁 Network directory created: {}", style(&network_dir).green());
+    });
+    
+    // Stage 1: live host discovery
+    let addresses = expand_targets(target)?;
+    let discovery_spinner = mp.add(create_spinner(&format!("Discovering live hosts in {}...", style(target).cyan())));
+    let discovered = match perform_discovery(&discovery_spinner, &addresses, &options.discovery, &base_filename, &network_dir) {
+        Ok(hosts) => {
+            discovery_spinner.finish_with_message(format!("{} Host discovery completed ({} of {} addresses up)",
+                style("✓").green(), hosts.len(), addresses.len()));
+            hosts
+        },
+        Err(e) => {
+            discovery_spinner.finish_with_message(format!("{} Host discovery failed: {}", style("⚠").yellow(), e));
+            return Err(e);
+        }
+    };
+    
+    // Stage 2: the selected tools against every live host
+    let mut scans: Vec<(String, ScanResults)> = Vec::new();
+    for (index, host) in discovered.iter().enumerate() {
+        overall_spinner.set_message(format!("Scanning host {}/{}: {}...", index + 1, discovered.len(), style(&host.address).cyan()));
+        
+        let host_base = host.address.replace(".", "_").replace(":", "_");
+        let host_dir = format!("{}/{}", network_dir, host_base);
+        
+        // A failing host is recorded and the pipeline moves on; whatever it collected is still reported
+        let mut results = ScanResults::new(&host.address);
+        results.merge_host(host.clone());
+        if let Err(e) = fs::create_dir_all(&host_dir).map_err(Box::<dyn Error>::from)
+            .and_then(|_| run_target_scans(&mp, matches, &options, &host.address, &host_base, &host_dir, &mut results)) {
+            results.errors.push(format!("Scan failed: {}", e));
+        }
+        if let Err(e) = write_reports(matches, &host.address, &host_dir, &results) {
+            results.errors.push(format!("Reports failed: {}", e));
+        }
+        for error in &results.errors {
+            overall_spinner.suspend(|| {
+                println!("{} {}: {}", style("⚠").yellow(), style(&host.address).cyan(), error);
+            });
+        }
+        scans.push((host_base, results));
+    }
+    
+    let failed = scans.iter().filter(|(_, results)| !results.errors.is_empty()).count();
+    overall_spinner.finish_with_message(format!("{} Pipeline completed: {} hosts scanned{}", style("✓").green().bold(), scans.len(),
+        if failed > 0 { format!(", {} with errors", failed) } else { String::new() }));
+    
+    // Stage 3: network-wide reports linking every host
+    create_network_summary(target, &network_dir, addresses.len(), &scans)?;
+    let mut network = ScanResults::new(target);
+    network.record_raw_output("Host Discovery", &format!("{}_discovery.txt", base_filename));
+    for (directory, results) in scans {
+        network.merge_results(results, &directory);
+    }
+    write_consolidated_report(&network, &network_dir)?;
+    let topology = if network.traces.is_empty() {
+        None
+    } else {
+        Some(write_topology_graph(&network, &network_dir)?)
+    };
+    write_html_report(&network, &network_dir, topology.as_ref())?;
+    
+    println!("\n{} Pipeline completed for {}!", style("✓").green().bold(), style(target).cyan());
+    println!("📁 Results saved in: {}", style(&network_dir).green());
+    println!("📝 Network summary created: {}/NETWORK_SUMMARY.md", style(&network_dir).green());
+    println!("📊 Consolidated report created: {}/SCAN_RESULTS.json", style(&network_dir).green());
+    println!("🌐 HTML report created: {}/REPORT.html", style(&network_dir).green());
+    
+    Ok(())
+}
\ No newline at end of file
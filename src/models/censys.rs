@@ -0,0 +1,89 @@
+use serde::Serialize;
+
+use crate::models::host::{Host, Port, Service, TlsInfo};
+
+// A software component Censys identified (from its CPE)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CensysSoftware {
+    pub vendor: Option<String>,
+    pub product: String,
+    pub version: Option<String>,
+}
+
+// A service Censys observed on a host
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CensysService {
+    pub port: u16,
+    pub transport: String,                   // tcp / udp
+    pub service_name: String,                // HTTP, SSH, UNKNOWN, ...
+    pub extended_service_name: Option<String>,  // HTTPS when HTTP runs over TLS
+    pub software: Vec<CensysSoftware>,
+    pub labels: Vec<String>,                 // login-page, remote-access, database, ...
+    pub banner: Option<String>,
+    pub certificate: Option<String>,         // SHA-256 fingerprint of the leaf certificate
+    pub tls: Option<TlsInfo>,
+    pub observed_at: Option<String>,
+}
+
+// What Censys knows about one IP address
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CensysHost {
+    pub ip: String,
+    pub asn: Option<u64>,
+    pub as_name: Option<String>,
+    pub country: Option<String>,
+    pub city: Option<String>,
+    pub os: Option<String>,
+    pub dns_names: Vec<String>,              // Forward and reverse DNS names pointing at the host
+    pub labels: Vec<String>,
+    pub last_updated: Option<String>,
+    pub services: Vec<CensysService>,
+}
+
+impl CensysHost {
+    // The host as Censys last saw it, with every service as an open port
+    pub fn to_host(&self) -> Host {
+        let mut host = Host::new(&self.ip);
+        host.hostnames.extend(self.dns_names.iter().map(|h| h.to_lowercase()));
+        host.os = self.os.clone();
+        host.ports = self.services.iter().map(|service| Port {
+            protocol: service.transport.clone(),
+            port: service.port,
+            state: "open".to_string(),
+            service: Some(Service {
+                name: service.extended_service_name.as_deref()
+                    .unwrap_or(&service.service_name)
+                    .to_lowercase(),
+                product: service.software.first().map(|s| s.product.clone()),
+                version: service.software.first().and_then(|s| s.version.clone()),
+                extra_info: (!service.labels.is_empty()).then(|| service.labels.join(", ")),
+                banner: service.banner.clone(),
+                tls: service.tls.clone(),
+            }),
+            source: "Censys".to_string(),
+            last_seen: service.observed_at.clone(),
+        }).collect();
+        host
+    }
+}
+
+// A certificate from the Censys certificate index
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CensysCertificate {
+    pub fingerprint: String,                 // SHA-256
+    pub subject_dn: Option<String>,
+    pub issuer_dn: Option<String>,
+    pub names: Vec<String>,                  // Subject CN and SANs
+    pub not_before: Option<String>,
+    pub not_after: Option<String>,
+}
+
+// Everything one Censys lookup returned
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CensysInfo {
+    pub target: String,
+    pub query: Option<String>,               // Host search used for domains and ranges
+    pub total: Option<u64>,                  // Hosts matching that search
+    pub hosts: Vec<CensysHost>,
+    pub certificates: Vec<CensysCertificate>,
+}
\ No newline at end of file
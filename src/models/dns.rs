@@ -0,0 +1,246 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+// A single DNS record reported by one of the DNS tools
+#[derive(Debug, Clone, Serialize)]
+pub struct DnsRecord {
+    pub source: String,              // Tool that reported the record
+    pub record_type: String,         // A, AAAA, MX, NS, SRV, PTR, TXT, ...
+    pub name: String,
+    pub address: Option<String>,
+    pub target: Option<String>,      // MX/NS/SRV/CNAME target
+    pub port: Option<u16>,
+    pub data: Option<String>,        // Free text (TXT/SOA strings)
+}
+
+// A discovered name in the target's DNS space
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DnsAsset {
+    pub name: String,
+    pub addresses: BTreeSet<String>,
+    pub sources: BTreeSet<String>,
+    pub wildcard: bool,                     // Covered by a wildcard certificate (*.name)
+    pub first_seen: Option<String>,         // Earliest sighting (certificate validity, passive DNS)
+    pub last_seen: Option<String>,          // Latest sighting
+}
+
+impl DnsAsset {
+    // Widen the seen window with another sighting
+    pub fn seen(&mut self, first: Option<&str>, last: Option<&str>) {
+        widen_seen(&mut self.first_seen, &mut self.last_seen, first, last);
+    }
+}
+
+// A name found in Certificate Transparency logs, merged across every certificate listing it
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CtName {
+    pub name: String,
+    pub wildcard: bool,
+    pub first_seen: Option<String>,
+    pub last_seen: Option<String>,
+}
+
+// A record a passive DNS provider saw the name resolve to over a period of time
+#[derive(Debug, Clone, Serialize)]
+pub struct PassiveDnsRecord {
+    pub source: String,              // Provider that observed it
+    pub name: String,
+    pub record_type: String,         // A, AAAA, ...
+    pub value: String,
+    pub organization: Option<String>,  // Owner of the address at the time
+    pub first_seen: Option<String>,
+    pub last_seen: Option<String>,
+}
+
+// Outcome of a zone transfer attempt against one name server
+#[derive(Debug, Clone, Serialize)]
+pub enum TransferStatus {
+    Allowed,
+    Partial(String),     // Records were sent but the transfer stopped before the closing SOA
+    Refused(String),     // Server answered but refused (response code or empty answer)
+    Failed(String),      // Connection/protocol error
+}
+
+impl fmt::Display for TransferStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransferStatus::Allowed => write!(f, "Allowed"),
+            TransferStatus::Partial(reason) => write!(f, "Partial ({})", reason),
+            TransferStatus::Refused(reason) => write!(f, "Refused ({})", reason),
+            TransferStatus::Failed(reason) => write!(f, "Failed ({})", reason),
+        }
+    }
+}
+
+// Per-name-server AXFR/IXFR results
+#[derive(Debug, Clone, Serialize)]
+pub struct ZoneTransferResult {
+    pub server: String,                  // Name server host name
+    pub address: String,
+    pub axfr: TransferStatus,
+    pub ixfr: TransferStatus,
+    pub records: usize,                  // Records received via AXFR
+    pub zone_file: Option<String>,       // Saved copy of the transferred zone
+}
+
+// Chain-of-trust state of a zone (RFC 4035 section 4.3)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum DnssecStatus {
+    Secure,
+    Insecure,         // No DS delegation: responses are unauthenticated
+    Bogus,            // Signed but fails validation
+    Indeterminate,    // A parent could not be validated
+}
+
+impl fmt::Display for DnssecStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DnssecStatus::Secure => write!(f, "Secure"),
+            DnssecStatus::Insecure => write!(f, "Insecure"),
+            DnssecStatus::Bogus => write!(f, "Bogus"),
+            DnssecStatus::Indeterminate => write!(f, "Indeterminate"),
+        }
+    }
+}
+
+// DNSSEC state of one zone on the delegation chain
+#[derive(Debug, Clone, Serialize)]
+pub struct DnssecZone {
+    pub zone: String,
+    pub status: DnssecStatus,
+    pub ds_records: usize,
+    pub dnskeys: usize,
+    pub algorithms: Vec<String>,
+    pub signature_expiry: Option<String>,   // Earliest RRSIG expiration seen for the zone
+    pub problems: Vec<String>,
+}
+
+// DNSSEC assessment of the target domain, root zone first
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DnssecReport {
+    pub domain: String,
+    pub chain: Vec<DnssecZone>,
+    pub denial: Option<String>,             // Authenticated denial of existence in use (NSEC/NSEC3)
+    pub walked_names: Vec<String>,          // Names enumerated by following the NSEC chain
+}
+
+// Every DNS record and asset discovered for the target domain
+#[derive(Debug, Default, Serialize)]
+pub struct DnsInventory {
+    pub records: Vec<DnsRecord>,
+    pub subdomains: BTreeMap<String, DnsAsset>,
+    pub addresses: BTreeSet<String>,
+    pub zone_transfers: Vec<ZoneTransferResult>,
+    pub dnssec: Option<DnssecReport>,
+    pub history: Vec<PassiveDnsRecord>,                         // Historical resolutions from passive DNS
+    pub associated_domains: BTreeMap<String, BTreeSet<String>>,  // Related domain -> sources
+}
+
+impl DnsInventory {
+    // Add a record and update the subdomain/address inventory for the given domain
+    pub fn add_record(&mut self, domain: &str, record: DnsRecord) {
+        let name = normalise_name(&record.name);
+        let address = record.address.clone().filter(|a| !a.is_empty());
+
+        if let Some(address) = &address {
+            self.addresses.insert(address.clone());
+        }
+
+        // Names in the domain (SRV service labels excluded) become subdomain assets
+        if is_in_domain(&name, domain) && !name.starts_with('_') {
+            let asset = self.add_subdomain(&name, &record.source);
+            if let Some(address) = address
+                && matches!(record.record_type.as_str(), "A" | "AAAA" | "PTR") {
+                asset.addresses.insert(address);
+            }
+        }
+
+        // MX/NS/CNAME/SRV targets inside the domain are assets too
+        if let Some(target) = record.target.as_deref().map(normalise_name)
+            && is_in_domain(&target, domain) {
+            self.add_subdomain(&target, &record.source);
+        }
+
+        self.records.push(record);
+    }
+
+    // Register a name from Certificate Transparency with its first/last seen window
+    pub fn add_ct_name(&mut self, ct: &CtName, source: &str) {
+        let asset = self.add_subdomain(&ct.name, source);
+        asset.wildcard |= ct.wildcard;
+        asset.seen(ct.first_seen.as_deref(), ct.last_seen.as_deref());
+    }
+
+    // Add a historical resolution; names in the domain become assets with its seen window
+    pub fn add_history(&mut self, domain: &str, record: PassiveDnsRecord) {
+        let name = normalise_name(&record.name);
+        if is_in_domain(&name, domain) {
+            let asset = self.add_subdomain(&name, &record.source);
+            asset.seen(record.first_seen.as_deref(), record.last_seen.as_deref());
+        }
+        self.history.push(record);
+    }
+
+    // Register a domain that shares the target's owner or infrastructure
+    pub fn add_associated_domain(&mut self, name: &str, source: &str) {
+        self.associated_domains.entry(normalise_name(name)).or_default().insert(source.to_string());
+    }
+
+    // Fold another inventory into this one, e.g. per-host scans into a network-wide view
+    pub fn merge(&mut self, other: DnsInventory) {
+        self.records.extend(other.records);
+        for asset in other.subdomains.into_values() {
+            let existing = self.subdomains.entry(asset.name.clone()).or_insert_with(|| DnsAsset {
+                name: asset.name.clone(),
+                ..Default::default()
+            });
+            existing.addresses.extend(asset.addresses);
+            existing.sources.extend(asset.sources);
+            existing.wildcard |= asset.wildcard;
+            existing.seen(asset.first_seen.as_deref(), asset.last_seen.as_deref());
+        }
+        self.addresses.extend(other.addresses);
+        self.zone_transfers.extend(other.zone_transfers);
+        // DNSSEC is validated for the target domain, which per-host scans of addresses never have
+        self.dnssec = self.dnssec.take().or(other.dnssec);
+        self.history.extend(other.history);
+        for (domain, sources) in other.associated_domains {
+            self.associated_domains.entry(domain).or_default().extend(sources);
+        }
+    }
+
+    // Register a discovered name (returns the asset so callers can attach addresses)
+    pub fn add_subdomain(&mut self, name: &str, source: &str) -> &mut DnsAsset {
+        let name = normalise_name(name);
+        let asset = self.subdomains.entry(name.clone()).or_insert_with(|| DnsAsset {
+            name,
+            ..Default::default()
+        });
+        asset.sources.insert(source.to_string());
+        asset
+    }
+}
+
+// Lower-case a DNS name and strip the trailing root dot
+pub fn normalise_name(name: &str) -> String {
+    name.trim().trim_end_matches('.').to_lowercase()
+}
+
+// Keep the earliest first and latest last timestamp (ISO 8601, so they compare as strings)
+pub fn widen_seen(first_seen: &mut Option<String>, last_seen: &mut Option<String>, first: Option<&str>, last: Option<&str>) {
+    if let Some(first) = first
+        && first_seen.as_deref().is_none_or(|seen| first < seen) {
+        *first_seen = Some(first.to_string());
+    }
+    if let Some(last) = last
+        && last_seen.as_deref().is_none_or(|seen| last > seen) {
+        *last_seen = Some(last.to_string());
+    }
+}
+
+// True when the name is the domain itself or one of its subdomains
+pub fn is_in_domain(name: &str, domain: &str) -> bool {
+    let domain = normalise_name(domain);
+    !name.is_empty() && (name == domain || name.ends_with(&format!(".{}", domain)))
+}
@@ -0,0 +1,20 @@
+use serde::Serialize;
+
+// Parsed email authentication records for a domain
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EmailSecurity {
+    pub domain: String,
+    pub spf: Option<String>,                 // Top-level SPF record
+    pub spf_all: Option<String>,             // Qualified "all" term (-all, ~all, ?all, +all)
+    pub spf_lookups: usize,                  // DNS-querying terms across the include tree (RFC 7208 limit: 10)
+    pub spf_includes: Vec<String>,           // Every domain visited through include/redirect
+    pub dmarc: Option<String>,
+    pub dmarc_policy: Option<String>,        // p=
+    pub dmarc_subdomain_policy: Option<String>,  // sp=
+    pub dmarc_pct: Option<u8>,
+    pub dmarc_rua: Vec<String>,
+    pub dkim_selectors: Vec<String>,         // Common selectors that publish a key
+    pub mta_sts: Option<String>,
+    pub mta_sts_mode: Option<String>,        // mode: from the HTTPS policy file
+    pub tls_rpt: Option<String>,
+}
\ No newline at end of file
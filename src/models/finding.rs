@@ -0,0 +1,67 @@
+use serde::Serialize;
+use std::fmt;
+
+// Normalised severity levels shared by every tool that produces findings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum Severity {
+    Info,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    // All severities, most severe first (used for report ordering)
+    pub const DESCENDING: [Severity; 5] = [
+        Severity::Critical,
+        Severity::High,
+        Severity::Medium,
+        Severity::Low,
+        Severity::Info,
+    ];
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Severity::Info => "Info",
+            Severity::Low => "Low",
+            Severity::Medium => "Medium",
+            Severity::High => "High",
+            Severity::Critical => "Critical",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+// A single normalised finding reported by one of the scanners
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub source: String,              // Tool or module that produced the finding
+    pub id: Option<String>,          // Tool-specific identifier (e.g. Nikto test ID)
+    pub severity: Severity,
+    pub location: String,            // URL, host or domain the finding applies to
+    pub method: Option<String>,      // HTTP method for web findings
+    pub description: String,
+    pub references: Vec<String>,     // OSVDB/CVE identifiers and reference links
+}
+
+impl Finding {
+    // Fold in the same issue reported by another tool: sources and references are combined, the higher severity wins
+    pub fn merge(&mut self, other: Finding) {
+        self.source = format!("{}, {}", self.source, other.source);
+        self.severity = self.severity.max(other.severity);
+        if self.method.is_none() {
+            self.method = other.method;
+        }
+        if self.description != other.description {
+            self.description = format!("{}; {}", self.description, other.description);
+        }
+        for reference in other.references {
+            if !self.references.contains(&reference) {
+                self.references.push(reference);
+            }
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use serde::Serialize;
+
+// Location and network owner of an address, from offline GeoIP/ASN databases
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GeoInfo {
+    pub country: Option<String>,
+    pub country_code: Option<String>,        // ISO 3166-1 alpha-2
+    pub city: Option<String>,
+    pub asn: Option<u32>,
+    pub organization: Option<String>,        // AS owner
+    pub network: Option<String>,             // Range the ASN data applies to
+    pub sources: Vec<String>,                // Databases that answered (GeoLite2-City, IP2ASN, ...)
+}
+
+impl GeoInfo {
+    // "AS15169 Google LLC"
+    pub fn owner(&self) -> Option<String> {
+        match (self.asn, &self.organization) {
+            (Some(asn), Some(organization)) => Some(format!("AS{} {}", asn, organization)),
+            (Some(asn), None) => Some(format!("AS{}", asn)),
+            (None, Some(organization)) => Some(organization.clone()),
+            (None, None) => None,
+        }
+    }
+
+    // "Mountain View, US"
+    pub fn location(&self) -> Option<String> {
+        let country = self.country_code.clone().or_else(|| self.country.clone());
+        let parts: Vec<String> = [self.city.clone(), country].into_iter().flatten().collect();
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+
+    // Location and owner for a report cell: "Mountain View, US · AS15169 Google LLC"
+    pub fn summary(&self) -> String {
+        let parts: Vec<String> = [self.location(), self.owner()].into_iter().flatten().collect();
+        if parts.is_empty() { "-".to_string() } else { parts.join(" · ") }
+    }
+}
@@ -0,0 +1,282 @@
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+use crate::models::geo::GeoInfo;
+
+// Sources that report what internet-wide scanners saw earlier rather than what is open now
+pub const PASSIVE_SOURCES: [&str; 2] = ["Shodan", "Censys"];
+
+// TLS details of a service
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TlsInfo {
+    pub subject: Option<String>,     // Subject CN
+    pub issuer: Option<String>,      // Issuer CN or O
+    pub expires: Option<String>,
+    pub expired: bool,
+    pub versions: Vec<String>,       // Protocol versions offered (TLSv1.2, -SSLv3, ...)
+}
+
+// A service identified on a port
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Service {
+    pub name: String,                // http, ssh, microsoft-ds, ...
+    pub product: Option<String>,
+    pub version: Option<String>,
+    pub extra_info: Option<String>,
+    pub banner: Option<String>,
+    pub tls: Option<TlsInfo>,
+}
+
+// A port on a host as reported by one tool
+#[derive(Debug, Clone, Serialize)]
+pub struct Port {
+    pub protocol: String,            // tcp / udp
+    pub port: u16,
+    pub state: String,               // open, closed, filtered, open|filtered
+    pub service: Option<Service>,
+    pub source: String,              // Tool that reported the port
+    pub last_seen: Option<String>,   // When a passive source observed it
+}
+
+impl Port {
+    pub fn is_passive(&self) -> bool {
+        PASSIVE_SOURCES.contains(&self.source.as_str())
+    }
+}
+
+// Ports nmap probed but summarised in <extraports> instead of listing them one by one
+#[derive(Debug, Clone, Serialize)]
+pub struct UnlistedPorts {
+    pub state: String,               // closed, filtered, ...
+    pub count: usize,
+    pub ranges: Vec<(u16, u16)>,     // From <extrareasons ports=...>; empty before Nmap 7.80
+}
+
+// Ports an active scan probed for one protocol (<scaninfo services=...>)
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanCoverage {
+    pub source: String,
+    pub protocol: String,
+    pub ranges: Vec<(u16, u16)>,
+    pub unlisted: Vec<UnlistedPorts>,
+}
+
+impl ScanCoverage {
+    pub fn covers(&self, port: u16) -> bool {
+        in_ranges(&self.ranges, port)
+    }
+
+    // State a probed but unlisted port was folded into; unknown when several states hide unnamed ports
+    pub fn unlisted_state(&self, port: u16) -> Option<String> {
+        self.unlisted.iter().find(|u| in_ranges(&u.ranges, port))
+            .or(match self.unlisted.as_slice() {
+                [only] => Some(only),
+                _ => None,
+            })
+            .map(|u| u.state.clone())
+    }
+}
+
+fn in_ranges(ranges: &[(u16, u16)], port: u16) -> bool {
+    ranges.iter().any(|(start, end)| (*start..=*end).contains(&port))
+}
+
+// Nmap's port list syntax: "21-23,80,443"
+pub fn parse_port_ranges(text: &str) -> Vec<(u16, u16)> {
+    text.split(',').filter_map(|part| {
+        let part = part.trim();
+        // A protocol prefix (T:, U:) only appears in mixed lists, which scaninfo already splits
+        let part = part.split_once(':').map(|(_, p)| p).unwrap_or(part);
+        match part.split_once('-') {
+            Some((start, end)) => Some((start.parse().ok()?, end.parse().ok()?)),
+            None => part.parse().ok().map(|p| (p, p)),
+        }
+    }).collect()
+}
+
+// How a port seen by a passive source compares with the active scan of the same host
+#[derive(Debug, Clone, Serialize)]
+pub struct PortComparison {
+    pub address: String,
+    pub protocol: String,
+    pub port: u16,
+    pub status: String,              // confirmed, no longer open, newly open, not scanned
+    pub passive_sources: Vec<String>,
+    pub last_seen: Option<String>,
+    pub active_state: Option<String>,  // closed / filtered when the active scan probed the port
+}
+
+// A host in the scan scope
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Host {
+    pub address: String,
+    pub status: Option<String>,      // up / down
+    pub reason: Option<String>,      // Why the host is up: arp-response, echo-reply, syn-ack, ...
+    pub hostnames: BTreeSet<String>,
+    pub mac: Option<String>,
+    pub vendor: Option<String>,
+    pub os: Option<String>,
+    pub ports: Vec<Port>,
+    pub coverage: Vec<ScanCoverage>, // Which ports each active port scan probed
+    pub geo: Option<GeoInfo>,        // Offline GeoIP/ASN enrichment
+}
+
+impl Host {
+    pub fn new(address: &str) -> Self {
+        Host {
+            address: address.to_string(),
+            ..Default::default()
+        }
+    }
+
+    // Name to show in reports: "hostname (address)", or the bare address when no name is known
+    pub fn display_name(&self) -> String {
+        match self.hostnames.iter().next() {
+            Some(name) => format!("{} ({})", name, self.address),
+            None => self.address.clone(),
+        }
+    }
+
+    // Ports an active scan found open
+    pub fn open_ports(&self) -> impl Iterator<Item = &Port> {
+        self.ports.iter().filter(|p| p.state == "open" && !p.is_passive())
+    }
+
+    // Ports only passive sources report, once per port (the first source wins)
+    pub fn passive_only_ports(&self) -> Vec<&Port> {
+        let mut ports: Vec<&Port> = Vec::new();
+        for port in self.ports.iter().filter(|p| p.is_passive()) {
+            let known = |p: &&Port| p.port == port.port && p.protocol == port.protocol;
+            if !self.open_ports().any(|p| known(&p)) && !ports.iter().any(known) {
+                ports.push(port);
+            }
+        }
+        ports
+    }
+
+    // Passive ports against the active port scans; a port only counts as no longer open when a scan probed it
+    pub fn compare_ports(&self) -> Vec<PortComparison> {
+        let passive: Vec<&Port> = self.ports.iter().filter(|p| p.is_passive()).collect();
+        let active: Vec<&Port> = self.ports.iter().filter(|p| !p.is_passive()).collect();
+        // Discovery stops at the first port that answers, so only port scans with known coverage count
+        if passive.is_empty() || self.coverage.is_empty() {
+            return Vec::new();
+        }
+
+        let mut keys: Vec<(&str, u16)> = self.ports.iter().filter(|p| p.state == "open").map(|p| (p.protocol.as_str(), p.port)).collect();
+        keys.sort();
+        keys.dedup();
+        keys.into_iter().map(|(protocol, number)| {
+            let seen: Vec<&&Port> = passive.iter().filter(|p| p.protocol == protocol && p.port == number).collect();
+            let current = active.iter().find(|p| p.protocol == protocol && p.port == number);
+            let open = current.is_some_and(|p| p.state == "open");
+            let probed = self.coverage.iter().find(|c| c.protocol == protocol && c.covers(number));
+            let (status, active_state) = match (seen.is_empty(), open, current, probed) {
+                (false, true, _, _) => ("confirmed", None),
+                (true, _, _, _) => ("newly open", None),
+                // Listed closed or filtered
+                (false, false, Some(port), _) => ("no longer open", Some(port.state.clone())),
+                // Probed and folded into <extraports>
+                (false, false, None, Some(coverage)) => ("no longer open", coverage.unlisted_state(number)),
+                (false, false, None, None) => ("not scanned", None),
+            };
+            PortComparison {
+                address: self.address.clone(),
+                protocol: protocol.to_string(),
+                port: number,
+                status: status.to_string(),
+                passive_sources: seen.iter().map(|p| p.source.clone()).collect(),
+                last_seen: seen.iter().filter_map(|p| p.last_seen.clone()).max(),
+                active_state,
+            }
+        }).collect()
+    }
+
+    // Fold another report of the same host into this one (ports are replaced per tool)
+    pub fn merge(&mut self, other: Host) {
+        if other.status.is_some() {
+            self.status = other.status;
+            self.reason = other.reason.or(self.reason.take());
+        }
+        self.mac = other.mac.or(self.mac.take());
+        self.vendor = other.vendor.or(self.vendor.take());
+        self.os = other.os.or(self.os.take());
+        self.geo = other.geo.or(self.geo.take());
+        self.hostnames.extend(other.hostnames);
+        for port in other.ports {
+            self.ports.retain(|p| !(p.port == port.port && p.protocol == port.protocol && p.source == port.source));
+            self.ports.push(port);
+        }
+        self.ports.sort_by(|a, b| (a.protocol.as_str(), a.port).cmp(&(b.protocol.as_str(), b.port)));
+        for coverage in other.coverage {
+            self.coverage.retain(|c| !(c.source == coverage.source && c.protocol == coverage.protocol));
+            self.coverage.push(coverage);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn port(number: u16, state: &str, source: &str) -> Port {
+        Port { protocol: "tcp".to_string(), port: number, state: state.to_string(), service: None, source: source.to_string(), last_seen: None }
+    }
+
+    #[test]
+    fn compare_ports_only_reports_probed_ports_as_no_longer_open() {
+        let mut host = Host::new("192.0.2.1");
+        host.ports = vec![
+            port(22, "open", "Nmap"), port(22, "open", "Shodan"),
+            port(23, "filtered", "Nmap"), port(23, "open", "Shodan"),
+            port(25, "open", "Censys"),
+            port(8443, "open", "Shodan"),
+            port(80, "open", "Nmap"),
+        ];
+        host.coverage = vec![ScanCoverage {
+            source: "Nmap".to_string(),
+            protocol: "tcp".to_string(),
+            ranges: parse_port_ranges("1-100"),
+            unlisted: vec![UnlistedPorts { state: "closed".to_string(), count: 97, ranges: Vec::new() }],
+        }];
+
+        let statuses: Vec<(u16, String, Option<String>)> = host.compare_ports().into_iter()
+            .map(|c| (c.port, c.status, c.active_state))
+            .collect();
+        assert_eq!(statuses, vec![
+            (22, "confirmed".to_string(), None),
+            (23, "no longer open".to_string(), Some("filtered".to_string())),
+            (25, "no longer open".to_string(), Some("closed".to_string())),
+            (80, "newly open".to_string(), None),
+            (8443, "not scanned".to_string(), None),
+        ]);
+    }
+
+    #[test]
+    fn compare_ports_needs_a_port_scan() {
+        let mut host = Host::new("192.0.2.1");
+        host.ports = vec![port(80, "open", "Discovery"), port(443, "open", "Shodan")];
+        assert!(host.compare_ports().is_empty());
+    }
+
+    #[test]
+    fn unlisted_state_prefers_named_ranges() {
+        let coverage = ScanCoverage {
+            source: "Nmap".to_string(),
+            protocol: "tcp".to_string(),
+            ranges: parse_port_ranges("1-1000"),
+            unlisted: vec![
+                UnlistedPorts { state: "closed".to_string(), count: 2, ranges: parse_port_ranges("1,3") },
+                UnlistedPorts { state: "filtered".to_string(), count: 3, ranges: parse_port_ranges("4-6") },
+            ],
+        };
+        assert_eq!(coverage.unlisted_state(5).as_deref(), Some("filtered"));
+        assert_eq!(coverage.unlisted_state(3).as_deref(), Some("closed"));
+        assert_eq!(coverage.unlisted_state(500), None);
+    }
+
+    #[test]
+    fn parses_nmap_port_lists() {
+        assert_eq!(parse_port_ranges("1,3-4,T:80,bogus"), vec![(1, 1), (3, 4), (80, 80)]);
+    }
+}
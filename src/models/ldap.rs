@@ -0,0 +1,67 @@
+use serde::Serialize;
+
+use crate::models::smb::PasswordPolicy;
+
+// Server facts published in the RootDSE, readable before any bind
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LdapRootDse {
+    pub naming_contexts: Vec<String>,
+    pub default_naming_context: Option<String>,
+    pub dns_host_name: Option<String>,
+    pub server_name: Option<String>,
+    pub vendor: Option<String>,              // vendorName and vendorVersion (OpenLDAP, 389 DS, ...)
+    pub ldap_versions: Vec<String>,
+    pub sasl_mechanisms: Vec<String>,
+    pub domain_functionality: Option<String>,  // Active Directory functional levels
+    pub forest_functionality: Option<String>,
+    pub dc_functionality: Option<String>,
+    pub global_catalog: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LdapUser {
+    pub dn: String,
+    pub name: String,                        // sAMAccountName, uid or cn
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub disabled: bool,
+    pub password_not_required: bool,
+    pub password_never_expires: bool,
+    pub preauth_not_required: bool,          // AS-REP roastable
+    pub service_principal_names: Vec<String>,  // Kerberoastable when set on a user
+    pub admin_count: bool,
+    pub member_of: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LdapGroup {
+    pub dn: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub members: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LdapComputer {
+    pub dn: String,
+    pub name: String,
+    pub dns_host_name: Option<String>,
+    pub os: Option<String>,
+}
+
+// Everything read from one directory server
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LdapInfo {
+    pub target: String,
+    pub port: u16,
+    pub tls: bool,
+    pub root_dse: LdapRootDse,
+    pub anonymous_bind: Option<bool>,        // Bind with empty credentials accepted
+    pub anonymous_search: Option<bool>,      // Directory readable after that bind
+    pub bound_as: Option<String>,            // Supplied credentials that were used
+    pub users: Vec<LdapUser>,
+    pub groups: Vec<LdapGroup>,
+    pub computers: Vec<LdapComputer>,
+    pub password_policy: Option<PasswordPolicy>,
+    pub machine_account_quota: Option<i64>,
+}
\ No newline at end of file
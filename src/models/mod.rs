@@ -1 +1,31 @@
-pub mod scan;
\ No newline at end of file
+pub mod scan;
+pub mod finding;
+pub mod technology;
+pub mod censys;
+pub mod dns;
+pub mod email;
+pub mod geo;
+pub mod host;
+pub mod ldap;
+pub mod network;
+pub mod reputation;
+pub mod shodan;
+pub mod smb;
+pub mod snmp;
+pub mod virustotal;
+
+pub use scan::*;
+pub use finding::*;
+pub use technology::*;
+pub use censys::*;
+pub use dns::*;
+pub use email::*;
+pub use geo::*;
+pub use host::*;
+pub use ldap::*;
+pub use network::*;
+pub use reputation::*;
+pub use shodan::*;
+pub use smb::*;
+pub use snmp::*;
+pub use virustotal::*;
\ No newline at end of file
@@ -0,0 +1,169 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::models::geo::GeoInfo;
+
+// One responder at a given TTL (several per TTL when paths are load-balanced; no address for timeouts)
+#[derive(Debug, Clone, Serialize)]
+pub struct Hop {
+    pub ttl: u8,
+    pub address: Option<String>,
+    pub hostname: Option<String>,    // PTR name reported by traceroute
+    pub asn: Option<String>,         // Origin AS (traceroute -A)
+    pub rtts: Vec<f64>,              // Round-trip times in milliseconds
+    pub geo: Option<GeoInfo>,        // Offline GeoIP/ASN enrichment
+}
+
+// The path to one target
+#[derive(Debug, Clone, Serialize)]
+pub struct Trace {
+    pub target: String,
+    pub destination: Option<String>, // Address traceroute resolved the target to
+    pub reached: bool,
+    pub hops: Vec<Hop>,
+}
+
+// A router, target or unanswered hop in the merged topology
+#[derive(Debug, Clone, Serialize)]
+pub struct TopologyNode {
+    pub id: String,
+    pub address: Option<String>,
+    pub hostname: Option<String>,
+    pub asn: Option<String>,
+    pub is_target: bool,
+    pub traces: BTreeSet<String>,    // Targets whose path runs through this node
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct TopologyEdge {
+    pub from: String,
+    pub to: String,
+    pub inferred: bool,              // Target not reached: link from the last responding hop
+}
+
+// Every trace merged into one graph, starting at the scanning host
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Topology {
+    pub nodes: BTreeMap<String, TopologyNode>,
+    pub edges: BTreeSet<TopologyEdge>,
+}
+
+// Node id of the scanning host
+pub const SCANNER_NODE: &str = "scanner";
+
+impl Topology {
+    pub fn from_traces(traces: &[Trace]) -> Self {
+        let mut topology = Topology::default();
+        topology.add_node(SCANNER_NODE, None, None);
+
+        for trace in traces {
+            let mut previous: Vec<String> = vec![SCANNER_NODE.to_string()];
+            let ttls: BTreeSet<u8> = trace.hops.iter().map(|h| h.ttl).collect();
+
+            for ttl in ttls {
+                let mut current = Vec::new();
+                for hop in trace.hops.iter().filter(|h| h.ttl == ttl) {
+                    // Unanswered hops are kept per trace so unrelated paths don't merge through "*"
+                    let id = match &hop.address {
+                        Some(address) => address.clone(),
+                        None => format!("unknown:{}:{}", trace.target, ttl),
+                    };
+                    // The offline ASN stands in when traceroute -A reported none
+                    let asn = hop.asn.clone().or_else(|| hop.geo.as_ref().and_then(|g| g.asn).map(|asn| format!("AS{}", asn)));
+                    let node = topology.add_node(&id, hop.address.clone(), asn);
+                    if node.hostname.is_none() {
+                        node.hostname = hop.hostname.clone();
+                    }
+                    node.traces.insert(trace.target.clone());
+                    if !current.contains(&id) {
+                        current.push(id);
+                    }
+                }
+                for from in &previous {
+                    for to in &current {
+                        topology.edges.insert(TopologyEdge { from: from.clone(), to: to.clone(), inferred: false });
+                    }
+                }
+                previous = current;
+            }
+
+            // Mark the destination, adding it with an inferred link when traceroute never reached it
+            let destination = trace.destination.clone().unwrap_or_else(|| trace.target.clone());
+            let node = topology.add_node(&destination, Some(destination.clone()), None);
+            node.is_target = true;
+            node.traces.insert(trace.target.clone());
+            if !trace.reached {
+                for from in previous.iter().filter(|p| **p != destination) {
+                    topology.edges.insert(TopologyEdge { from: from.clone(), to: destination.clone(), inferred: true });
+                }
+            }
+        }
+
+        topology
+    }
+
+    fn add_node(&mut self, id: &str, address: Option<String>, asn: Option<String>) -> &mut TopologyNode {
+        let node = self.nodes.entry(id.to_string()).or_insert_with(|| TopologyNode {
+            id: id.to_string(),
+            address: None,
+            hostname: None,
+            asn: None,
+            is_target: false,
+            traces: BTreeSet::new(),
+        });
+        node.address = node.address.take().or(address);
+        node.asn = node.asn.take().or(asn);
+        node
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hop(ttl: u8, address: Option<&str>) -> Hop {
+        Hop { ttl, address: address.map(|a| a.to_string()), hostname: None, asn: None, rtts: Vec::new(), geo: None }
+    }
+
+    fn trace(target: &str, reached: bool, hops: Vec<Hop>) -> Trace {
+        Trace { target: target.to_string(), destination: Some(target.to_string()), reached, hops }
+    }
+
+    fn edges(topology: &Topology) -> Vec<String> {
+        topology.edges.iter()
+            .map(|e| format!("{} -> {}{}", e.from, e.to, if e.inferred { " (inferred)" } else { "" }))
+            .collect()
+    }
+
+    #[test]
+    fn traces_merge_through_shared_routers() {
+        let traces = vec![
+            trace("203.0.113.5", true, vec![hop(1, Some("192.168.1.1")), hop(2, None), hop(3, Some("198.51.100.1")),
+                hop(3, Some("198.51.100.2")), hop(4, Some("203.0.113.5"))]),
+            trace("203.0.113.9", false, vec![hop(1, Some("192.168.1.1")), hop(2, None)]),
+        ];
+        let topology = Topology::from_traces(&traces);
+
+        assert_eq!(edges(&topology), vec![
+            "192.168.1.1 -> unknown:203.0.113.5:2",
+            "192.168.1.1 -> unknown:203.0.113.9:2",
+            "198.51.100.1 -> 203.0.113.5",
+            "198.51.100.2 -> 203.0.113.5",
+            "scanner -> 192.168.1.1",
+            "unknown:203.0.113.5:2 -> 198.51.100.1",
+            "unknown:203.0.113.5:2 -> 198.51.100.2",
+            "unknown:203.0.113.9:2 -> 203.0.113.9 (inferred)",
+        ]);
+        assert_eq!(topology.nodes["192.168.1.1"].traces.len(), 2);
+        assert!(topology.nodes["203.0.113.9"].is_target && topology.nodes["203.0.113.5"].is_target);
+        assert!(!topology.nodes["198.51.100.1"].is_target);
+    }
+
+    #[test]
+    fn offline_asn_fills_in_for_traceroute() {
+        let mut annotated = hop(1, Some("198.51.100.1"));
+        annotated.geo = Some(GeoInfo { asn: Some(64500), ..Default::default() });
+        let topology = Topology::from_traces(&[trace("198.51.100.1", true, vec![annotated])]);
+        assert_eq!(topology.nodes["198.51.100.1"].asn.as_deref(), Some("AS64500"));
+    }
+}
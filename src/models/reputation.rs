@@ -0,0 +1,65 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+// What GreyNoise knows about an address scanning the internet
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GreyNoiseInfo {
+    pub ip: String,
+    pub plan: String,                        // community / enterprise
+    pub noise: bool,                         // Seen scanning the internet
+    pub riot: bool,                          // Known business service (CDN, SaaS, DNS resolver, ...)
+    pub classification: Option<String>,      // benign / malicious / suspicious / unknown
+    pub name: Option<String>,                // Actor or service name
+    pub category: Option<String>,            // RIOT category (enterprise)
+    pub trust_level: Option<String>,         // RIOT trust level (enterprise)
+    pub tags: Vec<String>,                   // Scanner behaviour tags (enterprise)
+    pub cves: Vec<String>,                   // CVEs it was seen exploiting (enterprise)
+    pub first_seen: Option<String>,
+    pub last_seen: Option<String>,
+    pub country: Option<String>,
+    pub organization: Option<String>,
+    pub tor: bool,
+    pub vpn_service: Option<String>,
+    pub link: Option<String>,                // GreyNoise visualizer page
+}
+
+// One report filed against an address on AbuseIPDB
+#[derive(Debug, Clone, Serialize)]
+pub struct AbuseReport {
+    pub reported_at: String,
+    pub categories: Vec<String>,
+    pub reporter_country: Option<String>,
+    pub comment: Option<String>,
+}
+
+// AbuseIPDB check of one address
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AbuseIpdbInfo {
+    pub ip: String,
+    pub max_age_days: u32,                   // Reporting window the check covers
+    pub confidence: u8,                      // Abuse confidence score, 0-100
+    pub total_reports: u64,
+    pub distinct_reporters: u64,
+    pub last_reported: Option<String>,
+    pub country: Option<String>,
+    pub usage_type: Option<String>,
+    pub isp: Option<String>,
+    pub domain: Option<String>,
+    pub hostnames: Vec<String>,
+    pub tor: bool,
+    pub whitelisted: bool,
+    pub reports: Vec<AbuseReport>,           // Most recent first
+}
+
+impl AbuseIpdbInfo {
+    // Report categories by how often they were used, most common first
+    pub fn top_categories(&self) -> Vec<(String, usize)> {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for category in self.reports.iter().flat_map(|r| r.categories.iter()) {
+            *counts.entry(category.clone()).or_insert(0) += 1;
+        }
+        let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+        counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        counts
+    }
+}
@@ -1,3 +1,334 @@
-// Common data structures for the application 
-// This file is created as a placeholder for future use
-// Currently, the application does not require complex data structures
\ No newline at end of file
+// Common data structures for the application
+// Results from every scanner are collected here so the summary and
+// consolidated report can be built from parsed data rather than raw files
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+
+use crate::models::censys::CensysInfo;
+use crate::models::dns::{is_in_domain, normalise_name, DnsInventory};
+use crate::models::email::EmailSecurity;
+use crate::models::finding::{Finding, Severity};
+use crate::models::geo::GeoInfo;
+use crate::models::host::{Host, PortComparison};
+use crate::models::ldap::LdapInfo;
+use crate::models::network::{Topology, Trace};
+use crate::models::reputation::{AbuseIpdbInfo, GreyNoiseInfo};
+use crate::models::shodan::ShodanInfo;
+use crate::models::smb::SmbInfo;
+use crate::models::snmp::SnmpInfo;
+use crate::models::technology::{StackEntry, Technology};
+use crate::models::virustotal::VirusTotalInfo;
+
+// Parsed results for a single target
+#[derive(Debug, Default, Serialize)]
+pub struct ScanResults {
+    pub target: String,
+    pub started: String,
+    pub hosts: BTreeMap<String, Host>,          // Address -> host
+    pub findings: Vec<Finding>,
+    pub technologies: Vec<Technology>,
+    pub dns: DnsInventory,
+    pub email: Vec<EmailSecurity>,             // One per analysed domain
+    pub traces: Vec<Trace>,
+    pub smb: Vec<SmbInfo>,                     // One per host, merged across SMB tools
+    pub snmp: Vec<SnmpInfo>,                   // One per agent that answered
+    pub ldap: Vec<LdapInfo>,                   // One per directory server
+    pub shodan: Vec<ShodanInfo>,               // One per looked up target
+    pub censys: Vec<CensysInfo>,
+    pub virustotal: Vec<VirusTotalInfo>,
+    pub greynoise: Vec<GreyNoiseInfo>,
+    pub abuseipdb: Vec<AbuseIpdbInfo>,
+    pub raw_outputs: BTreeMap<String, String>,  // Tool name -> raw output file
+    pub errors: Vec<String>,                    // Scans that failed outright (pipeline hosts)
+}
+
+impl ScanResults {
+    pub fn new(target: &str) -> Self {
+        ScanResults {
+            target: target.to_string(),
+            started: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            ..Default::default()
+        }
+    }
+
+    // Remember which file holds the unparsed output of a tool
+    pub fn record_raw_output(&mut self, tool: &str, filename: &str) {
+        self.raw_outputs.insert(tool.to_string(), filename.to_string());
+    }
+
+    // The host with this address, created on first use
+    pub fn host_mut(&mut self, address: &str) -> &mut Host {
+        self.hosts.entry(address.to_string()).or_insert_with(|| Host::new(address))
+    }
+
+    // Merge a host reported by a scanner into the inventory
+    pub fn merge_host(&mut self, host: Host) {
+        let address = host.address.clone();
+        self.host_mut(&address).merge(host);
+    }
+
+    // Fold the results of a per-host scan into a network-wide view (raw files are relative to `directory`)
+    pub fn merge_results(&mut self, other: ScanResults, directory: &str) {
+        for host in other.hosts.into_values() {
+            self.merge_host(host);
+        }
+        self.add_findings(other.findings);
+        self.technologies.extend(other.technologies);
+        self.dns.merge(other.dns);
+        self.email.extend(other.email);
+        for smb in other.smb {
+            self.merge_smb(smb);
+        }
+        self.traces.extend(other.traces);
+        self.snmp.extend(other.snmp);
+        self.ldap.extend(other.ldap);
+        self.shodan.extend(other.shodan);
+        self.censys.extend(other.censys);
+        self.virustotal.extend(other.virustotal);
+        self.greynoise.extend(other.greynoise);
+        self.abuseipdb.extend(other.abuseipdb);
+        self.errors.extend(other.errors.into_iter().map(|e| format!("{}: {}", other.target, e)));
+        for (tool, filename) in other.raw_outputs {
+            self.raw_outputs.insert(format!("{} ({})", tool, other.target), format!("{}/{}", directory, filename));
+        }
+    }
+
+    // Record findings; one another tool already reported for the same location is merged rather than repeated
+    pub fn add_findings(&mut self, findings: Vec<Finding>) {
+        for finding in findings {
+            // Only merge across tools: one tool may report several findings with the same id (e.g. per account)
+            let existing = finding.id.as_ref().and_then(|id| self.findings.iter_mut().find(|f| {
+                f.id.as_ref() == Some(id) && f.location == finding.location
+                    && !f.source.split(", ").any(|s| s == finding.source)
+            }));
+            match existing {
+                Some(existing) => existing.merge(finding),
+                None => self.findings.push(finding),
+            }
+        }
+    }
+
+    // Record SMB facts, merging with what another tool already found
+    pub fn merge_smb(&mut self, smb: SmbInfo) {
+        if let Some(os) = &smb.os {
+            let host = self.host_mut(&smb.target);
+            if host.os.is_none() {
+                host.os = Some(os.clone());
+            }
+        }
+        if let Some(name) = &smb.dns_name {
+            self.host_mut(&smb.target).hostnames.insert(name.to_lowercase());
+        }
+        match self.smb.iter_mut().find(|s| s.target == smb.target) {
+            Some(existing) => existing.merge(smb),
+            None => self.smb.push(smb),
+        }
+    }
+
+    // Record what an SNMP agent disclosed; sysDescr stands in for the OS when nothing better is known
+    pub fn merge_snmp(&mut self, snmp: SnmpInfo) {
+        if let Some(description) = &snmp.system.description {
+            let host = self.host_mut(&snmp.target);
+            if host.os.is_none() {
+                host.os = Some(description.clone());
+            }
+        }
+        self.snmp.push(snmp);
+    }
+
+    // Record a directory server; its DNS host name also names the host
+    pub fn merge_ldap(&mut self, ldap: LdapInfo) {
+        if let Some(name) = &ldap.root_dse.dns_host_name {
+            self.host_mut(&ldap.target).hostnames.insert(name.to_lowercase());
+        }
+        self.ldap.push(ldap);
+    }
+
+    // Merge a host reported by an internet-wide scanner: what active tools found takes precedence
+    fn merge_passive_host(&mut self, mut host: Host) {
+        if self.hosts.get(&host.address).is_some_and(|h| h.os.is_some()) {
+            host.os = None;
+        }
+        self.merge_host(host);
+    }
+
+    // Record a Shodan lookup; the host it describes joins the inventory with its ports marked as passive
+    pub fn merge_shodan(&mut self, shodan: ShodanInfo) {
+        if let Some(host) = &shodan.host {
+            let mut host = host.to_host();
+            host.hostnames.extend(shodan.resolved.iter()
+                .filter(|(_, ip)| **ip == host.address)
+                .map(|(name, _)| name.to_lowercase()));
+            self.merge_passive_host(host);
+        }
+        self.shodan.push(shodan);
+    }
+
+    // Record a Censys lookup; every host it describes joins the inventory with passive ports
+    pub fn merge_censys(&mut self, censys: CensysInfo) {
+        for host in &censys.hosts {
+            self.merge_passive_host(host.to_host());
+        }
+        self.censys.push(censys);
+    }
+
+    // Record a VirusTotal lookup; subdomains it knows for a domain join the DNS inventory
+    pub fn merge_virustotal(&mut self, virustotal: VirusTotalInfo) {
+        if virustotal.kind == "domain" {
+            for name in &virustotal.subdomains {
+                if is_in_domain(&normalise_name(name), &virustotal.target) {
+                    self.dns.add_subdomain(name, "VirusTotal");
+                }
+            }
+        }
+        self.virustotal.push(virustotal);
+    }
+
+    // Annotate every host and traceroute hop with location and ASN data; returns how many addresses were found
+    pub fn annotate_geo(&mut self, lookup: impl Fn(IpAddr) -> Option<GeoInfo>) -> usize {
+        let mut found = 0;
+        let hops = self.traces.iter_mut().flat_map(|t| t.hops.iter_mut()).map(|h| (h.address.as_deref(), &mut h.geo));
+        let hosts = self.hosts.values_mut().map(|h| (Some(h.address.as_str()), &mut h.geo));
+        for (address, geo) in hosts.chain(hops) {
+            if let Some(address) = address.and_then(|a| a.parse::<IpAddr>().ok())
+                && let Some(info) = lookup(address) {
+                *geo = Some(info);
+                found += 1;
+            }
+        }
+        found
+    }
+
+    // Hosts ordered by address (numerically for IPs)
+    pub fn sorted_hosts(&self) -> Vec<&Host> {
+        let mut hosts: Vec<&Host> = self.hosts.values().collect();
+        hosts.sort_by_key(|h| (h.address.parse::<std::net::IpAddr>().ok(), h.address.clone()));
+        hosts
+    }
+
+    // Number of findings at the given severity
+    pub fn count_findings(&self, severity: Severity) -> usize {
+        self.findings.iter().filter(|f| f.severity == severity).count()
+    }
+
+    // Findings ordered from most to least severe
+    pub fn sorted_findings(&self) -> Vec<&Finding> {
+        let mut findings: Vec<&Finding> = self.findings.iter().collect();
+        findings.sort_by_key(|f| std::cmp::Reverse(f.severity));
+        findings
+    }
+
+    // Ports passive sources reported, checked against the active scans of the same hosts
+    pub fn port_comparison(&self) -> Vec<PortComparison> {
+        self.sorted_hosts().iter().flat_map(|h| h.compare_ports()).collect()
+    }
+
+    // Network topology merged from every traceroute
+    pub fn topology(&self) -> Topology {
+        Topology::from_traces(&self.traces)
+    }
+
+    // Group technologies across every scanned URL by product and version
+    pub fn technology_stack(&self) -> Vec<StackEntry> {
+        let mut stack: BTreeMap<(String, Option<String>), StackEntry> = BTreeMap::new();
+        for tech in &self.technologies {
+            let key = (tech.product.to_lowercase(), tech.version.clone());
+            let entry = stack.entry(key).or_insert_with(|| StackEntry {
+                product: tech.product.clone(),
+                version: tech.version.clone(),
+                category: tech.category.clone(),
+                urls: Vec::new(),
+            });
+            if !entry.urls.contains(&tech.url) {
+                entry.urls.push(tech.url.clone());
+            }
+        }
+        stack.into_values().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::dns::DnsRecord;
+
+    fn host_results(address: &str, name: &str) -> ScanResults {
+        let mut results = ScanResults::new(address);
+        results.dns.add_record(address, DnsRecord {
+            source: "Reverse DNS".to_string(),
+            record_type: "PTR".to_string(),
+            name: name.to_string(),
+            address: Some(address.to_string()),
+            target: None,
+            port: None,
+            data: None,
+        });
+        results.merge_smb(SmbInfo { target: address.to_string(), workgroup: Some("LAB".to_string()), ..Default::default() });
+        results
+    }
+
+    #[test]
+    fn merge_results_keeps_dns_smb_and_errors_per_host() {
+        let mut network = ScanResults::new("192.0.2.0/30");
+        network.merge_results(host_results("192.0.2.1", "a.example.com"), "192_0_2_1");
+        let mut second = host_results("192.0.2.2", "b.example.com");
+        second.errors.push("Scan failed: timeout".to_string());
+        network.merge_results(second, "192_0_2_2");
+
+        assert_eq!(network.dns.records.len(), 2);
+        assert_eq!(network.dns.addresses.len(), 2);
+        assert_eq!(network.smb.iter().map(|s| s.target.as_str()).collect::<Vec<_>>(), vec!["192.0.2.1", "192.0.2.2"]);
+        assert_eq!(network.errors, vec!["192.0.2.2: Scan failed: timeout".to_string()]);
+    }
+
+    fn axfr_finding(source: &str, severity: Severity, description: &str) -> Finding {
+        Finding {
+            source: source.to_string(),
+            id: Some("dns-axfr".to_string()),
+            severity,
+            location: "example.com".to_string(),
+            method: None,
+            description: description.to_string(),
+            references: vec!["https://www.rfc-editor.org/rfc/rfc5936".to_string()],
+        }
+    }
+
+    #[test]
+    fn add_findings_merges_the_same_issue_from_different_tools() {
+        let mut results = ScanResults::new("example.com");
+        results.add_findings(vec![axfr_finding("DNSrecon", Severity::Medium, "allowed by ns1")]);
+        results.add_findings(vec![axfr_finding("Zone transfer", Severity::High, "allowed by ns1, ns2")]);
+        results.add_findings(vec![axfr_finding("Zone transfer", Severity::High, "allowed by ns1, ns2")]);
+
+        assert_eq!(results.findings.len(), 2);
+        let merged = &results.findings[0];
+        assert_eq!(merged.source, "DNSrecon, Zone transfer");
+        assert_eq!(merged.severity, Severity::High);
+        assert_eq!(merged.description, "allowed by ns1; allowed by ns1, ns2");
+        assert_eq!(merged.references.len(), 1);
+    }
+
+    #[test]
+    fn add_findings_keeps_repeated_ids_from_one_tool() {
+        let password_hint = |source: &str, id: &str, location: &str, account: &str| Finding {
+            source: source.to_string(),
+            id: Some(id.to_string()),
+            severity: Severity::High,
+            location: location.to_string(),
+            method: None,
+            description: format!("Account description of {} may contain a password: Password1", account),
+            references: Vec::new(),
+        };
+        let mut results = ScanResults::new("10.0.0.5");
+        results.add_findings(vec![
+            password_hint("LDAP", "ldap-password-in-description", "10.0.0.5:389", "alice"),
+            password_hint("LDAP", "ldap-password-in-description", "10.0.0.5:389", "bob"),
+        ]);
+        results.add_findings(vec![password_hint("SMB", "smb-password-in-description", "10.0.0.5\\carol", "carol")]);
+
+        let descriptions: Vec<&str> = results.findings.iter().map(|f| f.description.as_str()).collect();
+        assert_eq!(descriptions.len(), 3);
+        assert!(descriptions[1].contains("bob") && !descriptions[0].contains("bob"));
+    }
+}
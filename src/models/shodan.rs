@@ -0,0 +1,138 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::models::host::{Host, Port, Service, TlsInfo};
+
+// A vulnerability Shodan inferred from a banner (product and version, rarely verified)
+#[derive(Debug, Clone, Serialize)]
+pub struct ShodanVuln {
+    pub cve: String,
+    pub cvss: Option<f64>,
+    pub summary: Option<String>,
+    pub verified: bool,
+}
+
+// One banner from the data[] array of a host lookup
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ShodanService {
+    pub port: u16,
+    pub transport: String,                   // tcp / udp
+    pub module: Option<String>,              // Shodan crawler module: http, ssh, https-simple-new, ...
+    pub product: Option<String>,
+    pub version: Option<String>,
+    pub banner: Option<String>,
+    pub cpe: Vec<String>,
+    pub ssl: Option<TlsInfo>,
+    pub vulns: Vec<ShodanVuln>,              // Tied to this banner
+    pub timestamp: Option<String>,           // When Shodan collected the banner
+}
+
+// What Shodan knows about one IP address
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ShodanHost {
+    pub ip: String,
+    pub hostnames: Vec<String>,
+    pub domains: Vec<String>,
+    pub org: Option<String>,
+    pub isp: Option<String>,
+    pub asn: Option<String>,
+    pub os: Option<String>,
+    pub country: Option<String>,
+    pub city: Option<String>,
+    pub tags: Vec<String>,                   // cloud, vpn, self-signed, ...
+    pub last_update: Option<String>,
+    pub ports: Vec<u16>,
+    pub services: Vec<ShodanService>,
+    pub vulns: Vec<String>,
+}
+
+impl ShodanHost {
+    // The host as Shodan last saw it, with every banner as an open port
+    pub fn to_host(&self) -> Host {
+        let mut host = Host::new(&self.ip);
+        host.hostnames.extend(self.hostnames.iter().map(|h| h.to_lowercase()));
+        host.os = self.os.clone();
+        host.ports = self.services.iter().map(|service| Port {
+            protocol: service.transport.clone(),
+            port: service.port,
+            state: "open".to_string(),
+            service: Some(Service {
+                // Crawler modules are named after the protocol: http-simple-new, https, ssh, ...
+                name: service.module.as_deref()
+                    .map(|m| m.split('-').next().unwrap_or(m).to_string())
+                    .unwrap_or_default(),
+                product: service.product.clone(),
+                version: service.version.clone(),
+                extra_info: None,
+                banner: service.banner.clone(),
+                tls: service.ssl.clone(),
+            }),
+            source: "Shodan".to_string(),
+            last_seen: service.timestamp.clone(),
+        }).collect();
+        host
+    }
+}
+
+// A DNS record from Shodan's domain database
+#[derive(Debug, Clone, Serialize)]
+pub struct ShodanDnsRecord {
+    pub subdomain: String,                   // Empty for the apex
+    pub record_type: String,
+    pub value: String,
+    pub last_seen: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ShodanDomain {
+    pub domain: String,
+    pub tags: Vec<String>,
+    pub subdomains: Vec<String>,
+    pub records: Vec<ShodanDnsRecord>,
+}
+
+// A host matching a search query
+#[derive(Debug, Clone, Serialize)]
+pub struct ShodanMatch {
+    pub ip: String,
+    pub port: u16,
+    pub transport: String,
+    pub product: Option<String>,
+    pub org: Option<String>,
+    pub hostnames: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ShodanFacetValue {
+    pub value: String,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ShodanSearch {
+    pub query: String,
+    pub total: u64,
+    pub matches: Vec<ShodanMatch>,
+    pub facets: BTreeMap<String, Vec<ShodanFacetValue>>,  // Facet name -> top values
+}
+
+// Plan and remaining credits of the API key
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ShodanAccount {
+    pub plan: Option<String>,
+    pub query_credits: Option<i64>,
+    pub scan_credits: Option<i64>,
+    pub monitored_ips: Option<i64>,
+}
+
+// Everything one Shodan lookup returned
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ShodanInfo {
+    pub target: String,
+    pub resolved: BTreeMap<String, String>,  // Hostname -> IP for domain targets
+    pub host: Option<ShodanHost>,
+    pub honeyscore: Option<f64>,             // 0.0 (real system) to 1.0 (honeypot)
+    pub domain: Option<ShodanDomain>,
+    pub search: Option<ShodanSearch>,
+    pub account: Option<ShodanAccount>,
+}
\ No newline at end of file
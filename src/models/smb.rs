@@ -0,0 +1,119 @@
+use serde::Serialize;
+
+// A share and what an anonymous session could do with it
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SmbShare {
+    pub name: String,
+    pub share_type: Option<String>,          // Disk, IPC, Printer
+    pub comment: Option<String>,
+    pub mapping: Option<String>,             // OK / DENIED / N/A as reported by the tool
+    pub listing: Option<String>,
+    pub writing: Option<String>,
+}
+
+impl SmbShare {
+    pub fn readable(&self) -> bool {
+        is_ok(&self.mapping) && is_ok(&self.listing)
+    }
+
+    pub fn writable(&self) -> bool {
+        is_ok(&self.writing)
+    }
+}
+
+fn is_ok(value: &Option<String>) -> bool {
+    value.as_deref().is_some_and(|v| v.eq_ignore_ascii_case("ok"))
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SmbUser {
+    pub name: String,
+    pub rid: Option<String>,
+    pub full_name: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SmbGroup {
+    pub name: String,
+    pub rid: Option<String>,
+    pub kind: Option<String>,                // builtin / local / domain
+    pub members: Vec<String>,
+}
+
+// Domain password and lockout policy
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PasswordPolicy {
+    pub min_length: Option<u32>,
+    pub history_length: Option<u32>,
+    pub max_age: Option<String>,
+    pub min_age: Option<String>,
+    pub complexity: Option<bool>,
+    pub lockout_threshold: Option<u32>,      // 0 when accounts are never locked out
+    pub lockout_duration: Option<String>,
+    pub lockout_window: Option<String>,
+}
+
+// Everything learned about a host over SMB/RPC
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SmbInfo {
+    pub target: String,
+    pub sources: Vec<String>,                // Tools that contributed
+    pub workgroup: Option<String>,           // Domain or workgroup name
+    pub domain_sid: Option<String>,
+    pub netbios_name: Option<String>,
+    pub os: Option<String>,
+    pub server: Option<String>,              // Server software (e.g. Samba 4.3.11)
+    pub dns_domain: Option<String>,
+    pub dns_name: Option<String>,            // Fully qualified host name from NTLM
+    pub dialects: Vec<String>,               // Supported SMB2/3 dialects
+    pub smb1: Option<bool>,
+    pub signing_required: Option<bool>,
+    pub null_session: Option<bool>,
+    pub users: Vec<SmbUser>,
+    pub groups: Vec<SmbGroup>,
+    pub shares: Vec<SmbShare>,
+    pub password_policy: Option<PasswordPolicy>,
+}
+
+impl SmbInfo {
+    // Fold the results of another tool into this one, keeping values already known
+    pub fn merge(&mut self, other: SmbInfo) {
+        for source in other.sources {
+            if !self.sources.contains(&source) {
+                self.sources.push(source);
+            }
+        }
+        self.workgroup = self.workgroup.take().or(other.workgroup);
+        self.domain_sid = self.domain_sid.take().or(other.domain_sid);
+        self.netbios_name = self.netbios_name.take().or(other.netbios_name);
+        self.os = self.os.take().or(other.os);
+        self.server = self.server.take().or(other.server);
+        self.dns_domain = self.dns_domain.take().or(other.dns_domain);
+        self.dns_name = self.dns_name.take().or(other.dns_name);
+        for dialect in other.dialects {
+            if !self.dialects.contains(&dialect) {
+                self.dialects.push(dialect);
+            }
+        }
+        self.smb1 = self.smb1.or(other.smb1);
+        self.signing_required = self.signing_required.or(other.signing_required);
+        self.null_session = self.null_session.or(other.null_session);
+        for user in other.users {
+            if !self.users.iter().any(|u| u.name == user.name) {
+                self.users.push(user);
+            }
+        }
+        for group in other.groups {
+            if !self.groups.iter().any(|g| g.name == group.name) {
+                self.groups.push(group);
+            }
+        }
+        for share in other.shares {
+            if !self.shares.iter().any(|s| s.name == share.name) {
+                self.shares.push(share);
+            }
+        }
+        self.password_policy = self.password_policy.take().or(other.password_policy);
+    }
+}
\ No newline at end of file
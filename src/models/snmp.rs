@@ -0,0 +1,78 @@
+use serde::Serialize;
+
+// A community string the agent accepted
+#[derive(Debug, Clone, Serialize)]
+pub struct SnmpCommunity {
+    pub name: String,
+    pub versions: Vec<String>,               // v1 / v2c
+}
+
+// An SNMPv3 user name the agent confirmed to exist
+#[derive(Debug, Clone, Serialize)]
+pub struct SnmpUser {
+    pub name: String,
+    pub no_auth: bool,                       // Usable without authentication (noAuthNoPriv)
+}
+
+// The MIB-II system group
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SnmpSystem {
+    pub description: Option<String>,
+    pub object_id: Option<String>,
+    pub uptime: Option<String>,
+    pub contact: Option<String>,
+    pub name: Option<String>,
+    pub location: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SnmpInterface {
+    pub index: u32,
+    pub name: Option<String>,
+    pub kind: Option<String>,                // ethernetCsmacd, softwareLoopback, ...
+    pub mtu: Option<u64>,
+    pub speed: Option<u64>,                  // Bits per second
+    pub mac: Option<String>,
+    pub admin_status: Option<String>,
+    pub oper_status: Option<String>,
+    pub addresses: Vec<String>,              // address/prefix
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SnmpRoute {
+    pub destination: String,
+    pub mask: Option<String>,
+    pub next_hop: Option<String>,
+    pub interface: Option<u32>,
+    pub route_type: Option<String>,          // direct / indirect
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SnmpProcess {
+    pub pid: u32,
+    pub name: Option<String>,
+    pub path: Option<String>,
+    pub parameters: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SnmpSoftware {
+    pub name: String,
+    pub installed: Option<String>,
+}
+
+// Everything an SNMP agent disclosed
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SnmpInfo {
+    pub target: String,
+    pub communities: Vec<SnmpCommunity>,
+    pub engine_id: Option<String>,           // SNMPv3 authoritative engine (hex)
+    pub engine_boots: Option<i64>,
+    pub engine_time: Option<i64>,            // Seconds since the last boot
+    pub users: Vec<SnmpUser>,
+    pub system: SnmpSystem,
+    pub interfaces: Vec<SnmpInterface>,
+    pub routes: Vec<SnmpRoute>,
+    pub processes: Vec<SnmpProcess>,
+    pub software: Vec<SnmpSoftware>,
+}
\ No newline at end of file
@@ -0,0 +1,21 @@
+use serde::Serialize;
+
+// A web technology identified on a URL (by WhatWeb or the built-in fingerprinter)
+#[derive(Debug, Clone, Serialize)]
+pub struct Technology {
+    pub source: String,              // Tool that identified the technology
+    pub url: String,
+    pub product: String,
+    pub version: Option<String>,
+    pub category: String,
+    pub evidence: Vec<String>,       // Strings/values that matched
+}
+
+// One row of the cross-target technology stack: a product/version and where it runs
+#[derive(Debug, Clone, Serialize)]
+pub struct StackEntry {
+    pub product: String,
+    pub version: Option<String>,
+    pub category: String,
+    pub urls: Vec<String>,
+}
\ No newline at end of file
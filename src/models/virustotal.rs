@@ -0,0 +1,51 @@
+use serde::Serialize;
+
+// Verdicts of the antivirus engines and URL scanners in the last analysis
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DetectionStats {
+    pub malicious: u64,
+    pub suspicious: u64,
+    pub harmless: u64,
+    pub undetected: u64,
+}
+
+impl DetectionStats {
+    pub fn total(&self) -> u64 {
+        self.malicious + self.suspicious + self.harmless + self.undetected
+    }
+}
+
+// A passive DNS resolution VirusTotal observed
+#[derive(Debug, Clone, Serialize)]
+pub struct VtResolution {
+    pub host_name: String,
+    pub ip_address: String,
+    pub date: Option<String>,
+}
+
+// A file that contacted the target when executed in a sandbox
+#[derive(Debug, Clone, Serialize)]
+pub struct VtFile {
+    pub sha256: String,
+    pub name: Option<String>,
+    pub file_type: Option<String>,
+    pub stats: DetectionStats,
+}
+
+// Reputation and relationships of an IP address or domain
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VirusTotalInfo {
+    pub target: String,
+    pub kind: String,                        // ip_address / domain
+    pub reputation: Option<i64>,             // Community score; negative is bad
+    pub stats: Option<DetectionStats>,
+    pub last_analysis: Option<String>,
+    pub as_owner: Option<String>,            // IP addresses
+    pub country: Option<String>,
+    pub registrar: Option<String>,           // Domains
+    pub categories: Vec<String>,             // Domain categories from URL filtering vendors
+    pub tags: Vec<String>,
+    pub resolutions: Vec<VtResolution>,
+    pub subdomains: Vec<String>,
+    pub communicating_files: Vec<VtFile>,
+}
\ No newline at end of file
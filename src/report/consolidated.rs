@@ -0,0 +1,25 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+
+use crate::models::ScanResults;
+
+// Write every parsed result for the scan to a single machine readable file
+pub fn write_consolidated_report(results: &ScanResults, scan_dir: &str) -> Result<String, Box<dyn Error>> {
+    let report_file = format!("{}/SCAN_RESULTS.json", scan_dir);
+    let mut report = serde_json::to_value(results)?;
+    
+    // Derived views that are handy to query directly from the JSON
+    report["technology_stack"] = serde_json::to_value(results.technology_stack())?;
+    let comparison = results.port_comparison();
+    if !comparison.is_empty() {
+        report["port_comparison"] = serde_json::to_value(comparison)?;
+    }
+    if !results.traces.is_empty() {
+        report["topology"] = serde_json::to_value(results.topology())?;
+    }
+    
+    let mut file = File::create(&report_file)?;
+    file.write_all(serde_json::to_string_pretty(&report)?.as_bytes())?;
+    Ok(report_file)
+}
\ No newline at end of file
@@ -0,0 +1,194 @@
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::Write;
+
+use crate::models::{ScanResults, Severity, Trace};
+use crate::report::topology::TopologyFiles;
+
+// Rose Pine styling shared with the NMAP HTML report
+const STYLE: &str = r#"
+body { background: #191724; color: #e0def4; font-family: Helvetica, Arial, sans-serif; margin: 2em; }
+h1 { color: #ebbcba; }
+h2 { color: #9ccfd8; border-bottom: 1px solid #26233a; padding-bottom: 0.3em; margin-top: 2em; }
+h3 { color: #c4a7e7; }
+table { border-collapse: collapse; width: 100%; margin: 1em 0; background: #1f1d2e; }
+th { background: #26233a; color: #f6c177; text-align: left; }
+th, td { padding: 0.4em 0.8em; border: 1px solid #26233a; vertical-align: top; }
+pre { background: #1f1d2e; padding: 1em; overflow-x: auto; }
+.muted { color: #908caa; }
+.sev-Critical, .sev-High { color: #eb6f92; font-weight: bold; }
+.sev-Medium { color: #f6c177; }
+.sev-Low { color: #9ccfd8; }
+.sev-Info { color: #908caa; }
+.topology svg { max-width: 100%; height: auto; }
+"#;
+
+// Write a self-contained HTML report of the parsed scan results
+pub fn write_html_report(results: &ScanResults, scan_dir: &str, topology: Option<&TopologyFiles>) -> Result<String, Box<dyn Error>> {
+    let report_file = format!("{}/REPORT.html", scan_dir);
+    
+    let mut html = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>Reconnaissance Report: {}</title>\n", escape_html(&results.target)));
+    html.push_str(&format!("<style>{}</style>\n</head>\n<body>\n", STYLE));
+    html.push_str(&format!("<h1>Reconnaissance Report: {}</h1>\n", escape_html(&results.target)));
+    html.push_str(&format!("<p class=\"muted\">Started {}</p>\n", escape_html(&results.started)));
+    
+    if !results.hosts.is_empty() {
+        html.push_str(&render_hosts(results));
+    }
+    if !results.findings.is_empty() {
+        html.push_str(&render_findings(results));
+    }
+    if !results.technologies.is_empty() {
+        html.push_str(&render_technology_stack(results));
+    }
+    if !results.dns.subdomains.is_empty() {
+        html.push_str(&render_dns(results));
+    }
+    if !results.traces.is_empty() {
+        html.push_str(&render_topology(results, topology)?);
+    }
+    
+    html.push_str("</body>\n</html>\n");
+    
+    let mut file = File::create(&report_file)?;
+    file.write_all(html.as_bytes())?;
+    Ok(report_file)
+}
+
+fn render_hosts(results: &ScanResults) -> String {
+    let mut section = String::from("<h2>Hosts</h2>\n<table>\n<tr><th>Host</th><th>Status</th><th>MAC / Vendor</th><th>OS</th><th>Location / AS</th><th>Open Ports</th></tr>\n");
+    for host in results.sorted_hosts() {
+        let mac = match (&host.mac, &host.vendor) {
+            (Some(mac), Some(vendor)) => format!("{} ({})", mac, vendor),
+            (Some(mac), None) => mac.clone(),
+            _ => "-".to_string(),
+        };
+        let status = match (&host.status, &host.reason) {
+            (Some(status), Some(reason)) => format!("{} ({})", status, reason),
+            (Some(status), None) => status.clone(),
+            _ => "-".to_string(),
+        };
+        let ports: Vec<String> = host.open_ports()
+            .map(|p| match &p.service {
+                Some(service) => format!("{}/{} {}", p.port, p.protocol, service.name),
+                None => format!("{}/{}", p.port, p.protocol),
+            })
+            .chain(host.passive_only_ports().iter().map(|p| format!("{}/{} [{}]", p.port, p.protocol, p.source)))
+            .collect();
+        section.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            escape_html(&host.display_name()),
+            escape_html(&status),
+            escape_html(&mac),
+            escape_html(host.os.as_deref().unwrap_or("-")),
+            escape_html(&host.geo.as_ref().map(|g| g.summary()).unwrap_or_else(|| "-".to_string())),
+            if ports.is_empty() { "-".to_string() } else { escape_html(&ports.join(", ")) }));
+    }
+    section.push_str("</table>\n");
+    section
+}
+
+fn render_findings(results: &ScanResults) -> String {
+    let mut section = String::from("<h2>Findings</h2>\n<p>");
+    let counts: Vec<String> = Severity::DESCENDING.iter()
+        .map(|severity| format!("<span class=\"sev-{}\">{}: {}</span>", severity, severity, results.count_findings(*severity)))
+        .collect();
+    section.push_str(&counts.join(" | "));
+    section.push_str("</p>\n<table>\n<tr><th>Severity</th><th>Source</th><th>Location</th><th>Description</th><th>References</th></tr>\n");
+    for finding in results.sorted_findings() {
+        section.push_str(&format!("<tr><td class=\"sev-{}\">{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            finding.severity,
+            finding.severity,
+            escape_html(&finding.source),
+            escape_html(&finding.location),
+            escape_html(&finding.description),
+            escape_html(&finding.references.join(", "))));
+    }
+    section.push_str("</table>\n");
+    section
+}
+
+fn render_technology_stack(results: &ScanResults) -> String {
+    let mut section = String::from("<h2>Technology Stack</h2>\n<table>\n<tr><th>Product</th><th>Version</th><th>Category</th><th>Seen On</th></tr>\n");
+    for entry in results.technology_stack() {
+        section.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            escape_html(&entry.product),
+            escape_html(entry.version.as_deref().unwrap_or("-")),
+            escape_html(&entry.category),
+            escape_html(&entry.urls.join(", "))));
+    }
+    section.push_str("</table>\n");
+    section
+}
+
+fn render_dns(results: &ScanResults) -> String {
+    let mut section = String::from("<h2>DNS Assets</h2>\n<table>\n<tr><th>Name</th><th>Addresses</th><th>Sources</th><th>First Seen</th><th>Last Seen</th></tr>\n");
+    let date = |seen: &Option<String>| seen.as_deref().map(|t| t.split('T').next().unwrap_or(t).to_string()).unwrap_or_else(|| "-".to_string());
+    for asset in results.dns.subdomains.values() {
+        let addresses: Vec<&str> = asset.addresses.iter().map(|a| a.as_str()).collect();
+        let sources: Vec<&str> = asset.sources.iter().map(|s| s.as_str()).collect();
+        section.push_str(&format!("<tr><td>{}{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            escape_html(&asset.name),
+            if asset.wildcard { " (wildcard)" } else { "" },
+            if addresses.is_empty() { "-".to_string() } else { escape_html(&addresses.join(", ")) },
+            escape_html(&sources.join(", ")),
+            escape_html(&date(&asset.first_seen)),
+            escape_html(&date(&asset.last_seen))));
+    }
+    section.push_str("</table>\n");
+    section
+}
+
+// Topology graph (inline SVG, or the DOT source without Graphviz) followed by every trace hop by hop
+fn render_topology(results: &ScanResults, topology: Option<&TopologyFiles>) -> Result<String, Box<dyn Error>> {
+    let mut section = String::from("<h2>Network Topology</h2>\n");
+    
+    if let Some(files) = topology {
+        match &files.svg {
+            Some(svg) => {
+                let svg = fs::read_to_string(svg)?;
+                // Drop the XML prolog and doctype so the SVG can be inlined
+                let start = svg.find("<svg").unwrap_or(0);
+                section.push_str(&format!("<div class=\"topology\">{}</div>\n", &svg[start..]));
+            },
+            None => {
+                section.push_str("<p class=\"muted\">Graphviz is not installed; render the graph with <code>dot -Tsvg TOPOLOGY.dot</code>.</p>\n");
+                section.push_str(&format!("<pre>{}</pre>\n", escape_html(&fs::read_to_string(&files.dot)?)));
+            }
+        }
+    }
+    
+    for trace in &results.traces {
+        section.push_str(&render_trace(trace));
+    }
+    Ok(section)
+}
+
+fn render_trace(trace: &Trace) -> String {
+    let mut section = format!("<h3>{}{}</h3>\n",
+        escape_html(&trace.target),
+        if trace.reached { "" } else { " <span class=\"muted\">(not reached)</span>" });
+    section.push_str("<table>\n<tr><th>TTL</th><th>Address</th><th>Name</th><th>AS</th><th>Location</th><th>RTT (ms)</th></tr>\n");
+    for hop in &trace.hops {
+        let rtts: Vec<String> = hop.rtts.iter().map(|rtt| format!("{:.2}", rtt)).collect();
+        let owner = hop.geo.as_ref().and_then(|g| g.owner()).or_else(|| hop.asn.clone());
+        section.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            hop.ttl,
+            escape_html(hop.address.as_deref().unwrap_or("*")),
+            escape_html(hop.hostname.as_deref().unwrap_or("-")),
+            escape_html(owner.as_deref().unwrap_or("-")),
+            escape_html(hop.geo.as_ref().and_then(|g| g.location()).as_deref().unwrap_or("-")),
+            if rtts.is_empty() { "-".to_string() } else { rtts.join(" / ") }));
+    }
+    section.push_str("</table>\n");
+    section
+}
+
+// Escape text for HTML element content and attribute values
+fn escape_html(value: &str) -> String {
+    value.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
\ No newline at end of file
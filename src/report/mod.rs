@@ -1,3 +1,11 @@
 pub mod summary;
+pub mod consolidated;
+pub mod topology;
+pub mod html;
+pub mod network;
 
-pub use summary::*;
\ No newline at end of file
+pub use summary::*;
+pub use consolidated::*;
+pub use topology::*;
+pub use html::*;
+pub use network::*;
\ No newline at end of file
@@ -0,0 +1,104 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+use console::style;
+
+use crate::models::{ScanResults, Severity};
+use crate::ui::progress::create_spinner;
+
+// Create the network-level summary linking the scan directory of every live host
+pub fn create_network_summary(target: &str, network_dir: &str, addresses: usize, scans: &[(String, ScanResults)]) -> Result<(), Box<dyn Error>> {
+    let summary_spinner = create_spinner("Creating network summary...");
+    let summary_file = format!("{}/NETWORK_SUMMARY.md", network_dir);
+    let mut summary = File::create(summary_file)?;
+    
+    let header = format!(r#"# Network Reconnaissance Summary
+## Network: {}
+## Date: {}
+## Live Hosts: {} of {} addresses
+
+"#, target, chrono::Local::now().format("%Y-%m-%d %H:%M:%S"), scans.len(), addresses);
+    summary.write_all(header.as_bytes())?;
+    
+    if scans.is_empty() {
+        summary.write_all(b"No live hosts were discovered.\n")?;
+        summary_spinner.finish_with_message(format!("{} Network summary created", style("✓").green()));
+        return Ok(());
+    }
+    
+    // One row per host with links to its own reports
+    summary.write_all(b"## Hosts:\n\n")?;
+    summary.write_all(b"| Host | Status | MAC / Vendor | Open Ports | Findings (C/H/M/L/I) | Reports |\n")?;
+    summary.write_all(b"|------|--------|--------------|------------|----------------------|---------|\n")?;
+    for (directory, results) in scans {
+        let host = results.hosts.get(&results.target);
+        let name = host.map(|h| h.display_name()).unwrap_or_else(|| results.target.clone());
+        let status = match host.map(|h| (&h.status, &h.reason)) {
+            Some((Some(status), Some(reason))) => format!("{} ({})", status, reason),
+            Some((Some(status), None)) => status.clone(),
+            _ => "-".to_string(),
+        };
+        let mac = match host.map(|h| (&h.mac, &h.vendor)) {
+            Some((Some(mac), Some(vendor))) => format!("{} ({})", mac, vendor),
+            Some((Some(mac), None)) => mac.clone(),
+            _ => "-".to_string(),
+        };
+        let ports: BTreeSet<String> = results.hosts.values()
+            .flat_map(|h| h.open_ports())
+            .map(|p| format!("{}/{}", p.port, p.protocol))
+            .collect();
+        let findings: Vec<String> = Severity::DESCENDING.iter()
+            .map(|severity| results.count_findings(*severity).to_string())
+            .collect();
+        summary.write_all(format!("| {} | {} | {} | {} | {} | [summary]({}/SCAN_SUMMARY.md) · [html]({}/REPORT.html) |\n",
+            name,
+            status,
+            mac,
+            if ports.is_empty() { "-".to_string() } else { ports.into_iter().collect::<Vec<_>>().join(", ") },
+            findings.join("/"),
+            directory,
+            directory).as_bytes())?;
+    }
+    
+    // Which hosts expose each service, to spot the interesting ones across the range
+    let mut services: BTreeMap<(String, u16), (BTreeSet<String>, BTreeSet<String>)> = BTreeMap::new();
+    for (_, results) in scans {
+        for host in results.hosts.values() {
+            for port in host.open_ports() {
+                let entry = services.entry((port.protocol.clone(), port.port)).or_default();
+                entry.0.insert(host.address.clone());
+                if let Some(service) = &port.service {
+                    entry.1.insert(service.name.clone());
+                }
+            }
+        }
+    }
+    if !services.is_empty() {
+        summary.write_all(b"\n## Open Services:\n\n")?;
+        summary.write_all(b"| Port | Service | Hosts |\n")?;
+        summary.write_all(b"|------|---------|-------|\n")?;
+        for ((protocol, port), (hosts, names)) in &services {
+            summary.write_all(format!("| {}/{} | {} | {} |\n",
+                port,
+                protocol,
+                if names.is_empty() { "-".to_string() } else { names.iter().cloned().collect::<Vec<_>>().join(", ") },
+                hosts.iter().cloned().collect::<Vec<_>>().join(", ")).as_bytes())?;
+        }
+    }
+    
+    // Hosts whose scan or reports failed; the pipeline carried on without them
+    let failed: Vec<&ScanResults> = scans.iter().map(|(_, results)| results).filter(|r| !r.errors.is_empty()).collect();
+    if !failed.is_empty() {
+        summary.write_all(b"\n## Scan Errors:\n\n")?;
+        for results in failed {
+            for error in &results.errors {
+                summary.write_all(format!("- {}: {}\n", results.target, error).as_bytes())?;
+            }
+        }
+    }
+    
+    summary_spinner.finish_with_message(format!("{} Network summary created", style("✓").green()));
+    
+    Ok(())
+}
\ No newline at end of file
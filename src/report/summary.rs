@@ -1,15 +1,19 @@
 use clap::ArgMatches;
+use std::collections::BTreeMap;
 use std::error::Error;
 use std::fs::File;
 use std::io::Write;
 use console::style;
 
+use crate::models::{AbuseIpdbInfo, CensysInfo, DnssecReport, EmailSecurity, GreyNoiseInfo, LdapInfo, PortComparison, ScanResults, Severity, ShodanInfo, SmbInfo, SnmpInfo, VirusTotalInfo};
+use crate::api::ApiSettings;
+use crate::scanners::dns::ct_source;
 use crate::ui::progress::create_spinner;
 
 // Create scan summary
-pub fn create_scan_summary(matches: &ArgMatches, target_ip: &str, scan_dir: &str) -> Result<(), Box<dyn Error>> {
+pub fn create_scan_summary(matches: &ArgMatches, target_ip: &str, scan_dir: &str, results: &ScanResults) -> Result<(), Box<dyn Error>> {
     // Create scan summary
-    let summary_spinner = create_spinner(&format!("Creating scan summary..."));
+    let summary_spinner = create_spinner("Creating scan summary...");
     let summary_file = format!("{}/SCAN_SUMMARY.md", scan_dir);
     let mut summary = File::create(summary_file)?;
     
@@ -30,22 +34,132 @@ pub fn create_scan_summary(matches: &ArgMatches, target_ip: &str, scan_dir: &str
     if matches.is_present("noisey") { summary.write_all(b"- NMAP (Noisey Scan)\n")?; }
     if matches.is_present("firewall-bypass") { summary.write_all(b"- NMAP (Firewall Bypass Techniques)\n")?; }
     if matches.is_present("whois") { summary.write_all(b"- WHOIS Lookup\n")?; }
-    if matches.is_present("shodan") { summary.write_all(b"- Shodan API Lookup\n")?; }
+    if matches.is_present("shodan") || !results.shodan.is_empty() { summary.write_all(b"- Shodan API Lookup\n")?; }
+    if matches.is_present("censys") || !results.censys.is_empty() { summary.write_all(b"- Censys Search\n")?; }
+    if matches.is_present("virustotal") || !results.virustotal.is_empty() { summary.write_all(b"- VirusTotal Lookup\n")?; }
+    if matches.is_present("greynoise") || !results.greynoise.is_empty() { summary.write_all(b"- GreyNoise Lookup\n")?; }
+    if matches.is_present("abuseipdb") || !results.abuseipdb.is_empty() { summary.write_all(b"- AbuseIPDB Check\n")?; }
     if matches.is_present("nslookup") { summary.write_all(b"- NSLookup\n")?; }
     if matches.is_present("dig") { summary.write_all(b"- DIG DNS Lookup\n")?; }
     if matches.is_present("gobuster") { summary.write_all(b"- Gobuster Web Directory Enumeration\n")?; }
     if matches.is_present("nikto") { summary.write_all(b"- Nikto Web Vulnerability Scanner\n")?; }
     if matches.is_present("enum4linux") { summary.write_all(b"- Enum4linux Windows/Samba Enumeration\n")?; }
+    if matches.is_present("enum4linux-ng") { summary.write_all(b"- Enum4linux-ng Windows/Samba Enumeration\n")?; }
+    if matches.is_present("smb") { summary.write_all(b"- Native SMB Enumeration\n")?; }
+    if matches.is_present("snmp") || !results.snmp.is_empty() { summary.write_all(b"- SNMP Enumeration\n")?; }
+    if matches.is_present("ldap") || !results.ldap.is_empty() { summary.write_all(b"- LDAP/Active Directory Enumeration\n")?; }
     if matches.is_present("whatweb") { summary.write_all(b"- WhatWeb Technology Identification\n")?; }
+    if matches.is_present("fingerprint") { summary.write_all(b"- Built-in Web Technology Fingerprinting\n")?; }
     if matches.is_present("dnsrecon") { summary.write_all(b"- DNSrecon Domain Enumeration\n")?; }
+    if matches.is_present("axfr") { summary.write_all(b"- Native Zone Transfer (AXFR/IXFR) Checks\n")?; }
+    if matches.is_present("email-security") { summary.write_all(b"- Email Security Posture (SPF/DMARC/DKIM/MTA-STS)\n")?; }
+    if matches.is_present("dnssec") { summary.write_all(b"- DNSSEC Chain of Trust Validation\n")?; }
+    if matches.is_present("rdns") { summary.write_all(b"- Reverse DNS (PTR) Sweep\n")?; }
+    if let Some(provider) = matches.value_of("passive-dns") { summary.write_all(format!("- Passive DNS History ({})\n", provider).as_bytes())?; }
+    if matches.is_present("ct") { summary.write_all(format!("- Certificate Transparency ({})\n", ct_source(&ApiSettings::default())).as_bytes())?; }
+    if matches.is_present("subdomains") { summary.write_all(b"- Native Subdomain Enumeration\n")?; }
     if matches.is_present("traceroute") { summary.write_all(b"- Traceroute Network Path Discovery\n")?; }
+    if matches.is_present("geoip") { summary.write_all(b"- Offline GeoIP/ASN Enrichment\n")?; }
     if matches.is_present("ssl-check") { summary.write_all(b"- SSL/TLS Configuration Check\n")?; }
     
+    // Hosts with their names and open ports
+    if !results.hosts.is_empty() {
+        summary.write_all(render_hosts_section(results).as_bytes())?;
+    }
+    
+    // Passive port data checked against the active scans
+    let comparison = results.port_comparison();
+    if !comparison.is_empty() {
+        summary.write_all(render_port_comparison_section(&comparison).as_bytes())?;
+    }
+    
+    // Parsed findings from all tools
+    if !results.findings.is_empty() || !results.raw_outputs.is_empty() {
+        summary.write_all(render_findings_section(results).as_bytes())?;
+    }
+    
+    // Technologies seen across all scanned URLs
+    if !results.technologies.is_empty() {
+        summary.write_all(render_technology_stack_section(results).as_bytes())?;
+    }
+    
+    // DNS records and discovered names
+    if !results.dns.records.is_empty() || !results.dns.subdomains.is_empty() {
+        summary.write_all(render_dns_section(results).as_bytes())?;
+    }
+    
+    // Per-name-server zone transfer results
+    if !results.dns.zone_transfers.is_empty() {
+        summary.write_all(render_zone_transfer_section(results).as_bytes())?;
+    }
+    
+    // Historical resolutions and associated domains from passive DNS
+    if !results.dns.history.is_empty() || !results.dns.associated_domains.is_empty() {
+        summary.write_all(render_passive_dns_section(results).as_bytes())?;
+    }
+    
+    // SPF/DMARC/DKIM/MTA-STS posture
+    for email in &results.email {
+        summary.write_all(render_email_section(email).as_bytes())?;
+    }
+    
+    // DNSSEC chain of trust
+    if let Some(dnssec) = &results.dns.dnssec {
+        summary.write_all(render_dnssec_section(dnssec).as_bytes())?;
+    }
+    
+    // Passive data from Shodan
+    for shodan in &results.shodan {
+        summary.write_all(render_shodan_section(shodan).as_bytes())?;
+    }
+    
+    // Passive data from Censys
+    for censys in &results.censys {
+        summary.write_all(render_censys_section(censys).as_bytes())?;
+    }
+    
+    // Reputation and relationships from VirusTotal
+    for virustotal in &results.virustotal {
+        summary.write_all(render_virustotal_section(virustotal).as_bytes())?;
+    }
+    
+    // Scanner classification and abuse reports
+    if !results.greynoise.is_empty() || !results.abuseipdb.is_empty() {
+        summary.write_all(render_reputation_section(&results.greynoise, &results.abuseipdb).as_bytes())?;
+    }
+    
+    // SMB users, groups, shares and policy
+    for smb in &results.smb {
+        summary.write_all(render_smb_section(smb).as_bytes())?;
+    }
+    
+    // Directory servers: RootDSE, access and enumerated objects
+    for ldap in &results.ldap {
+        summary.write_all(render_ldap_section(ldap).as_bytes())?;
+    }
+    
+    // SNMP agents and the MIB data they disclosed
+    for snmp in &results.snmp {
+        summary.write_all(render_snmp_section(snmp).as_bytes())?;
+    }
+    
+    // Traceroute paths
+    if !results.traces.is_empty() {
+        summary.write_all(render_traceroute_section(results).as_bytes())?;
+    }
+    
+    // Scans that stopped before finishing
+    if !results.errors.is_empty() {
+        summary.write_all(b"\n## Errors:\n\n")?;
+        for error in &results.errors {
+            summary.write_all(format!("- {}\n", error).as_bytes())?;
+        }
+    }
+    
     // List files generated
     summary.write_all(b"\n## Files Generated:\n\n")?;
     
-    // Fix: Use a reference to scan_dir to avoid moving its ownership
-    let paths = std::fs::read_dir(&scan_dir)?;
+    let paths = std::fs::read_dir(scan_dir)?;
     for path in paths {
         let entry = path?;
         if entry.file_name() != "SCAN_SUMMARY.md" {
@@ -57,4 +171,797 @@ pub fn create_scan_summary(matches: &ArgMatches, target_ip: &str, scan_dir: &str
     summary_spinner.finish_with_message(format!("{} Scan summary created", style("✓").green()));
     
     Ok(())
+}
+
+// Render the findings section: counts per severity followed by one row per finding
+fn render_findings_section(results: &ScanResults) -> String {
+    let mut section = String::from("\n## Findings:\n\n");
+    
+    let counts: Vec<String> = Severity::DESCENDING.iter()
+        .map(|severity| format!("{}: {}", severity, results.count_findings(*severity)))
+        .collect();
+    section.push_str(&format!("**{}**\n\n", counts.join(" | ")));
+    
+    section.push_str("| Severity | Source | ID | Method | Location | Description | References |\n");
+    section.push_str("|----------|--------|----|--------|----------|-------------|------------|\n");
+    for finding in results.sorted_findings() {
+        section.push_str(&format!("| {} | {} | {} | {} | {} | {} | {} |\n",
+            finding.severity,
+            finding.source,
+            finding.id.as_deref().unwrap_or("-"),
+            finding.method.as_deref().unwrap_or("-"),
+            escape_cell(&finding.location),
+            escape_cell(&finding.description),
+            escape_cell(&finding.references.join(", "))));
+    }
+    
+    // Point readers at the unparsed output of each tool
+    if !results.raw_outputs.is_empty() {
+        section.push_str("\nRaw output:\n\n");
+        for (tool, filename) in &results.raw_outputs {
+            section.push_str(&format!("- {}: `{}`\n", tool, filename));
+        }
+    }
+    
+    section
+}
+
+// Render the cross-target technology stack: one row per product/version with the URLs running it
+fn render_technology_stack_section(results: &ScanResults) -> String {
+    let mut section = String::from("\n## Technology Stack:\n\n");
+    section.push_str("| Product | Version | Category | Seen On |\n");
+    section.push_str("|---------|---------|----------|---------|\n");
+    for entry in results.technology_stack() {
+        section.push_str(&format!("| {} | {} | {} | {} |\n",
+            escape_cell(&entry.product),
+            escape_cell(entry.version.as_deref().unwrap_or("-")),
+            entry.category,
+            escape_cell(&entry.urls.join(", "))));
+    }
+    section
+}
+
+// Render every host, named by its hostname where one is known
+fn render_hosts_section(results: &ScanResults) -> String {
+    let mut section = String::from("\n## Hosts:\n\n");
+    section.push_str("| Host | Status | MAC / Vendor | OS | Location / AS | Open Ports |\n");
+    section.push_str("|------|--------|--------------|----|---------------|------------|\n");
+    for host in results.sorted_hosts() {
+        let mac = match (&host.mac, &host.vendor) {
+            (Some(mac), Some(vendor)) => format!("{} ({})", mac, vendor),
+            (Some(mac), None) => mac.clone(),
+            _ => "-".to_string(),
+        };
+        let status = match (&host.status, &host.reason) {
+            (Some(status), Some(reason)) => format!("{} ({})", status, reason),
+            (Some(status), None) => status.clone(),
+            _ => "-".to_string(),
+        };
+        let ports: Vec<String> = host.open_ports()
+            .map(|p| match &p.service {
+                Some(service) => {
+                    let detail: Vec<&str> = [service.product.as_deref(), service.version.as_deref()].into_iter().flatten().collect();
+                    if detail.is_empty() {
+                        format!("{}/{} {}", p.port, p.protocol, service.name)
+                    } else {
+                        format!("{}/{} {} ({})", p.port, p.protocol, service.name, detail.join(" "))
+                    }
+                },
+                None => format!("{}/{}", p.port, p.protocol),
+            })
+            .chain(host.passive_only_ports().iter().map(|p| format!("{}/{} [{}]", p.port, p.protocol, p.source)))
+            .collect();
+        section.push_str(&format!("| {} | {} | {} | {} | {} | {} |\n",
+            host.display_name(),
+            status,
+            mac,
+            escape_cell(host.os.as_deref().unwrap_or("-")),
+            escape_cell(&host.geo.as_ref().map(|g| g.summary()).unwrap_or_else(|| "-".to_string())),
+            if ports.is_empty() { "-".to_string() } else { escape_cell(&ports.join(", ")) }));
+    }
+    section
+}
+
+// Render passive port reports next to what the active scans see now
+fn render_port_comparison_section(comparison: &[PortComparison]) -> String {
+    let mut section = String::from("\n## Passive vs Active Ports:\n\n");
+    section.push_str("| Host | Port | Status | Passive Source | Last Seen | Active Scan |\n");
+    section.push_str("|------|------|--------|----------------|-----------|-------------|\n");
+    for entry in comparison {
+        section.push_str(&format!("| {} | {}/{} | {} | {} | {} | {} |\n",
+            entry.address,
+            entry.port, entry.protocol,
+            entry.status,
+            if entry.passive_sources.is_empty() { "-".to_string() } else { entry.passive_sources.join(", ") },
+            entry.last_seen.as_deref().map(|t| t.split('T').next().unwrap_or(t)).unwrap_or("-"),
+            match (entry.status.as_str(), &entry.active_state) {
+                (_, Some(state)) => state.as_str(),
+                ("no longer open", None) => "not open",
+                ("not scanned", None) => "not probed",
+                _ => "open",
+            }));
+    }
+    section
+}
+
+// Render the DNS asset inventory: record counts by type, then every discovered name
+fn render_dns_section(results: &ScanResults) -> String {
+    let mut section = String::from("\n## DNS Assets:\n\n");
+    
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for record in &results.dns.records {
+        *counts.entry(record.record_type.as_str()).or_insert(0) += 1;
+    }
+    if !counts.is_empty() {
+        let counts: Vec<String> = counts.iter().map(|(t, n)| format!("{}: {}", t, n)).collect();
+        section.push_str(&format!("**Records:** {}\n\n", counts.join(" | ")));
+    }
+    section.push_str(&format!("**Unique addresses:** {}\n\n", results.dns.addresses.len()));
+    
+    if !results.dns.subdomains.is_empty() {
+        section.push_str("| Name | Addresses | Sources | First Seen | Last Seen |\n");
+        section.push_str("|------|-----------|---------|------------|-----------|\n");
+        let date = |seen: &Option<String>| seen.as_deref().map(|t| t.split('T').next().unwrap_or(t).to_string()).unwrap_or_else(|| "-".to_string());
+        for asset in results.dns.subdomains.values() {
+            let addresses: Vec<&str> = asset.addresses.iter().map(|a| a.as_str()).collect();
+            let sources: Vec<&str> = asset.sources.iter().map(|s| s.as_str()).collect();
+            section.push_str(&format!("| {}{} | {} | {} | {} | {} |\n",
+                asset.name,
+                if asset.wildcard { " (wildcard)" } else { "" },
+                if addresses.is_empty() { "-".to_string() } else { addresses.join(", ") },
+                sources.join(", "),
+                date(&asset.first_seen),
+                date(&asset.last_seen)));
+        }
+    }
+    
+    section
+}
+
+// Render the AXFR/IXFR status of every name server tested
+fn render_zone_transfer_section(results: &ScanResults) -> String {
+    let mut section = String::from("\n## Zone Transfers:\n\n");
+    section.push_str("| Name Server | Address | AXFR | IXFR | Records | Zone File |\n");
+    section.push_str("|-------------|---------|------|------|---------|-----------|\n");
+    for attempt in &results.dns.zone_transfers {
+        section.push_str(&format!("| {} | {} | {} | {} | {} | {} |\n",
+            attempt.server,
+            attempt.address,
+            escape_cell(&attempt.axfr.to_string()),
+            escape_cell(&attempt.ixfr.to_string()),
+            attempt.records,
+            attempt.zone_file.as_deref().unwrap_or("-")));
+    }
+    section
+}
+
+// Render historical resolutions, newest first, and the domains associated with the target
+fn render_passive_dns_section(results: &ScanResults) -> String {
+    let mut section = String::from("\n## Passive DNS History:\n\n");
+    
+    if !results.dns.history.is_empty() {
+        let mut history: Vec<_> = results.dns.history.iter().collect();
+        history.sort_by(|a, b| b.last_seen.cmp(&a.last_seen).then_with(|| a.value.cmp(&b.value)));
+        section.push_str("| Name | Type | Value | Organization | First Seen | Last Seen | Source |\n");
+        section.push_str("|------|------|-------|--------------|------------|-----------|--------|\n");
+        for record in history {
+            section.push_str(&format!("| {} | {} | {} | {} | {} | {} | {} |\n",
+                record.name,
+                record.record_type,
+                record.value,
+                escape_cell(record.organization.as_deref().unwrap_or("-")),
+                record.first_seen.as_deref().unwrap_or("-"),
+                record.last_seen.as_deref().unwrap_or("-"),
+                record.source));
+        }
+        section.push('\n');
+    }
+    
+    if !results.dns.associated_domains.is_empty() {
+        let domains: Vec<&str> = results.dns.associated_domains.keys().map(|d| d.as_str()).collect();
+        section.push_str(&format!("**Associated domains ({}):** {}\n", domains.len(), domains.join(", ")));
+    }
+    
+    section
+}
+
+// Render the email authentication records of the domain
+fn render_email_section(email: &EmailSecurity) -> String {
+    let mut section = format!("\n## Email Security: {}\n\n", email.domain);
+    let missing = || "Not published".to_string();
+    
+    let spf = match &email.spf {
+        Some(_) => format!("{} ({} DNS lookups)", email.spf_all.as_deref().unwrap_or("no all"), email.spf_lookups),
+        None => missing(),
+    };
+    let dmarc = match &email.dmarc {
+        Some(_) => format!("p={} sp={} pct={}",
+            email.dmarc_policy.as_deref().unwrap_or("-"),
+            email.dmarc_subdomain_policy.as_deref().unwrap_or("-"),
+            email.dmarc_pct.unwrap_or(100)),
+        None => missing(),
+    };
+    let dkim = if email.dkim_selectors.is_empty() { "No common selector found".to_string() } else { email.dkim_selectors.join(", ") };
+    let mta_sts = match &email.mta_sts {
+        Some(_) => format!("mode={}", email.mta_sts_mode.as_deref().unwrap_or("unknown")),
+        None => missing(),
+    };
+    
+    section.push_str("| Control | Status | Record |\n");
+    section.push_str("|---------|--------|--------|\n");
+    for (control, status, record) in [
+        ("SPF", spf, &email.spf),
+        ("DMARC", dmarc, &email.dmarc),
+        ("DKIM", dkim, &None),
+        ("MTA-STS", mta_sts, &email.mta_sts),
+        ("TLS-RPT", if email.tls_rpt.is_some() { "Published".to_string() } else { missing() }, &email.tls_rpt),
+    ] {
+        section.push_str(&format!("| {} | {} | {} |\n", control, escape_cell(&status), escape_cell(record.as_deref().unwrap_or("-"))));
+    }
+    if !email.spf_includes.is_empty() {
+        section.push_str(&format!("\n**SPF includes:** {}\n", email.spf_includes.join(", ")));
+    }
+    
+    section
+}
+
+// Render the DNSSEC status of every zone from the root down to the target
+fn render_dnssec_section(dnssec: &DnssecReport) -> String {
+    let mut section = String::from("\n## DNSSEC:\n\n");
+    section.push_str("| Zone | Status | DS | DNSKEY | Algorithms | Earliest Signature Expiry | Problems |\n");
+    section.push_str("|------|--------|----|--------|------------|---------------------------|----------|\n");
+    for zone in &dnssec.chain {
+        section.push_str(&format!("| {} | {} | {} | {} | {} | {} | {} |\n",
+            zone.zone,
+            zone.status,
+            zone.ds_records,
+            zone.dnskeys,
+            if zone.algorithms.is_empty() { "-".to_string() } else { zone.algorithms.join(", ") },
+            zone.signature_expiry.as_deref().unwrap_or("-"),
+            if zone.problems.is_empty() { "-".to_string() } else { escape_cell(&zone.problems.join("; ")) }));
+    }
+    if let Some(denial) = &dnssec.denial {
+        section.push_str(&format!("\n**Denial of existence:** {}\n", denial));
+    }
+    if !dnssec.walked_names.is_empty() {
+        section.push_str(&format!("\n**Names enumerated via NSEC:** {}\n", dnssec.walked_names.join(", ")));
+    }
+    section
+}
+
+// Render the SMB enumeration: host facts, shares with anonymous access, policy, users and groups
+fn render_smb_section(smb: &SmbInfo) -> String {
+    let mut section = format!("\n## SMB Enumeration: {}\n\n", smb.target);
+    let facts = [
+        ("Workgroup/Domain", smb.workgroup.clone()),
+        ("NetBIOS name", smb.netbios_name.clone()),
+        ("Domain SID", smb.domain_sid.clone()),
+        ("OS", smb.os.clone()),
+        ("Server", smb.server.clone()),
+        ("DNS domain", smb.dns_domain.clone()),
+        ("DNS name", smb.dns_name.clone()),
+        ("Dialects", Some(smb.dialects.join(", ")).filter(|d| !d.is_empty())),
+        ("SMBv1", smb.smb1.map(|enabled| if enabled { "enabled".to_string() } else { "disabled".to_string() })),
+        ("Signing", smb.signing_required.map(|required| if required { "required".to_string() } else { "not required".to_string() })),
+        ("Null session", smb.null_session.map(|allowed| if allowed { "allowed".to_string() } else { "denied".to_string() })),
+    ];
+    for (label, value) in facts {
+        if let Some(value) = value {
+            section.push_str(&format!("**{}:** {}  \n", label, value));
+        }
+    }
+    section.push_str(&format!("**Sources:** {}\n", smb.sources.join(", ")));
+    
+    if !smb.shares.is_empty() {
+        section.push_str("\n| Share | Type | Comment | Mapping | Listing | Writing |\n");
+        section.push_str("|-------|------|---------|---------|---------|---------|\n");
+        for share in &smb.shares {
+            section.push_str(&format!("| {} | {} | {} | {} | {} | {} |\n",
+                escape_cell(&share.name),
+                share.share_type.as_deref().unwrap_or("-"),
+                escape_cell(share.comment.as_deref().unwrap_or("-")),
+                share.mapping.as_deref().unwrap_or("-"),
+                share.listing.as_deref().unwrap_or("-"),
+                share.writing.as_deref().unwrap_or("-")));
+        }
+    }
+    
+    if let Some(policy) = &smb.password_policy {
+        let number = |value: Option<u32>| value.map(|n| n.to_string()).unwrap_or_else(|| "-".to_string());
+        section.push_str("\n**Password policy:** ");
+        section.push_str(&format!("minimum length {}, history {}, complexity {}, lockout threshold {}",
+            number(policy.min_length),
+            number(policy.history_length),
+            match policy.complexity { Some(true) => "on", Some(false) => "off", None => "-" },
+            number(policy.lockout_threshold)));
+        if let Some(duration) = &policy.lockout_duration {
+            section.push_str(&format!(", lockout duration {}", duration));
+        }
+        section.push('\n');
+    }
+    
+    if !smb.users.is_empty() {
+        let users: Vec<String> = smb.users.iter()
+            .map(|u| match &u.description {
+                Some(description) => format!("{} ({})", u.name, description),
+                None => u.name.clone(),
+            })
+            .collect();
+        section.push_str(&format!("\n**Users ({}):** {}\n", users.len(), escape_cell(&users.join(", "))));
+    }
+    
+    if !smb.groups.is_empty() {
+        section.push_str("\n| Group | Type | RID | Members |\n");
+        section.push_str("|-------|------|-----|---------|\n");
+        for group in &smb.groups {
+            section.push_str(&format!("| {} | {} | {} | {} |\n",
+                escape_cell(&group.name),
+                group.kind.as_deref().unwrap_or("-"),
+                group.rid.as_deref().unwrap_or("-"),
+                if group.members.is_empty() { "-".to_string() } else { escape_cell(&group.members.join(", ")) }));
+        }
+    }
+    
+    section
+}
+
+// Render one Shodan lookup: host facts, indexed services, DNS data and search results
+fn render_shodan_section(shodan: &ShodanInfo) -> String {
+    let mut section = format!("\n## Shodan: {}\n\n", shodan.target);
+    let resolved: Vec<String> = shodan.resolved.iter().map(|(name, ip)| format!("{} → {}", name, ip)).collect();
+    let host = shodan.host.as_ref();
+    let list = |values: Option<&Vec<String>>| values.map(|v| v.join(", ")).filter(|v| !v.is_empty());
+    let facts = [
+        ("Resolved", Some(resolved.join(", ")).filter(|r| !r.is_empty())),
+        ("Organization", host.and_then(|h| h.org.clone())),
+        ("ISP", host.and_then(|h| h.isp.clone())),
+        ("ASN", host.and_then(|h| h.asn.clone())),
+        ("Location", host.and_then(|h| match (&h.city, &h.country) {
+            (Some(city), Some(country)) => Some(format!("{}, {}", city, country)),
+            (city, country) => city.clone().or_else(|| country.clone()),
+        })),
+        ("OS", host.and_then(|h| h.os.clone())),
+        ("Hostnames", list(host.map(|h| &h.hostnames))),
+        ("Tags", list(host.map(|h| &h.tags))),
+        ("Last update", host.and_then(|h| h.last_update.clone())),
+        ("Honeyscore", shodan.honeyscore.map(|score| format!("{:.1}{}", score, if score >= 0.5 { " (likely honeypot)" } else { "" }))),
+        ("Vulnerabilities", list(host.map(|h| &h.vulns))),
+        ("API plan", shodan.account.as_ref().map(|a| format!("{} ({} query credits, {} scan credits left)",
+            a.plan.as_deref().unwrap_or("unknown"),
+            a.query_credits.map(|c| c.to_string()).unwrap_or_else(|| "?".to_string()),
+            a.scan_credits.map(|c| c.to_string()).unwrap_or_else(|| "?".to_string())))),
+    ];
+    for (label, value) in facts {
+        if let Some(value) = value {
+            section.push_str(&format!("**{}:** {}  \n", label, escape_cell(&value)));
+        }
+    }
+    if shodan.host.is_none() && shodan.search.is_none() {
+        section.push_str("\nShodan has no host data for this target.\n");
+    }
+    
+    if let Some(host) = host.filter(|h| !h.services.is_empty()) {
+        section.push_str("\n| Port | Module | Product | Banner | TLS | Seen |\n");
+        section.push_str("|------|--------|---------|--------|-----|------|\n");
+        for service in &host.services {
+            let product = match (&service.product, &service.version) {
+                (Some(product), Some(version)) => format!("{} {}", product, version),
+                (Some(product), None) => product.clone(),
+                _ => "-".to_string(),
+            };
+            let tls = service.ssl.as_ref()
+                .map(|ssl| format!("{}{}", ssl.subject.as_deref().unwrap_or("-"), if ssl.expired { " (expired)" } else { "" }))
+                .unwrap_or_else(|| "-".to_string());
+            section.push_str(&format!("| {}/{} | {} | {} | {} | {} | {} |\n",
+                service.port, service.transport,
+                service.module.as_deref().unwrap_or("-"),
+                escape_cell(&product),
+                escape_cell(service.banner.as_deref().and_then(|b| b.lines().next()).unwrap_or("-")),
+                escape_cell(&tls),
+                service.timestamp.as_deref().map(|t| t.split('T').next().unwrap_or(t)).unwrap_or("-")));
+        }
+    }
+    
+    if let Some(domain) = &shodan.domain {
+        section.push_str(&format!("\n**DNS ({} subdomains, {} records):** {}\n",
+            domain.subdomains.len(), domain.records.len(), escape_cell(&domain.subdomains.join(", "))));
+    }
+    
+    if let Some(search) = &shodan.search {
+        section.push_str(&format!("\n**Search `{}`:** {} results\n", search.query, search.total));
+        for (facet, values) in &search.facets {
+            let values: Vec<String> = values.iter().map(|v| format!("{} ({})", v.value, v.count)).collect();
+            section.push_str(&format!("\n**Top {}:** {}\n", facet, escape_cell(&values.join(", "))));
+        }
+        if !search.matches.is_empty() {
+            section.push_str("\n| IP | Port | Product | Organization | Hostnames |\n");
+            section.push_str("|----|------|---------|--------------|-----------|\n");
+            for result in &search.matches {
+                section.push_str(&format!("| {} | {}/{} | {} | {} | {} |\n",
+                    result.ip, result.port, result.transport,
+                    escape_cell(result.product.as_deref().unwrap_or("-")),
+                    escape_cell(result.org.as_deref().unwrap_or("-")),
+                    escape_cell(&result.hostnames.join(", "))));
+            }
+        }
+    }
+    section
+}
+
+// Render one Censys lookup: hosts with their services and software, then certificates
+fn render_censys_section(censys: &CensysInfo) -> String {
+    let mut section = format!("\n## Censys: {}\n", censys.target);
+    if let Some(query) = &censys.query {
+        section.push_str(&format!("\n**Search `{}`:** {} hosts\n", query, censys.total.unwrap_or(censys.hosts.len() as u64)));
+    }
+    if censys.hosts.is_empty() {
+        section.push_str("\nCensys has no host data for this target.\n");
+    }
+    
+    for host in &censys.hosts {
+        let location = [host.city.clone(), host.country.clone()].into_iter().flatten().collect::<Vec<_>>().join(", ");
+        let asn = host.asn.map(|asn| format!("AS{} {}", asn, host.as_name.as_deref().unwrap_or("")).trim().to_string());
+        section.push_str(&format!("\n### {}\n\n", host.ip));
+        let facts = [
+            ("AS", asn),
+            ("Location", Some(location).filter(|l| !l.is_empty())),
+            ("OS", host.os.clone()),
+            ("DNS names", Some(host.dns_names.join(", ")).filter(|n| !n.is_empty())),
+            ("Labels", Some(host.labels.join(", ")).filter(|l| !l.is_empty())),
+            ("Last updated", host.last_updated.clone()),
+        ];
+        for (label, value) in facts {
+            if let Some(value) = value {
+                section.push_str(&format!("**{}:** {}  \n", label, escape_cell(&value)));
+            }
+        }
+        if !host.services.is_empty() {
+            section.push_str("\n| Port | Service | Software | Labels | TLS | Observed |\n");
+            section.push_str("|------|---------|----------|--------|-----|----------|\n");
+            for service in &host.services {
+                let software: Vec<String> = service.software.iter()
+                    .map(|s| [s.vendor.as_deref(), Some(s.product.as_str()), s.version.as_deref()].into_iter().flatten().collect::<Vec<_>>().join(" "))
+                    .collect();
+                let tls = service.tls.as_ref()
+                    .map(|tls| format!("{}{}", tls.subject.as_deref().unwrap_or("-"), if tls.expired { " (expired)" } else { "" }))
+                    .unwrap_or_else(|| "-".to_string());
+                section.push_str(&format!("| {}/{} | {} | {} | {} | {} | {} |\n",
+                    service.port, service.transport,
+                    service.extended_service_name.as_deref().unwrap_or(&service.service_name),
+                    if software.is_empty() { "-".to_string() } else { escape_cell(&software.join(", ")) },
+                    if service.labels.is_empty() { "-".to_string() } else { service.labels.join(", ") },
+                    escape_cell(&tls),
+                    service.observed_at.as_deref().map(|t| t.split('T').next().unwrap_or(t)).unwrap_or("-")));
+            }
+        }
+    }
+    
+    if !censys.certificates.is_empty() {
+        section.push_str(&format!("\n**Certificates ({}):**\n\n", censys.certificates.len()));
+        section.push_str("| Names | Issuer | Valid Until | SHA-256 |\n");
+        section.push_str("|-------|--------|-------------|---------|\n");
+        for certificate in &censys.certificates {
+            section.push_str(&format!("| {} | {} | {} | {} |\n",
+                escape_cell(&certificate.names.join(", ")),
+                escape_cell(certificate.issuer_dn.as_deref().unwrap_or("-")),
+                certificate.not_after.as_deref().map(|t| t.split('T').next().unwrap_or(t)).unwrap_or("-"),
+                certificate.fingerprint.get(..16).unwrap_or(&certificate.fingerprint)));
+        }
+    }
+    section
+}
+
+// Render one VirusTotal lookup: reputation, then passive DNS, subdomains and communicating files
+fn render_virustotal_section(virustotal: &VirusTotalInfo) -> String {
+    let mut section = format!("\n## VirusTotal: {}\n\n", virustotal.target);
+    let Some(stats) = &virustotal.stats else {
+        section.push_str("VirusTotal has no report for this target.\n");
+        return section;
+    };
+    let facts = [
+        ("Detections", Some(format!("{} malicious, {} suspicious, {} harmless, {} undetected",
+            stats.malicious, stats.suspicious, stats.harmless, stats.undetected))),
+        ("Community reputation", virustotal.reputation.map(|r| r.to_string())),
+        ("Last analysed", virustotal.last_analysis.clone()),
+        ("AS owner", virustotal.as_owner.clone()),
+        ("Country", virustotal.country.clone()),
+        ("Registrar", virustotal.registrar.clone()),
+        ("Categories", Some(virustotal.categories.join(", ")).filter(|c| !c.is_empty())),
+        ("Tags", Some(virustotal.tags.join(", ")).filter(|t| !t.is_empty())),
+    ];
+    for (label, value) in facts {
+        if let Some(value) = value {
+            section.push_str(&format!("**{}:** {}  \n", label, escape_cell(&value)));
+        }
+    }
+    
+    if !virustotal.resolutions.is_empty() {
+        section.push_str(&format!("\n**Passive DNS ({}):**\n\n", virustotal.resolutions.len()));
+        section.push_str("| Host Name | Address | Last Resolved |\n");
+        section.push_str("|-----------|---------|---------------|\n");
+        for resolution in &virustotal.resolutions {
+            section.push_str(&format!("| {} | {} | {} |\n",
+                resolution.host_name, resolution.ip_address, resolution.date.as_deref().unwrap_or("-")));
+        }
+    }
+    
+    if !virustotal.subdomains.is_empty() {
+        section.push_str(&format!("\n**Subdomains ({}):** {}\n", virustotal.subdomains.len(), virustotal.subdomains.join(", ")));
+    }
+    
+    if !virustotal.communicating_files.is_empty() {
+        section.push_str(&format!("\n**Communicating Files ({}):**\n\n", virustotal.communicating_files.len()));
+        section.push_str("| Name | Type | Detections | SHA-256 |\n");
+        section.push_str("|------|------|------------|---------|\n");
+        for file in &virustotal.communicating_files {
+            section.push_str(&format!("| {} | {} | {}/{} | {} |\n",
+                escape_cell(file.name.as_deref().unwrap_or("-")),
+                escape_cell(file.file_type.as_deref().unwrap_or("-")),
+                file.stats.malicious, file.stats.total(),
+                file.sha256.get(..16).unwrap_or(&file.sha256)));
+        }
+    }
+    section
+}
+
+// Render GreyNoise classifications and AbuseIPDB confidence side by side, then the details of each address
+fn render_reputation_section(greynoise: &[GreyNoiseInfo], abuseipdb: &[AbuseIpdbInfo]) -> String {
+    let mut section = String::from("\n## IP Reputation:\n\n");
+    let mut addresses: Vec<&str> = greynoise.iter().map(|g| g.ip.as_str())
+        .chain(abuseipdb.iter().map(|a| a.ip.as_str()))
+        .collect();
+    addresses.sort();
+    addresses.dedup();
+    
+    section.push_str("| Address | GreyNoise | Name | Last Seen | Abuse Confidence | Reports | Last Reported |\n");
+    section.push_str("|---------|-----------|------|-----------|------------------|---------|---------------|\n");
+    for address in &addresses {
+        let noise = greynoise.iter().find(|g| g.ip == *address);
+        let abuse = abuseipdb.iter().find(|a| a.ip == *address);
+        let classification = noise.map(|g| match (g.noise, g.riot) {
+            (_, true) => format!("benign service{}", g.trust_level.as_deref().map(|t| format!(" (trust level {})", t)).unwrap_or_default()),
+            (true, _) => format!("{} scanner", g.classification.as_deref().unwrap_or("unknown")),
+            _ => "not observed".to_string(),
+        });
+        section.push_str(&format!("| {} | {} | {} | {} | {} | {} | {} |\n",
+            address,
+            classification.as_deref().unwrap_or("-"),
+            escape_cell(noise.and_then(|g| g.name.as_deref()).unwrap_or("-")),
+            noise.and_then(|g| g.last_seen.as_deref()).unwrap_or("-"),
+            abuse.map(|a| format!("{}%", a.confidence)).unwrap_or_else(|| "-".to_string()),
+            abuse.map(|a| format!("{} ({} users)", a.total_reports, a.distinct_reporters)).unwrap_or_else(|| "-".to_string()),
+            abuse.and_then(|a| a.last_reported.as_deref()).map(|t| t.split('T').next().unwrap_or(t)).unwrap_or("-")));
+    }
+    
+    for noise in greynoise.iter().filter(|g| g.plan == "enterprise" && (g.noise || g.riot)) {
+        section.push_str(&format!("\n### GreyNoise: {}\n\n", noise.ip));
+        let facts = [
+            ("Category", noise.category.clone()),
+            ("First seen", noise.first_seen.clone()),
+            ("Organization", noise.organization.clone()),
+            ("Country", noise.country.clone()),
+            ("Tor exit node", noise.tor.then(|| "yes".to_string())),
+            ("VPN", noise.vpn_service.clone()),
+            ("Tags", Some(noise.tags.join(", ")).filter(|t| !t.is_empty())),
+            ("CVEs exploited", Some(noise.cves.join(", ")).filter(|c| !c.is_empty())),
+        ];
+        for (label, value) in facts {
+            if let Some(value) = value {
+                section.push_str(&format!("**{}:** {}  \n", label, escape_cell(&value)));
+            }
+        }
+    }
+    
+    for abuse in abuseipdb {
+        section.push_str(&format!("\n### AbuseIPDB: {} (last {} days)\n\n", abuse.ip, abuse.max_age_days));
+        let categories: Vec<String> = abuse.top_categories().into_iter().map(|(name, count)| format!("{} ({})", name, count)).collect();
+        let facts = [
+            ("Usage type", abuse.usage_type.clone()),
+            ("ISP", abuse.isp.clone()),
+            ("Domain", abuse.domain.clone()),
+            ("Country", abuse.country.clone()),
+            ("Hostnames", Some(abuse.hostnames.join(", ")).filter(|h| !h.is_empty())),
+            ("Tor exit node", abuse.tor.then(|| "yes".to_string())),
+            ("Whitelisted", abuse.whitelisted.then(|| "yes".to_string())),
+            ("Recent report categories", Some(categories.join(", ")).filter(|c| !c.is_empty())),
+        ];
+        for (label, value) in facts {
+            if let Some(value) = value {
+                section.push_str(&format!("**{}:** {}  \n", label, escape_cell(&value)));
+            }
+        }
+        if !abuse.reports.is_empty() {
+            section.push_str("\n| Reported | Categories | Reporter | Comment |\n");
+            section.push_str("|----------|------------|----------|---------|\n");
+            for report in &abuse.reports {
+                let comment = report.comment.as_deref().unwrap_or("-");
+                let comment: String = if comment.chars().count() > 80 {
+                    format!("{}...", comment.chars().take(80).collect::<String>())
+                } else {
+                    comment.to_string()
+                };
+                section.push_str(&format!("| {} | {} | {} | {} |\n",
+                    report.reported_at.split('T').next().unwrap_or(&report.reported_at),
+                    escape_cell(&report.categories.join(", ")),
+                    report.reporter_country.as_deref().unwrap_or("-"),
+                    escape_cell(&comment.replace('\n', " "))));
+            }
+        }
+    }
+    section
+}
+
+// Render one directory server: RootDSE facts, access, policy and the enumerated objects
+fn render_ldap_section(ldap: &LdapInfo) -> String {
+    let mut section = format!("\n## LDAP: {}:{}{}\n\n", ldap.target, ldap.port, if ldap.tls { " (TLS)" } else { "" });
+    let root = &ldap.root_dse;
+    let allowed = |value: Option<bool>| value.map(|allowed| if allowed { "allowed".to_string() } else { "denied".to_string() });
+    let facts = [
+        ("Naming contexts", Some(root.naming_contexts.join(", ")).filter(|n| !n.is_empty())),
+        ("DNS host name", root.dns_host_name.clone()),
+        ("Vendor", root.vendor.clone()),
+        ("Domain functional level", root.domain_functionality.clone()),
+        ("Forest functional level", root.forest_functionality.clone()),
+        ("DC functional level", root.dc_functionality.clone()),
+        ("SASL mechanisms", Some(root.sasl_mechanisms.join(", ")).filter(|m| !m.is_empty())),
+        ("Anonymous bind", allowed(ldap.anonymous_bind)),
+        ("Anonymous search", allowed(ldap.anonymous_search)),
+        ("Bound as", ldap.bound_as.clone()),
+        ("Machine account quota", ldap.machine_account_quota.map(|quota| quota.to_string())),
+    ];
+    for (label, value) in facts {
+        if let Some(value) = value {
+            section.push_str(&format!("**{}:** {}  \n", label, escape_cell(&value)));
+        }
+    }
+    
+    if let Some(policy) = &ldap.password_policy {
+        let number = |value: Option<u32>| value.map(|n| n.to_string()).unwrap_or_else(|| "-".to_string());
+        section.push_str(&format!("\n**Password policy:** minimum length {}, history {}, complexity {}, lockout threshold {}, maximum age {}\n",
+            number(policy.min_length),
+            number(policy.history_length),
+            match policy.complexity { Some(true) => "on", Some(false) => "off", None => "-" },
+            number(policy.lockout_threshold),
+            policy.max_age.as_deref().unwrap_or("-")));
+    }
+    
+    if !ldap.users.is_empty() {
+        section.push_str(&format!("\n**Users ({}):**\n\n", ldap.users.len()));
+        section.push_str("| User | Description | Flags |\n");
+        section.push_str("|------|-------------|-------|\n");
+        for user in &ldap.users {
+            let mut flags = Vec::new();
+            if user.disabled { flags.push("disabled"); }
+            if user.admin_count { flags.push("admin"); }
+            if user.preauth_not_required { flags.push("no preauth"); }
+            if !user.service_principal_names.is_empty() { flags.push("SPN"); }
+            if user.password_not_required { flags.push("password not required"); }
+            if user.password_never_expires { flags.push("password never expires"); }
+            section.push_str(&format!("| {} | {} | {} |\n",
+                escape_cell(&user.name),
+                escape_cell(user.description.as_deref().unwrap_or("-")),
+                if flags.is_empty() { "-".to_string() } else { flags.join(", ") }));
+        }
+    }
+    
+    if !ldap.groups.is_empty() {
+        let groups: Vec<String> = ldap.groups.iter()
+            .map(|g| format!("{} ({})", g.name, g.members.len()))
+            .collect();
+        section.push_str(&format!("\n**Groups ({}):** {}\n", groups.len(), escape_cell(&groups.join(", "))));
+    }
+    
+    if !ldap.computers.is_empty() {
+        section.push_str("\n| Computer | DNS Name | OS |\n");
+        section.push_str("|----------|----------|----|\n");
+        for computer in &ldap.computers {
+            section.push_str(&format!("| {} | {} | {} |\n",
+                escape_cell(&computer.name),
+                computer.dns_host_name.as_deref().unwrap_or("-"),
+                escape_cell(computer.os.as_deref().unwrap_or("-"))));
+        }
+    }
+    section
+}
+
+// Render one SNMP agent: access, system group, interfaces, routes, processes and software
+fn render_snmp_section(snmp: &SnmpInfo) -> String {
+    let mut section = format!("\n## SNMP: {}\n\n", snmp.target);
+    let communities: Vec<String> = snmp.communities.iter()
+        .map(|c| format!("{} ({})", c.name, c.versions.join(", ")))
+        .collect();
+    let users: Vec<String> = snmp.users.iter()
+        .map(|u| if u.no_auth { format!("{} (noAuthNoPriv)", u.name) } else { u.name.clone() })
+        .collect();
+    let facts = [
+        ("Communities", Some(communities.join(", ")).filter(|c| !c.is_empty())),
+        ("SNMPv3 engine", snmp.engine_id.clone()),
+        ("SNMPv3 users", Some(users.join(", ")).filter(|u| !u.is_empty())),
+        ("Description", snmp.system.description.clone()),
+        ("Name", snmp.system.name.clone()),
+        ("Object ID", snmp.system.object_id.clone()),
+        ("Uptime", snmp.system.uptime.clone()),
+        ("Contact", snmp.system.contact.clone()),
+        ("Location", snmp.system.location.clone()),
+    ];
+    for (label, value) in facts {
+        if let Some(value) = value {
+            section.push_str(&format!("**{}:** {}  \n", label, escape_cell(&value)));
+        }
+    }
+    
+    if !snmp.interfaces.is_empty() {
+        section.push_str("\n| Interface | Type | MAC | Status | Addresses |\n");
+        section.push_str("|-----------|------|-----|--------|-----------|\n");
+        for interface in &snmp.interfaces {
+            section.push_str(&format!("| {} {} | {} | {} | {} | {} |\n",
+                interface.index,
+                escape_cell(interface.name.as_deref().unwrap_or("-")),
+                interface.kind.as_deref().unwrap_or("-"),
+                interface.mac.as_deref().unwrap_or("-"),
+                interface.oper_status.as_deref().unwrap_or("-"),
+                interface.addresses.join(", ")));
+        }
+    }
+    
+    if !snmp.routes.is_empty() {
+        section.push_str("\n| Destination | Mask | Next Hop | Interface | Type |\n");
+        section.push_str("|-------------|------|----------|-----------|------|\n");
+        for route in &snmp.routes {
+            section.push_str(&format!("| {} | {} | {} | {} | {} |\n",
+                route.destination,
+                route.mask.as_deref().unwrap_or("-"),
+                route.next_hop.as_deref().unwrap_or("-"),
+                route.interface.map(|i| i.to_string()).unwrap_or_else(|| "-".to_string()),
+                route.route_type.as_deref().unwrap_or("-")));
+        }
+    }
+    
+    if !snmp.processes.is_empty() {
+        section.push_str(&format!("\n**Processes ({}):**\n\n", snmp.processes.len()));
+        section.push_str("| PID | Name | Path | Parameters |\n");
+        section.push_str("|-----|------|------|------------|\n");
+        for process in &snmp.processes {
+            section.push_str(&format!("| {} | {} | {} | {} |\n",
+                process.pid,
+                escape_cell(process.name.as_deref().unwrap_or("-")),
+                escape_cell(process.path.as_deref().unwrap_or("-")),
+                escape_cell(process.parameters.as_deref().unwrap_or("-"))));
+        }
+    }
+    
+    if !snmp.software.is_empty() {
+        let software: Vec<String> = snmp.software.iter().map(|s| s.name.clone()).collect();
+        section.push_str(&format!("\n**Installed software ({}):** {}\n", software.len(), escape_cell(&software.join(", "))));
+    }
+    section
+}
+
+// Render every traced path hop by hop
+fn render_traceroute_section(results: &ScanResults) -> String {
+    let mut section = String::from("\n## Network Paths:\n\n");
+    let topology = results.topology();
+    section.push_str(&format!("**Topology:** {} nodes, {} links (see `TOPOLOGY.dot`)\n", topology.nodes.len(), topology.edges.len()));
+    for trace in &results.traces {
+        section.push_str(&format!("\n### {}{}\n\n", trace.target, if trace.reached { "" } else { " (not reached)" }));
+        section.push_str("| TTL | Address | Name | AS | Location | RTT (ms) |\n");
+        section.push_str("|-----|---------|------|----|----------|----------|\n");
+        for hop in &trace.hops {
+            let rtts: Vec<String> = hop.rtts.iter().map(|rtt| format!("{:.2}", rtt)).collect();
+            let owner = hop.geo.as_ref().and_then(|g| g.owner()).or_else(|| hop.asn.clone());
+            section.push_str(&format!("| {} | {} | {} | {} | {} | {} |\n",
+                hop.ttl,
+                hop.address.as_deref().unwrap_or("*"),
+                hop.hostname.as_deref().unwrap_or("-"),
+                escape_cell(owner.as_deref().unwrap_or("-")),
+                escape_cell(hop.geo.as_ref().and_then(|g| g.location()).as_deref().unwrap_or("-")),
+                if rtts.is_empty() { "-".to_string() } else { rtts.join(" / ") }));
+        }
+    }
+    section
+}
+
+// Escape characters that would break a markdown table cell
+fn escape_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
 }
\ No newline at end of file
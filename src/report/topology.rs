@@ -0,0 +1,124 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+use std::process::Command;
+
+use crate::models::{ScanResults, Topology, TopologyNode, SCANNER_NODE};
+
+// Paths of the exported topology graph
+pub struct TopologyFiles {
+    pub dot: String,
+    pub svg: Option<String>,         // Only when Graphviz is installed
+}
+
+// Export the traceroute topology as Graphviz DOT, rendering it to SVG when `dot` is available
+pub fn write_topology_graph(results: &ScanResults, scan_dir: &str) -> Result<TopologyFiles, Box<dyn Error>> {
+    let dot_file = format!("{}/TOPOLOGY.dot", scan_dir);
+    let mut file = File::create(&dot_file)?;
+    file.write_all(render_dot(&results.topology()).as_bytes())?;
+    
+    let svg_file = format!("{}/TOPOLOGY.svg", scan_dir);
+    let rendered = Command::new("dot")
+        .args(["-Tsvg", &dot_file, "-o", &svg_file])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    
+    Ok(TopologyFiles {
+        dot: dot_file,
+        svg: rendered.then_some(svg_file),
+    })
+}
+
+// Render the topology as a left-to-right DOT graph in the report colour scheme
+fn render_dot(topology: &Topology) -> String {
+    let mut dot = String::from("digraph topology {\n");
+    dot.push_str("    rankdir=LR;\n");
+    dot.push_str("    bgcolor=\"#191724\";\n");
+    dot.push_str("    node [shape=box, style=\"rounded,filled\", fillcolor=\"#26233a\", color=\"#9ccfd8\", fontcolor=\"#e0def4\", fontname=\"Helvetica\", fontsize=10];\n");
+    dot.push_str("    edge [color=\"#908caa\", arrowsize=0.6];\n\n");
+    
+    for node in topology.nodes.values() {
+        let attributes = if node.id == SCANNER_NODE {
+            "shape=ellipse, fillcolor=\"#31748f\"".to_string()
+        } else if node.is_target {
+            "fillcolor=\"#eb6f92\", color=\"#ebbcba\", fontcolor=\"#191724\"".to_string()
+        } else if node.address.is_none() {
+            "style=\"rounded,dashed\", color=\"#6e6a86\", fontcolor=\"#908caa\"".to_string()
+        } else {
+            String::new()
+        };
+        let separator = if attributes.is_empty() { "" } else { ", " };
+        dot.push_str(&format!("    \"{}\" [label=\"{}\"{}{}];\n",
+            escape_dot(&node.id), escape_dot(&node_label(node)), separator, attributes));
+    }
+    dot.push('\n');
+    
+    for edge in &topology.edges {
+        let style = if edge.inferred { " [style=dashed]" } else { "" };
+        dot.push_str(&format!("    \"{}\" -> \"{}\"{};\n", escape_dot(&edge.from), escape_dot(&edge.to), style));
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+// Node label: PTR name, address and AS on separate lines
+fn node_label(node: &TopologyNode) -> String {
+    if node.id == SCANNER_NODE {
+        return "Scanner".to_string();
+    }
+    let Some(address) = &node.address else {
+        return "*".to_string();
+    };
+    let mut lines = Vec::new();
+    if let Some(hostname) = &node.hostname {
+        lines.push(hostname.clone());
+    }
+    lines.push(address.clone());
+    if let Some(asn) = &node.asn {
+        lines.push(asn.clone());
+    }
+    lines.join("\n")
+}
+
+// Escape a value for use inside a quoted DOT string
+fn escape_dot(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Hop, Trace};
+
+    #[test]
+    fn dot_lists_nodes_and_edges_with_escaped_labels() {
+        let hop = |ttl: u8, address: Option<&str>, hostname: Option<&str>, asn: Option<&str>| Hop {
+            ttl,
+            address: address.map(|a| a.to_string()),
+            hostname: hostname.map(|h| h.to_string()),
+            asn: asn.map(|a| a.to_string()),
+            rtts: Vec::new(),
+            geo: None,
+        };
+        let trace = Trace {
+            target: "203.0.113.5".to_string(),
+            destination: Some("203.0.113.5".to_string()),
+            reached: false,
+            hops: vec![hop(1, Some("192.168.1.1"), Some("gw\\\"lab\""), Some("AS64500")), hop(2, None, None, None)],
+        };
+        let dot = render_dot(&Topology::from_traces(&[trace]));
+
+        let lines: Vec<&str> = dot.lines().map(|l| l.trim()).filter(|l| l.starts_with('"')).collect();
+        assert_eq!(lines, vec![
+            r##""192.168.1.1" [label="gw\\\"lab\"\n192.168.1.1\nAS64500"];"##,
+            r##""203.0.113.5" [label="203.0.113.5", fillcolor="#eb6f92", color="#ebbcba", fontcolor="#191724"];"##,
+            r##""scanner" [label="Scanner", shape=ellipse, fillcolor="#31748f"];"##,
+            r##""unknown:203.0.113.5:2" [label="*", style="rounded,dashed", color="#6e6a86", fontcolor="#908caa"];"##,
+            r##""192.168.1.1" -> "unknown:203.0.113.5:2";"##,
+            r##""scanner" -> "192.168.1.1";"##,
+            r##""unknown:203.0.113.5:2" -> "203.0.113.5" [style=dashed];"##,
+        ]);
+        assert!(dot.starts_with("digraph topology {\n") && dot.ends_with("}\n"));
+    }
+}
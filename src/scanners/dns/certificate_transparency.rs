@@ -0,0 +1,132 @@
+use console::style;
+use indicatif::ProgressBar;
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+use std::net::IpAddr;
+use std::time::Duration;
+use serde_json::Value;
+
+use crate::api::{api_base_url, ApiAuth, ApiClient, ApiSettings};
+use crate::models::{is_in_domain, normalise_name, widen_seen, CtName};
+
+// Any crt.sh-style JSON endpoint; overridable with CT_API_URL, e.g. to point at a local mock server
+const DEFAULT_API_URL: &str = "https://crt.sh";
+
+// crt.sh answers slowly for large domains; one query per lookup is all it takes
+const REQUEST_INTERVAL: Duration = Duration::from_secs(5);
+
+// Names found in Certificate Transparency logs and the certificates they came from
+pub struct CtResults {
+    pub source: String,              // Host of the endpoint searched (e.g. crt.sh)
+    pub names: Vec<CtName>,
+    pub certificates: usize,
+}
+
+// Search Certificate Transparency logs through a crt.sh-style JSON endpoint for names in the domain
+pub fn perform_ct_lookup(spinner: &ProgressBar, domain: &str, api: &ApiSettings, base_filename: &str, scan_dir: &str) -> Result<CtResults, Box<dyn Error>> {
+    if domain.parse::<IpAddr>().is_ok() || domain.contains('/') {
+        return Err("Certificate Transparency lookups need a domain".into());
+    }
+    let domain = normalise_name(domain);
+    let base_url = api_base_url(api, "CT_API_URL", DEFAULT_API_URL);
+    let source = ct_source(api);
+    let client = ApiClient::new("crt.sh", ApiAuth::None, REQUEST_INTERVAL, api)?;
+
+    spinner.set_message(format!("Searching Certificate Transparency logs for {} on {}...", style(&domain).cyan(), source));
+    let query = format!("%.{}", domain);
+    let response = client.get(&format!("{}/", base_url), &[("q", query.as_str()), ("output", "json")])?;
+    if !response.is_success() {
+        return Err(format!("Certificate Transparency search failed: HTTP {}", response.status).into());
+    }
+    let Some(entries) = response.body.as_array() else {
+        return Err("Certificate Transparency search did not return a JSON list".into());
+    };
+
+    let mut file = File::create(format!("{}/{}_ct.json", scan_dir, base_filename))?;
+    file.write_all(serde_json::to_string_pretty(&response.body)?.as_bytes())?;
+
+    spinner.set_message(format!("Merging names from {} certificates...", style(entries.len()).cyan()));
+    let (names, certificates) = parse_ct_entries(&domain, entries);
+    Ok(CtResults { source, names, certificates })
+}
+
+// Names in the domain from crt.sh-style entries, and the number of distinct certificates they came from
+fn parse_ct_entries(domain: &str, entries: &[Value]) -> (Vec<CtName>, usize) {
+    let mut names: BTreeMap<String, CtName> = BTreeMap::new();
+    let mut certificates = BTreeSet::new();
+    for entry in entries {
+        // crt.sh lists a certificate once per matching identity, so count distinct IDs
+        certificates.insert(entry["id"].as_u64().map(|id| id.to_string())
+            .or_else(|| entry["serial_number"].as_str().map(|s| s.to_string()))
+            .unwrap_or_default());
+        let first = entry["not_before"].as_str().filter(|s| !s.is_empty());
+        let last = entry["not_after"].as_str().filter(|s| !s.is_empty());
+
+        // name_value holds every SAN, one per line; the common name is usually among them
+        let listed = entry["name_value"].as_str().unwrap_or_default().lines()
+            .chain(entry["common_name"].as_str());
+        for listed in listed {
+            let listed = normalise_name(listed);
+            let (name, wildcard) = match listed.strip_prefix("*.") {
+                Some(name) => (name.to_string(), true),
+                None => (listed, false),
+            };
+            // Skip e-mail addresses and stray text some certificates carry in their subject
+            if !is_in_domain(&name, domain) || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.' || c == '_') {
+                continue;
+            }
+            let ct = names.entry(name.clone()).or_insert_with(|| CtName { name, ..Default::default() });
+            ct.wildcard |= wildcard;
+            widen_seen(&mut ct.first_seen, &mut ct.last_seen, first, last);
+        }
+    }
+    (names.into_values().collect(), certificates.len())
+}
+
+// Label for names found through the configured endpoint: its host, so a mirror or mock is not credited to crt.sh
+pub fn ct_source(api: &ApiSettings) -> String {
+    let base_url = api_base_url(api, "CT_API_URL", DEFAULT_API_URL);
+    reqwest::Url::parse(&base_url).ok()
+        .and_then(|url| url.host_str().map(|host| match url.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.to_string(),
+        }))
+        .unwrap_or(base_url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn entries_are_merged_per_name() {
+        let entries = json!([
+            {"id": 1, "common_name": "example.com", "name_value": "example.com\n*.example.com", "not_before": "2021-03-01T00:00:00", "not_after": "2021-05-30T00:00:00"},
+            {"id": 1, "common_name": "example.com", "name_value": "example.com\n*.example.com", "not_before": "2021-03-01T00:00:00", "not_after": "2021-05-30T00:00:00"},
+            {"id": 2, "common_name": "WWW.Example.com", "name_value": "www.example.com\nadmin@example.com\n*.dev.example.com", "not_before": "2024-01-01T00:00:00", "not_after": "2026-12-01T00:00:00"},
+            {"serial_number": "cc", "common_name": "www.example.com.", "name_value": "www.example.com\nother.org\nexample.com.evil.net", "not_before": "2019-06-01T00:00:00", "not_after": ""}
+        ]);
+        let (names, certificates) = parse_ct_entries("example.com", entries.as_array().unwrap());
+        assert_eq!(certificates, 3);
+
+        let summary: Vec<(&str, bool, Option<&str>, Option<&str>)> = names.iter()
+            .map(|n| (n.name.as_str(), n.wildcard, n.first_seen.as_deref(), n.last_seen.as_deref()))
+            .collect();
+        assert_eq!(summary, vec![
+            ("dev.example.com", true, Some("2024-01-01T00:00:00"), Some("2026-12-01T00:00:00")),
+            ("example.com", true, Some("2021-03-01T00:00:00"), Some("2021-05-30T00:00:00")),
+            ("www.example.com", false, Some("2019-06-01T00:00:00"), Some("2026-12-01T00:00:00")),
+        ]);
+    }
+
+    #[test]
+    fn entries_without_names_are_ignored() {
+        let entries = json!([{"id": 7}, {"id": 8, "name_value": null, "common_name": 5}]);
+        let (names, certificates) = parse_ct_entries("example.com", entries.as_array().unwrap());
+        assert!(names.is_empty());
+        assert_eq!(certificates, 2);
+    }
+}
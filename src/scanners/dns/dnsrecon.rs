@@ -5,25 +5,33 @@ use std::error::Error;
 use std::fs::File;
 use std::io::Write;
 
-// Perform DNSrecon domain enumeration with spinner
-pub fn perform_dnsrecon(spinner: &ProgressBar, target: &str, base_filename: &str, scan_dir: &str) -> Result<(), Box<dyn Error>> {
-    let output_file = format!("{}/{}_dnsrecon.txt", scan_dir, base_filename);
-    
+use crate::models::{DnsRecord, Finding, Severity};
+
+// Parsed output of a dnsrecon run
+pub struct DnsreconResults {
+    pub records: Vec<DnsRecord>,
+    pub findings: Vec<Finding>,
+}
+
+// Perform DNSrecon domain enumeration with spinner and return the parsed records
+pub fn perform_dnsrecon(spinner: &ProgressBar, target: &str, base_filename: &str, scan_dir: &str) -> Result<DnsreconResults, Box<dyn Error>> {
+    let output_file = format!("{}/{}_dnsrecon.csv", scan_dir, base_filename);
+
     // Update spinner message
     spinner.set_message(format!("Starting DNS reconnaissance on {}...", style(target).cyan()));
-    
+
     let output = Command::new("dnsrecon")
         .arg("-d").arg(target)
         .arg("-t").arg("std,srv,axfr,rvl")  // Standard tests
         .arg("--csv").arg(&output_file)
         .output()?;
-    
+
     // Save text output too
     spinner.set_message(format!("Processing DNSrecon results for {}...", style(target).cyan()));
     let text_output = format!("{}/{}_dnsrecon_text.txt", scan_dir, base_filename);
     let mut file = File::create(text_output)?;
     file.write_all(&output.stdout)?;
-    
+
     // If there was an error, save stderr
     if !output.status.success() {
         let error_file = format!("{}/{}_dnsrecon_error.txt", scan_dir, base_filename);
@@ -31,6 +39,144 @@ pub fn perform_dnsrecon(spinner: &ProgressBar, target: &str, base_filename: &str
         file.write_all(&output.stderr)?;
         return Err(format!("DNSrecon errors - see {} for details", error_file).into());
     }
-    
-    Ok(())
-}
\ No newline at end of file
+
+    let records = parse_dnsrecon_csv(&output_file)?;
+    let findings = parse_zone_transfer_results(target, &String::from_utf8_lossy(&output.stdout));
+
+    Ok(DnsreconResults { records, findings })
+}
+
+// Parse the dnsrecon --csv file into typed DNS records
+pub fn parse_dnsrecon_csv(csv_file: &str) -> Result<Vec<DnsRecord>, Box<dyn Error>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_path(csv_file)?;
+
+    // Column order differs between dnsrecon releases, so locate columns by header
+    let headers: Vec<String> = reader.headers()?.iter().map(|h| h.trim().to_lowercase()).collect();
+    let column = |name: &str| headers.iter().position(|h| h == name);
+    let (type_col, name_col, address_col, target_col, port_col, string_col) = (
+        column("type"), column("name"), column("address"), column("target"), column("port"), column("string"),
+    );
+    let type_col = type_col.ok_or("dnsrecon CSV has no Type column")?;
+
+    let mut records = Vec::new();
+    for row in reader.records() {
+        let row = row?;
+        let field = |col: Option<usize>| col
+            .and_then(|c| row.get(c))
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+
+        let record_type = match field(Some(type_col)) {
+            Some(t) => t.to_uppercase(),
+            None => continue,
+        };
+        // "info" rows are dnsrecon status messages, not records
+        if record_type == "INFO" {
+            continue;
+        }
+
+        records.push(DnsRecord {
+            source: "DNSrecon".to_string(),
+            record_type,
+            name: field(name_col).unwrap_or_default(),
+            address: field(address_col),
+            target: field(target_col),
+            port: field(port_col).and_then(|p| p.parse().ok()),
+            data: field(string_col),
+        });
+    }
+
+    Ok(records)
+}
+
+// Turn dnsrecon's AXFR console output into a finding listing the name servers that allowed the transfer
+pub fn parse_zone_transfer_results(domain: &str, stdout: &str) -> Vec<Finding> {
+    let mut servers: Vec<String> = Vec::new();
+    let mut current_server = String::new();
+
+    for line in stdout.lines() {
+        if let Some(server) = line.split("Trying NS server ").nth(1) {
+            current_server = server.trim().to_string();
+        } else if line.contains("Zone Transfer was successful") {
+            let server = if current_server.is_empty() { "unknown name server" } else { current_server.as_str() };
+            if !servers.iter().any(|s| s == server) {
+                servers.push(server.to_string());
+            }
+        }
+    }
+
+    if servers.is_empty() {
+        return Vec::new();
+    }
+    vec![Finding {
+        source: "DNSrecon".to_string(),
+        id: Some("dns-axfr".to_string()),
+        severity: Severity::High,
+        location: domain.to_string(),
+        method: None,
+        description: format!("DNS zone transfer (AXFR) of {} allowed by {}", domain, servers.join(", ")),
+        references: vec!["https://www.rfc-editor.org/rfc/rfc5936".to_string()],
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(name: &str, csv: &str) -> Result<Vec<DnsRecord>, Box<dyn Error>> {
+        let path = std::env::temp_dir().join(format!("rust_recon_dnsrecon_{}_{}.csv", name, std::process::id()));
+        std::fs::write(&path, csv).unwrap();
+        let records = parse_dnsrecon_csv(path.to_str().unwrap());
+        std::fs::remove_file(&path).ok();
+        records
+    }
+
+    #[test]
+    fn csv_columns_are_found_by_header() {
+        let records = parse("columns", "Domain,Type,Name,Address,Target,Port,String\n\
+            example.com,info,,,,,Zone Transfer was successful\n\
+            example.com,A,www.example.com,192.0.2.10,,,\n\
+            example.com,mx,example.com,192.0.2.20,mail.example.com,,\n\
+            example.com,SRV,_sip._tcp.example.com,192.0.2.30,sip.example.com,5060,\n\
+            example.com,TXT,example.com,,,,v=spf1 -all\n\
+            example.com,,ignored.example.com,,,,\n").unwrap();
+
+        let summary: Vec<String> = records.iter()
+            .map(|r| format!("{} {} {:?} {:?} {:?} {:?}", r.record_type, r.name, r.address, r.target, r.port, r.data))
+            .collect();
+        assert_eq!(summary, vec![
+            r#"A www.example.com Some("192.0.2.10") None None None"#,
+            r#"MX example.com Some("192.0.2.20") Some("mail.example.com") None None"#,
+            r#"SRV _sip._tcp.example.com Some("192.0.2.30") Some("sip.example.com") Some(5060) None"#,
+            r#"TXT example.com None None None Some("v=spf1 -all")"#,
+        ]);
+    }
+
+    #[test]
+    fn older_column_orders_and_short_rows_are_accepted() {
+        let records = parse("order", "Type,Address,Name\nPTR,192.0.2.1,host1.example.com\nA\n").unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!((records[0].name.as_str(), records[0].address.as_deref()), ("host1.example.com", Some("192.0.2.1")));
+        assert_eq!((records[1].name.as_str(), records[1].address.as_deref()), ("", None));
+
+        assert!(parse("no_type", "Name,Address\nwww.example.com,192.0.2.1\n").is_err());
+    }
+
+    #[test]
+    fn successful_transfers_are_one_finding() {
+        let stdout = "[*] Checking for Zone Transfer for example.com name servers\n\
+            [*] Trying NS server 192.0.2.53\n\
+            [+] 192.0.2.53 Has port 53 TCP Open\n\
+            [+] Zone Transfer was successful!!\n\
+            [*] Trying NS server 192.0.2.54\n\
+            [-] Zone Transfer Failed for 192.0.2.54!\n\
+            [*] Trying NS server 192.0.2.55\n\
+            [+] Zone Transfer was successful!!\n";
+        let findings = parse_zone_transfer_results("example.com", stdout);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].description, "DNS zone transfer (AXFR) of example.com allowed by 192.0.2.53, 192.0.2.55");
+        assert!(parse_zone_transfer_results("example.com", "[-] Zone Transfer Failed!\n").is_empty());
+    }
+}
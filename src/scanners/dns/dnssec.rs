@@ -0,0 +1,594 @@
+use console::style;
+use indicatif::ProgressBar;
+use ring::{digest, signature};
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+use std::net::SocketAddr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::models::{DnsRecord, DnssecReport, DnssecStatus, DnssecZone, Finding, Severity};
+use crate::scanners::dns::resolver::{
+    encode_name, rcode_name, type_name, DnsClient, RData, ResourceRecord, RCODE_NOERROR, RCODE_NXDOMAIN,
+    TYPE_DNSKEY, TYPE_DS, TYPE_NSEC, TYPE_NSEC3PARAM, TYPE_SOA,
+};
+
+// IANA root zone trust anchors (KSK-2017 and KSK-2024) as SHA-256 DS digests
+const ROOT_TRUST_ANCHORS: &[(u16, u8, &str)] = &[
+    (20326, 8, "e06d44b80b8f1d39a95c0b0d7c65d08458e880409bbc683457104237c7f8ec8d"),
+    (38696, 8, "683d2d0acb8c9b712a1948b27f741219298d0a450d612c483af444a4c0fb2b16"),
+];
+
+// Signatures expiring within this window are reported
+const EXPIRY_WARNING_SECS: i64 = 7 * 24 * 3600;
+
+// Maximum number of names enumerated when demonstrating an NSEC walk
+const NSEC_WALK_LIMIT: usize = 25;
+
+// DNSSEC assessment plus findings and any names enumerated through NSEC
+pub struct DnssecResults {
+    pub report: DnssecReport,
+    pub records: Vec<DnsRecord>,
+    pub findings: Vec<Finding>,
+}
+
+// A DS digest, either published by the parent or a built-in trust anchor
+struct Ds {
+    key_tag: u16,
+    algorithm: u8,
+    digest_type: u8,
+    digest: Vec<u8>,
+}
+
+// Walk the delegation chain from the root to the domain's zone, validating DS/DNSKEY/RRSIG at each step
+pub fn perform_dnssec(spinner: &ProgressBar, domain: &str, resolvers: Vec<SocketAddr>, rate_limit: u32, base_filename: &str, scan_dir: &str) -> Result<DnssecResults, Box<dyn Error>> {
+    let output_file = format!("{}/{}_dnssec.txt", scan_dir, base_filename);
+    let domain = domain.trim_end_matches('.').to_lowercase();
+    let client = DnsClient::new(resolvers).with_rate_limit(rate_limit).with_dnssec();
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+
+    spinner.set_message(format!("Locating the zone apex of {}...", style(&domain).cyan()));
+    let apex = find_zone_apex(&client, &domain)?;
+
+    let mut report = DnssecReport { domain: domain.clone(), ..Default::default() };
+    let mut findings = Vec::new();
+    let mut log: Vec<String> = Vec::new();
+
+    // Validated keys of the parent zone (None once the chain is broken)
+    let mut parent_keys: Option<Vec<ResourceRecord>> = None;
+    let mut parent_status = DnssecStatus::Secure;
+    let mut apex_rrsigs = Vec::new();
+
+    for zone in chain_zones(&apex, |name| is_zone_apex(&client, name))? {
+        spinner.set_message(format!("Validating DNSSEC for zone {}...", style(&zone).cyan()));
+        log.push(format!("; ---- zone {} ----", zone));
+
+        let mut problems = Vec::new();
+        let (dnskeys, dnskey_sigs) = fetch_rrset(&client, &zone, TYPE_DNSKEY, &mut log)?;
+        let mut rrsigs: Vec<ResourceRecord> = dnskey_sigs.clone();
+
+        // DS set from the parent (built-in anchors for the root)
+        let ds_set: Vec<Ds> = if zone == "." {
+            ROOT_TRUST_ANCHORS.iter()
+                .map(|(key_tag, algorithm, digest)| Ds { key_tag: *key_tag, algorithm: *algorithm, digest_type: 2, digest: decode_hex(digest) })
+                .collect()
+        } else {
+            let (ds_records, ds_sigs) = fetch_rrset(&client, &zone, TYPE_DS, &mut log)?;
+            if let Some(keys) = &parent_keys
+                && !ds_records.is_empty()
+                && let Err(e) = verify_rrset(&ds_records, &ds_sigs, keys, now) {
+                problems.push(format!("DS RRset signature from the parent does not validate: {}", e));
+            }
+            ds_records.iter().filter_map(|r| match &r.data {
+                RData::Ds { key_tag, algorithm, digest_type, digest } => Some(Ds { key_tag: *key_tag, algorithm: *algorithm, digest_type: *digest_type, digest: digest.clone() }),
+                _ => None,
+            }).collect()
+        };
+
+        // DNSKEYs referenced by the DS set must sign the DNSKEY RRset
+        let mut validated_keys = None;
+        let root_unavailable = zone == "." && dnskeys.is_empty();
+        if !ds_set.is_empty() && !root_unavailable {
+            let anchored: Vec<ResourceRecord> = dnskeys.iter().filter(|k| ds_set.iter().any(|ds| ds_matches(&zone, k, ds))).cloned().collect();
+            if dnskeys.is_empty() {
+                problems.push("DS published at the parent but the zone serves no DNSKEY records".to_string());
+            } else if anchored.is_empty() {
+                problems.push(format!("None of the {} DNSKEYs match the parent's DS records (key tags {})", dnskeys.len(),
+                    ds_set.iter().map(|d| d.key_tag.to_string()).collect::<Vec<_>>().join(", ")));
+            } else {
+                match verify_rrset(&dnskeys, &dnskey_sigs, &anchored, now) {
+                    Ok(()) => validated_keys = Some(dnskeys.clone()),
+                    Err(e) => problems.push(format!("DNSKEY RRset signature does not validate: {}", e)),
+                }
+            }
+        }
+
+        // At the apex, the zone data itself must be signed by the validated keys
+        if zone == apex {
+            let (soa, soa_sigs) = fetch_rrset(&client, &zone, TYPE_SOA, &mut log)?;
+            rrsigs.extend(soa_sigs.iter().cloned());
+            if let Some(keys) = &validated_keys
+                && let Err(e) = verify_rrset(&soa, &soa_sigs, keys, now) {
+                problems.push(format!("SOA RRset signature does not validate: {}", e));
+            }
+        }
+
+        let status = if root_unavailable {
+            // Some resolvers won't serve the root DNSKEY set; the chain below can't be anchored
+            problems.push("root DNSKEY RRset not returned by the resolver".to_string());
+            DnssecStatus::Indeterminate
+        } else if ds_set.is_empty() {
+            DnssecStatus::Insecure
+        } else if !problems.is_empty() {
+            DnssecStatus::Bogus
+        } else if zone == "." || parent_status == DnssecStatus::Secure {
+            DnssecStatus::Secure
+        } else if parent_status == DnssecStatus::Insecure {
+            DnssecStatus::Insecure
+        } else {
+            DnssecStatus::Indeterminate
+        };
+        // Parent keys only matter while the chain is intact
+        parent_keys = if status == DnssecStatus::Secure { validated_keys } else { None };
+        parent_status = status;
+
+        let mut algorithms: Vec<String> = dnskeys.iter()
+            .filter_map(|k| match &k.data { RData::Dnskey { algorithm, .. } => Some(algorithm_name(*algorithm)), _ => None })
+            .collect();
+        algorithms.sort();
+        algorithms.dedup();
+
+        let earliest_expiry = rrsigs.iter()
+            .filter_map(|s| match &s.data { RData::Rrsig { expiration, .. } => Some(*expiration as i64), _ => None })
+            .min();
+
+        log.push(format!("; {} -> {}{}", zone, status, if problems.is_empty() { String::new() } else { format!(" ({})", problems.join("; ")) }));
+        if zone == apex {
+            apex_rrsigs = rrsigs;
+            apex_findings(&apex, status, &dnskeys, &ds_set, &problems, &mut findings);
+        }
+        report.chain.push(DnssecZone {
+            zone: zone.clone(),
+            status,
+            ds_records: ds_set.len(),
+            dnskeys: dnskeys.len(),
+            algorithms,
+            signature_expiry: earliest_expiry.map(format_timestamp),
+            problems,
+        });
+    }
+
+    // Signatures about to expire at the apex
+    for sig in &apex_rrsigs {
+        if let RData::Rrsig { type_covered, expiration, key_tag, .. } = &sig.data {
+            let remaining = *expiration as i64 - now;
+            if remaining > 0 && remaining < EXPIRY_WARNING_SECS {
+                findings.push(dnssec_finding("dnssec-signature-expiring", Severity::Medium, &apex,
+                    format!("RRSIG over the {} RRset of {} (key tag {}) expires on {} ({} hours left)",
+                        type_name(*type_covered), apex, key_tag, format_timestamp(*expiration as i64), remaining / 3600),
+                    "https://www.rfc-editor.org/rfc/rfc6781#section-4.4.2"));
+            }
+        }
+    }
+
+    // Authenticated denial of existence: NSEC chains can be walked to list every name in the zone
+    let signed = report.chain.last().map(|z| z.dnskeys > 0).unwrap_or(false);
+    let mut records = Vec::new();
+    if signed {
+        spinner.set_message(format!("Checking NSEC/NSEC3 configuration of {}...", style(&apex).cyan()));
+        check_denial(&client, &apex, &mut report, &mut records, &mut findings, &mut log)?;
+    }
+
+    let mut file = File::create(output_file)?;
+    writeln!(file, "; DNSSEC chain of trust for {} (zone {})", domain, apex)?;
+    for line in &log {
+        writeln!(file, "{}", line)?;
+    }
+
+    Ok(DnssecResults { report, records, findings })
+}
+
+// The zone that contains the domain: the closest enclosing name with its own SOA
+fn find_zone_apex(client: &DnsClient, domain: &str) -> Result<String, Box<dyn Error>> {
+    let labels: Vec<&str> = domain.split('.').collect();
+    for i in 0..labels.len() {
+        let candidate = labels[i..].join(".");
+        if is_zone_apex(client, &candidate)? {
+            return Ok(candidate);
+        }
+    }
+    Err(format!("No SOA found for {} or any parent zone", domain).into())
+}
+
+// Only a zone apex answers an SOA query with its own SOA; other names get NODATA
+fn is_zone_apex(client: &DnsClient, name: &str) -> Result<bool, Box<dyn Error>> {
+    let response = client.query(name, TYPE_SOA)?;
+    Ok(response.answers.iter().any(|r| r.rtype == TYPE_SOA && same_name(&r.name, name)))
+}
+
+// Zone cuts from the root down to the apex: ".", "com", "example.com"; names in between that
+// are not delegated belong to their parent's zone, whose keys sign the next DS down
+fn chain_zones(apex: &str, mut is_apex: impl FnMut(&str) -> Result<bool, Box<dyn Error>>) -> Result<Vec<String>, Box<dyn Error>> {
+    let labels: Vec<&str> = apex.split('.').collect();
+    let mut zones = vec![".".to_string()];
+    for i in (0..labels.len()).rev() {
+        let candidate = labels[i..].join(".");
+        if i == 0 || is_apex(&candidate)? {
+            zones.push(candidate);
+        }
+    }
+    Ok(zones)
+}
+
+// Records of one type at a name, plus the RRSIGs covering them
+fn fetch_rrset(client: &DnsClient, name: &str, rtype: u16, log: &mut Vec<String>) -> Result<(Vec<ResourceRecord>, Vec<ResourceRecord>), Box<dyn Error>> {
+    let response = client.query(name, rtype)?;
+    if response.rcode != RCODE_NOERROR && response.rcode != RCODE_NXDOMAIN {
+        return Err(format!("{} lookup for {} failed: {}", type_name(rtype), name,
+            rcode_name(response.rcode)).into());
+    }
+
+    let mut rrset = Vec::new();
+    let mut sigs = Vec::new();
+    for record in response.answers.into_iter().filter(|r| same_name(&r.name, name)) {
+        if record.rtype == rtype {
+            rrset.push(record);
+        } else if matches!(record.data, RData::Rrsig { type_covered, .. } if type_covered == rtype) {
+            sigs.push(record);
+        }
+    }
+    log.extend(rrset.iter().chain(sigs.iter()).map(|r| r.to_string()));
+    Ok((rrset, sigs))
+}
+
+// Validate an RRset against any RRSIG made by one of the given keys (RFC 4035 section 5.3)
+fn verify_rrset(rrset: &[ResourceRecord], sigs: &[ResourceRecord], keys: &[ResourceRecord], now: i64) -> Result<(), String> {
+    if rrset.is_empty() {
+        return Err("RRset is empty".to_string());
+    }
+    if sigs.is_empty() {
+        return Err("no RRSIG covers the RRset".to_string());
+    }
+
+    let mut last_error = String::from("no RRSIG was made by a trusted key");
+    for sig in sigs {
+        let RData::Rrsig { algorithm, labels, original_ttl, expiration, inception, key_tag, signature, .. } = &sig.data else { continue };
+        if now > *expiration as i64 {
+            last_error = format!("RRSIG (key tag {}) expired on {}", key_tag, format_timestamp(*expiration as i64));
+            continue;
+        }
+        if now < *inception as i64 {
+            last_error = format!("RRSIG (key tag {}) is not valid until {}", key_tag, format_timestamp(*inception as i64));
+            continue;
+        }
+
+        // Signed data: RRSIG RDATA without the signature, then the RRset in canonical order
+        let mut message = sig.rdata[..sig.rdata.len() - signature.len()].to_vec();
+        let owner = canonical_owner(&rrset[0].name, *labels);
+        let mut rdatas: Vec<&Vec<u8>> = rrset.iter().map(|r| &r.rdata).collect();
+        rdatas.sort();
+        rdatas.dedup();
+        for rdata in rdatas {
+            encode_name(&mut message, &owner);
+            message.extend_from_slice(&rrset[0].rtype.to_be_bytes());
+            message.extend_from_slice(&rrset[0].class.to_be_bytes());
+            message.extend_from_slice(&original_ttl.to_be_bytes());
+            message.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+            message.extend_from_slice(rdata);
+        }
+
+        for key in keys.iter().filter(|k| key_tag_of(&k.rdata) == *key_tag) {
+            let RData::Dnskey { algorithm: key_algorithm, public_key, .. } = &key.data else { continue };
+            if key_algorithm != algorithm {
+                continue;
+            }
+            match verify_signature(*algorithm, public_key, &message, signature) {
+                Ok(()) => return Ok(()),
+                Err(e) => last_error = format!("RRSIG (key tag {}): {}", key_tag, e),
+            }
+        }
+    }
+    Err(last_error)
+}
+
+// Verify one signature with a DNSKEY public key (RFC 3110, RFC 5702, RFC 6605, RFC 8080)
+fn verify_signature(algorithm: u8, public_key: &[u8], message: &[u8], sig: &[u8]) -> Result<(), String> {
+    match algorithm {
+        5 | 7 | 8 | 10 => {
+            // Exponent length is one byte, or zero followed by a two-byte length
+            let (exponent_len, offset) = match public_key.first() {
+                Some(0) if public_key.len() > 3 => (u16::from_be_bytes([public_key[1], public_key[2]]) as usize, 3),
+                Some(len) => (*len as usize, 1),
+                None => return Err("empty RSA key".to_string()),
+            };
+            let e = public_key.get(offset..offset + exponent_len).ok_or("truncated RSA key")?;
+            let n = &public_key[offset + exponent_len..];
+            let parameters = match algorithm {
+                5 | 7 => &signature::RSA_PKCS1_1024_8192_SHA1_FOR_LEGACY_USE_ONLY,
+                8 => &signature::RSA_PKCS1_1024_8192_SHA256_FOR_LEGACY_USE_ONLY,
+                _ => &signature::RSA_PKCS1_1024_8192_SHA512_FOR_LEGACY_USE_ONLY,
+            };
+            signature::RsaPublicKeyComponents { n, e }
+                .verify(parameters, message, sig)
+                .map_err(|_| "RSA signature mismatch".to_string())
+        },
+        13 | 14 => {
+            // DNSKEY carries the raw X||Y point; ring expects the uncompressed SEC1 encoding
+            let mut point = vec![0x04];
+            point.extend_from_slice(public_key);
+            let verifier = if algorithm == 13 { &signature::ECDSA_P256_SHA256_FIXED } else { &signature::ECDSA_P384_SHA384_FIXED };
+            signature::UnparsedPublicKey::new(verifier, point)
+                .verify(message, sig)
+                .map_err(|_| "ECDSA signature mismatch".to_string())
+        },
+        15 => signature::UnparsedPublicKey::new(&signature::ED25519, public_key)
+            .verify(message, sig)
+            .map_err(|_| "Ed25519 signature mismatch".to_string()),
+        other => Err(format!("unsupported algorithm {}", algorithm_name(other))),
+    }
+}
+
+// A DS matches a DNSKEY when tag, algorithm and digest of owner name + RDATA agree
+fn ds_matches(zone: &str, dnskey: &ResourceRecord, ds: &Ds) -> bool {
+    let RData::Dnskey { algorithm, .. } = &dnskey.data else { return false };
+    if *algorithm != ds.algorithm || key_tag_of(&dnskey.rdata) != ds.key_tag {
+        return false;
+    }
+    let mut data = Vec::new();
+    encode_name(&mut data, &zone.to_lowercase());
+    data.extend_from_slice(&dnskey.rdata);
+    let computed = match ds.digest_type {
+        1 => digest::digest(&digest::SHA1_FOR_LEGACY_USE_ONLY, &data),
+        2 => digest::digest(&digest::SHA256, &data),
+        4 => digest::digest(&digest::SHA384, &data),
+        _ => return false,
+    };
+    computed.as_ref() == ds.digest.as_slice()
+}
+
+// Key tag of a DNSKEY RDATA (RFC 4034 appendix B)
+fn key_tag_of(rdata: &[u8]) -> u16 {
+    let mut accumulator: u32 = 0;
+    for (i, byte) in rdata.iter().enumerate() {
+        accumulator += if i & 1 == 1 { *byte as u32 } else { (*byte as u32) << 8 };
+    }
+    accumulator += (accumulator >> 16) & 0xffff;
+    (accumulator & 0xffff) as u16
+}
+
+// Owner name as signed: lower-cased, with wildcard expansion undone when the RRSIG has fewer labels
+fn canonical_owner(name: &str, labels: u8) -> String {
+    let name = name.trim_end_matches('.').to_lowercase();
+    let owner_labels: Vec<&str> = name.split('.').filter(|l| !l.is_empty()).collect();
+    if (labels as usize) < owner_labels.len() {
+        let suffix = owner_labels[owner_labels.len() - labels as usize..].join(".");
+        if suffix.is_empty() { "*".to_string() } else { format!("*.{}", suffix) }
+    } else {
+        name
+    }
+}
+
+// Findings for the target's own zone
+fn apex_findings(apex: &str, status: DnssecStatus, dnskeys: &[ResourceRecord], ds_set: &[Ds], problems: &[String], findings: &mut Vec<Finding>) {
+    match status {
+        DnssecStatus::Insecure if ds_set.is_empty() && !dnskeys.is_empty() => findings.push(dnssec_finding("dnssec-missing-ds", Severity::Medium, apex,
+            format!("{} is signed ({} DNSKEYs) but the parent publishes no DS record, so resolvers treat it as unsigned", apex, dnskeys.len()),
+            "https://www.rfc-editor.org/rfc/rfc4035#section-4.3")),
+        DnssecStatus::Insecure if ds_set.is_empty() => findings.push(dnssec_finding("dnssec-unsigned", Severity::Low, apex,
+            format!("{} is not DNSSEC-signed: DNS answers for it can be spoofed or poisoned", apex),
+            "https://www.rfc-editor.org/rfc/rfc4033")),
+        DnssecStatus::Bogus => findings.push(dnssec_finding("dnssec-bogus", Severity::High, apex,
+            format!("DNSSEC validation fails for {} (validating resolvers return SERVFAIL): {}", apex, problems.join("; ")),
+            "https://www.rfc-editor.org/rfc/rfc4035#section-5.5")),
+        _ => {},
+    }
+
+    let weak: Vec<String> = dnskeys.iter()
+        .filter_map(|k| match &k.data { RData::Dnskey { algorithm, .. } if matches!(algorithm, 1 | 3 | 5 | 6 | 7 | 12) => Some(algorithm_name(*algorithm)), _ => None })
+        .collect();
+    if !weak.is_empty() {
+        findings.push(dnssec_finding("dnssec-weak-algorithm", Severity::Low, apex,
+            format!("{} signs with deprecated DNSSEC algorithm(s): {}", apex, weak.join(", ")),
+            "https://www.rfc-editor.org/rfc/rfc8624#section-3.1"));
+    }
+    if !ds_set.is_empty() && ds_set.iter().all(|d| d.digest_type == 1) {
+        findings.push(dnssec_finding("dnssec-sha1-ds", Severity::Low, apex,
+            format!("The DS records for {} only use SHA-1 digests", apex),
+            "https://www.rfc-editor.org/rfc/rfc8624#section-3.3"));
+    }
+}
+
+// Determine NSEC vs NSEC3 and demonstrate zone walking when plain NSEC is used
+fn check_denial(client: &DnsClient, apex: &str, report: &mut DnssecReport, records: &mut Vec<DnsRecord>, findings: &mut Vec<Finding>, log: &mut Vec<String>) -> Result<(), Box<dyn Error>> {
+    let (params, _) = fetch_rrset(client, apex, TYPE_NSEC3PARAM, log)?;
+    if let Some(RData::Nsec3Param { iterations, salt, .. }) = params.first().map(|p| &p.data) {
+        report.denial = Some(format!("NSEC3 ({} iterations, {})", iterations, if salt.is_empty() { "no salt".to_string() } else { format!("{}-byte salt", salt.len()) }));
+        if *iterations > 0 || !salt.is_empty() {
+            findings.push(dnssec_finding("dnssec-nsec3-parameters", Severity::Info, apex,
+                format!("NSEC3 for {} uses {} extra iterations and a {}-byte salt; RFC 9276 recommends 0 iterations and no salt", apex, iterations, salt.len()),
+                "https://www.rfc-editor.org/rfc/rfc9276#section-3.1"));
+        }
+        return Ok(());
+    }
+
+    // Follow the NSEC chain from the apex until it wraps around
+    let mut current = apex.to_string();
+    for _ in 0..NSEC_WALK_LIMIT {
+        let (nsec, _) = fetch_rrset(client, &current, TYPE_NSEC, log)?;
+        let Some(record) = nsec.first() else { break };
+        let Some(next) = record.target().map(|n| n.trim_end_matches('.').to_lowercase()) else { break };
+        records.push(record.to_dns_record("NSEC walk"));
+        report.walked_names.push(current.clone());
+        if same_name(&next, apex) || report.walked_names.contains(&next) {
+            break;
+        }
+        current = next;
+    }
+
+    if !report.walked_names.is_empty() {
+        report.denial = Some("NSEC (zone walkable)".to_string());
+        findings.push(dnssec_finding("dnssec-nsec-walkable", Severity::Medium, apex,
+            format!("{} uses NSEC, so its whole zone can be enumerated by walking the chain ({} names listed: {})",
+                apex, report.walked_names.len(), report.walked_names.join(", ")),
+            "https://www.rfc-editor.org/rfc/rfc5155#section-1"));
+    }
+    Ok(())
+}
+
+fn dnssec_finding(id: &str, severity: Severity, location: &str, description: String, reference: &str) -> Finding {
+    Finding {
+        source: "DNSSEC".to_string(),
+        id: Some(id.to_string()),
+        severity,
+        location: location.to_string(),
+        method: None,
+        description,
+        references: vec![reference.to_string()],
+    }
+}
+
+// IANA DNSSEC algorithm mnemonics
+fn algorithm_name(algorithm: u8) -> String {
+    match algorithm {
+        1 => "RSAMD5".to_string(),
+        3 => "DSA".to_string(),
+        5 => "RSASHA1".to_string(),
+        6 => "DSA-NSEC3-SHA1".to_string(),
+        7 => "RSASHA1-NSEC3-SHA1".to_string(),
+        8 => "RSASHA256".to_string(),
+        10 => "RSASHA512".to_string(),
+        12 => "ECC-GOST".to_string(),
+        13 => "ECDSAP256SHA256".to_string(),
+        14 => "ECDSAP384SHA384".to_string(),
+        15 => "ED25519".to_string(),
+        16 => "ED448".to_string(),
+        other => format!("ALG{}", other),
+    }
+}
+
+fn same_name(a: &str, b: &str) -> bool {
+    let a = a.trim_end_matches('.');
+    let b = b.trim_end_matches('.');
+    a.eq_ignore_ascii_case(b)
+}
+
+fn format_timestamp(secs: i64) -> String {
+    chrono::DateTime::from_timestamp(secs, 0)
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| secs.to_string())
+}
+
+fn decode_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len() / 2).filter_map(|i| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanners::dns::resolver::{parse_message, TYPE_MX, TYPE_RRSIG};
+    use base64::Engine;
+
+    fn base64(text: &str) -> Vec<u8> {
+        base64::engine::general_purpose::STANDARD.decode(text).unwrap()
+    }
+
+    // Parse records through a DNS answer, so their RDATA goes through the same canonicalisation as real answers
+    fn records(entries: &[(&str, u16, Vec<u8>)]) -> Vec<ResourceRecord> {
+        let mut message = vec![0, 0, 0x84, 0, 0, 0, 0, entries.len() as u8, 0, 0, 0, 0];
+        for (owner, rtype, rdata) in entries {
+            encode_name(&mut message, owner);
+            message.extend_from_slice(&rtype.to_be_bytes());
+            message.extend_from_slice(&[0, 1, 0, 0, 0x0e, 0x10]);
+            message.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+            message.extend_from_slice(rdata);
+        }
+        parse_message(&message).unwrap().answers
+    }
+
+    fn dnskey(flags: u16, algorithm: u8, public_key: &str) -> Vec<u8> {
+        let mut rdata = flags.to_be_bytes().to_vec();
+        rdata.extend_from_slice(&[3, algorithm]);
+        rdata.extend_from_slice(&base64(public_key));
+        rdata
+    }
+
+    // RFC 8080 section 6.1: Ed25519 KSK and the MX RRset it signs
+    const ED25519_KEY: &str = "l02Woi0iS8Aa25FQkUd9RMzZHJpBoRQwAQEX1SxZJA4=";
+    const ED25519_MX_SIGNATURE: &str = "oL9krJun7xfBOIWcGHi7mag5/hdZrKWw15jPGrHpjQeRAvTdszaPD+QLs3fx8A4M3e23mRZ9VrbpMngwcrqNAg==";
+
+    fn rrsig(type_covered: u16, key_tag: u16, signature: &str) -> Vec<u8> {
+        let mut rdata = type_covered.to_be_bytes().to_vec();
+        rdata.extend_from_slice(&[15, 2]);
+        rdata.extend_from_slice(&3600u32.to_be_bytes());
+        rdata.extend_from_slice(&1440021600u32.to_be_bytes());
+        rdata.extend_from_slice(&1438207200u32.to_be_bytes());
+        rdata.extend_from_slice(&key_tag.to_be_bytes());
+        encode_name(&mut rdata, "example.com");
+        rdata.extend_from_slice(&base64(signature));
+        rdata
+    }
+
+    #[test]
+    fn chain_skips_names_that_are_not_zone_cuts() {
+        let zones = chain_zones("a.b.example.com", |name| Ok(matches!(name, "com" | "example.com"))).unwrap();
+        assert_eq!(zones, vec![".", "com", "example.com", "a.b.example.com"]);
+    }
+
+    #[test]
+    fn chain_ends_at_the_apex_without_querying_it() {
+        let mut queried = Vec::new();
+        let zones = chain_zones("example.com", |name| {
+            queried.push(name.to_string());
+            Ok(true)
+        }).unwrap();
+        assert_eq!(zones, vec![".", "com", "example.com"]);
+        assert_eq!(queried, vec!["com"]);
+    }
+
+    #[test]
+    fn key_tag_matches_rfc_4034_example() {
+        // RFC 4034 section 5.4: dskey.example.com, key id 60485
+        let key = dnskey(256, 5, "AQOeiiR0GOMYkDshWoSKz9XzfwJr1AYtsmx3TGkJaNXVbfi/2pHm822aJ5iI9BMzNXxeYCmZDRD99WYwYqUSdjMmmAphXdvxegXd/M5+X7OrzKBaMbCVdFLUUh6DhweJBjEVv5f2wwjM9XzcnOf+EPbtG9DMBmADjFDc2w/rljwvFw==");
+        assert_eq!(key_tag_of(&key), 60485);
+
+        let dnskey = &records(&[("dskey.example.com", TYPE_DNSKEY, key)])[0];
+        let ds = Ds { key_tag: 60485, algorithm: 5, digest_type: 1, digest: decode_hex("2bb183af5f22588179a53b0a98631fad1a292118") };
+        assert!(ds_matches("dskey.example.com", dnskey, &ds));
+        assert!(ds_matches("DSKEY.Example.COM", dnskey, &ds));
+        assert!(!ds_matches("other.example.com", dnskey, &ds));
+    }
+
+    #[test]
+    fn ds_matches_rfc_8080_ed25519_key() {
+        let dnskey = &records(&[("example.com", TYPE_DNSKEY, dnskey(257, 15, ED25519_KEY))])[0];
+        assert_eq!(key_tag_of(&dnskey.rdata), 3613);
+        let ds = Ds { key_tag: 3613, algorithm: 15, digest_type: 2, digest: decode_hex("3aa5ab37efce57f737fc1627013fee07bdf241bd10f3b1964ab55c78e79a304b") };
+        assert!(ds_matches("example.com", dnskey, &ds));
+        assert!(!ds_matches("example.com", dnskey, &Ds { digest_type: 4, ..ds }));
+    }
+
+    #[test]
+    fn rrset_signature_verifies_rfc_8080_example() {
+        let keys = records(&[("example.com", TYPE_DNSKEY, dnskey(257, 15, ED25519_KEY))]);
+        // The MX target is compressed and mixed-case on the wire; the signed form is expanded and lower-cased
+        let mx = records(&[("Example.COM", TYPE_MX, vec![0, 10, 4, b'M', b'a', b'i', b'l', 0xc0, 12])]);
+        let sigs = records(&[("example.com", TYPE_RRSIG, rrsig(TYPE_MX, 3613, ED25519_MX_SIGNATURE))]);
+        let valid = 1439000000;
+
+        assert_eq!(verify_rrset(&mx, &sigs, &keys, valid), Ok(()));
+        // A record repeated in the answer is signed once
+        let repeated = [mx.clone(), mx.clone()].concat();
+        assert_eq!(verify_rrset(&repeated, &sigs, &keys, valid), Ok(()));
+        assert!(verify_rrset(&mx, &sigs, &keys, 1440021601).unwrap_err().contains("expired"));
+        assert!(verify_rrset(&mx, &sigs, &keys, 1438207199).unwrap_err().contains("not valid until"));
+
+        let other = records(&[("example.com", TYPE_MX, vec![0, 20, 4, b'm', b'a', b'i', b'l', 0xc0, 12])]);
+        assert!(verify_rrset(&other, &sigs, &keys, valid).unwrap_err().contains("Ed25519 signature mismatch"));
+    }
+
+    #[test]
+    fn wildcard_owner_is_restored() {
+        assert_eq!(canonical_owner("Host.Example.COM.", 3), "host.example.com");
+        assert_eq!(canonical_owner("a.b.example.com", 2), "*.example.com");
+    }
+}
@@ -0,0 +1,529 @@
+use base64::Engine;
+use console::style;
+use indicatif::ProgressBar;
+use reqwest::blocking::Client;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use crate::models::{EmailSecurity, Finding, Severity};
+use crate::scanners::dns::resolver::DnsClient;
+
+// RFC 7208 section 4.6.4: an SPF evaluation may cause at most 10 DNS-querying terms
+const SPF_LOOKUP_LIMIT: usize = 10;
+
+// Selectors used by common mail providers and MTAs (DKIM selectors cannot be enumerated)
+const DKIM_SELECTORS: &[&str] = &[
+    "default", "dkim", "mail", "email", "smtp", "selector1", "selector2", "google", "k1", "k2", "k3",
+    "s1", "s2", "s1024", "s2048", "key1", "key2", "sig1", "fm1", "fm2", "fm3", "mandrill", "mailjet",
+    "zoho", "protonmail", "protonmail2", "protonmail3", "everlytic", "mxvault", "amazonses",
+];
+
+// Parsed email security posture plus the misconfigurations found
+pub struct EmailSecurityResults {
+    pub posture: EmailSecurity,
+    pub findings: Vec<Finding>,
+}
+
+// TXT lookup used by the SPF evaluation (the DNS client, or canned zones in tests)
+type TxtLookup<'a> = dyn Fn(&str) -> Result<Vec<String>, Box<dyn Error>> + 'a;
+
+// State carried through the recursive SPF evaluation
+#[derive(Default)]
+struct SpfWalk {
+    lookups: usize,
+    all: Option<String>,
+    visited: BTreeSet<String>,
+    includes: Vec<String>,
+    broken: Vec<String>,       // include/redirect targets without a usable SPF record
+    uses_ptr: bool,
+}
+
+// Fetch and evaluate SPF, DMARC, DKIM, MTA-STS and TLS-RPT records for a domain
+pub fn perform_email_security(spinner: &ProgressBar, domain: &str, resolvers: Vec<SocketAddr>, rate_limit: u32, base_filename: &str, scan_dir: &str) -> Result<EmailSecurityResults, Box<dyn Error>> {
+    let output_file = format!("{}/{}_email_security.txt", scan_dir, base_filename);
+    let domain = domain.trim_end_matches('.').to_lowercase();
+    let client = DnsClient::new(resolvers).with_rate_limit(rate_limit);
+
+    let mut posture = EmailSecurity { domain: domain.clone(), ..Default::default() };
+    let mut findings = Vec::new();
+    let mut log = Vec::new();
+
+    spinner.set_message(format!("Evaluating SPF for {}...", style(&domain).cyan()));
+    check_spf(&|name| client.lookup_txt(name), &domain, &mut posture, &mut findings, &mut log)?;
+
+    spinner.set_message(format!("Checking DMARC policy for {}...", style(&domain).cyan()));
+    check_dmarc(&client, &domain, &mut posture, &mut findings, &mut log)?;
+
+    spinner.set_message(format!("Probing {} common DKIM selectors for {}...", DKIM_SELECTORS.len(), style(&domain).cyan()));
+    check_dkim(&client, &domain, &mut posture, &mut findings, &mut log);
+
+    spinner.set_message(format!("Checking MTA-STS and TLS-RPT for {}...", style(&domain).cyan()));
+    check_mta_sts(&client, &domain, &mut posture, &mut findings, &mut log)?;
+
+    let mut file = File::create(output_file)?;
+    writeln!(file, "# Email security records for {}", domain)?;
+    for line in &log {
+        writeln!(file, "{}", line)?;
+    }
+
+    Ok(EmailSecurityResults { posture, findings })
+}
+
+// SPF: locate the record, follow include/redirect recursively and count DNS lookups
+fn check_spf(lookup_txt: &TxtLookup, domain: &str, posture: &mut EmailSecurity, findings: &mut Vec<Finding>, log: &mut Vec<String>) -> Result<(), Box<dyn Error>> {
+    let records = spf_records(&lookup_txt(domain)?);
+    let Some(record) = records.first() else {
+        log.push(format!("SPF {}: none", domain));
+        findings.push(email_finding("spf-missing", Severity::Medium, domain,
+            format!("No SPF record published for {}: any host can send mail claiming to be from it", domain),
+            "https://www.rfc-editor.org/rfc/rfc7208"));
+        return Ok(());
+    };
+    if records.len() > 1 {
+        findings.push(email_finding("spf-multiple", Severity::Medium, domain,
+            format!("{} publishes {} SPF records, which makes SPF evaluation fail (permerror)", domain, records.len()),
+            "https://www.rfc-editor.org/rfc/rfc7208#section-4.5"));
+    }
+
+    let mut walk = SpfWalk::default();
+    walk.visited.insert(domain.to_string());
+    walk_spf(lookup_txt, domain, record, &mut walk, true, log);
+
+    posture.spf = Some(record.clone());
+    posture.spf_all = walk.all.clone();
+    posture.spf_lookups = walk.lookups;
+    posture.spf_includes = walk.includes.clone();
+    log.push(format!("SPF DNS lookups: {} (limit {})", walk.lookups, SPF_LOOKUP_LIMIT));
+
+    match walk.all.as_deref() {
+        Some("+all") => findings.push(email_finding("spf-plus-all", Severity::High, domain,
+            format!("SPF record for {} ends in +all, authorising every host on the internet to send its mail", domain),
+            "https://www.rfc-editor.org/rfc/rfc7208#section-5.1")),
+        Some("?all") => findings.push(email_finding("spf-neutral-all", Severity::Medium, domain,
+            format!("SPF record for {} ends in ?all (neutral), so unauthorised senders are not rejected", domain),
+            "https://www.rfc-editor.org/rfc/rfc7208#section-5.1")),
+        Some("~all") => findings.push(email_finding("spf-softfail-all", Severity::Info, domain,
+            format!("SPF record for {} ends in ~all (softfail); rely on DMARC enforcement or use -all", domain),
+            "https://www.rfc-editor.org/rfc/rfc7208#section-5.1")),
+        Some(_) => {},
+        None => findings.push(email_finding("spf-no-all", Severity::Low, domain,
+            format!("SPF record for {} has no all mechanism, so unmatched senders default to neutral", domain),
+            "https://www.rfc-editor.org/rfc/rfc7208#section-4.7")),
+    }
+    if walk.lookups > SPF_LOOKUP_LIMIT {
+        findings.push(email_finding("spf-lookup-limit", Severity::Medium, domain,
+            format!("SPF evaluation for {} needs {} DNS lookups (limit {}), so receivers return permerror", domain, walk.lookups, SPF_LOOKUP_LIMIT),
+            "https://www.rfc-editor.org/rfc/rfc7208#section-4.6.4"));
+    }
+    for target in &walk.broken {
+        findings.push(email_finding("spf-include-missing", Severity::Medium, domain,
+            format!("SPF for {} references {}, which has no SPF record (permerror)", domain, target),
+            "https://www.rfc-editor.org/rfc/rfc7208#section-5.2"));
+    }
+    if walk.uses_ptr {
+        findings.push(email_finding("spf-ptr", Severity::Low, domain,
+            format!("SPF for {} uses the deprecated ptr mechanism", domain),
+            "https://www.rfc-editor.org/rfc/rfc7208#section-5.5"));
+    }
+
+    Ok(())
+}
+
+// Evaluate one SPF record; only the top-level record (or its redirect) decides the "all" result
+fn walk_spf(lookup_txt: &TxtLookup, domain: &str, record: &str, walk: &mut SpfWalk, top_level: bool, log: &mut Vec<String>) {
+    log.push(format!("SPF {}: {}", domain, record));
+    let mut redirect = None;
+    let mut has_all = false;
+
+    for term in record.split_whitespace().skip(1) {
+        let term = term.to_lowercase();
+        let qualifier = term.chars().next().filter(|c| "+-~?".contains(*c)).unwrap_or('+');
+        let mechanism = term.trim_start_matches(['+', '-', '~', '?']);
+        let (name, value) = match mechanism.split_once([':', '=']) {
+            Some((name, value)) => (name, Some(value)),
+            None => (mechanism.split('/').next().unwrap_or(mechanism), None),
+        };
+
+        match name {
+            "include" => {
+                walk.lookups += 1;
+                if let Some(target) = value {
+                    follow_spf(lookup_txt, target, walk, false, log);
+                }
+            },
+            "a" | "mx" | "exists" => walk.lookups += 1,
+            "ptr" => {
+                walk.lookups += 1;
+                walk.uses_ptr = true;
+            },
+            "redirect" => redirect = value.map(|v| v.to_string()),
+            "all" => {
+                has_all = true;
+                if top_level {
+                    walk.all = Some(format!("{}all", qualifier));
+                }
+            },
+            _ => {},
+        }
+    }
+
+    // A redirect modifier is ignored when the record has an all mechanism
+    if let Some(target) = redirect
+        && !has_all {
+        walk.lookups += 1;
+        follow_spf(lookup_txt, &target, walk, top_level, log);
+    }
+}
+
+// Fetch the SPF record of an include/redirect target and evaluate it
+fn follow_spf(lookup_txt: &TxtLookup, target: &str, walk: &mut SpfWalk, top_level: bool, log: &mut Vec<String>) {
+    let target = target.trim_end_matches('.').to_lowercase();
+    if target.contains('%') {
+        log.push(format!("SPF {}: macro not expanded", target));
+        return;
+    }
+    if !walk.visited.insert(target.clone()) {
+        log.push(format!("SPF {}: already evaluated (loop)", target));
+        return;
+    }
+    walk.includes.push(target.clone());
+
+    // Stop following once the evaluation is clearly broken
+    if walk.lookups > SPF_LOOKUP_LIMIT * 2 {
+        return;
+    }
+    match lookup_txt(&target).map(|txt| spf_records(&txt)) {
+        Ok(records) if !records.is_empty() => walk_spf(lookup_txt, &target, &records[0], walk, top_level, log),
+        Ok(_) => {
+            log.push(format!("SPF {}: none", target));
+            walk.broken.push(target);
+        },
+        Err(e) => {
+            log.push(format!("SPF {}: lookup failed ({})", target, e));
+            walk.broken.push(target);
+        },
+    }
+}
+
+// DMARC: policy, subdomain policy, percentage and aggregate reporting
+fn check_dmarc(client: &DnsClient, domain: &str, posture: &mut EmailSecurity, findings: &mut Vec<Finding>, log: &mut Vec<String>) -> Result<(), Box<dyn Error>> {
+    let name = format!("_dmarc.{}", domain);
+    let records: Vec<String> = client.lookup_txt(&name)?
+        .into_iter()
+        .filter(|r| r.trim_start().to_lowercase().starts_with("v=dmarc1"))
+        .collect();
+    let Some(record) = records.first() else {
+        log.push(format!("DMARC {}: none", name));
+        findings.push(email_finding("dmarc-missing", Severity::Medium, &name,
+            format!("No DMARC record for {}: receivers get no policy for mail that fails SPF/DKIM", domain),
+            "https://www.rfc-editor.org/rfc/rfc7489"));
+        return Ok(());
+    };
+    log.push(format!("DMARC {}: {}", name, record));
+
+    let tags = parse_tags(record);
+    let tag = |key: &str| tags.iter().find(|(k, _)| k == key).map(|(_, v)| v.to_lowercase());
+    posture.dmarc = Some(record.clone());
+    posture.dmarc_policy = tag("p");
+    posture.dmarc_subdomain_policy = tag("sp");
+    posture.dmarc_pct = tag("pct").and_then(|p| p.parse().ok());
+    posture.dmarc_rua = tag("rua")
+        .map(|r| r.split(',').map(|u| u.trim().to_string()).filter(|u| !u.is_empty()).collect())
+        .unwrap_or_default();
+
+    match posture.dmarc_policy.as_deref() {
+        Some("none") => findings.push(email_finding("dmarc-p-none", Severity::Medium, &name,
+            format!("DMARC policy for {} is p=none (monitoring only): spoofed mail is still delivered", domain),
+            "https://www.rfc-editor.org/rfc/rfc7489#section-6.3")),
+        Some("quarantine") | Some("reject") => {
+            if posture.dmarc_subdomain_policy.as_deref() == Some("none") {
+                findings.push(email_finding("dmarc-sp-none", Severity::Low, &name,
+                    format!("DMARC for {} sets sp=none, leaving subdomains unprotected", domain),
+                    "https://www.rfc-editor.org/rfc/rfc7489#section-6.3"));
+            }
+        },
+        _ => findings.push(email_finding("dmarc-invalid", Severity::Medium, &name,
+            format!("DMARC record for {} has no valid p= policy and is ignored by receivers", domain),
+            "https://www.rfc-editor.org/rfc/rfc7489#section-6.3")),
+    }
+    if let Some(pct) = posture.dmarc_pct
+        && pct < 100 {
+        findings.push(email_finding("dmarc-pct", Severity::Low, &name,
+            format!("DMARC for {} only applies its policy to {}% of failing mail", domain, pct),
+            "https://www.rfc-editor.org/rfc/rfc7489#section-6.3"));
+    }
+    if posture.dmarc_rua.is_empty() {
+        findings.push(email_finding("dmarc-no-rua", Severity::Info, &name,
+            format!("DMARC for {} requests no aggregate reports (rua), so spoofing goes unnoticed", domain),
+            "https://www.rfc-editor.org/rfc/rfc7489#section-7.2"));
+    }
+
+    Ok(())
+}
+
+// DKIM: probe common selectors and check the published keys
+fn check_dkim(client: &DnsClient, domain: &str, posture: &mut EmailSecurity, findings: &mut Vec<Finding>, log: &mut Vec<String>) {
+    for selector in DKIM_SELECTORS {
+        let name = format!("{}._domainkey.{}", selector, domain);
+        let Ok(records) = client.lookup_txt(&name) else { continue };
+        let Some(record) = records.into_iter().find(|r| r.contains("p=")) else { continue };
+        log.push(format!("DKIM {}: {}", name, record));
+        posture.dkim_selectors.push(selector.to_string());
+
+        findings.extend(dkim_key_finding(domain, selector, &name, &record));
+    }
+
+    if posture.dkim_selectors.is_empty() {
+        log.push(format!("DKIM {}: no common selector found", domain));
+        findings.push(email_finding("dkim-not-found", Severity::Info, domain,
+            format!("No DKIM key found for {} under {} common selectors (custom selectors cannot be enumerated)", domain, DKIM_SELECTORS.len()),
+            "https://www.rfc-editor.org/rfc/rfc6376"));
+    }
+}
+
+// Problems with the key a DKIM selector publishes: revoked, not decodable, or too short
+fn dkim_key_finding(domain: &str, selector: &str, name: &str, record: &str) -> Option<Finding> {
+    let tags = parse_tags(record);
+    let tag = |key: &str| tags.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone());
+    let key = tag("p").unwrap_or_default().split_whitespace().collect::<String>();
+    if key.is_empty() {
+        return Some(email_finding("dkim-revoked", Severity::Info, name,
+            format!("DKIM selector {} for {} publishes an empty (revoked) key", selector, domain),
+            "https://www.rfc-editor.org/rfc/rfc6376#section-3.6.1"));
+    }
+
+    // Verifiers cannot use a key that does not decode, so every signature for this selector fails
+    let decoded = match base64::engine::general_purpose::STANDARD.decode(&key) {
+        Ok(decoded) => decoded,
+        Err(e) => return Some(email_finding("dkim-malformed-key", Severity::Medium, name,
+            format!("DKIM selector {} for {} publishes a key that is not valid base64 ({}); signatures using it cannot be verified", selector, domain, e),
+            "https://www.rfc-editor.org/rfc/rfc6376#section-3.6.1")),
+    };
+
+    // An RSA SubjectPublicKeyInfo for a 2048-bit key is ~294 bytes; ~162 bytes means 1024-bit
+    let is_rsa = tag("k").map(|k| k.eq_ignore_ascii_case("rsa")).unwrap_or(true);
+    if is_rsa && decoded.len() < 256 {
+        return Some(email_finding("dkim-weak-key", Severity::Low, name,
+            format!("DKIM selector {} for {} publishes an RSA key shorter than 2048 bits", selector, domain),
+            "https://www.rfc-editor.org/rfc/rfc8301"));
+    }
+    None
+}
+
+// MTA-STS (TXT record plus HTTPS policy) and TLS-RPT
+fn check_mta_sts(client: &DnsClient, domain: &str, posture: &mut EmailSecurity, findings: &mut Vec<Finding>, log: &mut Vec<String>) -> Result<(), Box<dyn Error>> {
+    let name = format!("_mta-sts.{}", domain);
+    posture.mta_sts = client.lookup_txt(&name)?
+        .into_iter()
+        .find(|r| r.trim_start().to_lowercase().starts_with("v=stsv1"));
+
+    match &posture.mta_sts {
+        Some(record) => {
+            log.push(format!("MTA-STS {}: {}", name, record));
+            // The policy must be served over HTTPS with a valid certificate
+            let policy_url = format!("https://mta-sts.{}/.well-known/mta-sts.txt", domain);
+            match fetch_mta_sts_policy(&policy_url) {
+                Ok(policy) => {
+                    log.push(format!("MTA-STS policy {}:\n{}", policy_url, policy.trim_end()));
+                    posture.mta_sts_mode = policy.lines()
+                        .filter_map(|l| l.split_once(':'))
+                        .find(|(k, _)| k.trim().eq_ignore_ascii_case("mode"))
+                        .map(|(_, v)| v.trim().to_lowercase());
+                    match posture.mta_sts_mode.as_deref() {
+                        Some("enforce") => {},
+                        Some(mode) => findings.push(email_finding("mta-sts-not-enforced", Severity::Low, &policy_url,
+                            format!("MTA-STS policy for {} is in {} mode, so TLS downgrades are not blocked", domain, mode),
+                            "https://www.rfc-editor.org/rfc/rfc8461#section-5")),
+                        None => findings.push(email_finding("mta-sts-invalid", Severity::Medium, &policy_url,
+                            format!("MTA-STS policy for {} has no mode and is ignored", domain),
+                            "https://www.rfc-editor.org/rfc/rfc8461#section-3.2")),
+                    }
+                },
+                Err(e) => {
+                    log.push(format!("MTA-STS policy {}: unreachable ({})", policy_url, e));
+                    findings.push(email_finding("mta-sts-policy-unreachable", Severity::Medium, &policy_url,
+                        format!("{} publishes an MTA-STS record but its policy could not be fetched: {}", domain, e),
+                        "https://www.rfc-editor.org/rfc/rfc8461#section-3.3"));
+                },
+            }
+        },
+        None => {
+            log.push(format!("MTA-STS {}: none", name));
+            findings.push(email_finding("mta-sts-missing", Severity::Info, &name,
+                format!("No MTA-STS record for {}: inbound SMTP TLS can be downgraded", domain),
+                "https://www.rfc-editor.org/rfc/rfc8461"));
+        },
+    }
+
+    let name = format!("_smtp._tls.{}", domain);
+    posture.tls_rpt = client.lookup_txt(&name)?
+        .into_iter()
+        .find(|r| r.trim_start().to_lowercase().starts_with("v=tlsrptv1"));
+    match &posture.tls_rpt {
+        Some(record) => log.push(format!("TLS-RPT {}: {}", name, record)),
+        None => {
+            log.push(format!("TLS-RPT {}: none", name));
+            findings.push(email_finding("tls-rpt-missing", Severity::Info, &name,
+                format!("No TLS-RPT record for {}: SMTP TLS failures are not reported", domain),
+                "https://www.rfc-editor.org/rfc/rfc8460"));
+        },
+    }
+
+    Ok(())
+}
+
+fn fetch_mta_sts_policy(url: &str) -> Result<String, Box<dyn Error>> {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(10))
+        .user_agent("Mozilla/5.0 (X11; Linux x86_64) RustRecon")
+        .build()?;
+    let response = client.get(url).send()?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()).into());
+    }
+    Ok(response.text()?)
+}
+
+// TXT strings that are SPF records
+fn spf_records(txt: &[String]) -> Vec<String> {
+    txt.iter()
+        .filter(|r| {
+            let lower = r.trim_start().to_lowercase();
+            lower == "v=spf1" || lower.starts_with("v=spf1 ")
+        })
+        .map(|r| r.trim().to_string())
+        .collect()
+}
+
+// Split a "k=v; k=v" record (DMARC, DKIM) into lower-case keys and trimmed values
+fn parse_tags(record: &str) -> Vec<(String, String)> {
+    record.split(';')
+        .filter_map(|t| t.split_once('='))
+        .map(|(k, v)| (k.trim().to_lowercase(), v.trim().to_string()))
+        .collect()
+}
+
+fn email_finding(id: &str, severity: Severity, location: &str, description: String, reference: &str) -> Finding {
+    Finding {
+        source: "Email security".to_string(),
+        id: Some(id.to_string()),
+        severity,
+        location: location.to_string(),
+        method: None,
+        description,
+        references: vec![reference.to_string()],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+
+    // Run the SPF checks for example.com against canned TXT records; unlisted names are NXDOMAIN
+    fn evaluate_spf(zone: &[(&str, &str)]) -> (EmailSecurity, Vec<String>, Vec<String>) {
+        let lookup = |name: &str| -> Result<Vec<String>, Box<dyn Error>> {
+            let records: Vec<String> = zone.iter().filter(|(owner, _)| *owner == name).map(|(_, txt)| txt.to_string()).collect();
+            if records.is_empty() { Err(format!("{}: NXDOMAIN", name).into()) } else { Ok(records) }
+        };
+        let mut posture = EmailSecurity { domain: "example.com".to_string(), ..Default::default() };
+        let mut findings = Vec::new();
+        let mut log = Vec::new();
+        check_spf(&lookup, "example.com", &mut posture, &mut findings, &mut log).unwrap();
+        (posture, findings.into_iter().filter_map(|f| f.id).collect(), log)
+    }
+
+    fn finding_id(record: &str) -> Option<String> {
+        dkim_key_finding("example.com", "default", "default._domainkey.example.com", record).and_then(|f| f.id)
+    }
+
+    #[test]
+    fn dkim_keys_are_classified() {
+        let engine = base64::engine::general_purpose::STANDARD;
+        assert_eq!(finding_id("v=DKIM1; k=rsa; p="), Some("dkim-revoked".to_string()));
+        assert_eq!(finding_id("v=DKIM1; k=rsa; p=not*base64!"), Some("dkim-malformed-key".to_string()));
+        assert_eq!(finding_id(&format!("v=DKIM1; k=rsa; p={}", engine.encode([0u8; 162]))), Some("dkim-weak-key".to_string()));
+        assert_eq!(finding_id(&format!("v=DKIM1; k=rsa; p={}", engine.encode([0u8; 294]))), None);
+        assert_eq!(finding_id(&format!("v=DKIM1; k=ed25519; p={}", engine.encode([0u8; 32]))), None);
+    }
+
+    #[test]
+    fn nested_includes_are_counted_and_only_the_top_level_all_applies() {
+        let (posture, findings, _) = evaluate_spf(&[
+            ("example.com", "v=spf1 include:_spf.example.com mx -all"),
+            ("example.com", "google-site-verification=abc123"),
+            ("_spf.example.com", "v=spf1 include:mail.provider.test ip4:192.0.2.0/24 ~all"),
+            ("mail.provider.test", "v=spf1 a ?all"),
+        ]);
+        assert_eq!(posture.spf.as_deref(), Some("v=spf1 include:_spf.example.com mx -all"));
+        assert_eq!(posture.spf_all.as_deref(), Some("-all"));
+        assert_eq!(posture.spf_includes, vec!["_spf.example.com", "mail.provider.test"]);
+        assert_eq!(posture.spf_lookups, 4);
+        assert!(findings.is_empty(), "{:?}", findings);
+    }
+
+    #[test]
+    fn include_loops_are_evaluated_once() {
+        let (posture, findings, log) = evaluate_spf(&[
+            ("example.com", "v=spf1 include:a.example.net -all"),
+            ("a.example.net", "v=spf1 include:b.example.net"),
+            ("b.example.net", "v=spf1 include:a.example.net include:example.com"),
+        ]);
+        assert_eq!(posture.spf_includes, vec!["a.example.net", "b.example.net"]);
+        assert_eq!(posture.spf_lookups, 4);
+        assert_eq!(log.iter().filter(|l| l.ends_with("already evaluated (loop)")).count(), 2);
+        assert!(findings.is_empty(), "{:?}", findings);
+    }
+
+    #[test]
+    fn lookups_beyond_the_limit_are_reported() {
+        let within = [
+            ("example.com", "v=spf1 mx include:one.example.net include:two.example.net -all"),
+            ("one.example.net", "v=spf1 a mx exists:%{i}.example.net"),
+            ("two.example.net", "v=spf1 a mx a:relay.example.net mx:backup.example.net"),
+        ];
+        let (posture, findings, _) = evaluate_spf(&within);
+        assert_eq!((posture.spf_lookups, findings.len()), (SPF_LOOKUP_LIMIT, 0));
+
+        let (posture, findings, _) = evaluate_spf(&[
+            within[0],
+            within[1],
+            ("two.example.net", "v=spf1 a mx a:relay.example.net mx:backup.example.net ptr"),
+        ]);
+        assert_eq!(posture.spf_lookups, SPF_LOOKUP_LIMIT + 1);
+        assert_eq!(findings, vec!["spf-lookup-limit", "spf-ptr"]);
+    }
+
+    #[test]
+    fn permissive_all_mechanisms_are_reported() {
+        let (_, findings, _) = evaluate_spf(&[("example.com", "v=spf1 ip4:192.0.2.1 +all")]);
+        assert_eq!(findings, vec!["spf-plus-all"]);
+        let (_, findings, _) = evaluate_spf(&[("example.com", "v=spf1 mx ?ALL")]);
+        assert_eq!(findings, vec!["spf-neutral-all"]);
+        let (_, findings, _) = evaluate_spf(&[("example.com", "v=spf1 mx")]);
+        assert_eq!(findings, vec!["spf-no-all"]);
+
+        // The redirect target's all mechanism applies, unless the record has its own
+        let (posture, findings, _) = evaluate_spf(&[("example.com", "v=spf1 redirect=_spf.example.net"), ("_spf.example.net", "v=spf1 all")]);
+        assert_eq!((posture.spf_all.as_deref(), posture.spf_lookups), (Some("+all"), 1));
+        assert_eq!(findings, vec!["spf-plus-all"]);
+        let (posture, _, _) = evaluate_spf(&[("example.com", "v=spf1 -all redirect=_spf.example.net"), ("_spf.example.net", "v=spf1 +all")]);
+        assert_eq!((posture.spf_all.as_deref(), posture.spf_lookups), (Some("-all"), 0));
+    }
+
+    #[test]
+    fn missing_include_targets_are_reported() {
+        let (posture, findings, log) = evaluate_spf(&[
+            ("example.com", "v=spf1 include:gone.example.net include:_spf.example.net -all"),
+            ("_spf.example.net", "google-site-verification=abc123"),
+        ]);
+        assert_eq!(posture.spf_lookups, 2);
+        assert_eq!(findings, vec!["spf-include-missing", "spf-include-missing"]);
+        assert!(log.contains(&"SPF gone.example.net: lookup failed (gone.example.net: NXDOMAIN)".to_string()));
+        assert!(log.contains(&"SPF _spf.example.net: none".to_string()));
+    }
+}
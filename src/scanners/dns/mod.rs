@@ -2,8 +2,21 @@ pub mod whois;
 pub mod nslookup;
 pub mod dig;
 pub mod dnsrecon;
+pub mod resolver;
+pub mod subdomains;
+pub mod zone_transfer;
+pub mod email_security;
+pub mod dnssec;
+pub mod reverse;
+pub mod certificate_transparency;
 
 pub use whois::*;
 pub use nslookup::*;
 pub use dig::*;
-pub use dnsrecon::*;
\ No newline at end of file
+pub use dnsrecon::*;
+pub use subdomains::*;
+pub use zone_transfer::*;
+pub use email_security::*;
+pub use dnssec::*;
+pub use reverse::*;
+pub use certificate_transparency::*;
\ No newline at end of file
@@ -0,0 +1,782 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{Read, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream, UdpSocket};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use base64::Engine;
+
+use crate::models::DnsRecord;
+
+// Minimal DNS client speaking the wire protocol directly (RFC 1035) so the native DNS
+// modules don't depend on dig/dnsrecon being installed
+
+// Record types used by the DNS modules
+pub const TYPE_A: u16 = 1;
+pub const TYPE_NS: u16 = 2;
+pub const TYPE_CNAME: u16 = 5;
+pub const TYPE_SOA: u16 = 6;
+pub const TYPE_PTR: u16 = 12;
+pub const TYPE_MX: u16 = 15;
+pub const TYPE_TXT: u16 = 16;
+pub const TYPE_AAAA: u16 = 28;
+pub const TYPE_SRV: u16 = 33;
+pub const TYPE_DS: u16 = 43;
+pub const TYPE_RRSIG: u16 = 46;
+pub const TYPE_NSEC: u16 = 47;
+pub const TYPE_DNSKEY: u16 = 48;
+pub const TYPE_NSEC3PARAM: u16 = 51;
+pub const TYPE_IXFR: u16 = 251;
+pub const TYPE_AXFR: u16 = 252;
+
+// Response codes
+pub const RCODE_NOERROR: u8 = 0;
+pub const RCODE_NXDOMAIN: u8 = 3;
+
+// Human readable name for a response code
+pub fn rcode_name(rcode: u8) -> String {
+    match rcode {
+        0 => "NOERROR".to_string(),
+        1 => "FORMERR".to_string(),
+        2 => "SERVFAIL".to_string(),
+        3 => "NXDOMAIN".to_string(),
+        4 => "NOTIMP".to_string(),
+        5 => "REFUSED".to_string(),
+        9 => "NOTAUTH".to_string(),
+        other => format!("RCODE{}", other),
+    }
+}
+
+// Human readable name for a record type
+pub fn type_name(rtype: u16) -> String {
+    match rtype {
+        TYPE_A => "A".to_string(),
+        TYPE_NS => "NS".to_string(),
+        TYPE_CNAME => "CNAME".to_string(),
+        TYPE_SOA => "SOA".to_string(),
+        TYPE_PTR => "PTR".to_string(),
+        TYPE_MX => "MX".to_string(),
+        TYPE_TXT => "TXT".to_string(),
+        TYPE_AAAA => "AAAA".to_string(),
+        TYPE_SRV => "SRV".to_string(),
+        TYPE_DS => "DS".to_string(),
+        TYPE_RRSIG => "RRSIG".to_string(),
+        TYPE_NSEC => "NSEC".to_string(),
+        TYPE_DNSKEY => "DNSKEY".to_string(),
+        TYPE_NSEC3PARAM => "NSEC3PARAM".to_string(),
+        TYPE_IXFR => "IXFR".to_string(),
+        TYPE_AXFR => "AXFR".to_string(),
+        other => format!("TYPE{}", other),
+    }
+}
+
+// Decoded record data for the types we interpret
+#[derive(Debug, Clone)]
+pub enum RData {
+    A(Ipv4Addr),
+    Aaaa(Ipv6Addr),
+    Name(String),                                        // NS, CNAME, PTR
+    Mx { preference: u16, exchange: String },
+    Txt(Vec<String>),
+    Soa { mname: String, rname: String, serial: u32 },
+    Srv { priority: u16, weight: u16, port: u16, target: String },
+    Ds { key_tag: u16, algorithm: u8, digest_type: u8, digest: Vec<u8> },
+    Dnskey { flags: u16, protocol: u8, algorithm: u8, public_key: Vec<u8> },
+    Rrsig {
+        type_covered: u16,
+        algorithm: u8,
+        labels: u8,
+        original_ttl: u32,
+        expiration: u32,
+        inception: u32,
+        key_tag: u16,
+        signer: String,
+        signature: Vec<u8>,
+    },
+    Nsec { next: String, types: Vec<u16> },
+    Nsec3Param { hash_algorithm: u8, flags: u8, iterations: u16, salt: Vec<u8> },
+    Other(Vec<u8>),
+}
+
+// A resource record from the answer/authority/additional sections
+#[derive(Debug, Clone)]
+pub struct ResourceRecord {
+    pub name: String,
+    pub rtype: u16,
+    pub class: u16,
+    pub ttl: u32,
+    pub data: RData,
+    pub rdata: Vec<u8>,             // Canonical wire-format RDATA (RFC 4034 6.2), used for DNSSEC
+}
+
+impl ResourceRecord {
+    // Address carried by A/AAAA records
+    pub fn address(&self) -> Option<IpAddr> {
+        match &self.data {
+            RData::A(ip) => Some(IpAddr::V4(*ip)),
+            RData::Aaaa(ip) => Some(IpAddr::V6(*ip)),
+            _ => None,
+        }
+    }
+
+    // Target name carried by NS/CNAME/PTR/MX/SRV records (and the next owner of an NSEC record)
+    pub fn target(&self) -> Option<&str> {
+        match &self.data {
+            RData::Name(name) => Some(name),
+            RData::Nsec { next, .. } => Some(next),
+            RData::Mx { exchange, .. } => Some(exchange),
+            RData::Srv { target, .. } => Some(target),
+            _ => None,
+        }
+    }
+
+    // Convert to the scan model's DNS record
+    pub fn to_dns_record(&self, source: &str) -> DnsRecord {
+        let (port, data) = match &self.data {
+            RData::Srv { port, .. } => (Some(*port), None),
+            RData::Txt(strings) => (None, Some(strings.concat())),
+            RData::Soa { mname, rname, serial } => (None, Some(format!("{} {} {}", mname, rname, serial))),
+            _ => (None, None),
+        };
+        DnsRecord {
+            source: source.to_string(),
+            record_type: type_name(self.rtype),
+            name: self.name.trim_end_matches('.').to_lowercase(),
+            address: self.address().map(|a| a.to_string()),
+            target: self.target().map(|t| t.trim_end_matches('.').to_lowercase()),
+            port,
+            data,
+        }
+    }
+}
+
+// Zone-file presentation of a record (used for raw output files)
+impl fmt::Display for ResourceRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let class = if self.class == 1 { "IN".to_string() } else { format!("CLASS{}", self.class) };
+        write!(f, "{}.\t{}\t{}\t{}\t", self.name.trim_end_matches('.'), self.ttl, class, type_name(self.rtype))?;
+        match &self.data {
+            RData::A(ip) => write!(f, "{}", ip),
+            RData::Aaaa(ip) => write!(f, "{}", ip),
+            RData::Name(name) => write!(f, "{}.", name),
+            RData::Mx { preference, exchange } => write!(f, "{} {}.", preference, exchange),
+            RData::Txt(strings) => {
+                let quoted: Vec<String> = strings.iter().map(|s| format!("\"{}\"", s.replace('"', "\\\""))).collect();
+                write!(f, "{}", quoted.join(" "))
+            },
+            RData::Soa { mname, rname, serial } => write!(f, "{}. {}. {}", mname, rname, serial),
+            RData::Srv { priority, weight, port, target } => write!(f, "{} {} {} {}.", priority, weight, port, target),
+            RData::Ds { key_tag, algorithm, digest_type, digest } => write!(f, "{} {} {} {}", key_tag, algorithm, digest_type, hex(digest)),
+            RData::Dnskey { flags, protocol, algorithm, public_key } => {
+                write!(f, "{} {} {} {}", flags, protocol, algorithm, base64::engine::general_purpose::STANDARD.encode(public_key))
+            },
+            RData::Rrsig { type_covered, algorithm, labels, original_ttl, expiration, inception, key_tag, signer, signature } => {
+                write!(f, "{} {} {} {} {} {} {} {}. {}", type_name(*type_covered), algorithm, labels, original_ttl,
+                    expiration, inception, key_tag, signer.trim_end_matches('.'), base64::engine::general_purpose::STANDARD.encode(signature))
+            },
+            RData::Nsec { next, types } => {
+                let types: Vec<String> = types.iter().map(|t| type_name(*t)).collect();
+                write!(f, "{}. {}", next.trim_end_matches('.'), types.join(" "))
+            },
+            RData::Nsec3Param { hash_algorithm, flags, iterations, salt } => {
+                write!(f, "{} {} {} {}", hash_algorithm, flags, iterations, if salt.is_empty() { "-".to_string() } else { hex(salt) })
+            },
+            RData::Other(bytes) => {
+                // RFC 3597 generic encoding
+                write!(f, "\\# {} {}", bytes.len(), hex(bytes))
+            },
+        }
+    }
+}
+
+// A parsed DNS response
+#[derive(Debug, Clone)]
+pub struct DnsMessage {
+    pub id: u16,
+    pub rcode: u8,
+    pub truncated: bool,
+    pub answers: Vec<ResourceRecord>,
+}
+
+// DNS client bound to a list of resolvers, with a shared query rate limit
+pub struct DnsClient {
+    resolvers: Vec<SocketAddr>,
+    timeout: Duration,
+    retries: usize,
+    min_interval: Option<Duration>,
+    last_query: Mutex<Instant>,
+    next_resolver: AtomicUsize,
+    next_id: AtomicUsize,
+    dnssec: bool,
+}
+
+impl DnsClient {
+    // Create a client for the given resolvers (falls back to the system resolvers when empty)
+    pub fn new(resolvers: Vec<SocketAddr>) -> Self {
+        let resolvers = if resolvers.is_empty() { system_resolvers() } else { resolvers };
+        let seed = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.subsec_nanos()).unwrap_or(0) as usize ^ std::process::id() as usize;
+        DnsClient {
+            resolvers,
+            timeout: Duration::from_secs(3),
+            retries: 2,
+            min_interval: None,
+            last_query: Mutex::new(Instant::now()),
+            next_resolver: AtomicUsize::new(0),
+            next_id: AtomicUsize::new(seed),
+            dnssec: false,
+        }
+    }
+
+    // Limit the client to a number of queries per second across all threads (0 = unlimited)
+    pub fn with_rate_limit(mut self, queries_per_second: u32) -> Self {
+        self.min_interval = if queries_per_second == 0 {
+            None
+        } else {
+            Some(Duration::from_secs_f64(1.0 / queries_per_second as f64))
+        };
+        self
+    }
+
+    // Request DNSSEC records (DO bit) and disable resolver validation (CD bit) so bogus data can be inspected
+    pub fn with_dnssec(mut self) -> Self {
+        self.dnssec = true;
+        self
+    }
+
+    pub fn resolvers(&self) -> &[SocketAddr] {
+        &self.resolvers
+    }
+
+    // Query the configured resolvers (round robin, with retries)
+    pub fn query(&self, name: &str, rtype: u16) -> Result<DnsMessage, Box<dyn Error>> {
+        let mut last_error: Box<dyn Error> = "no resolvers configured".into();
+        for _ in 0..=self.retries {
+            let index = self.next_resolver.fetch_add(1, Ordering::Relaxed) % self.resolvers.len().max(1);
+            let Some(server) = self.resolvers.get(index).copied() else { break };
+            match self.query_server(server, name, rtype) {
+                Ok(response) => return Ok(response),
+                Err(e) => last_error = e,
+            }
+        }
+        Err(last_error)
+    }
+
+    // TXT strings published at a name (each record's character-strings joined); empty for NXDOMAIN
+    pub fn lookup_txt(&self, name: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        let response = self.query(name, TYPE_TXT)?;
+        match response.rcode {
+            RCODE_NOERROR => Ok(response.answers.iter()
+                .filter_map(|r| match &r.data {
+                    RData::Txt(strings) if r.rtype == TYPE_TXT => Some(strings.concat()),
+                    _ => None,
+                })
+                .collect()),
+            RCODE_NXDOMAIN => Ok(Vec::new()),
+            rcode => Err(format!("TXT lookup for {} failed: {}", name, rcode_name(rcode)).into()),
+        }
+    }
+
+    // Query one specific server (UDP first, TCP when the answer is truncated)
+    pub fn query_server(&self, server: SocketAddr, name: &str, rtype: u16) -> Result<DnsMessage, Box<dyn Error>> {
+        self.wait_for_rate_limit();
+
+        let id = self.next_query_id();
+        let query = build_query(id, name, rtype, true, self.dnssec);
+
+        let bind_addr: SocketAddr = if server.is_ipv4() { "0.0.0.0:0".parse()? } else { "[::]:0".parse()? };
+        let socket = UdpSocket::bind(bind_addr)?;
+        socket.set_read_timeout(Some(self.timeout))?;
+        socket.connect(server)?;
+        socket.send(&query)?;
+
+        let mut buf = [0u8; 4096];
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let len = socket.recv(&mut buf)?;
+            let response = parse_message(&buf[..len])?;
+            // Ignore stray datagrams that don't belong to this query
+            if response.id != id {
+                if Instant::now() > deadline {
+                    return Err("timed out waiting for matching DNS response".into());
+                }
+                continue;
+            }
+            if response.truncated {
+                return self.query_server_tcp(server, name, rtype);
+            }
+            return Ok(response);
+        }
+    }
+
+    // Query a server over TCP (used for truncated answers)
+    pub fn query_server_tcp(&self, server: SocketAddr, name: &str, rtype: u16) -> Result<DnsMessage, Box<dyn Error>> {
+        let id = self.next_query_id();
+        let mut stream = self.connect_tcp(server)?;
+        send_tcp_message(&mut stream, &build_query(id, name, rtype, true, self.dnssec))?;
+        let message = read_tcp_message(&mut stream)?;
+        parse_message(&message)
+    }
+
+    // Open a TCP connection to a name server with the client's timeouts
+    pub fn connect_tcp(&self, server: SocketAddr) -> Result<TcpStream, Box<dyn Error>> {
+        let stream = TcpStream::connect_timeout(&server, self.timeout)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+        Ok(stream)
+    }
+
+    // Message ID for the next query
+    pub fn next_query_id(&self) -> u16 {
+        (self.next_id.fetch_add(7919, Ordering::Relaxed) & 0xffff) as u16
+    }
+
+    // Sleep long enough to respect the configured query rate
+    fn wait_for_rate_limit(&self) {
+        let Some(interval) = self.min_interval else { return };
+        let wait = {
+            let mut last = self.last_query.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            let next = *last + interval;
+            if next > now {
+                *last = next;
+                next - now
+            } else {
+                *last = now;
+                Duration::ZERO
+            }
+        };
+        if !wait.is_zero() {
+            thread::sleep(wait);
+        }
+    }
+}
+
+// Parse a comma separated resolver list ("1.1.1.1,8.8.8.8:53,[::1]:5353")
+pub fn parse_resolvers(list: &str) -> Result<Vec<SocketAddr>, Box<dyn Error>> {
+    let mut resolvers = Vec::new();
+    for entry in list.split(',').map(|e| e.trim()).filter(|e| !e.is_empty()) {
+        let resolver = match entry.parse::<SocketAddr>() {
+            Ok(addr) => addr,
+            Err(_) => SocketAddr::new(entry.parse::<IpAddr>().map_err(|_| format!("Invalid resolver address: {}", entry))?, 53),
+        };
+        resolvers.push(resolver);
+    }
+    Ok(resolvers)
+}
+
+// Name servers from /etc/resolv.conf, or public resolvers when none are configured
+pub fn system_resolvers() -> Vec<SocketAddr> {
+    let mut resolvers: Vec<SocketAddr> = fs::read_to_string("/etc/resolv.conf")
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("nameserver"))
+        .filter_map(|addr| addr.trim().split('%').next()?.parse::<IpAddr>().ok())
+        .map(|ip| SocketAddr::new(ip, 53))
+        .collect();
+    if resolvers.is_empty() {
+        resolvers.push(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)), 53));
+        resolvers.push(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(8, 8, 8, 8)), 53));
+    }
+    resolvers
+}
+
+// Build a query message with recursion desired and an EDNS0 OPT record (4096 byte payload)
+pub fn build_query(id: u16, name: &str, rtype: u16, recursion_desired: bool, dnssec_ok: bool) -> Vec<u8> {
+    let mut flags = 0u16;
+    if recursion_desired {
+        flags |= 0x0100;
+    }
+    if dnssec_ok {
+        flags |= 0x0010;                           // CD: return data even if the resolver can't validate it
+    }
+    let mut msg = Vec::with_capacity(64);
+    msg.extend_from_slice(&id.to_be_bytes());
+    msg.extend_from_slice(&flags.to_be_bytes());
+    msg.extend_from_slice(&1u16.to_be_bytes());   // QDCOUNT
+    msg.extend_from_slice(&0u16.to_be_bytes());   // ANCOUNT
+    msg.extend_from_slice(&0u16.to_be_bytes());   // NSCOUNT
+    msg.extend_from_slice(&1u16.to_be_bytes());   // ARCOUNT (OPT)
+    encode_name(&mut msg, name);
+    msg.extend_from_slice(&rtype.to_be_bytes());
+    msg.extend_from_slice(&1u16.to_be_bytes());   // IN
+
+    // EDNS0 OPT pseudo-record
+    msg.push(0);
+    msg.extend_from_slice(&41u16.to_be_bytes());
+    msg.extend_from_slice(&4096u16.to_be_bytes());
+    msg.extend_from_slice(&(if dnssec_ok { 0x8000u32 } else { 0 }).to_be_bytes());   // DO bit
+    msg.extend_from_slice(&0u16.to_be_bytes());
+    msg
+}
+
+// Build an IXFR query (RFC 1995): the authority section carries the SOA serial we claim to have
+pub fn build_ixfr_query(id: u16, zone: &str, serial: u32) -> Vec<u8> {
+    let mut msg = Vec::with_capacity(96);
+    msg.extend_from_slice(&id.to_be_bytes());
+    msg.extend_from_slice(&0u16.to_be_bytes());   // No recursion
+    msg.extend_from_slice(&1u16.to_be_bytes());   // QDCOUNT
+    msg.extend_from_slice(&0u16.to_be_bytes());   // ANCOUNT
+    msg.extend_from_slice(&1u16.to_be_bytes());   // NSCOUNT (SOA)
+    msg.extend_from_slice(&0u16.to_be_bytes());   // ARCOUNT
+    encode_name(&mut msg, zone);
+    msg.extend_from_slice(&TYPE_IXFR.to_be_bytes());
+    msg.extend_from_slice(&1u16.to_be_bytes());
+
+    let mut rdata = Vec::new();
+    encode_name(&mut rdata, ".");
+    encode_name(&mut rdata, ".");
+    rdata.extend_from_slice(&serial.to_be_bytes());
+    rdata.extend_from_slice(&[0u8; 16]);           // refresh, retry, expire, minimum
+    encode_name(&mut msg, zone);
+    msg.extend_from_slice(&TYPE_SOA.to_be_bytes());
+    msg.extend_from_slice(&1u16.to_be_bytes());
+    msg.extend_from_slice(&0u32.to_be_bytes());
+    msg.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+    msg.extend_from_slice(&rdata);
+    msg
+}
+
+// PTR owner name for an address (in-addr.arpa / nibble-format ip6.arpa)
+pub fn reverse_name(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(ip) => {
+            let o = ip.octets();
+            format!("{}.{}.{}.{}.in-addr.arpa", o[3], o[2], o[1], o[0])
+        },
+        IpAddr::V6(ip) => {
+            let nibbles: Vec<String> = ip.octets().iter().rev()
+                .flat_map(|b| [format!("{:x}", b & 0x0f), format!("{:x}", b >> 4)])
+                .collect();
+            format!("{}.ip6.arpa", nibbles.join("."))
+        },
+    }
+}
+
+// Encode a dotted name as DNS labels
+pub fn encode_name(buf: &mut Vec<u8>, name: &str) {
+    for label in name.trim_end_matches('.').split('.').filter(|l| !l.is_empty()) {
+        let bytes = label.as_bytes();
+        let len = bytes.len().min(63);
+        buf.push(len as u8);
+        buf.extend_from_slice(&bytes[..len]);
+    }
+    buf.push(0);
+}
+
+// Write a length-prefixed message to a TCP stream
+pub fn send_tcp_message(stream: &mut TcpStream, message: &[u8]) -> Result<(), Box<dyn Error>> {
+    let mut framed = Vec::with_capacity(message.len() + 2);
+    framed.extend_from_slice(&(message.len() as u16).to_be_bytes());
+    framed.extend_from_slice(message);
+    stream.write_all(&framed)?;
+    Ok(())
+}
+
+// Read one length-prefixed message from a TCP stream
+pub fn read_tcp_message(stream: &mut TcpStream) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut len_buf = [0u8; 2];
+    stream.read_exact(&mut len_buf)?;
+    let mut message = vec![0u8; u16::from_be_bytes(len_buf) as usize];
+    stream.read_exact(&mut message)?;
+    Ok(message)
+}
+
+// Parse a complete DNS message
+pub fn parse_message(buf: &[u8]) -> Result<DnsMessage, Box<dyn Error>> {
+    if buf.len() < 12 {
+        return Err("DNS message too short".into());
+    }
+    let id = u16::from_be_bytes([buf[0], buf[1]]);
+    let flags = u16::from_be_bytes([buf[2], buf[3]]);
+    let counts: Vec<usize> = (0..4).map(|i| u16::from_be_bytes([buf[4 + i * 2], buf[5 + i * 2]]) as usize).collect();
+
+    let mut pos = 12;
+    for _ in 0..counts[0] {
+        let (_, next) = read_name(buf, pos)?;
+        pos = next + 4;
+    }
+
+    let mut answers = Vec::with_capacity(counts[1]);
+    for _ in 0..counts[1] {
+        let (record, next) = read_record(buf, pos)?;
+        pos = next;
+        answers.push(record);
+    }
+
+    Ok(DnsMessage {
+        id,
+        rcode: (flags & 0x000f) as u8,
+        truncated: flags & 0x0200 != 0,
+        answers,
+    })
+}
+
+// Read a (possibly compressed) name starting at pos; returns the name and the offset after it
+pub fn read_name(buf: &[u8], mut pos: usize) -> Result<(String, usize), Box<dyn Error>> {
+    let mut labels: Vec<String> = Vec::new();
+    let mut end = None;
+    let mut jumps = 0;
+
+    loop {
+        let len = *buf.get(pos).ok_or("DNS name runs past end of message")? as usize;
+        if len == 0 {
+            pos += 1;
+            break;
+        }
+        if len & 0xc0 == 0xc0 {
+            let low = *buf.get(pos + 1).ok_or("Truncated DNS compression pointer")? as usize;
+            if end.is_none() {
+                end = Some(pos + 2);
+            }
+            pos = ((len & 0x3f) << 8) | low;
+            jumps += 1;
+            if jumps > 64 {
+                return Err("DNS compression loop".into());
+            }
+            continue;
+        }
+        let label = buf.get(pos + 1..pos + 1 + len).ok_or("DNS label runs past end of message")?;
+        labels.push(String::from_utf8_lossy(label).to_string());
+        pos += 1 + len;
+    }
+
+    let name = if labels.is_empty() { ".".to_string() } else { labels.join(".") };
+    Ok((name, end.unwrap_or(pos)))
+}
+
+// Read one resource record starting at pos
+fn read_record(buf: &[u8], pos: usize) -> Result<(ResourceRecord, usize), Box<dyn Error>> {
+    let (name, pos) = read_name(buf, pos)?;
+    let header = buf.get(pos..pos + 10).ok_or("Truncated DNS record header")?;
+    let rtype = u16::from_be_bytes([header[0], header[1]]);
+    let class = u16::from_be_bytes([header[2], header[3]]);
+    let ttl = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+    let rdlength = u16::from_be_bytes([header[8], header[9]]) as usize;
+    let start = pos + 10;
+    let end = start + rdlength;
+    let rdata = buf.get(start..end).ok_or("Truncated DNS record data")?;
+
+    let data = match rtype {
+        TYPE_A if rdlength == 4 => RData::A(Ipv4Addr::new(rdata[0], rdata[1], rdata[2], rdata[3])),
+        TYPE_AAAA if rdlength == 16 => {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(rdata);
+            RData::Aaaa(Ipv6Addr::from(octets))
+        },
+        TYPE_NS | TYPE_CNAME | TYPE_PTR => RData::Name(read_name(buf, start)?.0),
+        TYPE_MX if rdlength > 2 => RData::Mx {
+            preference: u16::from_be_bytes([rdata[0], rdata[1]]),
+            exchange: read_name(buf, start + 2)?.0,
+        },
+        TYPE_TXT => {
+            let mut strings = Vec::new();
+            let mut i = 0;
+            while i < rdata.len() {
+                let len = rdata[i] as usize;
+                let chunk = rdata.get(i + 1..i + 1 + len).ok_or("Truncated TXT string")?;
+                strings.push(String::from_utf8_lossy(chunk).to_string());
+                i += 1 + len;
+            }
+            RData::Txt(strings)
+        },
+        TYPE_SOA => {
+            let (mname, next) = read_name(buf, start)?;
+            let (rname, next) = read_name(buf, next)?;
+            let serial = buf.get(next..next + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]])).unwrap_or(0);
+            RData::Soa { mname, rname, serial }
+        },
+        TYPE_SRV if rdlength > 6 => RData::Srv {
+            priority: u16::from_be_bytes([rdata[0], rdata[1]]),
+            weight: u16::from_be_bytes([rdata[2], rdata[3]]),
+            port: u16::from_be_bytes([rdata[4], rdata[5]]),
+            target: read_name(buf, start + 6)?.0,
+        },
+        TYPE_DS if rdlength > 4 => RData::Ds {
+            key_tag: u16::from_be_bytes([rdata[0], rdata[1]]),
+            algorithm: rdata[2],
+            digest_type: rdata[3],
+            digest: rdata[4..].to_vec(),
+        },
+        TYPE_DNSKEY if rdlength > 4 => RData::Dnskey {
+            flags: u16::from_be_bytes([rdata[0], rdata[1]]),
+            protocol: rdata[2],
+            algorithm: rdata[3],
+            public_key: rdata[4..].to_vec(),
+        },
+        TYPE_RRSIG if rdlength > 18 => {
+            let (signer, next) = read_name(buf, start + 18)?;
+            RData::Rrsig {
+                type_covered: u16::from_be_bytes([rdata[0], rdata[1]]),
+                algorithm: rdata[2],
+                labels: rdata[3],
+                original_ttl: u32::from_be_bytes([rdata[4], rdata[5], rdata[6], rdata[7]]),
+                expiration: u32::from_be_bytes([rdata[8], rdata[9], rdata[10], rdata[11]]),
+                inception: u32::from_be_bytes([rdata[12], rdata[13], rdata[14], rdata[15]]),
+                key_tag: u16::from_be_bytes([rdata[16], rdata[17]]),
+                signer,
+                signature: buf.get(next..end).ok_or("Truncated RRSIG signature")?.to_vec(),
+            }
+        },
+        TYPE_NSEC => {
+            let (next, bitmap_start) = read_name(buf, start)?;
+            RData::Nsec { next, types: read_type_bitmap(buf.get(bitmap_start..end).ok_or("Truncated NSEC bitmap")?) }
+        },
+        TYPE_NSEC3PARAM if rdlength >= 5 => RData::Nsec3Param {
+            hash_algorithm: rdata[0],
+            flags: rdata[1],
+            iterations: u16::from_be_bytes([rdata[2], rdata[3]]),
+            salt: rdata.get(5..5 + rdata[4] as usize).ok_or("Truncated NSEC3PARAM salt")?.to_vec(),
+        },
+        _ => RData::Other(rdata.to_vec()),
+    };
+
+    // Names embedded in RDATA are decompressed and lower-cased for signature validation
+    let rdata = match rtype {
+        TYPE_NS | TYPE_CNAME | TYPE_PTR => canonical_rdata(buf, start, end, 0, 1)?,
+        TYPE_MX => canonical_rdata(buf, start, end, 2, 1)?,
+        TYPE_SRV => canonical_rdata(buf, start, end, 6, 1)?,
+        TYPE_SOA => canonical_rdata(buf, start, end, 0, 2)?,
+        TYPE_RRSIG => canonical_rdata(buf, start, end, 18, 1)?,
+        _ => rdata.to_vec(),
+    };
+
+    Ok((ResourceRecord { name, rtype, class, ttl, data, rdata }, end))
+}
+
+// Rebuild RDATA as fixed prefix + uncompressed lower-case names + remaining bytes
+fn canonical_rdata(buf: &[u8], start: usize, end: usize, prefix: usize, names: usize) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut rdata = buf.get(start..start + prefix).ok_or("Truncated DNS record data")?.to_vec();
+    let mut pos = start + prefix;
+    for _ in 0..names {
+        let (name, next) = read_name(buf, pos)?;
+        encode_name(&mut rdata, &name.to_lowercase());
+        pos = next;
+    }
+    rdata.extend_from_slice(buf.get(pos..end).ok_or("Truncated DNS record data")?);
+    Ok(rdata)
+}
+
+// Decode an NSEC/NSEC3 type bitmap (RFC 4034 4.1.2)
+fn read_type_bitmap(mut bitmap: &[u8]) -> Vec<u16> {
+    let mut types = Vec::new();
+    while bitmap.len() >= 2 {
+        let window = bitmap[0] as u16;
+        let len = (bitmap[1] as usize).min(bitmap.len() - 2);
+        for (i, byte) in bitmap[2..2 + len].iter().enumerate() {
+            for bit in 0..8 {
+                if byte & (0x80 >> bit) != 0 {
+                    types.push(window * 256 + (i * 8 + bit) as u16);
+                }
+            }
+        }
+        bitmap = &bitmap[2 + len..];
+    }
+    types
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Response header for `id` with one question and `answers` answer records
+    fn header(id: u16, answers: u16) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&id.to_be_bytes());
+        buf.extend_from_slice(&0x8180u16.to_be_bytes());
+        for count in [1, answers, 0, 0] {
+            buf.extend_from_slice(&count.to_be_bytes());
+        }
+        buf
+    }
+
+    fn record(buf: &mut Vec<u8>, name: &[u8], rtype: u16, rdata: &[u8]) {
+        buf.extend_from_slice(name);
+        buf.extend_from_slice(&rtype.to_be_bytes());
+        buf.extend_from_slice(&[0, 1, 0, 0, 0x0e, 0x10]);
+        buf.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        buf.extend_from_slice(rdata);
+    }
+
+    #[test]
+    fn build_query_encodes_header_question_and_opt() {
+        let query = build_query(0x1234, "www.example.com.", TYPE_A, true, true);
+        assert_eq!(&query[..12], &[0x12, 0x34, 0x01, 0x10, 0, 1, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(&query[12..29], b"\x03www\x07example\x03com\x00");
+        assert_eq!(&query[29..33], &[0, 1, 0, 1]);
+        // OPT: root owner, type 41, 4096 byte payload, DO bit set
+        assert_eq!(&query[33..], &[0, 0, 41, 0x10, 0, 0, 0, 0x80, 0, 0, 0]);
+
+        let plain = build_query(1, "example.com", TYPE_AXFR, false, false);
+        assert_eq!(&plain[2..4], &[0, 0]);
+        assert_eq!(&plain[plain.len() - 6..plain.len() - 2], &[0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn parse_message_follows_compression_pointers() {
+        let mut buf = header(7, 2);
+        encode_name(&mut buf, "www.example.com");
+        buf.extend_from_slice(&[0, 1, 0, 1]);
+        // CNAME owned by the question name, pointing at "cdn" + the "example.com" suffix (offset 16)
+        record(&mut buf, &[0xc0, 12], TYPE_CNAME, &[3, b'c', b'd', b'n', 0xc0, 16]);
+        record(&mut buf, &[3, b'c', b'd', b'n', 0xc0, 16], TYPE_A, &[192, 0, 2, 10]);
+
+        let message = parse_message(&buf).unwrap();
+        assert_eq!(message.id, 7);
+        assert_eq!(message.rcode, RCODE_NOERROR);
+        assert_eq!(message.answers.len(), 2);
+        assert_eq!(message.answers[0].name, "www.example.com");
+        assert_eq!(message.answers[0].target(), Some("cdn.example.com"));
+        assert_eq!(message.answers[1].name, "cdn.example.com");
+        assert_eq!(message.answers[1].address(), Some("192.0.2.10".parse().unwrap()));
+    }
+
+    #[test]
+    fn read_name_returns_offset_after_first_pointer() {
+        let mut buf = vec![0; 12];
+        encode_name(&mut buf, "example.com");
+        let pointer = buf.len();
+        buf.extend_from_slice(&[3, b'w', b'w', b'w', 0xc0, 12, 0xff]);
+        assert_eq!(read_name(&buf, pointer).unwrap(), ("www.example.com".to_string(), pointer + 6));
+        assert_eq!(read_name(&[0], 0).unwrap(), (".".to_string(), 1));
+    }
+
+    #[test]
+    fn read_name_rejects_pointer_loops() {
+        let mut buf = vec![0; 12];
+        buf.extend_from_slice(&[0xc0, 12]);
+        assert!(read_name(&buf, 12).unwrap_err().to_string().contains("loop"));
+
+        // Two pointers referring to each other
+        let mut buf = vec![0; 12];
+        buf.extend_from_slice(&[1, b'a', 0xc0, 16, 1, b'b', 0xc0, 12]);
+        assert!(read_name(&buf, 12).is_err());
+    }
+
+    #[test]
+    fn truncated_messages_are_errors() {
+        assert!(parse_message(&[0; 11]).is_err());
+
+        let mut buf = header(1, 1);
+        encode_name(&mut buf, "example.com");
+        buf.extend_from_slice(&[0, 1, 0, 1]);
+        let question_end = buf.len();
+        record(&mut buf, &[0xc0, 12], TYPE_A, &[192, 0, 2, 1]);
+        assert!(parse_message(&buf).is_ok());
+
+        // Every cut inside the answer must fail cleanly rather than panic
+        for end in question_end..buf.len() {
+            assert!(parse_message(&buf[..end]).is_err(), "accepted a message cut at {}", end);
+        }
+        // Label length running past the end, and a pointer missing its second byte
+        assert!(read_name(&[5, b'a', b'b'], 0).is_err());
+        assert!(read_name(&[0xc0], 0).is_err());
+    }
+}
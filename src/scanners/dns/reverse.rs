@@ -0,0 +1,169 @@
+use console::style;
+use indicatif::ProgressBar;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use crate::models::DnsRecord;
+use crate::scanners::dns::resolver::{reverse_name, DnsClient, RCODE_NOERROR, TYPE_PTR};
+
+// Resolve PTR records for every address in parallel and return them as DNS records
+pub fn perform_reverse_dns(spinner: &ProgressBar, targets: &[IpAddr], resolvers: Vec<SocketAddr>, rate_limit: u32, threads: usize, base_filename: &str, scan_dir: &str) -> Result<Vec<DnsRecord>, Box<dyn Error>> {
+    let output_file = format!("{}/{}_rdns.txt", scan_dir, base_filename);
+    let client = DnsClient::new(resolvers).with_rate_limit(rate_limit);
+
+    spinner.set_message(format!("Resolving PTR records for {} addresses...", style(targets.len()).cyan()));
+
+    let found: Mutex<BTreeMap<IpAddr, Vec<String>>> = Mutex::new(BTreeMap::new());
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(|| {
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(ip) = targets.get(index) else { break };
+
+                    let names = lookup_ptr(&client, *ip);
+                    if !names.is_empty() {
+                        let mut found = found.lock().unwrap_or_else(|e| e.into_inner());
+                        found.insert(*ip, names);
+                    }
+
+                    let completed = done.fetch_add(1, Ordering::Relaxed) + 1;
+                    if completed.is_multiple_of(25) {
+                        let hits = found.lock().map(|f| f.len()).unwrap_or(0);
+                        spinner.set_message(format!("Reverse DNS {}/{} addresses ({} named)...", completed, targets.len(), style(hits).green()));
+                    }
+                }
+            });
+        }
+    });
+    let found = found.into_inner().unwrap_or_else(|e| e.into_inner());
+
+    // Save the raw results
+    let mut file = File::create(output_file)?;
+    let resolvers: Vec<String> = client.resolvers().iter().map(|r| r.to_string()).collect();
+    writeln!(file, "# Reverse DNS sweep of {} addresses via {}", targets.len(), resolvers.join(", "))?;
+    for (ip, names) in &found {
+        writeln!(file, "{}\t{}", ip, names.join(", "))?;
+    }
+
+    Ok(found.into_iter()
+        .flat_map(|(ip, names)| names.into_iter().map(move |name| DnsRecord {
+            source: "Reverse DNS".to_string(),
+            record_type: "PTR".to_string(),
+            name,
+            address: Some(ip.to_string()),
+            target: None,
+            port: None,
+            data: None,
+        }))
+        .collect())
+}
+
+// PTR names for one address (empty when there is none or the lookup fails)
+fn lookup_ptr(client: &DnsClient, ip: IpAddr) -> Vec<String> {
+    match client.query(&reverse_name(ip), TYPE_PTR) {
+        Ok(response) if response.rcode == RCODE_NOERROR => {
+            let mut names: Vec<String> = response.answers.iter()
+                .filter(|r| r.rtype == TYPE_PTR)
+                .filter_map(|r| r.target())
+                .map(|n| n.trim_end_matches('.').to_lowercase())
+                .collect();
+            names.dedup();
+            names
+        },
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::net::UdpSocket;
+
+    // PTR names for a reverse name, or None for NXDOMAIN
+    fn zone(name: &str) -> Option<Vec<&'static str>> {
+        match name {
+            "1.2.0.192.in-addr.arpa" => Some(vec!["GW.example.com.", "gw.example.com.", "router.example.com."]),
+            "3.2.0.192.in-addr.arpa" => Some(Vec::new()),
+            "1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa" => Some(vec!["v6.example.com."]),
+            _ => None,
+        }
+    }
+
+    // UDP DNS stand-in answering PTR questions from `zone`
+    fn serve() -> SocketAddr {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = socket.local_addr().unwrap();
+        thread::spawn(move || {
+            let mut buf = [0u8; 512];
+            while let Ok((len, peer)) = socket.recv_from(&mut buf) {
+                let query = &buf[..len];
+                let mut labels = Vec::new();
+                let mut pos = 12;
+                while query[pos] != 0 {
+                    let end = pos + 1 + query[pos] as usize;
+                    labels.push(String::from_utf8_lossy(&query[pos + 1..end]).to_string());
+                    pos = end;
+                }
+                let answer = zone(&labels.join("."));
+                let rcode = if answer.is_some() { 0 } else { 3 };
+                let names = answer.unwrap_or_default();
+
+                let mut response = query[..pos + 5].to_vec();
+                response[2] = 0x81;
+                response[3] = 0x80 | rcode;
+                response[6..8].copy_from_slice(&(names.len() as u16).to_be_bytes());
+                response[8..12].copy_from_slice(&[0, 0, 0, 0]);
+                for name in names {
+                    let mut target = Vec::new();
+                    for label in name.trim_end_matches('.').split('.') {
+                        target.push(label.len() as u8);
+                        target.extend_from_slice(label.as_bytes());
+                    }
+                    target.push(0);
+                    response.extend_from_slice(&[0xc0, 12, 0, 12, 0, 1, 0, 0, 0x0e, 0x10]);
+                    response.extend_from_slice(&(target.len() as u16).to_be_bytes());
+                    response.extend_from_slice(&target);
+                }
+                let _ = socket.send_to(&response, peer);
+            }
+        });
+        address
+    }
+
+    #[test]
+    fn ptr_names_are_collected_per_address() {
+        let dir = std::env::temp_dir().join(format!("rust_recon_rdns_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let targets: Vec<IpAddr> = ["192.0.2.1", "192.0.2.2", "192.0.2.3", "2001:db8::1"].iter().map(|t| t.parse().unwrap()).collect();
+
+        let records = perform_reverse_dns(&ProgressBar::hidden(), &targets, vec![serve()], 0, 4, "test", &dir.to_string_lossy()).unwrap();
+
+        // Names are lower-cased and deduplicated; addresses without a PTR record are left out
+        let found: Vec<String> = records.iter()
+            .map(|r| format!("{} {} {}", r.record_type, r.address.as_deref().unwrap_or("-"), r.name))
+            .collect();
+        assert_eq!(found, vec![
+            "PTR 192.0.2.1 gw.example.com",
+            "PTR 192.0.2.1 router.example.com",
+            "PTR 2001:db8::1 v6.example.com",
+        ]);
+        assert!(records.iter().all(|r| r.source == "Reverse DNS"));
+
+        let saved = fs::read_to_string(dir.join("test_rdns.txt")).unwrap();
+        assert!(saved.starts_with("# Reverse DNS sweep of 4 addresses via 127.0.0.1:"));
+        assert!(saved.contains("\n192.0.2.1\tgw.example.com, router.example.com\n"));
+        assert!(saved.ends_with("\n2001:db8::1\tv6.example.com\n"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,429 @@
+use console::style;
+use indicatif::ProgressBar;
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::Write;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::models::{DnsRecord, Finding, Severity};
+use crate::scanners::dns::resolver::{DnsClient, DnsMessage, ResourceRecord, RCODE_NOERROR, TYPE_A, TYPE_AAAA, TYPE_CNAME};
+use crate::utils::fs::ResourceManager;
+
+// Name of the bundled wordlist looked up through the ResourceManager
+const WORDLIST_FILE: &str = "subdomains.txt";
+
+// Labels combined with discovered names to build permutations
+const PERMUTATION_WORDS: &[&str] = &[
+    "dev", "test", "staging", "stage", "prod", "qa", "uat", "api", "admin", "internal", "old", "new", "v2", "beta", "backup",
+];
+
+// Settings for the subdomain brute-forcer
+pub struct SubdomainOptions {
+    pub wordlist: Option<String>,
+    pub resolvers: Vec<SocketAddr>,
+    pub rate_limit: u32,           // Queries per second across all threads (0 = unlimited)
+    pub threads: usize,
+    pub permutations: bool,
+}
+
+// Names found by the brute-forcer
+pub struct SubdomainResults {
+    pub records: Vec<DnsRecord>,
+    pub findings: Vec<Finding>,
+}
+
+// A name that resolved: its addresses, CNAME chain and the answers seen
+struct Resolved {
+    addresses: BTreeSet<String>,
+    cnames: Vec<(String, String)>,
+    answers: Vec<ResourceRecord>,
+}
+
+// Brute-force subdomains of a domain with wildcard filtering and permutations
+pub fn perform_subdomain_enum(spinner: &ProgressBar, domain: &str, options: &SubdomainOptions, known_names: &[String], base_filename: &str, scan_dir: &str) -> Result<SubdomainResults, Box<dyn Error>> {
+    let output_file = format!("{}/{}_subdomains.txt", scan_dir, base_filename);
+    let domain = domain.trim_end_matches('.').to_lowercase();
+
+    let client = DnsClient::new(options.resolvers.clone()).with_rate_limit(options.rate_limit);
+
+    // Wildcard detection: random labels that resolve reveal the wildcard answers to filter out
+    spinner.set_message(format!("Checking {} for wildcard DNS...", style(&domain).cyan()));
+    let wildcard = detect_wildcard(&client, &domain);
+
+    // Wordlist brute force
+    let words = load_wordlist(options.wordlist.as_deref())?;
+    let candidates: Vec<String> = words.iter().map(|w| format!("{}.{}", w, domain)).collect();
+    spinner.set_message(format!("Brute-forcing {} names under {}...", candidates.len(), style(&domain).cyan()));
+    let mut found = resolve_candidates(spinner, &client, &candidates, options.threads, &wildcard);
+
+    // Permutations of everything found so far (and of names already in the inventory)
+    if options.permutations {
+        let mut seeds: BTreeSet<String> = found.keys().cloned().collect();
+        seeds.extend(known_names.iter().map(|n| n.trim_end_matches('.').to_lowercase()));
+        let tried: BTreeSet<&String> = candidates.iter().collect();
+        let permutations: Vec<String> = generate_permutations(&domain, &seeds)
+            .into_iter()
+            .filter(|p| !tried.contains(p) && !found.contains_key(p))
+            .collect();
+        if !permutations.is_empty() {
+            spinner.set_message(format!("Resolving {} permutations under {}...", permutations.len(), style(&domain).cyan()));
+            found.extend(resolve_candidates(spinner, &client, &permutations, options.threads, &wildcard));
+        }
+    }
+
+    // Build records for the inventory
+    let mut records = Vec::new();
+    for (name, resolved) in &found {
+        for (alias, target) in &resolved.cnames {
+            records.push(DnsRecord {
+                source: "Subdomain brute-force".to_string(),
+                record_type: "CNAME".to_string(),
+                name: alias.clone(),
+                address: None,
+                target: Some(target.clone()),
+                port: None,
+                data: None,
+            });
+        }
+        for address in &resolved.addresses {
+            records.push(DnsRecord {
+                source: "Subdomain brute-force".to_string(),
+                record_type: if address.contains(':') { "AAAA" } else { "A" }.to_string(),
+                name: name.clone(),
+                address: Some(address.clone()),
+                target: None,
+                port: None,
+                data: None,
+            });
+        }
+    }
+
+    let mut findings = Vec::new();
+    if !wildcard.is_empty() {
+        let addresses: Vec<&str> = wildcard.iter().map(|a| a.as_str()).collect();
+        findings.push(Finding {
+            source: "Subdomain brute-force".to_string(),
+            id: Some("dns-wildcard".to_string()),
+            severity: Severity::Info,
+            location: domain.clone(),
+            method: None,
+            description: format!("Wildcard DNS: any name under {} resolves to {} (matching results were filtered)", domain, addresses.join(", ")),
+            references: Vec::new(),
+        });
+    }
+
+    // Save the raw results
+    let mut file = File::create(output_file)?;
+    let resolvers: Vec<String> = client.resolvers().iter().map(|r| r.to_string()).collect();
+    writeln!(file, "# Subdomain enumeration for {} via {}", domain, resolvers.join(", "))?;
+    if !wildcard.is_empty() {
+        writeln!(file, "# Wildcard answers filtered: {}", wildcard.iter().cloned().collect::<Vec<_>>().join(", "))?;
+    }
+    for resolved in found.values() {
+        for answer in &resolved.answers {
+            writeln!(file, "{}", answer)?;
+        }
+    }
+
+    Ok(SubdomainResults { records, findings })
+}
+
+// Resolve a few random labels; anything they resolve to is a wildcard answer
+fn detect_wildcard(client: &DnsClient, domain: &str) -> BTreeSet<String> {
+    let mut wildcard = BTreeSet::new();
+    for i in 0..3 {
+        let probe = format!("{}.{}", random_label(i), domain);
+        if let Some(resolved) = resolve_name(client, &probe) {
+            wildcard.extend(resolved.addresses);
+            wildcard.extend(resolved.cnames.into_iter().map(|(_, target)| target));
+        }
+    }
+    wildcard
+}
+
+// Resolve candidates in parallel, dropping names that only return wildcard answers
+fn resolve_candidates(spinner: &ProgressBar, client: &DnsClient, candidates: &[String], threads: usize, wildcard: &BTreeSet<String>) -> BTreeMap<String, Resolved> {
+    let found = Mutex::new(BTreeMap::new());
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(|| {
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(name) = candidates.get(index) else { break };
+
+                    if let Some(resolved) = resolve_name(client, name)
+                        && !is_wildcard_answer(&resolved, wildcard) {
+                        let mut found = found.lock().unwrap_or_else(|e| e.into_inner());
+                        found.insert(name.clone(), resolved);
+                    }
+
+                    let completed = done.fetch_add(1, Ordering::Relaxed) + 1;
+                    if completed.is_multiple_of(25) {
+                        let hits = found.lock().map(|f| f.len()).unwrap_or(0);
+                        spinner.set_message(format!("Resolved {}/{} names ({} found)...", completed, candidates.len(), style(hits).green()));
+                    }
+                }
+            });
+        }
+    });
+
+    found.into_inner().unwrap_or_else(|e| e.into_inner())
+}
+
+// Resolve a single name (A, then AAAA when the name exists without IPv4 addresses)
+fn resolve_name(client: &DnsClient, name: &str) -> Option<Resolved> {
+    let mut resolved = Resolved { addresses: BTreeSet::new(), cnames: Vec::new(), answers: Vec::new() };
+
+    let response = client.query(name, TYPE_A).ok()?;
+    if response.rcode != RCODE_NOERROR {
+        return None;
+    }
+    collect_answers(&response, &mut resolved);
+
+    if resolved.addresses.is_empty()
+        && let Ok(response) = client.query(name, TYPE_AAAA) {
+        collect_answers(&response, &mut resolved);
+    }
+
+    if resolved.addresses.is_empty() && resolved.cnames.is_empty() {
+        None
+    } else {
+        Some(resolved)
+    }
+}
+
+fn collect_answers(response: &DnsMessage, resolved: &mut Resolved) {
+    resolved.answers.extend(response.answers.iter().cloned());
+    for record in &response.answers {
+        if let Some(address) = record.address() {
+            resolved.addresses.insert(address.to_string());
+        } else if record.rtype == TYPE_CNAME
+            && let Some(target) = record.target() {
+            let pair = (record.name.to_lowercase(), target.to_lowercase());
+            if !resolved.cnames.contains(&pair) {
+                resolved.cnames.push(pair);
+            }
+        }
+    }
+}
+
+// A result is a wildcard hit when everything it resolved to is a wildcard answer
+fn is_wildcard_answer(resolved: &Resolved, wildcard: &BTreeSet<String>) -> bool {
+    if wildcard.is_empty() {
+        return false;
+    }
+    let addresses_wild = resolved.addresses.iter().all(|a| wildcard.contains(a));
+    let cnames_wild = resolved.cnames.iter().all(|(_, target)| wildcard.contains(target));
+    addresses_wild && cnames_wild
+}
+
+// Build permutations of discovered names (dev-www, www-dev, www2, api.www, ...)
+fn generate_permutations(domain: &str, seeds: &BTreeSet<String>) -> Vec<String> {
+    let suffix = format!(".{}", domain);
+    let mut permutations = BTreeSet::new();
+
+    for seed in seeds {
+        let Some(prefix) = seed.strip_suffix(&suffix) else { continue };
+        let first = prefix.split('.').next().unwrap_or(prefix);
+        let rest = &prefix[first.len()..];
+
+        for word in PERMUTATION_WORDS {
+            permutations.insert(format!("{}-{}{}{}", word, first, rest, suffix));
+            permutations.insert(format!("{}-{}{}{}", first, word, rest, suffix));
+            permutations.insert(format!("{}.{}{}", word, prefix, suffix));
+        }
+
+        // Number increments: web1 -> web0/web2, www -> www1/www2
+        let stem = first.trim_end_matches(|c: char| c.is_ascii_digit());
+        let number: Option<u32> = first[stem.len()..].parse().ok();
+        match number {
+            Some(n) => {
+                for candidate in [n.saturating_sub(1), n + 1, n + 2] {
+                    permutations.insert(format!("{}{}{}{}", stem, candidate, rest, suffix));
+                }
+            },
+            None => {
+                for candidate in 1..=3 {
+                    permutations.insert(format!("{}{}{}{}", first, candidate, rest, suffix));
+                }
+            },
+        }
+    }
+
+    permutations.remove(domain);
+    permutations.into_iter().filter(|p| !seeds.contains(p)).collect()
+}
+
+// Load the wordlist from an explicit file, an installed/updated copy, or the bundled default
+fn load_wordlist(wordlist: Option<&str>) -> Result<Vec<String>, Box<dyn Error>> {
+    let content = match wordlist {
+        Some(path) => fs::read_to_string(path)?,
+        None => match ResourceManager::get_resource_path(WORDLIST_FILE) {
+            Some(path) => fs::read_to_string(path)?,
+            None => crate::utils::SUBDOMAIN_WORDLIST.to_string(),
+        },
+    };
+    let mut seen = BTreeSet::new();
+    Ok(content.lines()
+        .map(|l| l.trim().trim_end_matches('.').to_lowercase())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .filter(|l| seen.insert(l.clone()))
+        .collect())
+}
+
+// A label that almost certainly doesn't exist, used for wildcard probes
+fn random_label(salt: u32) -> String {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    let mixed = (nanos as u64 ^ (salt as u64 + 1).wrapping_mul(0x9e3779b97f4a7c15)).wrapping_mul(0xbf58476d1ce4e5b9);
+    format!("rr-{:016x}", mixed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::UdpSocket;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    // A records for a question, or None for NXDOMAIN
+    type Zone = fn(&str, u16) -> Option<Vec<[u8; 4]>>;
+
+    // UDP DNS stand-in answering from `zone`; returns its address and the number of queries answered
+    fn serve(zone: Zone) -> (SocketAddr, Arc<AtomicUsize>) {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = socket.local_addr().unwrap();
+        let queries = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&queries);
+        thread::spawn(move || {
+            let mut buf = [0u8; 512];
+            while let Ok((len, peer)) = socket.recv_from(&mut buf) {
+                counter.fetch_add(1, Ordering::Relaxed);
+                let query = &buf[..len];
+                let mut labels = Vec::new();
+                let mut pos = 12;
+                while query[pos] != 0 {
+                    let end = pos + 1 + query[pos] as usize;
+                    labels.push(String::from_utf8_lossy(&query[pos + 1..end]).to_string());
+                    pos = end;
+                }
+                let rtype = u16::from_be_bytes([query[pos + 1], query[pos + 2]]);
+                let answer = zone(&labels.join(".").to_lowercase(), rtype);
+                let rcode = if answer.is_some() { 0 } else { 3 };
+                let addresses = answer.unwrap_or_default();
+
+                // Header and question of the query (without its OPT record), then the answers
+                let mut response = query[..pos + 5].to_vec();
+                response[2] = 0x81;
+                response[3] = 0x80 | rcode;
+                response[6..8].copy_from_slice(&(addresses.len() as u16).to_be_bytes());
+                response[8..12].copy_from_slice(&[0, 0, 0, 0]);
+                for address in addresses {
+                    response.extend_from_slice(&[0xc0, 12, 0, 1, 0, 1, 0, 0, 0x0e, 0x10, 0, 4]);
+                    response.extend_from_slice(&address);
+                }
+                let _ = socket.send_to(&response, peer);
+            }
+        });
+        (address, queries)
+    }
+
+    fn enumerate(domain: &str, resolver: SocketAddr, words: &[&str], rate_limit: u32) -> SubdomainResults {
+        let dir = std::env::temp_dir().join(format!("rust_recon_subdomains_{}_{}", domain, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let wordlist = dir.join("words.txt");
+        fs::write(&wordlist, words.join("\n")).unwrap();
+        let options = SubdomainOptions {
+            wordlist: Some(wordlist.to_string_lossy().to_string()),
+            resolvers: vec![resolver],
+            rate_limit,
+            threads: 4,
+            permutations: false,
+        };
+        perform_subdomain_enum(&ProgressBar::hidden(), domain, &options, &[], "test", &dir.to_string_lossy()).unwrap()
+    }
+
+    fn found(results: &SubdomainResults) -> Vec<String> {
+        results.records.iter().map(|r| format!("{} {}", r.name, r.address.as_deref().unwrap_or("-"))).collect()
+    }
+
+    fn resolved(addresses: &[&str], cnames: &[(&str, &str)]) -> Resolved {
+        Resolved {
+            addresses: addresses.iter().map(|a| a.to_string()).collect(),
+            cnames: cnames.iter().map(|(n, t)| (n.to_string(), t.to_string())).collect(),
+            answers: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn permutations_vary_the_first_label() {
+        let seeds: BTreeSet<String> = ["www.example.com", "web1.eu.example.com"].iter().map(|s| s.to_string()).collect();
+        let permutations = generate_permutations("example.com", &seeds);
+
+        for expected in ["dev-www.example.com", "www-dev.example.com", "api.www.example.com", "www1.example.com", "www3.example.com",
+            "web0.eu.example.com", "web2.eu.example.com", "web3.eu.example.com", "staging-web1.eu.example.com", "test.web1.eu.example.com"] {
+            assert!(permutations.contains(&expected.to_string()), "missing {}", expected);
+        }
+        assert!(!permutations.iter().any(|p| seeds.contains(p) || p == "example.com"));
+        assert!(permutations.iter().all(|p| p.ends_with(".example.com")));
+    }
+
+    #[test]
+    fn permutations_ignore_names_outside_the_domain() {
+        let seeds: BTreeSet<String> = ["www.example.org".to_string()].into_iter().collect();
+        assert!(generate_permutations("example.com", &seeds).is_empty());
+    }
+
+    #[test]
+    fn wildcard_answers_are_filtered() {
+        let wildcard: BTreeSet<String> = ["192.0.2.1", "wild.example.net"].iter().map(|s| s.to_string()).collect();
+
+        assert!(is_wildcard_answer(&resolved(&["192.0.2.1"], &[]), &wildcard));
+        assert!(is_wildcard_answer(&resolved(&["192.0.2.1"], &[("x.example.com", "wild.example.net")]), &wildcard));
+        // Any answer outside the wildcard set makes the name real
+        assert!(!is_wildcard_answer(&resolved(&["192.0.2.1", "192.0.2.2"], &[]), &wildcard));
+        assert!(!is_wildcard_answer(&resolved(&["192.0.2.1"], &[("x.example.com", "real.example.net")]), &wildcard));
+        // Without a wildcard nothing is filtered
+        assert!(!is_wildcard_answer(&resolved(&["192.0.2.1"], &[]), &BTreeSet::new()));
+    }
+
+    #[test]
+    fn brute_force_resolves_names_at_the_configured_rate() {
+        let (resolver, queries) = serve(|name, rtype| match (name, rtype) {
+            ("www.example.com", TYPE_A) => Some(vec![[192, 0, 2, 10]]),
+            ("mail.example.com", TYPE_A) => Some(vec![[192, 0, 2, 25], [192, 0, 2, 26]]),
+            _ => None,
+        });
+
+        let started = Instant::now();
+        let results = enumerate("example.com", resolver, &["www", "mail", "vpn", "ftp", "WWW"], 20);
+        assert_eq!(found(&results), vec!["mail.example.com 192.0.2.25", "mail.example.com 192.0.2.26", "www.example.com 192.0.2.10"]);
+        assert!(results.findings.is_empty());
+
+        // Three wildcard probes and one A query per distinct word, spaced 50 ms apart across all threads
+        assert_eq!(queries.load(Ordering::Relaxed), 7);
+        assert!(started.elapsed() >= Duration::from_millis(300), "{:?}", started.elapsed());
+    }
+
+    #[test]
+    fn brute_force_filters_wildcard_answers() {
+        let (resolver, _) = serve(|name, rtype| match (name, rtype) {
+            ("www.wild.test", TYPE_A) => Some(vec![[198, 51, 100, 80]]),
+            (_, TYPE_A) => Some(vec![[198, 51, 100, 1]]),
+            _ => Some(Vec::new()),
+        });
+
+        let results = enumerate("wild.test", resolver, &["www", "mail", "vpn"], 0);
+        assert_eq!(found(&results), vec!["www.wild.test 198.51.100.80"]);
+        assert_eq!(results.findings.len(), 1);
+        assert_eq!(results.findings[0].id.as_deref(), Some("dns-wildcard"));
+        assert!(results.findings[0].description.contains("198.51.100.1"));
+    }
+}
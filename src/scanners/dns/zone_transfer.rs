@@ -0,0 +1,278 @@
+use console::style;
+use indicatif::ProgressBar;
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+use std::net::{IpAddr, SocketAddr};
+
+use crate::models::{DnsRecord, Finding, Severity, TransferStatus, ZoneTransferResult};
+use crate::scanners::dns::resolver::{
+    build_ixfr_query, build_query, parse_message, rcode_name, read_tcp_message, send_tcp_message,
+    DnsClient, ResourceRecord, RCODE_NOERROR, TYPE_A, TYPE_AAAA, TYPE_AXFR, TYPE_NS, TYPE_SOA,
+};
+
+// Upper bound on AXFR messages read from one server
+const MAX_TRANSFER_MESSAGES: usize = 10_000;
+
+// Results of the zone transfer checks
+pub struct ZoneTransferResults {
+    pub attempts: Vec<ZoneTransferResult>,
+    pub records: Vec<DnsRecord>,
+    pub findings: Vec<Finding>,
+}
+
+// Resolve a domain's name servers and attempt AXFR/IXFR against each of them over TCP
+pub fn perform_zone_transfer(spinner: &ProgressBar, domain: &str, resolvers: Vec<SocketAddr>, servers: Vec<SocketAddr>, base_filename: &str, scan_dir: &str) -> Result<ZoneTransferResults, Box<dyn Error>> {
+    let domain = domain.trim_end_matches('.').to_lowercase();
+    let client = DnsClient::new(resolvers);
+
+    // Name servers to test: explicit servers, or the domain's NS records
+    let targets: Vec<(String, SocketAddr)> = if !servers.is_empty() {
+        servers.iter().map(|s| (s.ip().to_string(), *s)).collect()
+    } else {
+        spinner.set_message(format!("Resolving name servers for {}...", style(&domain).cyan()));
+        resolve_name_servers(&client, &domain)?
+    };
+    if targets.is_empty() {
+        return Err(format!("No name servers found for {}", domain).into());
+    }
+
+    let mut results = ZoneTransferResults { attempts: Vec::new(), records: Vec::new(), findings: Vec::new() };
+    let mut disclosed = Vec::new();
+    for (server, address) in targets {
+        spinner.set_message(format!("Attempting AXFR of {} from {} ({})...", style(&domain).cyan(), style(&server).yellow(), address));
+        let (axfr, zone) = attempt_axfr(&client, address, &domain);
+
+        spinner.set_message(format!("Attempting IXFR of {} from {} ({})...", style(&domain).cyan(), style(&server).yellow(), address));
+        let ixfr = attempt_ixfr(&client, address, &domain);
+
+        // Keep whatever part of the zone the server sent
+        let zone_file = if zone.is_empty() {
+            None
+        } else {
+            let filename = format!("{}_axfr_{}.zone", base_filename, address.ip().to_string().replace([':', '.'], "_"));
+            let mut file = File::create(format!("{}/{}", scan_dir, filename))?;
+            writeln!(file, "; AXFR of {} from {} ({}): {}", domain, server, address, axfr)?;
+            for record in &zone {
+                writeln!(file, "{}", record)?;
+            }
+            Some(filename)
+        };
+
+        match &axfr {
+            TransferStatus::Allowed => disclosed.push(format!("{} ({}): {} records", server, address.ip(), zone.len())),
+            TransferStatus::Partial(reason) => disclosed.push(format!("{} ({}): {} records, incomplete transfer ({})", server, address.ip(), zone.len(), reason)),
+            _ => {}
+        }
+
+        results.records.extend(zone.iter().map(|r| r.to_dns_record("AXFR")));
+        results.attempts.push(ZoneTransferResult {
+            server,
+            address: address.to_string(),
+            axfr,
+            ixfr,
+            records: zone.len(),
+            zone_file,
+        });
+    }
+
+    // One finding per zone, listing every name server that handed it out
+    if !disclosed.is_empty() {
+        results.findings.push(Finding {
+            source: "Zone transfer".to_string(),
+            id: Some("dns-axfr".to_string()),
+            severity: Severity::High,
+            location: domain.clone(),
+            method: None,
+            description: format!("DNS zone transfer (AXFR) of {} allowed by {}", domain, disclosed.join(", ")),
+            references: vec!["https://www.rfc-editor.org/rfc/rfc5936".to_string()],
+        });
+    }
+
+    Ok(results)
+}
+
+// Look up NS records and the addresses of each name server
+fn resolve_name_servers(client: &DnsClient, domain: &str) -> Result<Vec<(String, SocketAddr)>, Box<dyn Error>> {
+    let response = client.query(domain, TYPE_NS)?;
+    let mut targets = Vec::new();
+    for ns in response.answers.iter().filter(|r| r.rtype == TYPE_NS).filter_map(|r| r.target()) {
+        let ns = ns.trim_end_matches('.').to_lowercase();
+        for rtype in [TYPE_A, TYPE_AAAA] {
+            if let Ok(answer) = client.query(&ns, rtype) {
+                let addresses: Vec<IpAddr> = answer.answers.iter().filter_map(|r| r.address()).collect();
+                targets.extend(addresses.into_iter().map(|ip| (ns.clone(), SocketAddr::new(ip, 53))));
+            }
+        }
+    }
+    Ok(targets)
+}
+
+// Request a full zone transfer; the zone is complete once the closing SOA arrives, and records
+// received before the transfer broke off are kept with a partial status
+fn attempt_axfr(client: &DnsClient, server: SocketAddr, domain: &str) -> (TransferStatus, Vec<ResourceRecord>) {
+    let mut records: Vec<ResourceRecord> = Vec::new();
+    let status = match transfer_zone(client, server, domain, &mut records) {
+        Ok(()) => TransferStatus::Allowed,
+        Err(TransferStatus::Refused(reason) | TransferStatus::Failed(reason)) if !records.is_empty() => TransferStatus::Partial(reason),
+        Err(status) => status,
+    };
+    (status, records)
+}
+
+// Read AXFR messages into `records` until the SOA that opened the transfer is repeated
+fn transfer_zone(client: &DnsClient, server: SocketAddr, domain: &str, records: &mut Vec<ResourceRecord>) -> Result<(), TransferStatus> {
+    let mut stream = client.connect_tcp(server).map_err(|e| TransferStatus::Failed(e.to_string()))?;
+    let query = build_query(client.next_query_id(), domain, TYPE_AXFR, false, false);
+    send_tcp_message(&mut stream, &query).map_err(|e| TransferStatus::Failed(e.to_string()))?;
+
+    let mut soa_count = 0;
+    for _ in 0..MAX_TRANSFER_MESSAGES {
+        // Servers that refuse often just close the connection
+        let message = read_tcp_message(&mut stream).map_err(|e| TransferStatus::Refused(format!("connection closed: {}", e)))?;
+        let response = parse_message(&message).map_err(|e| TransferStatus::Failed(e.to_string()))?;
+        if response.rcode != RCODE_NOERROR {
+            return Err(TransferStatus::Refused(rcode_name(response.rcode)));
+        }
+        if response.answers.is_empty() {
+            return Err(TransferStatus::Refused("empty answer".to_string()));
+        }
+        for record in response.answers {
+            if record.rtype == TYPE_SOA {
+                soa_count += 1;
+                // The trailing SOA repeats the first one and ends the transfer
+                if soa_count == 2 {
+                    return Ok(());
+                }
+            }
+            records.push(record);
+        }
+    }
+
+    Err(TransferStatus::Failed(format!("no closing SOA after {} messages", MAX_TRANSFER_MESSAGES)))
+}
+
+// Request an incremental transfer from serial 0; zone data beyond the current SOA means IXFR is open.
+// A lone SOA is RFC 1995's "no newer version" answer and discloses nothing
+fn attempt_ixfr(client: &DnsClient, server: SocketAddr, domain: &str) -> TransferStatus {
+    let result = (|| -> Result<TransferStatus, Box<dyn Error>> {
+        let mut stream = client.connect_tcp(server)?;
+        send_tcp_message(&mut stream, &build_ixfr_query(client.next_query_id(), domain, 0))?;
+        let response = parse_message(&read_tcp_message(&mut stream)?)?;
+        if response.rcode != RCODE_NOERROR {
+            return Ok(TransferStatus::Refused(rcode_name(response.rcode)));
+        }
+        let mut answers = response.answers;
+        if !answers.iter().any(|r| r.rtype == TYPE_SOA) {
+            return Ok(TransferStatus::Refused("empty answer".to_string()));
+        }
+        // Servers sending one record per message continue in the next one; a lone SOA ends the answer
+        if answers.len() == 1
+            && let Ok(next) = read_tcp_message(&mut stream).and_then(|message| parse_message(&message))
+        {
+            answers.extend(next.answers);
+        }
+        Ok(if answers.len() > 1 {
+            TransferStatus::Allowed
+        } else {
+            TransferStatus::Refused("only the current SOA (zone up to date)".to_string())
+        })
+    })();
+    result.unwrap_or_else(|e| TransferStatus::Failed(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanners::dns::resolver::encode_name;
+    use std::io::Read;
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn soa() -> (u16, Vec<u8>) {
+        let mut rdata = Vec::new();
+        encode_name(&mut rdata, "ns1.example.com");
+        encode_name(&mut rdata, "admin.example.com");
+        rdata.extend_from_slice(&[0; 20]);
+        (TYPE_SOA, rdata)
+    }
+
+    // Answer the query's question (dropping its OPT or IXFR SOA record), each record owned by the queried name
+    fn response(query: &[u8], answers: &[(u16, Vec<u8>)]) -> Vec<u8> {
+        let question_end = 12 + query[12..].iter().position(|&b| b == 0).unwrap() + 5;
+        let mut message = query[..question_end].to_vec();
+        message[2] |= 0x80;
+        message[6..8].copy_from_slice(&(answers.len() as u16).to_be_bytes());
+        message[8..12].copy_from_slice(&[0, 0, 0, 0]);
+        for (rtype, rdata) in answers {
+            message.extend_from_slice(&[0xc0, 12]);
+            message.extend_from_slice(&rtype.to_be_bytes());
+            message.extend_from_slice(&[0, 1, 0, 0, 0x0e, 0x10]);
+            message.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+            message.extend_from_slice(rdata);
+        }
+        message
+    }
+
+    // Serve one AXFR or IXFR, sending the given messages and then closing the connection
+    fn serve(messages: Vec<Vec<(u16, Vec<u8>)>>) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut length = [0; 2];
+            stream.read_exact(&mut length).unwrap();
+            let mut query = vec![0; u16::from_be_bytes(length) as usize];
+            stream.read_exact(&mut query).unwrap();
+            for answers in messages {
+                send_tcp_message(&mut stream, &response(&query, &answers)).unwrap();
+            }
+        });
+        address
+    }
+
+    #[test]
+    fn complete_transfer_is_allowed() {
+        let server = serve(vec![vec![soa(), (TYPE_A, vec![192, 0, 2, 1])], vec![soa()]]);
+        let (status, records) = attempt_axfr(&DnsClient::new(Vec::new()), server, "example.com");
+        assert!(matches!(status, TransferStatus::Allowed), "{}", status);
+        assert_eq!(records.len(), 2);
+    }
+
+    #[test]
+    fn transfer_without_closing_soa_is_partial() {
+        let server = serve(vec![vec![soa(), (TYPE_A, vec![192, 0, 2, 1])]]);
+        let (status, records) = attempt_axfr(&DnsClient::new(Vec::new()), server, "example.com");
+        assert!(matches!(status, TransferStatus::Partial(_)), "{}", status);
+        assert_eq!(records.len(), 2);
+    }
+
+    #[test]
+    fn closed_connection_is_refused() {
+        let server = serve(Vec::new());
+        let (status, records) = attempt_axfr(&DnsClient::new(Vec::new()), server, "example.com");
+        assert!(matches!(status, TransferStatus::Refused(_)), "{}", status);
+        assert!(records.is_empty());
+    }
+
+    #[test]
+    fn ixfr_with_zone_data_is_allowed() {
+        let server = serve(vec![vec![soa(), soa(), (TYPE_A, vec![192, 0, 2, 1]), soa()]]);
+        let status = attempt_ixfr(&DnsClient::new(Vec::new()), server, "example.com");
+        assert!(matches!(status, TransferStatus::Allowed), "{}", status);
+    }
+
+    #[test]
+    fn ixfr_one_record_per_message_is_allowed() {
+        let server = serve(vec![vec![soa()], vec![(TYPE_A, vec![192, 0, 2, 1])], vec![soa()]]);
+        let status = attempt_ixfr(&DnsClient::new(Vec::new()), server, "example.com");
+        assert!(matches!(status, TransferStatus::Allowed), "{}", status);
+    }
+
+    #[test]
+    fn ixfr_single_soa_discloses_nothing() {
+        let server = serve(vec![vec![soa()]]);
+        let status = attempt_ixfr(&DnsClient::new(Vec::new()), server, "example.com");
+        assert!(matches!(status, TransferStatus::Refused(_)), "{}", status);
+    }
+}
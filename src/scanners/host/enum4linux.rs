@@ -1,12 +1,23 @@
 use console::style;
 use indicatif::ProgressBar;
+use regex::Regex;
+use serde_json::Value;
 use std::process::Command;
 use std::error::Error;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::Write;
 
+use crate::models::{Finding, PasswordPolicy, SmbGroup, SmbInfo, SmbShare, SmbUser};
+use super::smb::smb_findings;
+
+// Parsed enumeration and the findings derived from it
+pub struct Enum4linuxResults {
+    pub smb: SmbInfo,
+    pub findings: Vec<Finding>,
+}
+
 // Perform Enum4linux Windows/Samba enumeration with spinner
-pub fn perform_enum4linux(spinner: &ProgressBar, target: &str, base_filename: &str, scan_dir: &str) -> Result<(), Box<dyn Error>> {
+pub fn perform_enum4linux(spinner: &ProgressBar, target: &str, base_filename: &str, scan_dir: &str) -> Result<Enum4linuxResults, Box<dyn Error>> {
     let output_file = format!("{}/{}_enum4linux.txt", scan_dir, base_filename);
     
     // Update spinner message
@@ -22,5 +33,451 @@ pub fn perform_enum4linux(spinner: &ProgressBar, target: &str, base_filename: &s
     let mut file = File::create(output_file)?;
     file.write_all(&output.stdout)?;
     
-    Ok(())
-}
\ No newline at end of file
+    let smb = parse_enum4linux(target, &String::from_utf8_lossy(&output.stdout));
+    let findings = smb_findings(&smb, "Enum4linux");
+    Ok(Enum4linuxResults { smb, findings })
+}
+
+// Perform enum4linux-ng enumeration, reading back its JSON export
+pub fn perform_enum4linux_ng(spinner: &ProgressBar, target: &str, base_filename: &str, scan_dir: &str) -> Result<Enum4linuxResults, Box<dyn Error>> {
+    let output_file = format!("{}/{}_enum4linux_ng.txt", scan_dir, base_filename);
+    // enum4linux-ng appends the .json extension itself
+    let json_base = format!("{}/{}_enum4linux_ng", scan_dir, base_filename);
+    
+    spinner.set_message(format!("Enumerating Windows/Samba services on {} with enum4linux-ng...", style(target).cyan()));
+    
+    let output = Command::new("enum4linux-ng")
+        .args(["-A", "-oJ", &json_base, target])
+        .output()?;
+    
+    let mut file = File::create(output_file)?;
+    file.write_all(&output.stdout)?;
+    
+    spinner.set_message(format!("Processing enum4linux-ng results for {}...", style(target).cyan()));
+    
+    let json = fs::read_to_string(format!("{}.json", json_base))
+        .map_err(|e| format!("enum4linux-ng produced no JSON output ({})", e))?;
+    let smb = parse_enum4linux_ng(target, &serde_json::from_str(&json)?);
+    let findings = smb_findings(&smb, "Enum4linux-ng");
+    Ok(Enum4linuxResults { smb, findings })
+}
+
+// Parse the text report of classic enum4linux -a
+pub fn parse_enum4linux(target: &str, output: &str) -> SmbInfo {
+    let mut smb = SmbInfo {
+        target: target.to_string(),
+        sources: vec!["Enum4linux".to_string()],
+        ..Default::default()
+    };
+    let mut policy = PasswordPolicy::default();
+    let mut policy_seen = false;
+    let mut group_kind: Option<&str> = None;
+    let mut in_share_table = false;
+
+    let bracketed = Regex::new(r"(\w+)=\[([^\]]*)\]").unwrap();
+    let share_access = Regex::new(r"(Mapping|Listing|Writing):\s*([A-Za-z/]+)").unwrap();
+    let user_line = Regex::new(r"RID:\s*(0x[0-9a-fA-F]+).*?Account:\s*(.*?)\tName:\s*(.*?)\tDesc:\s*(.*)$").unwrap();
+    let rid_line = Regex::new(r"^(user|group):\[(.*)\] rid:\[(0x[0-9a-fA-F]+)\]").unwrap();
+    let member_line = Regex::new(r"^Group '(.*)' \(RID: (\d+)\) has member: (.*)$").unwrap();
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+
+        // Share table rows are indented; the table ends at the first blank or unindented line
+        if in_share_table {
+            if trimmed.is_empty() || !line.starts_with(char::is_whitespace) {
+                in_share_table = false;
+            } else if !trimmed.starts_with("---") {
+                let tokens: Vec<&str> = trimmed.split_whitespace().collect();
+                if tokens.len() >= 2 {
+                    let comment = tokens[2..].join(" ");
+                    share_mut(&mut smb, tokens[0]).share_type = Some(tokens[1].to_string());
+                    share_mut(&mut smb, tokens[0]).comment = Some(comment).filter(|c| !c.is_empty());
+                }
+                continue;
+            }
+        }
+        if trimmed.starts_with("Sharename") && trimmed.contains("Type") {
+            in_share_table = true;
+            continue;
+        }
+
+        // //host/share  Mapping: OK, Listing: OK, Writing: N/A
+        if let Some(path) = trimmed.strip_prefix("//") && share_access.is_match(trimmed) {
+            let name = path.split(|c: char| c.is_whitespace()).next().unwrap_or("").rsplit('/').next().unwrap_or("");
+            let share = share_mut(&mut smb, name);
+            for capture in share_access.captures_iter(trimmed) {
+                let value = Some(capture[2].to_string());
+                match &capture[1] {
+                    "Mapping" => share.mapping = value,
+                    "Listing" => share.listing = value,
+                    _ => share.writing = value,
+                }
+            }
+            continue;
+        }
+
+        if trimmed.contains("allows sessions using username '', password ''") {
+            smb.null_session = Some(true);
+        } else if trimmed.contains("doesn't allow session using username '', password ''") {
+            smb.null_session = Some(false);
+        }
+
+        if trimmed.contains("Got OS info") && trimmed.contains("from smbclient") {
+            for capture in bracketed.captures_iter(trimmed) {
+                let value = Some(capture[2].to_string()).filter(|v| !v.is_empty());
+                match &capture[1] {
+                    "Domain" => smb.workgroup = smb.workgroup.take().or(value),
+                    "OS" => smb.os = value,
+                    "Server" => smb.server = value,
+                    _ => {}
+                }
+            }
+            continue;
+        }
+
+        // NetBIOS name table from nmblookup: NAME <00> - B <ACTIVE>
+        let tokens: Vec<&str> = trimmed.split_whitespace().collect();
+        if tokens.get(1) == Some(&"<00>") && !trimmed.contains("<GROUP>") && smb.netbios_name.is_none() {
+            smb.netbios_name = Some(tokens[0].to_string());
+            continue;
+        }
+
+        if let Some(capture) = user_line.captures(trimmed) {
+            let user = user_mut(&mut smb, capture[2].trim());
+            user.rid = Some(capture[1].to_string());
+            user.full_name = Some(capture[3].trim().to_string()).filter(|v| !v.is_empty() && v != "(null)");
+            user.description = Some(capture[4].trim().to_string()).filter(|v| !v.is_empty() && v != "(null)");
+            continue;
+        }
+        if let Some(capture) = rid_line.captures(trimmed) {
+            if &capture[1] == "user" {
+                user_mut(&mut smb, &capture[2]).rid = Some(capture[3].to_string());
+            } else {
+                let group = group_mut(&mut smb, &capture[2]);
+                group.rid = Some(capture[3].to_string());
+                group.kind = group_kind.map(|k| k.to_string());
+            }
+            continue;
+        }
+        if let Some(capture) = member_line.captures(trimmed) {
+            let group = group_mut(&mut smb, &capture[1]);
+            if group.rid.is_none() {
+                group.rid = Some(format!("0x{:x}", capture[2].parse::<u32>().unwrap_or(0)));
+            }
+            group.members.push(capture[3].to_string());
+            continue;
+        }
+
+        // [+] Key: value lines (workgroup, SID and password policy)
+        let content = trimmed.trim_start_matches("[+]").trim();
+        if content.starts_with("Getting builtin group") {
+            group_kind = Some("builtin");
+        } else if content.starts_with("Getting local group") {
+            group_kind = Some("local");
+        } else if content.starts_with("Getting domain group") {
+            group_kind = Some("domain");
+        }
+        let Some((key, value)) = content.split_once(':') else { continue };
+        let value = value.trim();
+        match key.trim() {
+            "Got domain/workgroup name" | "Domain Name" if smb.workgroup.is_none() => {
+                smb.workgroup = Some(value.to_string()).filter(|v| !v.is_empty());
+            },
+            "Domain Sid" if value != "(NULL SID)" => smb.domain_sid = Some(value.to_string()),
+            "Minimum password length" | "Minimum Password Length" => {
+                policy_seen = true;
+                policy.min_length = policy.min_length.or(policy_number(value));
+            },
+            "Password history length" => {
+                policy_seen = true;
+                policy.history_length = policy_number(value);
+            },
+            "Maximum password age" => policy.max_age = Some(value.to_string()),
+            "Minimum password age" => policy.min_age = Some(value.to_string()),
+            "Domain Password Complex" => {
+                policy_seen = true;
+                policy.complexity = Some(value == "1");
+            },
+            "Password Complexity" => {
+                policy_seen = true;
+                policy.complexity = policy.complexity.or(Some(value.eq_ignore_ascii_case("enabled")));
+            },
+            "Account Lockout Threshold" => {
+                policy_seen = true;
+                policy.lockout_threshold = policy_number(value);
+            },
+            "Locked Account Duration" => policy.lockout_duration = Some(value.to_string()),
+            "Reset Account Lockout Counter" => policy.lockout_window = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    if policy_seen {
+        smb.password_policy = Some(policy);
+    }
+    smb
+}
+
+// Parse the JSON export of enum4linux-ng (-oJ)
+pub fn parse_enum4linux_ng(target: &str, json: &Value) -> SmbInfo {
+    let text = |value: &Value| match value {
+        Value::String(s) if !matches!(s.as_str(), "" | "null" | "(null)" | "not supported") => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    };
+
+    let mut smb = SmbInfo {
+        target: target.to_string(),
+        sources: vec!["Enum4linux-ng".to_string()],
+        ..Default::default()
+    };
+
+    let domain_info = &json["smb_domain_info"];
+    let rpc_info = &json["rpc_domain_info"];
+    smb.workgroup = text(&domain_info["NetBIOS domain name"])
+        .or_else(|| text(&rpc_info["Domain"]))
+        .or_else(|| text(&json["workgroup"]));
+    smb.netbios_name = text(&domain_info["NetBIOS computer name"]);
+    smb.domain_sid = text(&rpc_info["Domain SID"]).filter(|sid| sid != "NULL SID");
+
+    let os_info = &json["os_info"];
+    smb.os = text(&os_info["OS"]).map(|os| match text(&os_info["OS build"]) {
+        Some(build) => format!("{} (build {})", os, build),
+        None => os,
+    });
+    smb.server = text(&os_info["Native LAN manager"]).or_else(|| text(&os_info["Server type string"]));
+    smb.null_session = json["sessions"]["null"].as_bool();
+
+    if let Some(users) = json["users"].as_object() {
+        for (rid, user) in users {
+            let Some(name) = text(&user["username"]) else { continue };
+            smb.users.push(SmbUser {
+                name,
+                rid: Some(rid.clone()),
+                full_name: text(&user["name"]),
+                description: text(&user["description"]),
+            });
+        }
+    }
+
+    if let Some(groups) = json["groups"].as_object() {
+        for (rid, group) in groups {
+            let Some(name) = text(&group["groupname"]) else { continue };
+            // Members are a list in recent releases and a comma separated string in older ones
+            let members = match &group["members"] {
+                Value::Array(members) => members.iter().filter_map(text).collect(),
+                Value::String(members) => members.split(',').map(|m| m.trim().to_string()).filter(|m| !m.is_empty()).collect(),
+                _ => Vec::new(),
+            };
+            smb.groups.push(SmbGroup {
+                name,
+                rid: Some(rid.clone()),
+                kind: text(&group["type"]),
+                members,
+            });
+        }
+    }
+
+    if let Some(shares) = json["shares"].as_object() {
+        for (name, share) in shares {
+            let access = &share["access"];
+            smb.shares.push(SmbShare {
+                name: name.clone(),
+                share_type: text(&share["type"]),
+                comment: text(&share["comment"]),
+                mapping: text(&access["mapping"]).map(|v| v.to_uppercase()),
+                listing: text(&access["listing"]).map(|v| v.to_uppercase()),
+                writing: text(&access["writing"]).map(|v| v.to_uppercase()),
+            });
+        }
+    }
+
+    let policy = &json["policy"];
+    if policy.is_object() {
+        let password = &policy["Domain password information"];
+        let lockout = &policy["Domain lockout information"];
+        let number = |value: &Value| value.as_u64().map(|n| n as u32).or_else(|| text(value).and_then(|v| policy_number(&v)));
+        let complexity = password["Password properties"].as_array()
+            .and_then(|properties| properties.iter().find_map(|p| p["DOMAIN_PASSWORD_COMPLEX"].as_bool()));
+        smb.password_policy = Some(PasswordPolicy {
+            min_length: number(&password["Minimum password length"]),
+            history_length: number(&password["Password history length"]),
+            max_age: text(&password["Maximum password age"]),
+            min_age: text(&password["Minimum password age"]),
+            complexity,
+            lockout_threshold: number(&lockout["Lockout threshold"]),
+            lockout_duration: text(&lockout["Lockout duration"]),
+            lockout_window: text(&lockout["Lockout observation window"]),
+        });
+    }
+
+    smb
+}
+
+// Policy numbers are printed as "None" or "Not Set" when disabled
+fn policy_number(value: &str) -> Option<u32> {
+    match value.trim() {
+        "None" | "Not Set" => Some(0),
+        value => value.split_whitespace().next()?.parse().ok(),
+    }
+}
+
+fn share_mut<'a>(smb: &'a mut SmbInfo, name: &str) -> &'a mut SmbShare {
+    let index = match smb.shares.iter().position(|s| s.name == name) {
+        Some(index) => index,
+        None => {
+            smb.shares.push(SmbShare { name: name.to_string(), ..Default::default() });
+            smb.shares.len() - 1
+        }
+    };
+    &mut smb.shares[index]
+}
+
+fn user_mut<'a>(smb: &'a mut SmbInfo, name: &str) -> &'a mut SmbUser {
+    let index = match smb.users.iter().position(|u| u.name == name) {
+        Some(index) => index,
+        None => {
+            smb.users.push(SmbUser { name: name.to_string(), ..Default::default() });
+            smb.users.len() - 1
+        }
+    };
+    &mut smb.users[index]
+}
+
+fn group_mut<'a>(smb: &'a mut SmbInfo, name: &str) -> &'a mut SmbGroup {
+    let index = match smb.groups.iter().position(|g| g.name == name) {
+        Some(index) => index,
+        None => {
+            smb.groups.push(SmbGroup { name: name.to_string(), ..Default::default() });
+            smb.groups.len() - 1
+        }
+    };
+    &mut smb.groups[index]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPORT: &str = "\
+ =========================================== 
+|    Enumerating Workgroup/Domain on 192.0.2.20    |
+ =========================================== 
+[+] Got domain/workgroup name: LAB
+
+ =============================== 
+|    Nbtstat Information for 192.0.2.20    |
+ =============================== 
+\tFILESRV         <00> -         B <ACTIVE>  Workstation Service
+\tLAB             <00> - <GROUP> B <ACTIVE>  Domain/Workgroup Name
+
+[+] Server 192.0.2.20 allows sessions using username '', password ''
+[+] Got OS info for 192.0.2.20 from smbclient: Domain=[LAB] OS=[Windows 6.1] Server=[Samba 4.15.13]
+Domain Name: LAB
+Domain Sid: S-1-5-21-1-2-3
+
+index: 0x1 RID: 0x3e8 acb: 0x00000010 Account: alice\tName: Alice Smith\tDesc: Temp password Summer2024
+index: 0x2 RID: 0x3e9 acb: 0x00000010 Account: bob\tName: (null)\tDesc: (null)
+user:[alice] rid:[0x3e8]
+user:[svc_backup] rid:[0x3ea]
+
+\tSharename       Type      Comment
+\t---------       ----      -------
+\tprint$          Disk      Printer Drivers
+\tpublic          Disk      
+\tIPC$            IPC       IPC Service (Samba 4.15.13)
+Reconnecting with SMB1 for workgroup listing.
+//192.0.2.20/print$\tMapping: DENIED, Listing: N/A
+//192.0.2.20/public\tMapping: OK, Listing: OK, Writing: N/A
+
+[+] Getting domain group memberships:
+Group 'Domain Admins' (RID: 512) has member: LAB\\alice
+[+] Getting builtin groups:
+group:[Administrators] rid:[0x220]
+
+[+] Minimum password length: 5
+[+] Password history length: None
+[+] Maximum password age: 37 days 6 hours 21 minutes 
+[+] Password Complexity Flags: 000000
+[+] Domain Password Complex: 0
+[+] Account Lockout Threshold: None
+";
+
+    #[test]
+    fn classic_report_is_parsed() {
+        let smb = parse_enum4linux("192.0.2.20", REPORT);
+        assert_eq!(smb.workgroup.as_deref(), Some("LAB"));
+        assert_eq!(smb.netbios_name.as_deref(), Some("FILESRV"));
+        assert_eq!(smb.null_session, Some(true));
+        assert_eq!((smb.os.as_deref(), smb.server.as_deref()), (Some("Windows 6.1"), Some("Samba 4.15.13")));
+        assert_eq!(smb.domain_sid.as_deref(), Some("S-1-5-21-1-2-3"));
+
+        let users: Vec<(&str, Option<&str>, Option<&str>)> = smb.users.iter()
+            .map(|u| (u.name.as_str(), u.rid.as_deref(), u.description.as_deref()))
+            .collect();
+        assert_eq!(users, vec![
+            ("alice", Some("0x3e8"), Some("Temp password Summer2024")),
+            ("bob", Some("0x3e9"), None),
+            ("svc_backup", Some("0x3ea"), None),
+        ]);
+        assert_eq!(smb.users[0].full_name.as_deref(), Some("Alice Smith"));
+
+        let shares: Vec<String> = smb.shares.iter()
+            .map(|s| format!("{} {:?} {:?} {:?}", s.name, s.share_type, s.comment, s.mapping))
+            .collect();
+        assert_eq!(shares, vec![
+            r#"print$ Some("Disk") Some("Printer Drivers") Some("DENIED")"#,
+            r#"public Some("Disk") None Some("OK")"#,
+            r#"IPC$ Some("IPC") Some("IPC Service (Samba 4.15.13)") None"#,
+        ]);
+        assert_eq!(smb.shares[1].listing.as_deref(), Some("OK"));
+
+        assert_eq!(smb.groups[0].name, "Domain Admins");
+        assert_eq!((smb.groups[0].rid.as_deref(), smb.groups[0].members.clone()), (Some("0x200"), vec!["LAB\\alice".to_string()]));
+        assert_eq!((smb.groups[1].name.as_str(), smb.groups[1].kind.as_deref()), ("Administrators", Some("builtin")));
+
+        let policy = smb.password_policy.unwrap();
+        assert_eq!((policy.min_length, policy.history_length, policy.complexity, policy.lockout_threshold), (Some(5), Some(0), Some(false), Some(0)));
+        assert_eq!(policy.max_age.as_deref(), Some("37 days 6 hours 21 minutes"));
+    }
+
+    #[test]
+    fn report_without_policy_has_none() {
+        let smb = parse_enum4linux("192.0.2.21", "[E] Server doesn't allow session using username '', password ''.  Aborting remainder of tests.\n");
+        assert_eq!(smb.null_session, Some(false));
+        assert!(smb.password_policy.is_none());
+        assert!(smb.users.is_empty() && smb.shares.is_empty());
+    }
+
+    #[test]
+    fn ng_json_is_parsed() {
+        let json = serde_json::json!({
+            "smb_domain_info": {"NetBIOS domain name": "LAB", "NetBIOS computer name": "DC01"},
+            "rpc_domain_info": {"Domain": "LAB", "Domain SID": "S-1-5-21-4-5-6"},
+            "os_info": {"OS": "Windows Server 2019", "OS build": "17763", "Native LAN manager": "not supported"},
+            "sessions": {"null": true},
+            "users": {"1000": {"username": "alice", "name": "(null)", "description": "Welcome1!"}, "1001": {"name": "no username"}},
+            "groups": {"512": {"groupname": "Domain Admins", "type": "domain", "members": "LAB\\alice, LAB\\Administrator"}},
+            "shares": {"SYSVOL": {"type": "Disk", "comment": "Logon server share", "access": {"mapping": "ok", "listing": "ok"}}},
+            "policy": {
+                "Domain password information": {"Minimum password length": 7, "Password history length": "None",
+                    "Password properties": [{"DOMAIN_PASSWORD_COMPLEX": true}]},
+                "Domain lockout information": {"Lockout threshold": "Not Set"}
+            }
+        });
+        let smb = parse_enum4linux_ng("192.0.2.22", &json);
+        assert_eq!((smb.workgroup.as_deref(), smb.netbios_name.as_deref()), (Some("LAB"), Some("DC01")));
+        assert_eq!(smb.os.as_deref(), Some("Windows Server 2019 (build 17763)"));
+        assert_eq!(smb.server, None);
+        assert_eq!(smb.null_session, Some(true));
+
+        assert_eq!(smb.users.len(), 1);
+        assert_eq!((smb.users[0].full_name.as_deref(), smb.users[0].description.as_deref()), (None, Some("Welcome1!")));
+        assert_eq!(smb.groups[0].members, vec!["LAB\\alice", "LAB\\Administrator"]);
+        assert_eq!((smb.shares[0].mapping.as_deref(), smb.shares[0].writing.as_deref()), (Some("OK"), None));
+
+        let policy = smb.password_policy.unwrap();
+        assert_eq!((policy.min_length, policy.history_length, policy.complexity, policy.lockout_threshold), (Some(7), Some(0), Some(true), Some(0)));
+    }
+}
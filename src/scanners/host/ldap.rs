@@ -0,0 +1,496 @@
+use console::style;
+use indicatif::ProgressBar;
+use std::error::Error;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::net::ToSocketAddrs;
+
+use crate::models::{Finding, LdapComputer, LdapGroup, LdapInfo, LdapRootDse, LdapUser, PasswordPolicy, Severity};
+use super::ldap_client::{Entry, Filter, LdapConnection, Scope, RESULT_SUCCESS};
+use super::smb::password_policy_findings;
+
+// Ports tried when nothing is known about the host: LDAP, then LDAPS
+pub const LDAP_PORTS: [u16; 2] = [389, 636];
+
+// Maximum entries read per object type
+const MAX_ENTRIES: usize = 10000;
+
+// userAccountControl flags
+const UAC_ACCOUNT_DISABLED: i64 = 0x0002;
+const UAC_PASSWORD_NOT_REQUIRED: i64 = 0x0020;
+const UAC_PASSWORD_NEVER_EXPIRES: i64 = 0x1_0000;
+const UAC_PREAUTH_NOT_REQUIRED: i64 = 0x40_0000;
+
+const ROOT_DSE_ATTRIBUTES: [&str; 14] = [
+    "namingContexts", "defaultNamingContext", "dnsHostName", "serverName", "vendorName", "vendorVersion",
+    "supportedLDAPVersion", "supportedSASLMechanisms", "domainFunctionality", "forestFunctionality",
+    "domainControllerFunctionality", "isGlobalCatalogReady", "rootDomainNamingContext", "configurationNamingContext",
+];
+
+// Credentials for an authenticated bind (DN, user@domain or DOMAIN\user)
+pub struct LdapOptions {
+    pub bind_user: Option<String>,
+    pub bind_password: Option<String>,
+}
+
+// Parsed directory data and the findings derived from it
+pub struct LdapResults {
+    pub ldap: LdapInfo,
+    pub findings: Vec<Finding>,
+}
+
+// Read the RootDSE, test anonymous bind and enumerate the directory when it can be read
+pub fn perform_ldap_enum(spinner: &ProgressBar, target: &str, ports: &[u16], options: &LdapOptions, base_filename: &str, scan_dir: &str) -> Result<LdapResults, Box<dyn Error>> {
+    let output_file = format!("{}/{}_ldap.txt", scan_dir, base_filename);
+
+    // First port that accepts a connection; 636 and 3269 speak TLS from the start
+    let mut connection = None;
+    let mut errors = Vec::new();
+    for port in ports {
+        spinner.set_message(format!("Connecting to LDAP on {}:{}...", style(target).cyan(), port));
+        let tls = matches!(port, 636 | 3269);
+        let attempt = (target, *port).to_socket_addrs()?.next()
+            .ok_or_else(|| format!("Could not resolve {}", target).into())
+            .and_then(|address| LdapConnection::connect(address, target, tls));
+        match attempt {
+            Ok(opened) => {
+                connection = Some((opened, *port, tls));
+                break;
+            },
+            Err(e) => errors.push(format!("{}: {}", port, e)),
+        }
+    }
+    let Some((mut connection, port, tls)) = connection else {
+        return Err(format!("No LDAP port reachable ({})", errors.join("; ")).into());
+    };
+
+    let mut ldap = LdapInfo { target: target.to_string(), port, tls, ..Default::default() };
+    let mut log = vec![format!("# LDAP enumeration of {}:{}{}", target, port, if tls { " (TLS)" } else { "" })];
+
+    spinner.set_message(format!("Reading RootDSE of {}...", style(target).cyan()));
+    let (entries, result) = connection.search("", Scope::Base, &Filter::Present("objectClass"), &ROOT_DSE_ATTRIBUTES, 1)?;
+    match entries.first() {
+        Some(root) => ldap.root_dse = parse_root_dse(root),
+        None => log.push(format!("RootDSE not readable: {}", result.describe())),
+    }
+    log.extend(describe_root_dse(&ldap.root_dse));
+
+    // Active Directory accepts anonymous binds but refuses searches after them, so both are tested
+    let base = ldap.root_dse.default_naming_context.clone()
+        .or_else(|| ldap.root_dse.naming_contexts.first().cloned());
+    spinner.set_message(format!("Testing anonymous LDAP bind on {}...", style(target).cyan()));
+    let bind = connection.bind("", "")?;
+    ldap.anonymous_bind = Some(bind.code == RESULT_SUCCESS);
+    log.push(format!("Anonymous bind: {}", bind.describe()));
+    if bind.code == RESULT_SUCCESS && let Some(base) = &base {
+        let (entries, result) = connection.search(base, Scope::Base, &Filter::Present("objectClass"), &["objectClass"], 1)?;
+        ldap.anonymous_search = Some(result.code == RESULT_SUCCESS && !entries.is_empty());
+        log.push(format!("Anonymous search of {}: {}", base, result.describe()));
+    }
+
+    if let (Some(user), Some(password)) = (&options.bind_user, &options.bind_password) {
+        spinner.set_message(format!("Binding to {} as {}...", style(target).cyan(), user));
+        let bind = connection.bind(user, password)?;
+        log.push(format!("Bind as {}: {}", user, bind.describe()));
+        if bind.code == RESULT_SUCCESS {
+            ldap.bound_as = Some(user.clone());
+        }
+    }
+
+    if let Some(base) = &base && (ldap.bound_as.is_some() || ldap.anonymous_search == Some(true)) {
+        spinner.set_message(format!("Enumerating users in {}...", style(base).cyan()));
+        let users = Filter::And(vec![
+            Filter::Or(vec![
+                Filter::Equals("objectClass", "user"),
+                Filter::Equals("objectClass", "person"),
+                Filter::Equals("objectClass", "inetOrgPerson"),
+                Filter::Equals("objectClass", "posixAccount"),
+            ]),
+            Filter::Not(Box::new(Filter::Equals("objectClass", "computer"))),
+        ]);
+        let attributes = ["sAMAccountName", "uid", "cn", "displayName", "description", "userAccountControl",
+            "servicePrincipalName", "adminCount", "memberOf"];
+        ldap.users = search_all(&mut connection, base, &users, &attributes, "users", &mut log).iter().map(parse_user).collect();
+
+        spinner.set_message(format!("Enumerating groups in {}...", style(base).cyan()));
+        let groups = Filter::Or(vec![
+            Filter::Equals("objectClass", "group"),
+            Filter::Equals("objectClass", "groupOfNames"),
+            Filter::Equals("objectClass", "groupOfUniqueNames"),
+            Filter::Equals("objectClass", "posixGroup"),
+        ]);
+        let attributes = ["sAMAccountName", "cn", "description", "member", "uniqueMember", "memberUid"];
+        ldap.groups = search_all(&mut connection, base, &groups, &attributes, "groups", &mut log).iter().map(parse_group).collect();
+
+        spinner.set_message(format!("Enumerating computers in {}...", style(base).cyan()));
+        let attributes = ["sAMAccountName", "cn", "dNSHostName", "operatingSystem", "operatingSystemVersion"];
+        ldap.computers = search_all(&mut connection, base, &Filter::Equals("objectClass", "computer"), &attributes, "computers", &mut log)
+            .iter().map(parse_computer).collect();
+
+        // Domain-wide password and lockout policy lives on the domain object itself
+        let attributes = ["minPwdLength", "pwdHistoryLength", "maxPwdAge", "minPwdAge", "pwdProperties",
+            "lockoutThreshold", "lockoutDuration", "lockOutObservationWindow", "ms-DS-MachineAccountQuota"];
+        if let Ok((entries, _)) = connection.search(base, Scope::Base, &Filter::Present("objectClass"), &attributes, 1)
+            && let Some(domain) = entries.first()
+        {
+            ldap.password_policy = parse_password_policy(domain);
+            ldap.machine_account_quota = domain.number("ms-DS-MachineAccountQuota");
+        }
+        log.extend(describe_directory(&ldap));
+    }
+    connection.unbind();
+
+    // One file for every directory server enumerated in this scan
+    let mut file = OpenOptions::new().create(true).append(true).open(output_file)?;
+    writeln!(file, "{}\n", log.join("\n"))?;
+
+    let findings = ldap_findings(&ldap);
+    Ok(LdapResults { ldap, findings })
+}
+
+// Subtree search whose failure is logged instead of aborting the enumeration
+fn search_all(connection: &mut LdapConnection, base: &str, filter: &Filter, attributes: &[&str], label: &str, log: &mut Vec<String>) -> Vec<Entry> {
+    match connection.search(base, Scope::Subtree, filter, attributes, MAX_ENTRIES) {
+        Ok((entries, result)) => {
+            log.push(format!("Searched {}: {} entries, {}", label, entries.len(), result.describe()));
+            entries
+        },
+        Err(e) => {
+            log.push(format!("Searching {} failed: {}", label, e));
+            Vec::new()
+        },
+    }
+}
+
+fn parse_root_dse(entry: &Entry) -> LdapRootDse {
+    let vendor = match (entry.first("vendorName"), entry.first("vendorVersion")) {
+        (Some(name), Some(version)) => Some(format!("{} {}", name, version)),
+        (name, version) => name.or(version),
+    };
+    LdapRootDse {
+        naming_contexts: entry.all("namingContexts"),
+        default_naming_context: entry.first("defaultNamingContext"),
+        dns_host_name: entry.first("dnsHostName"),
+        server_name: entry.first("serverName"),
+        vendor,
+        ldap_versions: entry.all("supportedLDAPVersion"),
+        sasl_mechanisms: entry.all("supportedSASLMechanisms"),
+        domain_functionality: entry.number("domainFunctionality").map(functional_level),
+        forest_functionality: entry.number("forestFunctionality").map(functional_level),
+        dc_functionality: entry.number("domainControllerFunctionality").map(functional_level),
+        global_catalog: entry.first("isGlobalCatalogReady").map(|v| v.eq_ignore_ascii_case("true")),
+    }
+}
+
+// msDS-Behavior-Version values
+fn functional_level(level: i64) -> String {
+    match level {
+        0 => "Windows 2000".to_string(),
+        1 => "Windows Server 2003 interim".to_string(),
+        2 => "Windows Server 2003".to_string(),
+        3 => "Windows Server 2008".to_string(),
+        4 => "Windows Server 2008 R2".to_string(),
+        5 => "Windows Server 2012".to_string(),
+        6 => "Windows Server 2012 R2".to_string(),
+        7 => "Windows Server 2016".to_string(),
+        10 => "Windows Server 2025".to_string(),
+        other => format!("level {}", other),
+    }
+}
+
+fn parse_user(entry: &Entry) -> LdapUser {
+    let control = entry.number("userAccountControl").unwrap_or(0);
+    LdapUser {
+        dn: entry.dn.clone(),
+        name: entry.first("sAMAccountName").or_else(|| entry.first("uid")).or_else(|| entry.first("cn")).unwrap_or_else(|| entry.dn.clone()),
+        display_name: entry.first("displayName"),
+        description: entry.first("description"),
+        disabled: control & UAC_ACCOUNT_DISABLED != 0,
+        password_not_required: control & UAC_PASSWORD_NOT_REQUIRED != 0,
+        password_never_expires: control & UAC_PASSWORD_NEVER_EXPIRES != 0,
+        preauth_not_required: control & UAC_PREAUTH_NOT_REQUIRED != 0,
+        service_principal_names: entry.all("servicePrincipalName"),
+        admin_count: entry.number("adminCount").is_some_and(|count| count > 0),
+        member_of: entry.all("memberOf"),
+    }
+}
+
+fn parse_group(entry: &Entry) -> LdapGroup {
+    let mut members = entry.all("member");
+    members.extend(entry.all("uniqueMember"));
+    members.extend(entry.all("memberUid"));
+    LdapGroup {
+        dn: entry.dn.clone(),
+        name: entry.first("sAMAccountName").or_else(|| entry.first("cn")).unwrap_or_else(|| entry.dn.clone()),
+        description: entry.first("description"),
+        members,
+    }
+}
+
+fn parse_computer(entry: &Entry) -> LdapComputer {
+    let os = match (entry.first("operatingSystem"), entry.first("operatingSystemVersion")) {
+        (Some(os), Some(version)) => Some(format!("{} {}", os, version)),
+        (os, _) => os,
+    };
+    LdapComputer {
+        dn: entry.dn.clone(),
+        name: entry.first("sAMAccountName").map(|n| n.trim_end_matches('$').to_string())
+            .or_else(|| entry.first("cn"))
+            .unwrap_or_else(|| entry.dn.clone()),
+        dns_host_name: entry.first("dNSHostName"),
+        os,
+    }
+}
+
+// Active Directory stores the policy as negative 100ns intervals; only domain objects carry it
+fn parse_password_policy(domain: &Entry) -> Option<PasswordPolicy> {
+    domain.number("minPwdLength")?;
+    let number = |attribute: &str| domain.number(attribute).and_then(|n| u32::try_from(n).ok());
+    Some(PasswordPolicy {
+        min_length: number("minPwdLength"),
+        history_length: number("pwdHistoryLength"),
+        max_age: domain.number("maxPwdAge").map(ad_interval),
+        min_age: domain.number("minPwdAge").map(ad_interval),
+        complexity: domain.number("pwdProperties").map(|properties| properties & 1 != 0),
+        lockout_threshold: number("lockoutThreshold"),
+        lockout_duration: domain.number("lockoutDuration").map(ad_interval),
+        lockout_window: domain.number("lockOutObservationWindow").map(ad_interval),
+    })
+}
+
+fn ad_interval(value: i64) -> String {
+    if value == 0 || value == i64::MIN {
+        return "None".to_string();
+    }
+    let seconds = value.unsigned_abs() / 10_000_000;
+    match seconds {
+        s if s.is_multiple_of(86400) => format!("{} days", s / 86400),
+        s if s.is_multiple_of(3600) => format!("{} hours", s / 3600),
+        s if s.is_multiple_of(60) => format!("{} minutes", s / 60),
+        s => format!("{} seconds", s),
+    }
+}
+
+fn describe_root_dse(root: &LdapRootDse) -> Vec<String> {
+    let mut lines = Vec::new();
+    let facts = [
+        ("Naming contexts", Some(root.naming_contexts.join("; ")).filter(|n| !n.is_empty())),
+        ("Default naming context", root.default_naming_context.clone()),
+        ("DNS host name", root.dns_host_name.clone()),
+        ("Server name", root.server_name.clone()),
+        ("Vendor", root.vendor.clone()),
+        ("LDAP versions", Some(root.ldap_versions.join(", ")).filter(|v| !v.is_empty())),
+        ("SASL mechanisms", Some(root.sasl_mechanisms.join(", ")).filter(|m| !m.is_empty())),
+        ("Domain functional level", root.domain_functionality.clone()),
+        ("Forest functional level", root.forest_functionality.clone()),
+        ("DC functional level", root.dc_functionality.clone()),
+        ("Global catalog", root.global_catalog.map(|gc| gc.to_string())),
+    ];
+    for (label, value) in facts {
+        if let Some(value) = value {
+            lines.push(format!("{}: {}", label, value));
+        }
+    }
+    lines
+}
+
+fn describe_directory(ldap: &LdapInfo) -> Vec<String> {
+    let mut lines = Vec::new();
+    for user in &ldap.users {
+        let mut flags = Vec::new();
+        if user.disabled { flags.push("disabled"); }
+        if user.password_not_required { flags.push("password not required"); }
+        if user.password_never_expires { flags.push("password never expires"); }
+        if user.preauth_not_required { flags.push("no Kerberos preauth"); }
+        if !user.service_principal_names.is_empty() { flags.push("has SPN"); }
+        if user.admin_count { flags.push("adminCount"); }
+        lines.push(format!("User: {}{}{}",
+            user.name,
+            user.description.as_ref().map(|d| format!(" - {}", d)).unwrap_or_default(),
+            if flags.is_empty() { String::new() } else { format!(" [{}]", flags.join(", ")) }));
+    }
+    for group in &ldap.groups {
+        lines.push(format!("Group: {} ({} members)", group.name, group.members.len()));
+    }
+    for computer in &ldap.computers {
+        lines.push(format!("Computer: {} {} {}",
+            computer.name,
+            computer.dns_host_name.as_deref().unwrap_or("-"),
+            computer.os.as_deref().unwrap_or("-")));
+    }
+    if let Some(quota) = ldap.machine_account_quota {
+        lines.push(format!("Machine account quota: {}", quota));
+    }
+    lines
+}
+
+// Findings for anonymous access, roastable accounts, leaked passwords and weak domain policy
+pub fn ldap_findings(ldap: &LdapInfo) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let location = format!("{}:{}", ldap.target, ldap.port);
+    let finding = |id: &str, severity: Severity, description: String| Finding {
+        source: "LDAP".to_string(),
+        id: Some(id.to_string()),
+        severity,
+        location: location.clone(),
+        method: None,
+        description,
+        references: Vec::new(),
+    };
+
+    if ldap.anonymous_search == Some(true) {
+        findings.push(finding("ldap-anonymous-bind", Severity::Medium,
+            format!("Anonymous bind can read the directory ({} users, {} groups, {} computers enumerated)",
+                ldap.users.len(), ldap.groups.len(), ldap.computers.len())));
+    }
+
+    let enabled: Vec<&LdapUser> = ldap.users.iter().filter(|u| !u.disabled).collect();
+    let names = |users: Vec<&&LdapUser>| users.iter().map(|u| u.name.as_str()).collect::<Vec<_>>().join(", ");
+
+    let roastable: Vec<&&LdapUser> = enabled.iter().filter(|u| u.preauth_not_required).collect();
+    if !roastable.is_empty() {
+        findings.push(finding("ldap-asrep-roastable", Severity::High,
+            format!("Accounts without Kerberos pre-authentication (AS-REP roastable): {}", names(roastable))));
+    }
+    let kerberoastable: Vec<&&LdapUser> = enabled.iter()
+        .filter(|u| !u.service_principal_names.is_empty() && !u.name.eq_ignore_ascii_case("krbtgt"))
+        .collect();
+    if !kerberoastable.is_empty() {
+        findings.push(finding("ldap-kerberoastable", Severity::Medium,
+            format!("User accounts with service principal names (Kerberoastable): {}", names(kerberoastable))));
+    }
+    let no_password: Vec<&&LdapUser> = enabled.iter().filter(|u| u.password_not_required).collect();
+    if !no_password.is_empty() {
+        findings.push(finding("ldap-password-not-required", Severity::Medium,
+            format!("Enabled accounts that may have an empty password (PASSWD_NOTREQD): {}", names(no_password))));
+    }
+
+    // Administrators regularly leave initial passwords in the account description
+    for user in &ldap.users {
+        if let Some(description) = &user.description {
+            let lower = description.to_lowercase();
+            if ["pass", "pwd", "pw:", "pw="].iter().any(|hint| lower.contains(hint)) {
+                findings.push(finding("ldap-password-in-description", Severity::High,
+                    format!("Account description of {} may contain a password: {}", user.name, description)));
+            }
+        }
+    }
+
+    if let Some(quota) = ldap.machine_account_quota && quota > 0 {
+        findings.push(finding("ldap-machine-account-quota", Severity::Low,
+            format!("Any domain user can join up to {} computers to the domain (ms-DS-MachineAccountQuota)", quota)));
+    }
+
+    if let Some(policy) = &ldap.password_policy {
+        findings.extend(password_policy_findings(policy, "ldap", "LDAP", &location));
+    }
+
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(dn: &str, attributes: &[(&str, &str)]) -> Entry {
+        let mut entry = Entry { dn: dn.to_string(), ..Default::default() };
+        for (name, value) in attributes {
+            entry.attributes.entry(name.to_lowercase()).or_default().push(value.as_bytes().to_vec());
+        }
+        entry
+    }
+
+    fn user(name: &str, control: i64, extra: &[(&str, &str)]) -> LdapUser {
+        let control = control.to_string();
+        let mut attributes = vec![("sAMAccountName", name), ("userAccountControl", control.as_str())];
+        attributes.extend_from_slice(extra);
+        parse_user(&entry(&format!("CN={},CN=Users,DC=corp,DC=local", name), &attributes))
+    }
+
+    fn ids(findings: &[Finding]) -> Vec<&str> {
+        findings.iter().filter_map(|f| f.id.as_deref()).collect()
+    }
+
+    #[test]
+    fn account_control_flags_are_decoded() {
+        let roastable = user("svc_sql", 0x41_0200, &[("servicePrincipalName", "MSSQLSvc/db01.corp.local:1433")]);
+        assert!(roastable.preauth_not_required && roastable.password_never_expires);
+        assert!(!roastable.disabled && !roastable.password_not_required);
+        assert_eq!(roastable.service_principal_names, vec!["MSSQLSvc/db01.corp.local:1433"]);
+
+        let guest = user("Guest", 0x222, &[]);
+        assert!(guest.disabled && guest.password_not_required && !guest.preauth_not_required);
+    }
+
+    #[test]
+    fn roastable_and_passwordless_accounts_are_reported_when_enabled() {
+        let ldap = LdapInfo {
+            target: "10.0.0.5".to_string(),
+            port: 389,
+            users: vec![
+                user("svc_sql", 0x41_0200, &[("servicePrincipalName", "MSSQLSvc/db01.corp.local:1433")]),
+                user("krbtgt", 0x202, &[("servicePrincipalName", "kadmin/changepw")]),
+                user("Guest", 0x222, &[]),
+                user("printer", 0x220, &[]),
+                user("old_svc", 0x40_0202, &[("servicePrincipalName", "HTTP/old.corp.local")]),
+            ],
+            ..Default::default()
+        };
+        let findings = ldap_findings(&ldap);
+
+        assert_eq!(ids(&findings), vec!["ldap-asrep-roastable", "ldap-kerberoastable", "ldap-password-not-required"]);
+        assert!(findings.iter().all(|f| f.location == "10.0.0.5:389"));
+        assert!(findings[0].description.ends_with(": svc_sql"));
+        // krbtgt always has an SPN and disabled accounts cannot be roasted
+        assert!(findings[1].description.ends_with(": svc_sql"));
+        assert!(findings[2].description.ends_with(": printer"));
+    }
+
+    #[test]
+    fn passwords_in_descriptions_are_reported() {
+        let ldap = LdapInfo {
+            target: "10.0.0.5".to_string(),
+            port: 636,
+            users: vec![
+                user("alice", 0x200, &[("description", "Temp Password: Summer2024!")]),
+                user("bob", 0x200, &[("description", "Helpdesk, 2nd floor")]),
+                user("carol", 0x202, &[("description", "pw=Winter23")]),
+            ],
+            ..Default::default()
+        };
+        let findings = ldap_findings(&ldap);
+
+        assert_eq!(ids(&findings), vec!["ldap-password-in-description", "ldap-password-in-description"]);
+        assert_eq!(findings[0].description, "Account description of alice may contain a password: Temp Password: Summer2024!");
+        assert_eq!(findings[1].description, "Account description of carol may contain a password: pw=Winter23");
+        assert!(findings.iter().all(|f| f.location == "10.0.0.5:636" && f.severity == Severity::High));
+    }
+
+    #[test]
+    fn domain_policy_is_mapped_from_intervals() {
+        let domain = entry("DC=corp,DC=local", &[
+            ("minPwdLength", "7"),
+            ("pwdHistoryLength", "24"),
+            ("maxPwdAge", "-36288000000000"),
+            ("minPwdAge", "-864000000000"),
+            ("pwdProperties", "0"),
+            ("lockoutThreshold", "0"),
+            ("lockoutDuration", "-18000000000"),
+            ("lockOutObservationWindow", "-18000000000"),
+        ]);
+        let policy = parse_password_policy(&domain).unwrap();
+
+        assert_eq!((policy.min_length, policy.history_length), (Some(7), Some(24)));
+        assert_eq!(policy.max_age.as_deref(), Some("42 days"));
+        assert_eq!(policy.min_age.as_deref(), Some("1 days"));
+        assert_eq!(policy.lockout_duration.as_deref(), Some("30 minutes"));
+        assert_eq!((policy.complexity, policy.lockout_threshold), (Some(false), Some(0)));
+        assert_eq!(ad_interval(i64::MIN), "None");
+
+        let ldap = LdapInfo { target: "10.0.0.5".to_string(), port: 389, password_policy: Some(policy), ..Default::default() };
+        assert_eq!(ids(&ldap_findings(&ldap)), vec!["ldap-weak-password-length", "ldap-no-password-complexity", "ldap-no-lockout"]);
+
+        // Organisational units and other objects carry no policy
+        assert!(parse_password_policy(&entry("OU=Staff,DC=corp,DC=local", &[("pwdProperties", "1")])).is_none());
+    }
+}
@@ -0,0 +1,391 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+
+use crate::utils::ber::{self, BerReader, TAG_OCTET_STRING, TAG_SEQUENCE};
+
+// LDAPv3 (RFC 4511) over TCP or TLS: simple bind and paged searches, enough to read a directory
+
+// Protocol operations
+const BIND_REQUEST: u8 = 0x60;
+const BIND_RESPONSE: u8 = 0x61;
+const UNBIND_REQUEST: u8 = 0x42;
+const SEARCH_REQUEST: u8 = 0x63;
+const SEARCH_RESULT_ENTRY: u8 = 0x64;
+const SEARCH_RESULT_DONE: u8 = 0x65;
+
+const TAG_BOOLEAN: u8 = 0x01;
+const TAG_ENUMERATED: u8 = 0x0A;
+const TAG_SET: u8 = 0x31;
+const TAG_CONTROLS: u8 = 0xA0;
+
+// Simple Paged Results control (RFC 2696), needed to read past server size limits
+const PAGED_RESULTS: &str = "1.2.840.113556.1.4.319";
+const PAGE_SIZE: i64 = 500;
+
+pub const RESULT_SUCCESS: i64 = 0;
+const RESULT_SIZE_LIMIT_EXCEEDED: i64 = 4;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+// Largest message read from a server; paged search results stay far below this
+const MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Scope {
+    Base = 0,
+    Subtree = 2,
+}
+
+// Search filters (RFC 4515), built in code rather than parsed
+pub enum Filter {
+    And(Vec<Filter>),
+    Or(Vec<Filter>),
+    Not(Box<Filter>),
+    Equals(&'static str, &'static str),
+    Present(&'static str),
+}
+
+impl Filter {
+    fn encode(&self) -> Vec<u8> {
+        match self {
+            Filter::And(filters) => ber::encode(0xA0, &filters.iter().flat_map(|f| f.encode()).collect::<Vec<u8>>()),
+            Filter::Or(filters) => ber::encode(0xA1, &filters.iter().flat_map(|f| f.encode()).collect::<Vec<u8>>()),
+            Filter::Not(filter) => ber::encode(0xA2, &filter.encode()),
+            Filter::Equals(attribute, value) => ber::encode(0xA3, &[
+                ber::encode(TAG_OCTET_STRING, attribute.as_bytes()),
+                ber::encode(TAG_OCTET_STRING, value.as_bytes()),
+            ].concat()),
+            Filter::Present(attribute) => ber::encode(0x87, attribute.as_bytes()),
+        }
+    }
+}
+
+// The outcome of an operation: result code and the server's diagnostic message
+#[derive(Debug)]
+pub struct LdapResult {
+    pub code: i64,
+    pub message: String,
+}
+
+impl LdapResult {
+    pub fn describe(&self) -> String {
+        let name = match self.code {
+            0 => "success",
+            1 => "operationsError",
+            4 => "sizeLimitExceeded",
+            7 => "authMethodNotSupported",
+            8 => "strongerAuthRequired",
+            32 => "noSuchObject",
+            48 => "inappropriateAuthentication",
+            49 => "invalidCredentials",
+            50 => "insufficientAccessRights",
+            53 => "unwillingToPerform",
+            _ => "error",
+        };
+        if self.message.is_empty() {
+            format!("{} ({})", name, self.code)
+        } else {
+            format!("{} ({}): {}", name, self.code, self.message.trim_end_matches('\0'))
+        }
+    }
+}
+
+// A directory entry with its attributes (names lowercased)
+#[derive(Debug, Default)]
+pub struct Entry {
+    pub dn: String,
+    pub attributes: BTreeMap<String, Vec<Vec<u8>>>,
+}
+
+impl Entry {
+    pub fn first(&self, attribute: &str) -> Option<String> {
+        self.attributes.get(&attribute.to_lowercase())?.first().map(|v| String::from_utf8_lossy(v).to_string())
+    }
+
+    pub fn all(&self, attribute: &str) -> Vec<String> {
+        self.attributes.get(&attribute.to_lowercase())
+            .map(|values| values.iter().map(|v| String::from_utf8_lossy(v).to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn number(&self, attribute: &str) -> Option<i64> {
+        self.first(attribute)?.trim().parse().ok()
+    }
+}
+
+// A received LDAPMessage
+struct Message {
+    id: i64,
+    operation: u8,
+    content: Vec<u8>,
+    controls: Option<Vec<u8>>,
+}
+
+trait Stream: Read + Write {}
+impl<T: Read + Write> Stream for T {}
+
+pub struct LdapConnection {
+    stream: Box<dyn Stream>,
+    message_id: i64,
+}
+
+impl LdapConnection {
+    // Plain LDAP, or LDAPS without certificate validation (reconnaissance targets rarely have trusted certificates)
+    pub fn connect(address: SocketAddr, host: &str, tls: bool) -> Result<Self, Box<dyn Error>> {
+        let tcp = TcpStream::connect_timeout(&address, TIMEOUT)?;
+        tcp.set_read_timeout(Some(TIMEOUT))?;
+        tcp.set_write_timeout(Some(TIMEOUT))?;
+        let stream: Box<dyn Stream> = if tls {
+            let connector = native_tls::TlsConnector::builder()
+                .danger_accept_invalid_certs(true)
+                .danger_accept_invalid_hostnames(true)
+                .build()?;
+            Box::new(connector.connect(host, tcp).map_err(|e| format!("TLS handshake failed: {}", e))?)
+        } else {
+            Box::new(tcp)
+        };
+        Ok(LdapConnection { stream, message_id: 0 })
+    }
+
+    // Simple bind; an empty name and password is an anonymous bind
+    pub fn bind(&mut self, name: &str, password: &str) -> Result<LdapResult, Box<dyn Error>> {
+        let request = ber::encode(BIND_REQUEST, &[
+            ber::encode_integer(3),
+            ber::encode(TAG_OCTET_STRING, name.as_bytes()),
+            ber::encode(0x80, password.as_bytes()),
+        ].concat());
+        let id = self.send(&request, None)?;
+        loop {
+            let message = self.receive()?;
+            if message.id == id && message.operation == BIND_RESPONSE {
+                return parse_result(message.content);
+            }
+        }
+    }
+
+    // Search with paging; returns the entries read and the final result
+    pub fn search(&mut self, base: &str, scope: Scope, filter: &Filter, attributes: &[&str], limit: usize) -> Result<(Vec<Entry>, LdapResult), Box<dyn Error>> {
+        let request = ber::encode(SEARCH_REQUEST, &[
+            ber::encode(TAG_OCTET_STRING, base.as_bytes()),
+            ber::encode(TAG_ENUMERATED, &[scope as u8]),
+            ber::encode(TAG_ENUMERATED, &[0]),                    // Never dereference aliases
+            ber::encode_integer(0),                               // No size limit
+            ber::encode_integer(0),                               // No time limit
+            ber::encode(TAG_BOOLEAN, &[0]),                       // Types and values
+            filter.encode(),
+            ber::encode(TAG_SEQUENCE, &attributes.iter().flat_map(|a| ber::encode(TAG_OCTET_STRING, a.as_bytes())).collect::<Vec<u8>>()),
+        ].concat());
+
+        let mut entries = Vec::new();
+        let mut cookie: Vec<u8> = Vec::new();
+        loop {
+            let paging = matches!(scope, Scope::Subtree).then(|| paged_control(&cookie));
+            let id = self.send(&request, paging.as_deref())?;
+            let result = loop {
+                let message = self.receive()?;
+                if message.id != id {
+                    continue;
+                }
+                match message.operation {
+                    SEARCH_RESULT_ENTRY => entries.push(parse_entry(message.content)?),
+                    SEARCH_RESULT_DONE => {
+                        cookie = message.controls.map(response_cookie).unwrap_or_default();
+                        break parse_result(message.content)?;
+                    },
+                    _ => {}                                       // Referrals are not followed
+                }
+            };
+            if result.code != RESULT_SUCCESS || cookie.is_empty() || entries.len() >= limit {
+                // A server-side size limit still leaves the entries returned so far usable
+                let result = if result.code == RESULT_SIZE_LIMIT_EXCEEDED && !entries.is_empty() {
+                    LdapResult { code: RESULT_SUCCESS, message: result.message }
+                } else {
+                    result
+                };
+                entries.truncate(limit);
+                return Ok((entries, result));
+            }
+        }
+    }
+
+    pub fn unbind(&mut self) {
+        let _ = self.send(&ber::encode(UNBIND_REQUEST, &[]), None);
+    }
+
+    fn send(&mut self, operation: &[u8], controls: Option<&[u8]>) -> Result<i64, Box<dyn Error>> {
+        self.message_id += 1;
+        let mut message = [ber::encode_integer(self.message_id), operation.to_vec()].concat();
+        if let Some(controls) = controls {
+            message.extend(ber::encode(TAG_CONTROLS, controls));
+        }
+        self.stream.write_all(&ber::encode(TAG_SEQUENCE, &message))?;
+        Ok(self.message_id)
+    }
+
+    fn receive(&mut self) -> Result<Message, Box<dyn Error>> {
+        let mut header = [0u8; 2];
+        self.stream.read_exact(&mut header)?;
+        if header[0] != TAG_SEQUENCE {
+            return Err("Not an LDAP message".into());
+        }
+        let length = read_length(&mut self.stream, header[1])?;
+        let mut message = vec![0u8; length];
+        self.stream.read_exact(&mut message)?;
+
+        let mut reader = BerReader::new(&message);
+        let id = reader.integer()?;
+        let (operation, content) = reader.read()?;
+        let controls = if reader.is_empty() { None } else { Some(reader.expect(TAG_CONTROLS)?.to_vec()) };
+        Ok(Message { id, operation, content: content.to_vec(), controls })
+    }
+}
+
+// Length of a message from its first length octet, checked before anything is allocated for it
+fn read_length(stream: &mut impl Read, first: u8) -> Result<usize, Box<dyn Error>> {
+    if first & 0x80 == 0 {
+        return Ok(first as usize);
+    }
+    let count = (first & 0x7F) as usize;
+    if count == 0 || count > 4 {
+        return Err("Unsupported BER length in LDAP message".into());
+    }
+    let mut bytes = [0u8; 4];
+    stream.read_exact(&mut bytes[..count])?;
+    let length = bytes[..count].iter().fold(0usize, |length, b| (length << 8) | *b as usize);
+    if length > MAX_MESSAGE_SIZE {
+        return Err(format!("LDAP message of {} bytes exceeds the {} byte limit", length, MAX_MESSAGE_SIZE).into());
+    }
+    Ok(length)
+}
+
+fn paged_control(cookie: &[u8]) -> Vec<u8> {
+    let value = ber::encode(TAG_SEQUENCE, &[ber::encode_integer(PAGE_SIZE), ber::encode(TAG_OCTET_STRING, cookie)].concat());
+    ber::encode(TAG_SEQUENCE, &[
+        ber::encode(TAG_OCTET_STRING, PAGED_RESULTS.as_bytes()),
+        ber::encode(TAG_OCTET_STRING, &value),
+    ].concat())
+}
+
+// The cookie of the paged results response control; empty once the last page was sent
+fn response_cookie(controls: Vec<u8>) -> Vec<u8> {
+    let mut reader = BerReader::new(&controls);
+    while let Ok(control) = reader.expect(TAG_SEQUENCE) {
+        let mut control = BerReader::new(control);
+        if control.expect(TAG_OCTET_STRING).ok() != Some(PAGED_RESULTS.as_bytes()) {
+            continue;
+        }
+        while let Ok((tag, value)) = control.read() {
+            if tag == TAG_OCTET_STRING
+                && let Ok(value) = BerReader::new(value).expect(TAG_SEQUENCE)
+            {
+                let mut value = BerReader::new(value);
+                let _size = value.integer();
+                return value.expect(TAG_OCTET_STRING).map(|c| c.to_vec()).unwrap_or_default();
+            }
+        }
+    }
+    Vec::new()
+}
+
+fn parse_result(content: Vec<u8>) -> Result<LdapResult, Box<dyn Error>> {
+    let mut reader = BerReader::new(&content);
+    let code = ber::decode_integer(reader.expect(TAG_ENUMERATED)?);
+    let _matched = reader.expect(TAG_OCTET_STRING)?;
+    let message = String::from_utf8_lossy(reader.expect(TAG_OCTET_STRING)?).to_string();
+    Ok(LdapResult { code, message })
+}
+
+fn parse_entry(content: Vec<u8>) -> Result<Entry, Box<dyn Error>> {
+    let mut reader = BerReader::new(&content);
+    let mut entry = Entry {
+        dn: String::from_utf8_lossy(reader.expect(TAG_OCTET_STRING)?).to_string(),
+        ..Default::default()
+    };
+    let mut attributes = BerReader::new(reader.expect(TAG_SEQUENCE)?);
+    while !attributes.is_empty() {
+        let mut attribute = BerReader::new(attributes.expect(TAG_SEQUENCE)?);
+        let name = String::from_utf8_lossy(attribute.expect(TAG_OCTET_STRING)?).to_lowercase();
+        let mut values = BerReader::new(attribute.expect(TAG_SET)?);
+        let values_out = entry.attributes.entry(name).or_default();
+        while !values.is_empty() {
+            values_out.push(values.expect(TAG_OCTET_STRING)?.to_vec());
+        }
+    }
+    Ok(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn hex(text: &str) -> Vec<u8> {
+        (0..text.len()).step_by(2).map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap()).collect()
+    }
+
+    // Answers captured from an Active Directory domain controller: anonymous bind accepted (message 1),
+    // then one user entry and the end of the search (message 2)
+    const BIND_RESPONSE_OK: &str = "300c02010161070a010004000400";
+    const SEARCH_ENTRY: &str = "3081a502010264819f0424434e3d7376635f73716c2c434e3d55736572732c44433d636f72702c44433d6c6f63616c\
+        3077301b040e73414d4163636f756e744e616d65310904077376635f73716c30370414736572766963655072696e636970616c4e616d65\
+        311f041d4d5353514c5376632f646230312e636f72702e6c6f63616c3a31343333301f0412757365724163636f756e74436f6e74726f6c\
+        3109040734323630333532";
+    const SEARCH_DONE: &str = "300c02010265070a010004000400";
+
+    // Reply to each request read from the client with the next canned answer
+    fn serve(answers: Vec<Vec<u8>>) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            for answer in answers {
+                let mut header = [0u8; 2];
+                stream.read_exact(&mut header).unwrap();
+                let length = read_length(&mut stream, header[1]).unwrap();
+                stream.read_exact(&mut vec![0u8; length]).unwrap();
+                stream.write_all(&answer).unwrap();
+            }
+        });
+        address
+    }
+
+    #[test]
+    fn reads_short_and_long_lengths() {
+        assert_eq!(read_length(&mut &[][..], 0x05).unwrap(), 5);
+        assert_eq!(read_length(&mut &[0x01, 0x00][..], 0x82).unwrap(), 256);
+    }
+
+    #[test]
+    fn rejects_oversized_lengths_before_allocating() {
+        // 127 length octets would overflow usize
+        assert!(read_length(&mut &[0xFF; 127][..], 0xFF).is_err());
+        assert!(read_length(&mut &[][..], 0x80).is_err());
+        // 4 GiB - 1 fits in four octets but is far above the limit
+        assert!(read_length(&mut &[0xFF, 0xFF, 0xFF, 0xFF][..], 0x84).is_err());
+        // Truncated length octets
+        assert!(read_length(&mut &[0x01][..], 0x82).is_err());
+    }
+
+    #[test]
+    fn parses_captured_bind_and_search_responses() {
+        let server = serve(vec![hex(BIND_RESPONSE_OK), [hex(&SEARCH_ENTRY.replace(' ', "")), hex(SEARCH_DONE)].concat()]);
+        let mut connection = LdapConnection::connect(server, "127.0.0.1", false).unwrap();
+
+        let bind = connection.bind("", "").unwrap();
+        assert_eq!((bind.code, bind.describe()), (RESULT_SUCCESS, "success (0)".to_string()));
+
+        let (entries, result) = connection.search("DC=corp,DC=local", Scope::Subtree, &Filter::Equals("objectClass", "user"), &["sAMAccountName"], 100).unwrap();
+        assert_eq!(result.code, RESULT_SUCCESS);
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[0];
+        assert_eq!(entry.dn, "CN=svc_sql,CN=Users,DC=corp,DC=local");
+        // Attribute names are matched case-insensitively
+        assert_eq!(entry.first("SAMACCOUNTNAME").as_deref(), Some("svc_sql"));
+        assert_eq!(entry.all("servicePrincipalName"), vec!["MSSQLSvc/db01.corp.local:1433"]);
+        assert_eq!(entry.number("userAccountControl"), Some(0x410200));
+    }
+}
@@ -1,3 +1,12 @@
 pub mod enum4linux;
+pub mod ldap;
+pub mod ldap_client;
+pub mod smb;
+pub mod smb_client;
+pub mod snmp;
+pub mod snmp_client;
 
-pub use enum4linux::*;
\ No newline at end of file
+pub use enum4linux::*;
+pub use ldap::*;
+pub use smb::*;
+pub use snmp::*;
\ No newline at end of file
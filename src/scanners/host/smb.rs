@@ -0,0 +1,328 @@
+use console::style;
+use indicatif::ProgressBar;
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+use std::net::{SocketAddr, ToSocketAddrs};
+
+use crate::models::{Finding, PasswordPolicy, Severity, SmbInfo, SmbShare};
+use super::smb_client::{self, dialect_name, status_name, ShareEntry, SmbConnection, DIALECTS, STATUS_NO_MORE_FILES, STATUS_SUCCESS};
+
+const SMB_PORT: u16 = 445;
+
+// Share types returned by NetrShareEnum (the high bit marks administrative shares)
+const SHARE_TYPE_DISK: u32 = 0;
+const SHARE_TYPE_IPC: u32 = 3;
+
+// Native enumeration and the findings derived from it
+pub struct SmbResults {
+    pub smb: SmbInfo,
+    pub findings: Vec<Finding>,
+}
+
+// Enumerate SMB with the built-in client: dialects, signing, SMBv1, NTLM target info and anonymous shares
+pub fn perform_smb_enum(spinner: &ProgressBar, target: &str, base_filename: &str, scan_dir: &str) -> Result<SmbResults, Box<dyn Error>> {
+    let output_file = format!("{}/{}_smb.txt", scan_dir, base_filename);
+    let address = (target, SMB_PORT).to_socket_addrs()?.next()
+        .ok_or_else(|| format!("Could not resolve {}", target))?;
+    let mut smb = SmbInfo {
+        target: target.to_string(),
+        sources: vec!["SMB".to_string()],
+        ..Default::default()
+    };
+    let mut log = vec![format!("# SMB enumeration of {}", address)];
+
+    // Offer every dialect once for the negotiated dialect and signing policy
+    spinner.set_message(format!("Negotiating SMB dialects with {}...", style(target).cyan()));
+    let mut connection = SmbConnection::connect(address)?;
+    let negotiation = connection.negotiate(&DIALECTS)?;
+    smb.signing_required = Some(negotiation.signing_required);
+    log.push(format!("Negotiated dialect: {}", dialect_name(negotiation.dialect)));
+    log.push(format!("Signing: {}", match (negotiation.signing_enabled, negotiation.signing_required) {
+        (_, true) => "required",
+        (true, false) => "enabled, not required",
+        (false, false) => "disabled",
+    }));
+
+    // Servers answer with their best dialect, so each one is probed on its own connection
+    for dialect in DIALECTS {
+        if probe_dialect(address, dialect) {
+            smb.dialects.push(dialect_name(dialect));
+        }
+    }
+    log.push(format!("Dialects: {}", smb.dialects.join(", ")));
+
+    spinner.set_message(format!("Checking SMBv1 support on {}...", style(target).cyan()));
+    let smb1 = smb_client::smb1_supported(address).unwrap_or(false);
+    smb.smb1 = Some(smb1);
+    log.push(format!("SMBv1: {}", if smb1 { "supported" } else { "not supported" }));
+
+    // The NTLM challenge names the host and domain even when the session is refused
+    spinner.set_message(format!("Attempting anonymous SMB session on {}...", style(target).cyan()));
+    let session = connection.session_setup_anonymous()?;
+    if let Some(ntlm) = session.ntlm {
+        smb.workgroup = ntlm.netbios_domain;
+        smb.netbios_name = ntlm.netbios_computer;
+        smb.dns_domain = ntlm.dns_domain;
+        smb.dns_name = ntlm.dns_computer;
+        if let Some((major, minor, build)) = ntlm.version && build != 0 {
+            smb.os = Some(format!("Windows {}.{} Build {}", major, minor, build));
+        }
+        let facts = [
+            ("NetBIOS domain", &smb.workgroup),
+            ("NetBIOS name", &smb.netbios_name),
+            ("DNS domain", &smb.dns_domain),
+            ("DNS name", &smb.dns_name),
+            ("DNS tree", &ntlm.dns_tree),
+            ("OS", &smb.os),
+        ];
+        for (label, value) in facts {
+            if let Some(value) = value {
+                log.push(format!("{}: {}", label, value));
+            }
+        }
+    }
+
+    let anonymous = session.status == STATUS_SUCCESS;
+    smb.null_session = Some(anonymous);
+    log.push(format!("Anonymous session: {}{}", status_name(session.status),
+        if session.guest { " (guest)" } else if session.null { " (null)" } else { "" }));
+
+    if anonymous {
+        spinner.set_message(format!("Enumerating SMB shares on {}...", style(target).cyan()));
+        match list_shares(&mut connection) {
+            Ok(shares) => {
+                for entry in shares {
+                    spinner.set_message(format!("Testing anonymous access to \\\\{}\\{}...", target, style(&entry.name).cyan()));
+                    let share = test_share(&mut connection, entry);
+                    log.push(format!("Share {}: type {}, mapping {}, listing {}",
+                        share.name,
+                        share.share_type.as_deref().unwrap_or("-"),
+                        share.mapping.as_deref().unwrap_or("-"),
+                        share.listing.as_deref().unwrap_or("-")));
+                    smb.shares.push(share);
+                }
+            },
+            Err(e) => log.push(format!("Share enumeration failed: {}", e)),
+        }
+    }
+
+    let mut file = File::create(output_file)?;
+    writeln!(file, "{}", log.join("\n"))?;
+
+    let findings = smb_findings(&smb, "SMB");
+    Ok(SmbResults { smb, findings })
+}
+
+fn probe_dialect(address: SocketAddr, dialect: u16) -> bool {
+    SmbConnection::connect(address)
+        .and_then(|mut connection| connection.negotiate(&[dialect]))
+        .is_ok_and(|negotiation| negotiation.dialect == dialect)
+}
+
+// Shares from NetrShareEnum on the srvsvc pipe of IPC$
+fn list_shares(connection: &mut SmbConnection) -> Result<Vec<ShareEntry>, Box<dyn Error>> {
+    let status = connection.tree_connect("IPC$")?;
+    if status != STATUS_SUCCESS {
+        return Err(format!("IPC$ connect failed: {}", status_name(status)).into());
+    }
+    let (status, pipe) = connection.create("srvsvc", 0x0012_019F, 0)?;
+    let pipe = pipe.ok_or_else(|| format!("srvsvc open failed: {}", status_name(status)))?;
+    let shares = smb_client::enumerate_shares(connection, &pipe);
+    connection.close(&pipe)?;
+    connection.tree_disconnect()?;
+    shares
+}
+
+// Map the share and, for disk shares, list its root; nothing is ever written
+fn test_share(connection: &mut SmbConnection, entry: ShareEntry) -> SmbShare {
+    let base_type = entry.share_type & 0x0FFF_FFFF;
+    let mut share = SmbShare {
+        share_type: Some(match base_type {
+            SHARE_TYPE_DISK => "Disk",
+            1 => "Printer",
+            2 => "Device",
+            SHARE_TYPE_IPC => "IPC",
+            _ => "Unknown",
+        }.to_string()),
+        comment: Some(entry.remark).filter(|c| !c.is_empty()),
+        name: entry.name,
+        ..Default::default()
+    };
+    if base_type == SHARE_TYPE_IPC {
+        return share;
+    }
+
+    let status = match connection.tree_connect(&share.name) {
+        Ok(status) => status,
+        Err(_) => return share,
+    };
+    share.mapping = Some(access_result(status));
+    if status != STATUS_SUCCESS {
+        return share;
+    }
+
+    if base_type == SHARE_TYPE_DISK {
+        // FILE_READ_DATA | FILE_READ_ATTRIBUTES | SYNCHRONIZE on the root directory
+        share.listing = match connection.create("", 0x0010_0081, 0x0000_0001) {
+            Ok((_, Some(directory))) => {
+                let listing = connection.query_directory(&directory)
+                    .map(|(status, _)| access_result(if status == STATUS_NO_MORE_FILES { STATUS_SUCCESS } else { status }));
+                let _ = connection.close(&directory);
+                listing.ok()
+            },
+            Ok((status, None)) => Some(access_result(status)),
+            Err(_) => None,
+        };
+    }
+    let _ = connection.tree_disconnect();
+    share
+}
+
+// OK / DENIED in the vocabulary enum4linux uses, other statuses by name
+fn access_result(status: u32) -> String {
+    match status {
+        STATUS_SUCCESS => "OK".to_string(),
+        0xC000_0022 => "DENIED".to_string(),
+        other => status_name(other),
+    }
+}
+
+// Findings for anonymous access and weak policies
+pub fn smb_findings(smb: &SmbInfo, source: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let finding = |id: &str, severity: Severity, description: String| Finding {
+        source: source.to_string(),
+        id: Some(id.to_string()),
+        severity,
+        location: smb.target.clone(),
+        method: None,
+        description,
+        references: Vec::new(),
+    };
+
+    if smb.smb1 == Some(true) {
+        let mut smb1 = finding("smb-v1-enabled", Severity::High,
+            "SMBv1 is enabled: the legacy protocol is exposed to EternalBlue-class exploits".to_string());
+        smb1.references = vec!["https://learn.microsoft.com/en-us/security-updates/securitybulletins/2017/ms17-010".to_string()];
+        findings.push(smb1);
+    }
+    if smb.signing_required == Some(false) {
+        findings.push(finding("smb-signing-not-required", Severity::Medium,
+            "SMB signing is not required: NTLM relay to this host is possible".to_string()));
+    }
+
+    if smb.null_session == Some(true) {
+        findings.push(finding("smb-null-session", Severity::Medium,
+            "SMB null session allowed: users, groups and policies can be enumerated anonymously".to_string()));
+        if !smb.users.is_empty() {
+            findings.push(finding("smb-anonymous-users", Severity::Low,
+                format!("{} user accounts enumerated over the null session", smb.users.len())));
+        }
+    }
+
+    // Administrators regularly leave initial passwords in the account description
+    for user in &smb.users {
+        if let Some(description) = &user.description {
+            let lower = description.to_lowercase();
+            if ["pass", "pwd", "pw:", "pw="].iter().any(|hint| lower.contains(hint)) {
+                // One record per account, so the same account reported by another tool merges into it
+                findings.push(Finding {
+                    location: format!("{}\\{}", smb.target, user.name),
+                    ..finding("smb-password-in-description", Severity::High,
+                        format!("Account description of {} may contain a password: {}", user.name, description))
+                });
+            }
+        }
+    }
+
+    // Located at the share's UNC path, so findings for the same share merge across tools
+    for share in smb.shares.iter().filter(|s| !s.name.eq_ignore_ascii_case("IPC$")) {
+        let path = format!("\\\\{}\\{}", smb.target, share.name);
+        let access = if share.writable() {
+            finding("smb-anonymous-write", Severity::High, format!("Anonymous share write access: {}", path))
+        } else if share.readable() {
+            finding("smb-anonymous-read", Severity::Medium, format!("Anonymous share read access: {}", path))
+        } else {
+            continue;
+        };
+        findings.push(Finding { location: path, ..access });
+    }
+
+    if let Some(policy) = &smb.password_policy {
+        findings.extend(password_policy_findings(policy, "smb", source, &smb.target));
+    }
+
+    findings
+}
+
+// Weak length, complexity and lockout settings, with ids under the given prefix (smb-, ldap-)
+pub fn password_policy_findings(policy: &PasswordPolicy, prefix: &str, source: &str, location: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let finding = |id: &str, severity: Severity, description: String| Finding {
+        source: source.to_string(),
+        id: Some(format!("{}-{}", prefix, id)),
+        severity,
+        location: location.to_string(),
+        method: None,
+        description,
+        references: Vec::new(),
+    };
+
+    if let Some(length) = policy.min_length && length < 8 {
+        findings.push(finding("weak-password-length", Severity::Medium,
+            format!("Password policy allows passwords of {} characters", length)));
+    }
+    if policy.complexity == Some(false) {
+        findings.push(finding("no-password-complexity", Severity::Low,
+            "Password complexity is not enforced".to_string()));
+    }
+    if policy.lockout_threshold == Some(0) {
+        findings.push(finding("no-lockout", Severity::Medium,
+            "No account lockout threshold: passwords can be brute-forced".to_string()));
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ScanResults, SmbUser};
+
+    fn readable_share(name: &str) -> SmbShare {
+        SmbShare { name: name.to_string(), mapping: Some("OK".to_string()), listing: Some("OK".to_string()), ..Default::default() }
+    }
+
+    #[test]
+    fn share_findings_merge_per_share_across_tools() {
+        let enum4linux = SmbInfo { target: "10.0.0.5".to_string(), shares: vec![readable_share("A"), readable_share("B")], ..Default::default() };
+        let native = SmbInfo { target: "10.0.0.5".to_string(), shares: vec![readable_share("B")], ..Default::default() };
+
+        let mut results = ScanResults::default();
+        results.add_findings(smb_findings(&enum4linux, "Enum4linux"));
+        results.add_findings(smb_findings(&native, "SMB"));
+
+        let shares: Vec<String> = results.findings.iter()
+            .map(|f| format!("{} {} [{}]", f.id.as_deref().unwrap(), f.location, f.source))
+            .collect();
+        assert_eq!(shares, vec![
+            "smb-anonymous-read \\\\10.0.0.5\\A [Enum4linux]",
+            "smb-anonymous-read \\\\10.0.0.5\\B [Enum4linux, SMB]",
+        ]);
+    }
+
+    #[test]
+    fn password_hints_are_reported_per_account() {
+        let user = |name: &str, description: &str| SmbUser { name: name.to_string(), description: Some(description.to_string()), ..Default::default() };
+        let smb = SmbInfo {
+            target: "10.0.0.5".to_string(),
+            users: vec![user("svc_backup", "Password: Winter2024!"), user("alice", "Finance team"), user("bob", "initial pwd changeme")],
+            ..Default::default()
+        };
+        let locations: Vec<String> = smb_findings(&smb, "SMB").into_iter()
+            .filter(|f| f.id.as_deref() == Some("smb-password-in-description"))
+            .map(|f| f.location)
+            .collect();
+        assert_eq!(locations, vec!["10.0.0.5\\svc_backup", "10.0.0.5\\bob"]);
+    }
+}
@@ -0,0 +1,844 @@
+use std::error::Error;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+
+use crate::utils::ber;
+
+// Minimal SMB2/3 client (MS-SMB2) speaking just enough of the protocol for unauthenticated
+// reconnaissance: dialect negotiation, an anonymous NTLM session, share enumeration over
+// the srvsvc pipe (MS-SRVS) and directory listing
+
+// SMB2 commands
+const SMB2_NEGOTIATE: u16 = 0x0000;
+const SMB2_SESSION_SETUP: u16 = 0x0001;
+const SMB2_TREE_CONNECT: u16 = 0x0003;
+const SMB2_TREE_DISCONNECT: u16 = 0x0004;
+const SMB2_CREATE: u16 = 0x0005;
+const SMB2_CLOSE: u16 = 0x0006;
+const SMB2_READ: u16 = 0x0008;
+const SMB2_IOCTL: u16 = 0x000B;
+const SMB2_QUERY_DIRECTORY: u16 = 0x000E;
+
+// NT status codes
+pub const STATUS_SUCCESS: u32 = 0x0000_0000;
+const STATUS_PENDING: u32 = 0x0000_0103;
+const STATUS_BUFFER_OVERFLOW: u32 = 0x8000_0005;
+pub const STATUS_NO_MORE_FILES: u32 = 0x8000_0006;
+const STATUS_MORE_PROCESSING_REQUIRED: u32 = 0xC000_0016;
+
+// Dialects in the order they are probed
+pub const DIALECTS: [u16; 5] = [0x0202, 0x0210, 0x0300, 0x0302, 0x0311];
+
+// Security mode bits of the NEGOTIATE response
+const SIGNING_ENABLED: u16 = 0x0001;
+const SIGNING_REQUIRED: u16 = 0x0002;
+
+// Session flags of the SESSION_SETUP response
+const SESSION_FLAG_IS_GUEST: u16 = 0x0001;
+const SESSION_FLAG_IS_NULL: u16 = 0x0002;
+
+const FSCTL_PIPE_TRANSCEIVE: u32 = 0x0011_C017;
+const MAX_OUTPUT: u32 = 65536;
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+// Human readable name for the status codes reconnaissance runs into
+pub fn status_name(status: u32) -> String {
+    match status {
+        STATUS_SUCCESS => "SUCCESS".to_string(),
+        STATUS_NO_MORE_FILES => "NO_MORE_FILES".to_string(),
+        0xC000_0022 => "ACCESS_DENIED".to_string(),
+        0xC000_006D => "LOGON_FAILURE".to_string(),
+        0xC000_00BB => "NOT_SUPPORTED".to_string(),
+        0xC000_00CC => "BAD_NETWORK_NAME".to_string(),
+        0xC000_0034 => "OBJECT_NAME_NOT_FOUND".to_string(),
+        0xC000_0072 => "ACCOUNT_DISABLED".to_string(),
+        0xC000_0203 => "USER_SESSION_DELETED".to_string(),
+        other => format!("0x{:08X}", other),
+    }
+}
+
+// Dialect revision as it is usually written
+pub fn dialect_name(dialect: u16) -> String {
+    match dialect {
+        0x0202 => "SMB 2.0.2".to_string(),
+        0x0210 => "SMB 2.1".to_string(),
+        0x0300 => "SMB 3.0".to_string(),
+        0x0302 => "SMB 3.0.2".to_string(),
+        0x0311 => "SMB 3.1.1".to_string(),
+        other => format!("0x{:04X}", other),
+    }
+}
+
+// A decoded SMB2 response; offsets in the body are relative to the start of the header
+struct Response {
+    status: u32,
+    session_id: u64,
+    tree_id: u32,
+    message: Vec<u8>,
+}
+
+impl Response {
+    fn body(&self) -> &[u8] {
+        &self.message[64..]
+    }
+
+    // Slice of the message at a header-relative offset
+    fn buffer(&self, offset: usize, length: usize) -> Result<&[u8], Box<dyn Error>> {
+        self.message.get(offset..offset + length).ok_or_else(|| "SMB2 buffer outside the message".into())
+    }
+}
+
+pub struct Negotiation {
+    pub dialect: u16,
+    pub signing_enabled: bool,
+    pub signing_required: bool,
+}
+
+// Target information from the NTLM CHALLENGE message
+#[derive(Debug, Default)]
+pub struct NtlmInfo {
+    pub netbios_domain: Option<String>,
+    pub netbios_computer: Option<String>,
+    pub dns_domain: Option<String>,
+    pub dns_computer: Option<String>,
+    pub dns_tree: Option<String>,
+    pub version: Option<(u8, u8, u16)>,   // Major, minor, build
+}
+
+pub struct SessionSetup {
+    pub ntlm: Option<NtlmInfo>,
+    pub status: u32,
+    pub guest: bool,
+    pub null: bool,
+}
+
+// A share returned by NetrShareEnum (level 1)
+pub struct ShareEntry {
+    pub name: String,
+    pub share_type: u32,
+    pub remark: String,
+}
+
+pub type FileId = [u8; 16];
+
+pub struct SmbConnection {
+    stream: TcpStream,
+    message_id: u64,
+    session_id: u64,
+    tree_id: u32,
+    dialect: u16,
+    host: String,
+}
+
+impl SmbConnection {
+    pub fn connect(address: SocketAddr) -> Result<Self, Box<dyn Error>> {
+        let stream = TcpStream::connect_timeout(&address, TIMEOUT)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        Ok(SmbConnection {
+            stream,
+            message_id: 0,
+            session_id: 0,
+            tree_id: 0,
+            dialect: 0,
+            host: address.ip().to_string(),
+        })
+    }
+
+    // NEGOTIATE offering the given dialects (3.1.1 needs negotiate contexts)
+    pub fn negotiate(&mut self, dialects: &[u16]) -> Result<Negotiation, Box<dyn Error>> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&36u16.to_le_bytes());
+        body.extend_from_slice(&(dialects.len() as u16).to_le_bytes());
+        body.extend_from_slice(&SIGNING_ENABLED.to_le_bytes());
+        body.extend_from_slice(&[0, 0]);
+        body.extend_from_slice(&0u32.to_le_bytes());          // Capabilities
+        body.extend_from_slice(&client_guid());
+        let context_field = body.len();
+        body.extend_from_slice(&[0; 8]);                      // Context offset/count, or ClientStartTime
+        for dialect in dialects {
+            body.extend_from_slice(&dialect.to_le_bytes());
+        }
+
+        if dialects.contains(&0x0311) {
+            while !(64 + body.len()).is_multiple_of(8) {
+                body.push(0);
+            }
+            let offset = (64 + body.len()) as u32;
+            // Preauth integrity: SHA-512 with a 32 byte salt
+            let mut preauth = vec![1, 0, 32, 0, 1, 0];
+            preauth.extend_from_slice(&client_guid());
+            preauth.extend_from_slice(&client_guid());
+            push_negotiate_context(&mut body, 0x0001, &preauth);
+            while !body.len().is_multiple_of(8) {
+                body.push(0);
+            }
+            // Encryption: AES-128-GCM, AES-128-CCM
+            push_negotiate_context(&mut body, 0x0002, &[2, 0, 2, 0, 1, 0]);
+            body[context_field..context_field + 4].copy_from_slice(&offset.to_le_bytes());
+            body[context_field + 4..context_field + 6].copy_from_slice(&2u16.to_le_bytes());
+        }
+
+        let response = self.request(SMB2_NEGOTIATE, &body)?;
+        if response.status != STATUS_SUCCESS {
+            return Err(format!("NEGOTIATE failed: {}", status_name(response.status)).into());
+        }
+        let body = response.body();
+        if body.len() < 64 {
+            return Err("Truncated NEGOTIATE response".into());
+        }
+        let security_mode = u16::from_le_bytes([body[2], body[3]]);
+        self.dialect = u16::from_le_bytes([body[4], body[5]]);
+        Ok(Negotiation {
+            dialect: self.dialect,
+            signing_enabled: security_mode & SIGNING_ENABLED != 0,
+            signing_required: security_mode & SIGNING_REQUIRED != 0,
+        })
+    }
+
+    // Anonymous NTLM session: NEGOTIATE -> CHALLENGE (target info) -> empty AUTHENTICATE
+    pub fn session_setup_anonymous(&mut self) -> Result<SessionSetup, Box<dyn Error>> {
+        let response = self.request(SMB2_SESSION_SETUP, &session_setup_body(&spnego_init(&ntlm_negotiate())))?;
+        if response.status != STATUS_MORE_PROCESSING_REQUIRED {
+            return Ok(SessionSetup { ntlm: None, status: response.status, guest: false, null: false });
+        }
+        self.session_id = response.session_id;
+        let ntlm = security_buffer(&response).ok().and_then(parse_ntlm_challenge);
+
+        let response = self.request(SMB2_SESSION_SETUP, &session_setup_body(&spnego_response(&ntlm_authenticate_anonymous())))?;
+        let flags = response.body().get(2..4).map(|f| u16::from_le_bytes([f[0], f[1]])).unwrap_or(0);
+        Ok(SessionSetup {
+            ntlm,
+            status: response.status,
+            guest: flags & SESSION_FLAG_IS_GUEST != 0,
+            null: flags & SESSION_FLAG_IS_NULL != 0,
+        })
+    }
+
+    // Connect to \\host\share, returning the status (the tree is kept for later requests)
+    pub fn tree_connect(&mut self, share: &str) -> Result<u32, Box<dyn Error>> {
+        let path = utf16(&format!("\\\\{}\\{}", self.host, share));
+        let mut body = Vec::new();
+        body.extend_from_slice(&9u16.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&(64u16 + 8).to_le_bytes());
+        body.extend_from_slice(&(path.len() as u16).to_le_bytes());
+        body.extend_from_slice(&path);
+
+        let response = self.request(SMB2_TREE_CONNECT, &body)?;
+        if response.status == STATUS_SUCCESS {
+            self.tree_id = response.tree_id;
+        }
+        Ok(response.status)
+    }
+
+    pub fn tree_disconnect(&mut self) -> Result<(), Box<dyn Error>> {
+        self.request(SMB2_TREE_DISCONNECT, &[4, 0, 0, 0])?;
+        self.tree_id = 0;
+        Ok(())
+    }
+
+    // Open a file, pipe or directory on the connected tree
+    pub fn create(&mut self, name: &str, desired_access: u32, create_options: u32) -> Result<(u32, Option<FileId>), Box<dyn Error>> {
+        let name = utf16(name);
+        let mut body = Vec::new();
+        body.extend_from_slice(&57u16.to_le_bytes());
+        body.push(0);                                         // Security flags
+        body.push(0);                                         // No oplock
+        body.extend_from_slice(&2u32.to_le_bytes());          // Impersonation
+        body.extend_from_slice(&[0; 16]);                     // Create flags, reserved
+        body.extend_from_slice(&desired_access.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());          // File attributes
+        body.extend_from_slice(&7u32.to_le_bytes());          // Share read/write/delete
+        body.extend_from_slice(&1u32.to_le_bytes());          // FILE_OPEN
+        body.extend_from_slice(&create_options.to_le_bytes());
+        body.extend_from_slice(&(64u16 + 56).to_le_bytes());
+        body.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        body.extend_from_slice(&[0; 8]);                      // No create contexts
+        body.extend_from_slice(&name);
+        if name.is_empty() {
+            body.push(0);                                     // The buffer may not be empty
+        }
+
+        let response = self.request(SMB2_CREATE, &body)?;
+        if response.status != STATUS_SUCCESS {
+            return Ok((response.status, None));
+        }
+        let file_id: FileId = response.body().get(64..80)
+            .and_then(|id| id.try_into().ok())
+            .ok_or("Truncated CREATE response")?;
+        Ok((STATUS_SUCCESS, Some(file_id)))
+    }
+
+    pub fn close(&mut self, file_id: &FileId) -> Result<(), Box<dyn Error>> {
+        let mut body = vec![24, 0, 0, 0, 0, 0, 0, 0];
+        body.extend_from_slice(file_id);
+        self.request(SMB2_CLOSE, &body)?;
+        Ok(())
+    }
+
+    // List a directory, returning the status and the names of the first batch of entries
+    pub fn query_directory(&mut self, file_id: &FileId) -> Result<(u32, Vec<String>), Box<dyn Error>> {
+        let pattern = utf16("*");
+        let mut body = Vec::new();
+        body.extend_from_slice(&33u16.to_le_bytes());
+        body.push(0x01);                                      // FileDirectoryInformation
+        body.push(0x01);                                      // SMB2_RESTART_SCANS
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(file_id);
+        body.extend_from_slice(&(64u16 + 32).to_le_bytes());
+        body.extend_from_slice(&(pattern.len() as u16).to_le_bytes());
+        body.extend_from_slice(&MAX_OUTPUT.to_le_bytes());
+        body.extend_from_slice(&pattern);
+
+        let response = self.request(SMB2_QUERY_DIRECTORY, &body)?;
+        if response.status != STATUS_SUCCESS {
+            return Ok((response.status, Vec::new()));
+        }
+        let body = response.body();
+        let offset = u16::from_le_bytes([body[2], body[3]]) as usize;
+        let length = u32::from_le_bytes([body[4], body[5], body[6], body[7]]) as usize;
+        let entries = response.buffer(offset, length)?;
+
+        // FILE_DIRECTORY_INFORMATION entries chained by NextEntryOffset
+        let mut names = Vec::new();
+        let mut position = 0;
+        while let Some(entry) = entries.get(position..) && entry.len() >= 64 {
+            let next = u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]) as usize;
+            let name_length = u32::from_le_bytes([entry[60], entry[61], entry[62], entry[63]]) as usize;
+            if let Some(name) = entry.get(64..64 + name_length) {
+                let name = from_utf16(name);
+                if name != "." && name != ".." {
+                    names.push(name);
+                }
+            }
+            if next == 0 {
+                break;
+            }
+            position += next;
+        }
+        Ok((STATUS_SUCCESS, names))
+    }
+
+    // Write a request to a named pipe and read the complete reply
+    pub fn transceive(&mut self, file_id: &FileId, input: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&57u16.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&FSCTL_PIPE_TRANSCEIVE.to_le_bytes());
+        body.extend_from_slice(file_id);
+        body.extend_from_slice(&(64u32 + 56).to_le_bytes());  // Input offset
+        body.extend_from_slice(&(input.len() as u32).to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());          // Max input response
+        body.extend_from_slice(&0u32.to_le_bytes());          // Output offset
+        body.extend_from_slice(&0u32.to_le_bytes());          // Output count
+        body.extend_from_slice(&MAX_OUTPUT.to_le_bytes());
+        body.extend_from_slice(&1u32.to_le_bytes());          // SMB2_0_IOCTL_IS_FSCTL
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(input);
+
+        let response = self.request(SMB2_IOCTL, &body)?;
+        if response.status != STATUS_SUCCESS && response.status != STATUS_BUFFER_OVERFLOW {
+            return Err(format!("Pipe transceive failed: {}", status_name(response.status)).into());
+        }
+        let body = response.body();
+        if body.len() < 48 {
+            return Err("Truncated IOCTL response".into());
+        }
+        let offset = u32::from_le_bytes([body[32], body[33], body[34], body[35]]) as usize;
+        let count = u32::from_le_bytes([body[36], body[37], body[38], body[39]]) as usize;
+        let mut output = response.buffer(offset, count)?.to_vec();
+
+        // The rest of an oversized reply is fetched with READ
+        let mut status = response.status;
+        while status == STATUS_BUFFER_OVERFLOW {
+            let (read_status, data) = self.read(file_id)?;
+            output.extend_from_slice(&data);
+            status = read_status;
+        }
+        Ok(output)
+    }
+
+    fn read(&mut self, file_id: &FileId) -> Result<(u32, Vec<u8>), Box<dyn Error>> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&49u16.to_le_bytes());
+        body.push(0x50);                                      // Data offset hint
+        body.push(0);
+        body.extend_from_slice(&MAX_OUTPUT.to_le_bytes());
+        body.extend_from_slice(&0u64.to_le_bytes());
+        body.extend_from_slice(file_id);
+        body.extend_from_slice(&[0; 16]);                     // Minimum, channel, remaining, channel info
+        body.push(0);
+
+        let response = self.request(SMB2_READ, &body)?;
+        if response.status != STATUS_SUCCESS && response.status != STATUS_BUFFER_OVERFLOW {
+            return Err(format!("Pipe read failed: {}", status_name(response.status)).into());
+        }
+        let body = response.body();
+        let offset = body[2] as usize;
+        let length = u32::from_le_bytes([body[4], body[5], body[6], body[7]]) as usize;
+        Ok((response.status, response.buffer(offset, length)?.to_vec()))
+    }
+
+    // Send one request and wait for its final response (skipping interim STATUS_PENDING replies)
+    fn request(&mut self, command: u16, body: &[u8]) -> Result<Response, Box<dyn Error>> {
+        let message_id = self.message_id;
+        self.message_id += 1;
+
+        let mut header = Vec::with_capacity(64);
+        header.extend_from_slice(b"\xFESMB");
+        header.extend_from_slice(&64u16.to_le_bytes());
+        let credit_charge: u16 = if self.dialect > 0x0202 { 1 } else { 0 };
+        header.extend_from_slice(&credit_charge.to_le_bytes());
+        header.extend_from_slice(&0u32.to_le_bytes());        // Channel sequence
+        header.extend_from_slice(&command.to_le_bytes());
+        header.extend_from_slice(&31u16.to_le_bytes());       // Credits requested
+        header.extend_from_slice(&0u32.to_le_bytes());        // Flags
+        header.extend_from_slice(&0u32.to_le_bytes());        // Next command
+        header.extend_from_slice(&message_id.to_le_bytes());
+        header.extend_from_slice(&0xFEFFu32.to_le_bytes());   // Process id
+        header.extend_from_slice(&self.tree_id.to_le_bytes());
+        header.extend_from_slice(&self.session_id.to_le_bytes());
+        header.extend_from_slice(&[0; 16]);                   // Unsigned
+
+        send_frame(&mut self.stream, &[header, body.to_vec()].concat())?;
+        loop {
+            let message = read_frame(&mut self.stream)?;
+            if message.len() < 64 || &message[0..4] != b"\xFESMB" {
+                return Err("Not an SMB2 response".into());
+            }
+            let status = u32::from_le_bytes([message[8], message[9], message[10], message[11]]);
+            let response_id = u64::from_le_bytes(message[24..32].try_into()?);
+            if status == STATUS_PENDING || response_id != message_id {
+                continue;
+            }
+            return Ok(Response {
+                status,
+                session_id: u64::from_le_bytes(message[40..48].try_into()?),
+                tree_id: u32::from_le_bytes(message[36..40].try_into()?),
+                message,
+            });
+        }
+    }
+}
+
+// Whether the server still answers an SMB1 NEGOTIATE offering only "NT LM 0.12"
+pub fn smb1_supported(address: SocketAddr) -> Result<bool, Box<dyn Error>> {
+    let mut stream = TcpStream::connect_timeout(&address, TIMEOUT)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+
+    let mut message = Vec::new();
+    message.extend_from_slice(b"\xFFSMB");
+    message.push(0x72);                                       // SMB_COM_NEGOTIATE
+    message.extend_from_slice(&0u32.to_le_bytes());
+    message.push(0x18);                                       // Case insensitive, canonical paths
+    message.extend_from_slice(&0xC801u16.to_le_bytes());      // Unicode, NT status, extended security, long names
+    message.extend_from_slice(&[0; 12]);                      // PID high, signature, reserved
+    message.extend_from_slice(&0xFFFFu16.to_le_bytes());      // TID
+    message.extend_from_slice(&0xFEFFu16.to_le_bytes());      // PID
+    message.extend_from_slice(&[0; 4]);                       // UID, MID
+    message.push(0);                                          // Word count
+    let dialect = b"\x02NT LM 0.12\x00";
+    message.extend_from_slice(&(dialect.len() as u16).to_le_bytes());
+    message.extend_from_slice(dialect);
+    send_frame(&mut stream, &message)?;
+
+    // SMB1-disabled servers drop the connection or refuse every dialect
+    let Ok(response) = read_frame(&mut stream) else {
+        return Ok(false);
+    };
+    if response.len() < 36 || &response[0..4] != b"\xFFSMB" || response[4] != 0x72 {
+        return Ok(false);
+    }
+    let word_count = response[32];
+    Ok(word_count > 0 && u16::from_le_bytes([response[33], response[34]]) != 0xFFFF)
+}
+
+// NetrShareEnum (level 1) over an already opened srvsvc pipe
+pub fn enumerate_shares(connection: &mut SmbConnection, pipe: &FileId) -> Result<Vec<ShareEntry>, Box<dyn Error>> {
+    // Bind to srvsvc 4b324fc8-1670-01d3-1278-5a47bf6ee188 v3.0 with NDR transfer syntax
+    let mut bind = Vec::new();
+    bind.extend_from_slice(&4280u16.to_le_bytes());           // Max transmit fragment
+    bind.extend_from_slice(&4280u16.to_le_bytes());           // Max receive fragment
+    bind.extend_from_slice(&0u32.to_le_bytes());              // Association group
+    bind.extend_from_slice(&[1, 0, 0, 0]);                    // One context item
+    bind.extend_from_slice(&[0, 0, 1, 0]);                    // Context 0, one transfer syntax
+    bind.extend_from_slice(&[0xC8, 0x4F, 0x32, 0x4B, 0x70, 0x16, 0xD3, 0x01, 0x12, 0x78, 0x5A, 0x47, 0xBF, 0x6E, 0xE1, 0x88]);
+    bind.extend_from_slice(&[3, 0, 0, 0]);
+    bind.extend_from_slice(&[0x04, 0x5D, 0x88, 0x8A, 0xEB, 0x1C, 0xC9, 0x11, 0x9F, 0xE8, 0x08, 0x00, 0x2B, 0x10, 0x48, 0x60]);
+    bind.extend_from_slice(&2u32.to_le_bytes());
+
+    let reply = connection.transceive(pipe, &dcerpc_pdu(11, 1, &bind))?;
+    if reply.len() < 24 || reply[2] != 12 {
+        return Err("srvsvc bind was rejected".into());
+    }
+
+    // Server name, level 1 container, preferred maximum length, resume handle
+    let mut stub = Vec::new();
+    stub.extend_from_slice(&0x0002_0000u32.to_le_bytes());
+    ndr_string(&mut stub, &format!("\\\\{}", connection.host));
+    stub.extend_from_slice(&1u32.to_le_bytes());               // Level
+    stub.extend_from_slice(&1u32.to_le_bytes());               // Union discriminant
+    stub.extend_from_slice(&0x0002_0004u32.to_le_bytes());     // Container pointer
+    stub.extend_from_slice(&0u32.to_le_bytes());               // Entries read
+    stub.extend_from_slice(&0u32.to_le_bytes());               // Null buffer
+    stub.extend_from_slice(&u32::MAX.to_le_bytes());
+    stub.extend_from_slice(&0x0002_0008u32.to_le_bytes());
+    stub.extend_from_slice(&0u32.to_le_bytes());
+
+    let mut request = Vec::new();
+    request.extend_from_slice(&(stub.len() as u32).to_le_bytes());  // Allocation hint
+    request.extend_from_slice(&0u16.to_le_bytes());                  // Context id
+    request.extend_from_slice(&15u16.to_le_bytes());                 // NetrShareEnum
+    request.extend_from_slice(&stub);
+
+    let reply = connection.transceive(pipe, &dcerpc_pdu(0, 2, &request))?;
+    parse_share_enum(&dcerpc_stub(&reply)?)
+}
+
+// Concatenate the stub data of every response fragment
+fn dcerpc_stub(data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut stub = Vec::new();
+    let mut position = 0;
+    while position + 24 <= data.len() {
+        let fragment_length = u16::from_le_bytes([data[position + 8], data[position + 9]]) as usize;
+        match data[position + 2] {
+            2 => {}
+            3 => return Err("srvsvc returned a fault".into()),
+            other => return Err(format!("Unexpected DCE/RPC packet type {}", other).into()),
+        }
+        let fragment = data.get(position + 24..position + fragment_length).ok_or("Truncated DCE/RPC fragment")?;
+        stub.extend_from_slice(fragment);
+        position += fragment_length.max(24);
+    }
+    Ok(stub)
+}
+
+fn parse_share_enum(stub: &[u8]) -> Result<Vec<ShareEntry>, Box<dyn Error>> {
+    let mut reader = NdrReader { data: stub, position: 0 };
+    let _level = reader.u32()?;
+    let _discriminant = reader.u32()?;
+    if reader.u32()? == 0 {
+        return Ok(Vec::new());
+    }
+    let count = reader.u32()? as usize;
+    if reader.u32()? == 0 {
+        return Ok(Vec::new());
+    }
+    let _max_count = reader.u32()?;
+
+    // Fixed part of every SHARE_INFO_1, then the deferred strings in order
+    let mut fixed = Vec::new();
+    for _ in 0..count {
+        fixed.push((reader.u32()?, reader.u32()?, reader.u32()?));
+    }
+    let mut shares = Vec::new();
+    for (name_pointer, share_type, remark_pointer) in fixed {
+        let name = if name_pointer != 0 { reader.string()? } else { String::new() };
+        let remark = if remark_pointer != 0 { reader.string()? } else { String::new() };
+        shares.push(ShareEntry { name, share_type, remark });
+    }
+    Ok(shares)
+}
+
+struct NdrReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl NdrReader<'_> {
+    fn u32(&mut self) -> Result<u32, Box<dyn Error>> {
+        let bytes = self.data.get(self.position..self.position + 4).ok_or("Truncated NDR data")?;
+        self.position += 4;
+        Ok(u32::from_le_bytes(bytes.try_into()?))
+    }
+
+    // Conformant varying UTF-16 string, padded to four bytes
+    fn string(&mut self) -> Result<String, Box<dyn Error>> {
+        let _max_count = self.u32()?;
+        let _offset = self.u32()?;
+        let actual = self.u32()? as usize;
+        let bytes = self.data.get(self.position..self.position + actual * 2).ok_or("Truncated NDR string")?;
+        self.position += actual * 2;
+        self.position += (4 - self.position % 4) % 4;
+        Ok(from_utf16(bytes).trim_end_matches('\0').to_string())
+    }
+}
+
+fn ndr_string(buffer: &mut Vec<u8>, value: &str) {
+    let characters = value.encode_utf16().count() as u32 + 1;
+    buffer.extend_from_slice(&characters.to_le_bytes());
+    buffer.extend_from_slice(&0u32.to_le_bytes());
+    buffer.extend_from_slice(&characters.to_le_bytes());
+    buffer.extend_from_slice(&utf16(value));
+    buffer.extend_from_slice(&[0, 0]);
+    while !buffer.len().is_multiple_of(4) {
+        buffer.push(0);
+    }
+}
+
+// Connection-oriented DCE/RPC PDU (single fragment, little-endian, no authentication)
+fn dcerpc_pdu(packet_type: u8, call_id: u32, body: &[u8]) -> Vec<u8> {
+    let mut pdu = vec![5, 0, packet_type, 0x03, 0x10, 0, 0, 0];
+    pdu.extend_from_slice(&((16 + body.len()) as u16).to_le_bytes());
+    pdu.extend_from_slice(&0u16.to_le_bytes());
+    pdu.extend_from_slice(&call_id.to_le_bytes());
+    pdu.extend_from_slice(body);
+    pdu
+}
+
+fn push_negotiate_context(body: &mut Vec<u8>, context_type: u16, data: &[u8]) {
+    body.extend_from_slice(&context_type.to_le_bytes());
+    body.extend_from_slice(&(data.len() as u16).to_le_bytes());
+    body.extend_from_slice(&0u32.to_le_bytes());
+    body.extend_from_slice(data);
+}
+
+fn session_setup_body(token: &[u8]) -> Vec<u8> {
+    let mut body = Vec::new();
+    body.extend_from_slice(&25u16.to_le_bytes());
+    body.push(0);                                             // Flags
+    body.push(SIGNING_ENABLED as u8);
+    body.extend_from_slice(&0u32.to_le_bytes());              // Capabilities
+    body.extend_from_slice(&0u32.to_le_bytes());              // Channel
+    body.extend_from_slice(&(64u16 + 24).to_le_bytes());
+    body.extend_from_slice(&(token.len() as u16).to_le_bytes());
+    body.extend_from_slice(&0u64.to_le_bytes());              // Previous session
+    body.extend_from_slice(token);
+    body
+}
+
+fn security_buffer(response: &Response) -> Result<&[u8], Box<dyn Error>> {
+    let body = response.body();
+    if body.len() < 8 {
+        return Err("Truncated SESSION_SETUP response".into());
+    }
+    let offset = u16::from_le_bytes([body[4], body[5]]) as usize;
+    let length = u16::from_le_bytes([body[6], body[7]]) as usize;
+    response.buffer(offset, length)
+}
+
+// NTLMSSP negotiate flags: unicode, request target, NTLM, always sign, extended session
+// security, target info, version, 128 and 56 bit
+const NTLM_FLAGS: u32 = 0xA288_8205;
+const NTLMSSP_NEGOTIATE_ANONYMOUS: u32 = 0x0000_0800;
+
+fn ntlm_negotiate() -> Vec<u8> {
+    let mut message = b"NTLMSSP\0".to_vec();
+    message.extend_from_slice(&1u32.to_le_bytes());
+    message.extend_from_slice(&NTLM_FLAGS.to_le_bytes());
+    message.extend_from_slice(&[0; 16]);                      // Domain and workstation fields
+    message.extend_from_slice(&[6, 1, 0xB1, 0x1D, 0, 0, 0, 15]);  // Version 6.1.7601, NTLM revision 15
+    message
+}
+
+// AUTHENTICATE with empty credentials: a single zero byte LM response and no NT response
+fn ntlm_authenticate_anonymous() -> Vec<u8> {
+    let payload_offset: u32 = 72;
+    let mut message = b"NTLMSSP\0".to_vec();
+    message.extend_from_slice(&3u32.to_le_bytes());
+    let field = |length: u16, offset: u32| [length.to_le_bytes(), length.to_le_bytes()].concat().into_iter().chain(offset.to_le_bytes()).collect::<Vec<u8>>();
+    message.extend(field(1, payload_offset));                 // LM response
+    message.extend(field(0, payload_offset + 1));             // NT response
+    message.extend(field(0, payload_offset + 1));             // Domain
+    message.extend(field(0, payload_offset + 1));             // User
+    message.extend(field(0, payload_offset + 1));             // Workstation
+    message.extend(field(0, payload_offset + 1));             // Session key
+    message.extend_from_slice(&(NTLM_FLAGS | NTLMSSP_NEGOTIATE_ANONYMOUS).to_le_bytes());
+    message.extend_from_slice(&[6, 1, 0xB1, 0x1D, 0, 0, 0, 15]);
+    message.push(0);
+    message
+}
+
+// Target name and information from an NTLMSSP CHALLENGE, wherever it sits in the SPNEGO token
+fn parse_ntlm_challenge(token: &[u8]) -> Option<NtlmInfo> {
+    let start = token.windows(8).position(|w| w == b"NTLMSSP\0")?;
+    let message = &token[start..];
+    if message.len() < 48 || u32::from_le_bytes(message[8..12].try_into().ok()?) != 2 {
+        return None;
+    }
+
+    let mut info = NtlmInfo::default();
+    let flags = u32::from_le_bytes(message[20..24].try_into().ok()?);
+    if flags & 0x0200_0000 != 0 && message.len() >= 56 {
+        info.version = Some((message[48], message[49], u16::from_le_bytes([message[50], message[51]])));
+    }
+
+    // AV pairs: id, length, UTF-16 value; MsvAvEOL ends the list
+    let length = u16::from_le_bytes([message[40], message[41]]) as usize;
+    let offset = u32::from_le_bytes(message[44..48].try_into().ok()?) as usize;
+    let target_info = message.get(offset..offset + length)?;
+    let mut position = 0;
+    while position + 4 <= target_info.len() {
+        let id = u16::from_le_bytes([target_info[position], target_info[position + 1]]);
+        let value_length = u16::from_le_bytes([target_info[position + 2], target_info[position + 3]]) as usize;
+        let value = target_info.get(position + 4..position + 4 + value_length)?;
+        let text = Some(from_utf16(value)).filter(|v| !v.is_empty());
+        match id {
+            0 => break,
+            1 => info.netbios_computer = text,
+            2 => info.netbios_domain = text,
+            3 => info.dns_computer = text,
+            4 => info.dns_domain = text,
+            5 => info.dns_tree = text,
+            _ => {}
+        }
+        position += 4 + value_length;
+    }
+    Some(info)
+}
+
+// SPNEGO NegTokenInit offering NTLMSSP with the NEGOTIATE message as the mech token
+fn spnego_init(ntlm: &[u8]) -> Vec<u8> {
+    let ntlm_oid = ber::encode_oid(&[1, 3, 6, 1, 4, 1, 311, 2, 2, 10]);
+    let mech_types = ber::encode(0xA0, &ber::encode(ber::TAG_SEQUENCE, &ntlm_oid));
+    let mech_token = ber::encode(0xA2, &ber::encode(ber::TAG_OCTET_STRING, ntlm));
+    let init = ber::encode(0xA0, &ber::encode(ber::TAG_SEQUENCE, &[mech_types, mech_token].concat()));
+    let spnego_oid = ber::encode_oid(&[1, 3, 6, 1, 5, 5, 2]);
+    ber::encode(0x60, &[spnego_oid, init].concat())
+}
+
+// SPNEGO NegTokenResp carrying the AUTHENTICATE message
+fn spnego_response(ntlm: &[u8]) -> Vec<u8> {
+    ber::encode(0xA1, &ber::encode(ber::TAG_SEQUENCE, &ber::encode(0xA2, &ber::encode(ber::TAG_OCTET_STRING, ntlm))))
+}
+
+// Direct TCP transport: a zero byte and 24-bit length before every message
+fn send_frame(stream: &mut TcpStream, message: &[u8]) -> Result<(), Box<dyn Error>> {
+    let length = message.len() as u32;
+    stream.write_all(&[0, (length >> 16) as u8, (length >> 8) as u8, length as u8])?;
+    stream.write_all(message)?;
+    Ok(())
+}
+
+fn read_frame(stream: &mut TcpStream) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut header = [0u8; 4];
+    stream.read_exact(&mut header)?;
+    let length = u32::from_be_bytes([0, header[1], header[2], header[3]]) as usize;
+    let mut message = vec![0u8; length];
+    stream.read_exact(&mut message)?;
+    Ok(message)
+}
+
+fn client_guid() -> [u8; 16] {
+    let seed = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    (seed ^ (std::process::id() as u128) << 64).to_le_bytes()
+}
+
+fn utf16(value: &str) -> Vec<u8> {
+    value.encode_utf16().flat_map(|c| c.to_le_bytes()).collect()
+}
+
+fn from_utf16(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+    String::from_utf16_lossy(&units)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // NTLMSSP CHALLENGE with a version and the given AV pairs as target information
+    fn challenge(pairs: &[(u16, &str)]) -> Vec<u8> {
+        let mut target_info = Vec::new();
+        for (id, value) in pairs {
+            let value = utf16(value);
+            target_info.extend_from_slice(&id.to_le_bytes());
+            target_info.extend_from_slice(&(value.len() as u16).to_le_bytes());
+            target_info.extend_from_slice(&value);
+        }
+        target_info.extend_from_slice(&[0, 0, 0, 0]);
+
+        let mut message = b"NTLMSSP\0".to_vec();
+        message.extend_from_slice(&2u32.to_le_bytes());
+        message.extend_from_slice(&[0; 8]);                                     // Target name
+        message.extend_from_slice(&0x0280_0000u32.to_le_bytes());               // Version + target info
+        message.extend_from_slice(&[0x11; 8]);                                  // Server challenge
+        message.extend_from_slice(&[0; 8]);
+        message.extend_from_slice(&(target_info.len() as u16).to_le_bytes());
+        message.extend_from_slice(&(target_info.len() as u16).to_le_bytes());
+        message.extend_from_slice(&56u32.to_le_bytes());
+        message.extend_from_slice(&[10, 0, 0x61, 0x4a, 0, 0, 0, 15]);           // 10.0.19041
+        message.extend_from_slice(&target_info);
+        message
+    }
+
+    #[test]
+    fn ntlm_challenge_is_found_inside_spnego() {
+        let token = spnego_response(&challenge(&[(2, "LAB"), (1, "DC01"), (4, "lab.example.com"), (3, "dc01.lab.example.com"), (5, "example.com")]));
+        let info = parse_ntlm_challenge(&token).unwrap();
+        assert_eq!(info.netbios_domain.as_deref(), Some("LAB"));
+        assert_eq!(info.netbios_computer.as_deref(), Some("DC01"));
+        assert_eq!(info.dns_domain.as_deref(), Some("lab.example.com"));
+        assert_eq!(info.dns_computer.as_deref(), Some("dc01.lab.example.com"));
+        assert_eq!(info.dns_tree.as_deref(), Some("example.com"));
+        assert_eq!(info.version, Some((10, 0, 19041)));
+    }
+
+    #[test]
+    fn ntlm_challenge_rejects_other_messages_and_truncation() {
+        assert!(parse_ntlm_challenge(&ntlm_negotiate()).is_none());
+        assert!(parse_ntlm_challenge(b"no ntlm here").is_none());
+        let message = challenge(&[(1, "DC01")]);
+        assert!(parse_ntlm_challenge(&message[..message.len() - 6]).is_none());
+    }
+
+    // A response PDU (type 2) carrying `stub` after the 24 byte header
+    fn response(stub: &[u8]) -> Vec<u8> {
+        let mut pdu = dcerpc_pdu(2, 2, &[&[0u8; 8][..], stub].concat());
+        pdu[8..10].copy_from_slice(&((24 + stub.len()) as u16).to_le_bytes());
+        pdu
+    }
+
+    #[test]
+    fn dcerpc_stub_joins_fragments() {
+        let data = [response(b"abcd"), response(b"efgh")].concat();
+        assert_eq!(dcerpc_stub(&data).unwrap(), b"abcdefgh");
+
+        let mut fault = response(b"");
+        fault[2] = 3;
+        assert!(dcerpc_stub(&fault).unwrap_err().to_string().contains("fault"));
+
+        let mut truncated = response(b"abcd");
+        truncated.truncate(26);
+        assert!(dcerpc_stub(&truncated).is_err());
+    }
+
+    // NetrShareEnum level 1 reply stub for (name, type, remark) entries
+    fn share_enum(shares: &[(&str, u32, &str)]) -> Vec<u8> {
+        let mut stub = Vec::new();
+        for value in [1u32, 1, 0x0002_0000, shares.len() as u32, 0x0002_0004, shares.len() as u32] {
+            stub.extend_from_slice(&value.to_le_bytes());
+        }
+        for (index, (_, share_type, _)) in shares.iter().enumerate() {
+            stub.extend_from_slice(&(0x0002_0008 + index as u32 * 8).to_le_bytes());
+            stub.extend_from_slice(&share_type.to_le_bytes());
+            stub.extend_from_slice(&(0x0002_000c + index as u32 * 8).to_le_bytes());
+        }
+        for (name, _, remark) in shares {
+            ndr_string(&mut stub, name);
+            ndr_string(&mut stub, remark);
+        }
+        stub
+    }
+
+    #[test]
+    fn share_enum_reads_names_types_and_remarks() {
+        let shares = parse_share_enum(&share_enum(&[("ADMIN$", 0x8000_0000, "Remote Admin"), ("Public", 0, ""), ("IPC$", 0x8000_0003, "Remote IPC")])).unwrap();
+        let shares: Vec<(&str, u32, &str)> = shares.iter().map(|s| (s.name.as_str(), s.share_type, s.remark.as_str())).collect();
+        assert_eq!(shares, vec![("ADMIN$", 0x8000_0000, "Remote Admin"), ("Public", 0, ""), ("IPC$", 0x8000_0003, "Remote IPC")]);
+    }
+
+    #[test]
+    fn share_enum_handles_empty_and_truncated_replies() {
+        let mut empty = Vec::new();
+        for value in [1u32, 1, 0x0002_0000, 0, 0] {
+            empty.extend_from_slice(&value.to_le_bytes());
+        }
+        assert!(parse_share_enum(&empty).unwrap().is_empty());
+
+        let stub = share_enum(&[("Public", 0, "Shared files")]);
+        assert!(parse_share_enum(&stub[..stub.len() - 8]).is_err());
+    }
+}
@@ -0,0 +1,529 @@
+use console::style;
+use indicatif::ProgressBar;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::net::ToSocketAddrs;
+use std::time::Duration;
+
+use crate::models::{Finding, Severity, SnmpCommunity, SnmpInfo, SnmpInterface, SnmpProcess, SnmpRoute, SnmpSoftware, SnmpUser};
+use super::snmp_client::{hex, SnmpClient, SnmpValue, SnmpVersion, UserProbe, VarBind};
+
+const SNMP_PORT: u16 = 161;
+
+// Communities shipped as vendor defaults or commonly configured; any of them being accepted is a finding
+const DEFAULT_COMMUNITIES: [&str; 24] = [
+    "public", "private", "community", "manager", "admin", "cisco", "snmp", "snmpd", "monitor", "secret",
+    "read", "write", "default", "test", "system", "router", "switch", "network", "security", "ILMI",
+    "all private", "mngt", "agent", "private@es0",
+];
+
+// User names tried against SNMPv3 agents
+const DEFAULT_USERS: [&str; 14] = [
+    "admin", "administrator", "snmp", "snmpuser", "snmpv3", "user", "monitor", "manager", "root", "cisco",
+    "initial", "public", "private", "templateSHA",
+];
+
+// Standard MIB subtrees that are walked
+const SYSTEM: [u32; 7] = [1, 3, 6, 1, 2, 1, 1];
+const IF_TABLE: [u32; 9] = [1, 3, 6, 1, 2, 1, 2, 2, 1];
+const IP_ADDR_TABLE: [u32; 9] = [1, 3, 6, 1, 2, 1, 4, 20, 1];
+const IP_ROUTE_TABLE: [u32; 9] = [1, 3, 6, 1, 2, 1, 4, 21, 1];
+const HR_SW_RUN_TABLE: [u32; 10] = [1, 3, 6, 1, 2, 1, 25, 4, 2, 1];
+const HR_SW_INSTALLED_TABLE: [u32; 10] = [1, 3, 6, 1, 2, 1, 25, 6, 3, 1];
+
+// Word lists for community and user guessing (bundled defaults when not given)
+pub struct SnmpOptions {
+    pub communities: Option<String>,
+    pub users: Option<String>,
+    pub timeout: Duration,
+}
+
+// Parsed agent data and the findings derived from it
+pub struct SnmpResults {
+    pub snmp: SnmpInfo,
+    pub findings: Vec<Finding>,
+}
+
+// Test communities and SNMPv3 users, then walk the standard MIBs with the best community found
+pub fn perform_snmp_enum(spinner: &ProgressBar, target: &str, options: &SnmpOptions, base_filename: &str, scan_dir: &str) -> Result<SnmpResults, Box<dyn Error>> {
+    let output_file = format!("{}/{}_snmp.txt", scan_dir, base_filename);
+    let address = (target, SNMP_PORT).to_socket_addrs()?.next()
+        .ok_or_else(|| format!("Could not resolve {}", target))?;
+    let communities = load_list(options.communities.as_deref(), &DEFAULT_COMMUNITIES)?;
+    let users = load_list(options.users.as_deref(), &DEFAULT_USERS)?;
+    let mut client = SnmpClient::new(address, options.timeout, 1)?;
+    let mut snmp = SnmpInfo { target: target.to_string(), ..Default::default() };
+    let mut log = vec![format!("# SNMP enumeration of {}", address)];
+
+    // v2c first: agents accepting it can be walked with GETBULK
+    for version in [SnmpVersion::V2c, SnmpVersion::V1] {
+        spinner.set_message(format!("Testing {} SNMP {} communities on {}...", communities.len(), version, style(target).cyan()));
+        for name in client.probe_communities(version, &communities)? {
+            log.push(format!("Community accepted ({}): {}", version, name));
+            match snmp.communities.iter_mut().find(|c| c.name == name) {
+                Some(community) => community.versions.push(version.to_string()),
+                None => snmp.communities.push(SnmpCommunity { name, versions: vec![version.to_string()] }),
+            }
+        }
+    }
+
+    // SNMPv3 agents disclose their engine to anyone and tell known from unknown user names
+    spinner.set_message(format!("Discovering SNMPv3 engine on {}...", style(target).cyan()));
+    if let Some(engine) = client.discover_engine()? {
+        snmp.engine_id = Some(hex(&engine.id).replace(':', ""));
+        snmp.engine_boots = Some(engine.boots);
+        snmp.engine_time = Some(engine.time);
+        log.push(format!("SNMPv3 engine: {} (boots {}, up {}s)", snmp.engine_id.as_deref().unwrap_or(""), engine.boots, engine.time));
+
+        spinner.set_message(format!("Enumerating {} SNMPv3 users on {}...", users.len(), style(target).cyan()));
+        for user in &users {
+            match client.probe_user(&engine, user)? {
+                UserProbe::NoAuth => snmp.users.push(SnmpUser { name: user.clone(), no_auth: true }),
+                UserProbe::RequiresAuth => snmp.users.push(SnmpUser { name: user.clone(), no_auth: false }),
+                UserProbe::Unknown | UserProbe::NoReply => {}
+            }
+        }
+        for user in &snmp.users {
+            log.push(format!("SNMPv3 user: {}{}", user.name, if user.no_auth { " (noAuthNoPriv)" } else { "" }));
+        }
+    }
+
+    // Silence is not an empty result: the agent is absent, filtered or uses other credentials
+    if snmp.communities.is_empty() && snmp.engine_id.is_none() {
+        return Err(format!("No SNMP agent answered on {} ({} communities tried, no SNMPv3 engine)", address, communities.len()).into());
+    }
+
+    let credential = snmp.communities.iter()
+        .find(|c| c.versions.iter().any(|v| v == "v2c"))
+        .map(|c| (SnmpVersion::V2c, c.name.clone()))
+        .or_else(|| snmp.communities.first().map(|c| (SnmpVersion::V1, c.name.clone())));
+    if let Some((version, community)) = credential {
+        let mut walk = |label: &str, root: &[u32]| -> Vec<VarBind> {
+            spinner.set_message(format!("Walking {} on {} ({} '{}')...", label, style(target).cyan(), version, community));
+            match client.walk(version, &community, root) {
+                Ok(rows) => {
+                    log.push(format!("Walked {}: {} values", label, rows.len()));
+                    rows
+                },
+                Err(e) => {
+                    log.push(format!("Walking {} failed: {}", label, e));
+                    Vec::new()
+                },
+            }
+        };
+
+        let system = walk("system", &SYSTEM);
+        let interfaces = walk("interfaces", &IF_TABLE);
+        let addresses = walk("IP addresses", &IP_ADDR_TABLE);
+        let routes = walk("routes", &IP_ROUTE_TABLE);
+        let processes = walk("processes", &HR_SW_RUN_TABLE);
+        let software = walk("installed software", &HR_SW_INSTALLED_TABLE);
+
+        parse_system(&mut snmp, &system);
+        snmp.interfaces = parse_interfaces(&interfaces, &addresses);
+        snmp.routes = parse_routes(&routes);
+        snmp.processes = parse_processes(&processes);
+        snmp.software = parse_software(&software);
+        log.extend(describe(&snmp));
+    }
+
+    // One file for every agent enumerated in this scan
+    let mut file = OpenOptions::new().create(true).append(true).open(output_file)?;
+    writeln!(file, "{}\n", log.join("\n"))?;
+
+    let findings = snmp_findings(&snmp);
+    Ok(SnmpResults { snmp, findings })
+}
+
+// Rows of a conceptual table keyed by instance index, holding column -> value
+fn table(rows: &[VarBind], root: &[u32]) -> BTreeMap<Vec<u32>, HashMap<u32, SnmpValue>> {
+    let mut table: BTreeMap<Vec<u32>, HashMap<u32, SnmpValue>> = BTreeMap::new();
+    for (oid, value) in rows {
+        if let Some((column, index)) = oid.strip_prefix(root).and_then(|rest| rest.split_first()) {
+            table.entry(index.to_vec()).or_default().insert(*column, value.clone());
+        }
+    }
+    table
+}
+
+fn text(row: &HashMap<u32, SnmpValue>, column: u32) -> Option<String> {
+    row.get(&column).map(|v| v.as_text()).filter(|v| !v.is_empty())
+}
+
+fn parse_system(snmp: &mut SnmpInfo, rows: &[VarBind]) {
+    let scalars = table(rows, &SYSTEM);
+    let value = |column: u32| scalars.get(&vec![0]).and_then(|row| text(row, column));
+    snmp.system.description = value(1);
+    snmp.system.object_id = value(2);
+    snmp.system.uptime = value(3);
+    snmp.system.contact = value(4);
+    snmp.system.name = value(5);
+    snmp.system.location = value(6);
+}
+
+fn parse_interfaces(rows: &[VarBind], address_rows: &[VarBind]) -> Vec<SnmpInterface> {
+    let mut interfaces: Vec<SnmpInterface> = table(rows, &IF_TABLE).into_values()
+        .filter_map(|row| Some(SnmpInterface {
+            index: row.get(&1)?.as_u64()? as u32,
+            name: text(&row, 2),
+            kind: row.get(&3).and_then(|v| v.as_u64()).map(interface_type),
+            mtu: row.get(&4).and_then(|v| v.as_u64()),
+            speed: row.get(&5).and_then(|v| v.as_u64()),
+            mac: row.get(&6).and_then(|v| v.as_bytes()).filter(|b| !b.is_empty()).map(hex),
+            admin_status: row.get(&7).and_then(|v| v.as_u64()).map(interface_status),
+            oper_status: row.get(&8).and_then(|v| v.as_u64()).map(interface_status),
+            addresses: Vec::new(),
+        }))
+        .collect();
+
+    // ipAddrTable: address, interface index and netmask per assigned address
+    for row in table(address_rows, &IP_ADDR_TABLE).into_values() {
+        let (Some(address), Some(index)) = (text(&row, 1), row.get(&2).and_then(|v| v.as_u64())) else {
+            continue;
+        };
+        let prefix = text(&row, 3)
+            .and_then(|mask| mask.parse::<std::net::Ipv4Addr>().ok())
+            .map(|mask| u32::from(mask).count_ones());
+        let entry = match prefix {
+            Some(prefix) => format!("{}/{}", address, prefix),
+            None => address,
+        };
+        if let Some(interface) = interfaces.iter_mut().find(|i| i.index as u64 == index) {
+            interface.addresses.push(entry);
+        }
+    }
+    interfaces
+}
+
+fn parse_routes(rows: &[VarBind]) -> Vec<SnmpRoute> {
+    table(rows, &IP_ROUTE_TABLE).into_values()
+        .filter_map(|row| Some(SnmpRoute {
+            destination: text(&row, 1)?,
+            interface: row.get(&2).and_then(|v| v.as_u64()).map(|i| i as u32),
+            next_hop: text(&row, 7),
+            route_type: row.get(&8).and_then(|v| v.as_u64()).map(|kind| match kind {
+                2 => "invalid",
+                3 => "direct",
+                4 => "indirect",
+                _ => "other",
+            }.to_string()),
+            mask: text(&row, 11),
+        }))
+        .collect()
+}
+
+fn parse_processes(rows: &[VarBind]) -> Vec<SnmpProcess> {
+    table(rows, &HR_SW_RUN_TABLE).into_iter()
+        .filter_map(|(index, row)| Some(SnmpProcess {
+            pid: *index.first()?,
+            name: text(&row, 2),
+            path: text(&row, 4),
+            parameters: text(&row, 5),
+        }))
+        .collect()
+}
+
+fn parse_software(rows: &[VarBind]) -> Vec<SnmpSoftware> {
+    table(rows, &HR_SW_INSTALLED_TABLE).into_values()
+        .filter_map(|row| Some(SnmpSoftware {
+            name: text(&row, 2)?,
+            installed: row.get(&5).and_then(|v| v.as_bytes()).and_then(date_and_time),
+        }))
+        .collect()
+}
+
+// SNMPv2-TC DateAndTime: year (2 bytes), month, day, hour, minutes, seconds, ...
+fn date_and_time(bytes: &[u8]) -> Option<String> {
+    if bytes.len() < 7 {
+        return None;
+    }
+    let year = u16::from_be_bytes([bytes[0], bytes[1]]);
+    Some(format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}", year, bytes[2], bytes[3], bytes[4], bytes[5], bytes[6]))
+}
+
+// IANAifType names for the common interface types
+fn interface_type(kind: u64) -> String {
+    match kind {
+        6 => "ethernetCsmacd".to_string(),
+        23 => "ppp".to_string(),
+        24 => "softwareLoopback".to_string(),
+        53 => "propVirtual".to_string(),
+        71 => "ieee80211".to_string(),
+        131 => "tunnel".to_string(),
+        135 => "l2vlan".to_string(),
+        161 => "ieee8023adLag".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn interface_status(status: u64) -> String {
+    match status {
+        1 => "up",
+        2 => "down",
+        3 => "testing",
+        5 => "dormant",
+        6 => "notPresent",
+        7 => "lowerLayerDown",
+        _ => "unknown",
+    }.to_string()
+}
+
+// Human readable dump of the walked data for the raw output file
+fn describe(snmp: &SnmpInfo) -> Vec<String> {
+    let mut lines = Vec::new();
+    let system = [
+        ("sysDescr", &snmp.system.description),
+        ("sysObjectID", &snmp.system.object_id),
+        ("sysUpTime", &snmp.system.uptime),
+        ("sysContact", &snmp.system.contact),
+        ("sysName", &snmp.system.name),
+        ("sysLocation", &snmp.system.location),
+    ];
+    for (label, value) in system {
+        if let Some(value) = value {
+            lines.push(format!("{}: {}", label, value));
+        }
+    }
+    for interface in &snmp.interfaces {
+        lines.push(format!("Interface {}: {} {} mac {} {} {}",
+            interface.index,
+            interface.name.as_deref().unwrap_or("-"),
+            interface.kind.as_deref().unwrap_or("-"),
+            interface.mac.as_deref().unwrap_or("-"),
+            interface.oper_status.as_deref().unwrap_or("-"),
+            interface.addresses.join(", ")));
+    }
+    for route in &snmp.routes {
+        lines.push(format!("Route: {}/{} via {} (if {})",
+            route.destination,
+            route.mask.as_deref().unwrap_or("-"),
+            route.next_hop.as_deref().unwrap_or("-"),
+            route.interface.map(|i| i.to_string()).unwrap_or_else(|| "-".to_string())));
+    }
+    for process in &snmp.processes {
+        lines.push(format!("Process {}: {} {}",
+            process.pid,
+            process.path.as_deref().or(process.name.as_deref()).unwrap_or("-"),
+            process.parameters.as_deref().unwrap_or("")));
+    }
+    for software in &snmp.software {
+        lines.push(format!("Software: {} ({})", software.name, software.installed.as_deref().unwrap_or("-")));
+    }
+    lines
+}
+
+// Word list from a file, or the bundled defaults
+fn load_list(path: Option<&str>, defaults: &[&str]) -> Result<Vec<String>, Box<dyn Error>> {
+    let entries: Vec<String> = match path {
+        Some(path) => fs::read_to_string(path)?.lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .collect(),
+        None => defaults.iter().map(|d| d.to_string()).collect(),
+    };
+    let mut unique = Vec::new();
+    for entry in entries {
+        if !unique.contains(&entry) {
+            unique.push(entry);
+        }
+    }
+    Ok(unique)
+}
+
+// Findings for guessable communities, unauthenticated SNMPv3 and secrets on process command lines
+pub fn snmp_findings(snmp: &SnmpInfo) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let finding = |id: &str, severity: Severity, description: String| Finding {
+        source: "SNMP".to_string(),
+        id: Some(id.to_string()),
+        severity,
+        location: format!("{}:{}/udp", snmp.target, SNMP_PORT),
+        method: None,
+        description,
+        references: Vec::new(),
+    };
+
+    for community in &snmp.communities {
+        if DEFAULT_COMMUNITIES.contains(&community.name.as_str()) {
+            findings.push(finding("snmp-default-community", Severity::High,
+                format!("SNMP accepts the default community '{}' ({})", community.name, community.versions.join(", "))));
+        } else {
+            findings.push(finding("snmp-guessable-community", Severity::Medium,
+                format!("SNMP community '{}' guessed from the word list ({})", community.name, community.versions.join(", "))));
+        }
+    }
+
+    let no_auth: Vec<&str> = snmp.users.iter().filter(|u| u.no_auth).map(|u| u.name.as_str()).collect();
+    if !no_auth.is_empty() {
+        findings.push(finding("snmp-v3-noauth-user", Severity::Medium,
+            format!("SNMPv3 users answer without authentication (noAuthNoPriv): {}", no_auth.join(", "))));
+    }
+    if !snmp.users.is_empty() {
+        let names: Vec<&str> = snmp.users.iter().map(|u| u.name.as_str()).collect();
+        findings.push(finding("snmp-v3-user-enumeration", Severity::Low,
+            format!("SNMPv3 user names can be enumerated: {}", names.join(", "))));
+    }
+
+    // Credentials passed on the command line are readable through hrSWRunParameters
+    for process in &snmp.processes {
+        if let Some(parameters) = &process.parameters {
+            let lower = parameters.to_lowercase();
+            if ["pass", "pwd", "secret", "token", "apikey", "api_key"].iter().any(|hint| lower.contains(hint)) {
+                findings.push(finding("snmp-process-credentials", Severity::High,
+                    format!("Process {} ({}) exposes a possible secret in its arguments: {}",
+                        process.pid, process.name.as_deref().unwrap_or("-"), parameters)));
+            }
+        }
+    }
+
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(root: &[u32], column: u32, index: &[u32], value: SnmpValue) -> VarBind {
+        ([root, &[column], index].concat(), value)
+    }
+
+    fn string(value: &str) -> SnmpValue {
+        SnmpValue::String(value.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn table_groups_columns_by_instance_index() {
+        let rows = vec![
+            row(&IP_ADDR_TABLE, 1, &[10, 0, 0, 1], SnmpValue::IpAddress([10, 0, 0, 1])),
+            row(&IP_ADDR_TABLE, 1, &[127, 0, 0, 1], SnmpValue::IpAddress([127, 0, 0, 1])),
+            row(&IP_ADDR_TABLE, 2, &[10, 0, 0, 1], SnmpValue::Integer(2)),
+            row(&IP_ADDR_TABLE, 2, &[127, 0, 0, 1], SnmpValue::Integer(1)),
+            // Outside the table, or the bare entry OID without column
+            ([1, 3, 6, 1, 2, 1, 4, 21, 1, 1, 0, 0, 0, 0].to_vec(), SnmpValue::Integer(9)),
+            (IP_ADDR_TABLE.to_vec(), SnmpValue::Null),
+        ];
+        let table = table(&rows, &IP_ADDR_TABLE);
+        assert_eq!(table.keys().cloned().collect::<Vec<_>>(), vec![vec![10, 0, 0, 1], vec![127, 0, 0, 1]]);
+        let loopback = &table[&vec![127, 0, 0, 1]];
+        assert_eq!((loopback.len(), &loopback[&1], &loopback[&2]), (2, &SnmpValue::IpAddress([127, 0, 0, 1]), &SnmpValue::Integer(1)));
+    }
+
+    #[test]
+    fn walked_tables_are_parsed() {
+        let mut snmp = SnmpInfo::default();
+        parse_system(&mut snmp, &[
+            row(&SYSTEM, 1, &[0], string("Linux gw 5.10.0")),
+            row(&SYSTEM, 3, &[0], SnmpValue::TimeTicks(360000)),
+            row(&SYSTEM, 4, &[0], string("")),
+            row(&SYSTEM, 5, &[0], string("gw\0")),
+        ]);
+        assert_eq!((snmp.system.description.as_deref(), snmp.system.uptime.as_deref()), (Some("Linux gw 5.10.0"), Some("0d 01:00:00")));
+        assert_eq!((snmp.system.contact.as_deref(), snmp.system.name.as_deref()), (None, Some("gw")));
+
+        let interfaces = parse_interfaces(&[
+            row(&IF_TABLE, 1, &[1], SnmpValue::Integer(1)),
+            row(&IF_TABLE, 1, &[2], SnmpValue::Integer(2)),
+            row(&IF_TABLE, 2, &[1], string("lo")),
+            row(&IF_TABLE, 2, &[2], string("eth0")),
+            row(&IF_TABLE, 3, &[1], SnmpValue::Integer(24)),
+            row(&IF_TABLE, 3, &[2], SnmpValue::Integer(6)),
+            row(&IF_TABLE, 5, &[2], SnmpValue::Counter(1_000_000_000)),
+            row(&IF_TABLE, 6, &[1], SnmpValue::String(Vec::new())),
+            row(&IF_TABLE, 6, &[2], SnmpValue::String(vec![0x00, 0x11, 0x22, 0x33, 0x44, 0x55])),
+            row(&IF_TABLE, 8, &[2], SnmpValue::Integer(7)),
+        ], &[
+            row(&IP_ADDR_TABLE, 1, &[192, 0, 2, 1], SnmpValue::IpAddress([192, 0, 2, 1])),
+            row(&IP_ADDR_TABLE, 2, &[192, 0, 2, 1], SnmpValue::Integer(2)),
+            row(&IP_ADDR_TABLE, 3, &[192, 0, 2, 1], SnmpValue::IpAddress([255, 255, 255, 0])),
+            row(&IP_ADDR_TABLE, 1, &[198, 51, 100, 1], SnmpValue::IpAddress([198, 51, 100, 1])),
+            row(&IP_ADDR_TABLE, 2, &[198, 51, 100, 1], SnmpValue::Integer(2)),
+            // Assigned to an interface that was not walked
+            row(&IP_ADDR_TABLE, 1, &[203, 0, 113, 1], SnmpValue::IpAddress([203, 0, 113, 1])),
+            row(&IP_ADDR_TABLE, 2, &[203, 0, 113, 1], SnmpValue::Integer(9)),
+        ]);
+        assert_eq!(interfaces.len(), 2);
+        let (lo, eth0) = (&interfaces[0], &interfaces[1]);
+        assert_eq!((lo.name.as_deref(), lo.kind.as_deref(), lo.mac.as_deref()), (Some("lo"), Some("softwareLoopback"), None));
+        assert!(lo.addresses.is_empty());
+        assert_eq!((eth0.kind.as_deref(), eth0.speed, eth0.mac.as_deref()), (Some("ethernetCsmacd"), Some(1_000_000_000), Some("00:11:22:33:44:55")));
+        assert_eq!((eth0.admin_status.as_deref(), eth0.oper_status.as_deref()), (None, Some("lowerLayerDown")));
+        assert_eq!(eth0.addresses, vec!["192.0.2.1/24", "198.51.100.1"]);
+
+        let routes = parse_routes(&[
+            row(&IP_ROUTE_TABLE, 1, &[0, 0, 0, 0], SnmpValue::IpAddress([0, 0, 0, 0])),
+            row(&IP_ROUTE_TABLE, 2, &[0, 0, 0, 0], SnmpValue::Integer(2)),
+            row(&IP_ROUTE_TABLE, 7, &[0, 0, 0, 0], SnmpValue::IpAddress([192, 0, 2, 254])),
+            row(&IP_ROUTE_TABLE, 8, &[0, 0, 0, 0], SnmpValue::Integer(4)),
+            row(&IP_ROUTE_TABLE, 11, &[0, 0, 0, 0], SnmpValue::IpAddress([0, 0, 0, 0])),
+            // No destination column
+            row(&IP_ROUTE_TABLE, 8, &[10, 0, 0, 0], SnmpValue::Integer(3)),
+        ]);
+        assert_eq!(routes.len(), 1);
+        assert_eq!((routes[0].destination.as_str(), routes[0].next_hop.as_deref()), ("0.0.0.0", Some("192.0.2.254")));
+        assert_eq!((routes[0].interface, routes[0].route_type.as_deref(), routes[0].mask.as_deref()), (Some(2), Some("indirect"), Some("0.0.0.0")));
+
+        let processes = parse_processes(&[
+            row(&HR_SW_RUN_TABLE, 2, &[1], string("systemd")),
+            row(&HR_SW_RUN_TABLE, 2, &[812], string("mysqld")),
+            row(&HR_SW_RUN_TABLE, 4, &[812], string("/usr/sbin/mysqld")),
+            row(&HR_SW_RUN_TABLE, 5, &[812], string("--user=mysql")),
+        ]);
+        assert_eq!(processes.iter().map(|p| p.pid).collect::<Vec<_>>(), vec![1, 812]);
+        assert_eq!((processes[1].path.as_deref(), processes[1].parameters.as_deref()), (Some("/usr/sbin/mysqld"), Some("--user=mysql")));
+
+        let software = parse_software(&[
+            row(&HR_SW_INSTALLED_TABLE, 2, &[1], string("openssh-server_1:9.2p1")),
+            row(&HR_SW_INSTALLED_TABLE, 5, &[1], SnmpValue::String(vec![0x07, 0xEA, 3, 14, 9, 5, 30, 0])),
+            row(&HR_SW_INSTALLED_TABLE, 5, &[2], SnmpValue::String(vec![0x07, 0xEA])),
+        ]);
+        assert_eq!(software.len(), 1);
+        assert_eq!((software[0].name.as_str(), software[0].installed.as_deref()), ("openssh-server_1:9.2p1", Some("2026-03-14 09:05:30")));
+    }
+
+    #[test]
+    fn findings_for_communities_users_and_process_arguments() {
+        let snmp = SnmpInfo {
+            target: "192.0.2.1".to_string(),
+            communities: vec![
+                SnmpCommunity { name: "public".to_string(), versions: vec!["v2c".to_string(), "v1".to_string()] },
+                SnmpCommunity { name: "n0c-r34d".to_string(), versions: vec!["v2c".to_string()] },
+            ],
+            users: vec![
+                SnmpUser { name: "admin".to_string(), no_auth: false },
+                SnmpUser { name: "initial".to_string(), no_auth: true },
+            ],
+            processes: vec![
+                SnmpProcess { pid: 812, name: Some("mysqld".to_string()), parameters: Some("--user=mysql".to_string()), ..Default::default() },
+                SnmpProcess { pid: 1337, name: Some("backup.sh".to_string()), parameters: Some("--db-PASSWORD hunter2".to_string()), ..Default::default() },
+            ],
+            ..Default::default()
+        };
+        let findings = snmp_findings(&snmp);
+        let summary: Vec<(&str, Severity)> = findings.iter().map(|f| (f.id.as_deref().unwrap(), f.severity)).collect();
+        assert_eq!(summary, vec![
+            ("snmp-default-community", Severity::High),
+            ("snmp-guessable-community", Severity::Medium),
+            ("snmp-v3-noauth-user", Severity::Medium),
+            ("snmp-v3-user-enumeration", Severity::Low),
+            ("snmp-process-credentials", Severity::High),
+        ]);
+        assert!(findings.iter().all(|f| f.location == "192.0.2.1:161/udp"));
+        assert_eq!(findings[0].description, "SNMP accepts the default community 'public' (v2c, v1)");
+        assert_eq!(findings[2].description, "SNMPv3 users answer without authentication (noAuthNoPriv): initial");
+        assert_eq!(findings[3].description, "SNMPv3 user names can be enumerated: admin, initial");
+        assert!(findings[4].description.starts_with("Process 1337 (backup.sh)"));
+
+        assert!(snmp_findings(&SnmpInfo { engine_id: Some("80001f88".to_string()), ..Default::default() }).is_empty());
+    }
+
+    #[test]
+    fn word_lists_skip_comments_and_duplicates() {
+        assert_eq!(load_list(None, &DEFAULT_USERS).unwrap().len(), DEFAULT_USERS.len());
+        let path = std::env::temp_dir().join(format!("rust_recon_snmp_communities_{}.txt", std::process::id()));
+        fs::write(&path, "# site communities\npublic\n\n  n0c-r34d  \npublic\n").unwrap();
+        assert_eq!(load_list(path.to_str(), &DEFAULT_COMMUNITIES).unwrap(), vec!["public", "n0c-r34d"]);
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,584 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::net::{SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+use crate::utils::ber::{self, BerReader, TAG_INTEGER, TAG_NULL, TAG_OCTET_STRING, TAG_OID, TAG_SEQUENCE};
+
+// SNMP (RFC 3416) over UDP: community based v1/v2c requests and the unauthenticated part of
+// SNMPv3 (RFC 3414) needed for engine discovery and user enumeration
+
+// PDU types
+const GET_REQUEST: u8 = 0xA0;
+const GET_NEXT_REQUEST: u8 = 0xA1;
+const RESPONSE: u8 = 0xA2;
+const GET_BULK_REQUEST: u8 = 0xA5;
+const REPORT: u8 = 0xA8;
+
+// Application types
+const IP_ADDRESS: u8 = 0x40;
+const COUNTER32: u8 = 0x41;
+const GAUGE32: u8 = 0x42;
+const TIME_TICKS: u8 = 0x43;
+const OPAQUE: u8 = 0x44;
+const COUNTER64: u8 = 0x46;
+const NO_SUCH_OBJECT: u8 = 0x80;
+const NO_SUCH_INSTANCE: u8 = 0x81;
+const END_OF_MIB_VIEW: u8 = 0x82;
+
+// usmStatsUnknownUserNames, reported for user names the agent does not know
+const USM_UNKNOWN_USER_NAMES: [u32; 10] = [1, 3, 6, 1, 6, 3, 15, 1, 1, 3];
+
+pub const SYS_DESCR: [u32; 9] = [1, 3, 6, 1, 2, 1, 1, 1, 0];
+
+const MAX_WALK: usize = 5000;
+const BULK_REPETITIONS: i64 = 25;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SnmpVersion {
+    V1,
+    V2c,
+}
+
+impl SnmpVersion {
+    fn wire(self) -> i64 {
+        match self {
+            SnmpVersion::V1 => 0,
+            SnmpVersion::V2c => 1,
+        }
+    }
+}
+
+impl std::fmt::Display for SnmpVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnmpVersion::V1 => write!(f, "v1"),
+            SnmpVersion::V2c => write!(f, "v2c"),
+        }
+    }
+}
+
+// A typed variable binding value
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnmpValue {
+    Integer(i64),
+    String(Vec<u8>),
+    Oid(Vec<u32>),
+    IpAddress([u8; 4]),
+    Counter(u64),                            // Counter32, Gauge32 and Counter64
+    TimeTicks(u64),
+    Opaque(Vec<u8>),
+    Null,
+    EndOfView,                               // noSuchObject, noSuchInstance, endOfMibView
+}
+
+impl SnmpValue {
+    // Readable rendering: printable strings as text, binary strings as colon separated hex
+    pub fn as_text(&self) -> String {
+        match self {
+            SnmpValue::Integer(n) => n.to_string(),
+            // Some agents NUL-terminate their strings
+            SnmpValue::String(bytes) => match std::str::from_utf8(bytes).map(|text| text.trim_end_matches('\0')) {
+                Ok(text) if text.chars().all(|c| !c.is_control() || c.is_whitespace()) => text.to_string(),
+                _ => hex(bytes),
+            },
+            SnmpValue::Oid(oid) => ber::oid_to_string(oid),
+            SnmpValue::IpAddress(ip) => std::net::Ipv4Addr::from(*ip).to_string(),
+            SnmpValue::Counter(n) => n.to_string(),
+            SnmpValue::TimeTicks(ticks) => {
+                let seconds = ticks / 100;
+                format!("{}d {:02}:{:02}:{:02}", seconds / 86400, seconds % 86400 / 3600, seconds % 3600 / 60, seconds % 60)
+            },
+            SnmpValue::Opaque(bytes) => hex(bytes),
+            SnmpValue::Null | SnmpValue::EndOfView => String::new(),
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            SnmpValue::Integer(n) => u64::try_from(*n).ok(),
+            SnmpValue::Counter(n) | SnmpValue::TimeTicks(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            SnmpValue::String(bytes) | SnmpValue::Opaque(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(":")
+}
+
+pub type VarBind = (Vec<u32>, SnmpValue);
+
+// The authoritative engine learned from a discovery report
+pub struct EngineInfo {
+    pub id: Vec<u8>,
+    pub boots: i64,
+    pub time: i64,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UserProbe {
+    Unknown,
+    RequiresAuth,                            // Exists, but noAuthNoPriv is refused
+    NoAuth,                                  // Exists and answers without authentication
+    NoReply,
+}
+
+// A decoded message: request id, PDU type and bindings
+struct Pdu {
+    kind: u8,
+    request_id: i64,
+    varbinds: Vec<VarBind>,
+}
+
+pub struct SnmpClient {
+    socket: UdpSocket,
+    target: SocketAddr,
+    timeout: Duration,
+    retries: usize,
+    next_id: i64,
+}
+
+impl SnmpClient {
+    pub fn new(target: SocketAddr, timeout: Duration, retries: usize) -> Result<Self, Box<dyn Error>> {
+        let bind = if target.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+        let socket = UdpSocket::bind(bind)?;
+        socket.connect(target)?;
+        Ok(SnmpClient { socket, target, timeout, retries, next_id: 0x1000 })
+    }
+
+    // Try every community at once and return those the agent answered for
+    pub fn probe_communities(&mut self, version: SnmpVersion, communities: &[String]) -> Result<Vec<String>, Box<dyn Error>> {
+        let mut accepted = Vec::new();
+        let mut unanswered: Vec<&String> = communities.iter().collect();
+        for _ in 0..=self.retries {
+            if unanswered.is_empty() {
+                break;
+            }
+            let mut pending: HashMap<i64, &String> = HashMap::new();
+            for community in unanswered {
+                let id = self.request_id();
+                self.socket.send(&community_message(version, community, GET_REQUEST, id, 0, 0, &[SYS_DESCR.to_vec()]))?;
+                pending.insert(id, community);
+            }
+
+            let deadline = Instant::now() + self.timeout;
+            while !pending.is_empty() && let Some(message) = self.receive_until(deadline)? {
+                if let Ok(pdu) = parse_community_message(&message)
+                    && pdu.kind == RESPONSE
+                    && let Some(community) = pending.remove(&pdu.request_id)
+                {
+                    accepted.push(community.clone());
+                }
+            }
+            unanswered = pending.into_values().collect();
+        }
+        Ok(accepted)
+    }
+
+    // Walk a subtree with GETBULK (v2c) or GETNEXT (v1)
+    pub fn walk(&mut self, version: SnmpVersion, community: &str, root: &[u32]) -> Result<Vec<VarBind>, Box<dyn Error>> {
+        let mut results: Vec<VarBind> = Vec::new();
+        let mut current = root.to_vec();
+        'walk: while results.len() < MAX_WALK {
+            let (kind, repetitions) = match version {
+                SnmpVersion::V1 => (GET_NEXT_REQUEST, 0),
+                SnmpVersion::V2c => (GET_BULK_REQUEST, BULK_REPETITIONS),
+            };
+            let Some(pdu) = self.community_request(version, community, kind, repetitions, &[current.clone()])? else {
+                if results.is_empty() {
+                    return Err("No response from SNMP agent".into());
+                }
+                break;
+            };
+            if pdu.varbinds.is_empty() {
+                break;
+            }
+            for (oid, value) in pdu.varbinds {
+                // Stop at the end of the subtree, the end of the MIB or a non-increasing OID
+                if !oid.starts_with(root) || value == SnmpValue::EndOfView || oid <= current {
+                    break 'walk;
+                }
+                current = oid.clone();
+                results.push((oid, value));
+            }
+        }
+        Ok(results)
+    }
+
+    // Empty noAuthNoPriv request that makes the agent reveal its engine id, boots and time
+    pub fn discover_engine(&mut self) -> Result<Option<EngineInfo>, Box<dyn Error>> {
+        let engine = EngineInfo { id: Vec::new(), boots: 0, time: 0 };
+        Ok(self.v3_request(&engine, "", &[])?.map(|(engine, _)| engine))
+    }
+
+    // A user name the agent does not know is answered with usmStatsUnknownUserNames
+    pub fn probe_user(&mut self, engine: &EngineInfo, user: &str) -> Result<UserProbe, Box<dyn Error>> {
+        let Some((_, pdu)) = self.v3_request(engine, user, &[SYS_DESCR.to_vec()])? else {
+            return Ok(UserProbe::NoReply);
+        };
+        if pdu.kind == RESPONSE {
+            return Ok(UserProbe::NoAuth);
+        }
+        // Any other report (unsupported security level, time window) is only sent for users that exist
+        let report = pdu.varbinds.first().map(|(oid, _)| oid.as_slice()).unwrap_or(&[]);
+        Ok(if report.starts_with(&USM_UNKNOWN_USER_NAMES) { UserProbe::Unknown } else { UserProbe::RequiresAuth })
+    }
+
+    fn community_request(&mut self, version: SnmpVersion, community: &str, kind: u8, repetitions: i64, oids: &[Vec<u32>]) -> Result<Option<Pdu>, Box<dyn Error>> {
+        for _ in 0..=self.retries {
+            let id = self.request_id();
+            self.socket.send(&community_message(version, community, kind, id, 0, repetitions, oids))?;
+            let deadline = Instant::now() + self.timeout;
+            while let Some(message) = self.receive_until(deadline)? {
+                if let Ok(pdu) = parse_community_message(&message) && pdu.request_id == id {
+                    return Ok(Some(pdu));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn v3_request(&mut self, engine: &EngineInfo, user: &str, oids: &[Vec<u32>]) -> Result<Option<(EngineInfo, Pdu)>, Box<dyn Error>> {
+        for _ in 0..=self.retries {
+            let id = self.request_id();
+            self.socket.send(&v3_message(engine, user, id, oids))?;
+            let deadline = Instant::now() + self.timeout;
+            while let Some(message) = self.receive_until(deadline)? {
+                if let Ok((message_id, engine, pdu)) = parse_v3_message(&message) && message_id == id {
+                    return Ok(Some((engine, pdu)));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn receive_until(&self, deadline: Instant) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Ok(None);
+        }
+        self.socket.set_read_timeout(Some(remaining))?;
+        let mut buffer = vec![0u8; 65535];
+        match self.socket.recv(&mut buffer) {
+            Ok(length) => {
+                buffer.truncate(length);
+                Ok(Some(buffer))
+            },
+            Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => Ok(None),
+            // ICMP port unreachable surfaces as a refused connection on the next read
+            Err(e) if e.kind() == std::io::ErrorKind::ConnectionRefused => Err(format!("{} refused SNMP (port closed)", self.target).into()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn request_id(&mut self) -> i64 {
+        self.next_id += 1;
+        self.next_id
+    }
+}
+
+fn encode_pdu(kind: u8, request_id: i64, error_status: i64, error_index: i64, oids: &[Vec<u32>]) -> Vec<u8> {
+    let bindings: Vec<u8> = oids.iter()
+        .flat_map(|oid| ber::encode(TAG_SEQUENCE, &[ber::encode_oid(oid), ber::encode(TAG_NULL, &[])].concat()))
+        .collect();
+    ber::encode(kind, &[
+        ber::encode_integer(request_id),
+        ber::encode_integer(error_status),
+        ber::encode_integer(error_index),
+        ber::encode(TAG_SEQUENCE, &bindings),
+    ].concat())
+}
+
+// GETBULK reuses the error fields as non-repeaters and max-repetitions
+fn community_message(version: SnmpVersion, community: &str, kind: u8, request_id: i64, non_repeaters: i64, repetitions: i64, oids: &[Vec<u32>]) -> Vec<u8> {
+    ber::encode(TAG_SEQUENCE, &[
+        ber::encode_integer(version.wire()),
+        ber::encode(TAG_OCTET_STRING, community.as_bytes()),
+        encode_pdu(kind, request_id, non_repeaters, repetitions, oids),
+    ].concat())
+}
+
+fn v3_message(engine: &EngineInfo, user: &str, message_id: i64, oids: &[Vec<u32>]) -> Vec<u8> {
+    let global = ber::encode(TAG_SEQUENCE, &[
+        ber::encode_integer(message_id),
+        ber::encode_integer(65507),                          // Maximum message size
+        ber::encode(TAG_OCTET_STRING, &[0x04]),              // Reportable, noAuthNoPriv
+        ber::encode_integer(3),                              // User-based security model
+    ].concat());
+    let security = ber::encode(TAG_SEQUENCE, &[
+        ber::encode(TAG_OCTET_STRING, &engine.id),
+        ber::encode_integer(engine.boots),
+        ber::encode_integer(engine.time),
+        ber::encode(TAG_OCTET_STRING, user.as_bytes()),
+        ber::encode(TAG_OCTET_STRING, &[]),                  // No authentication parameters
+        ber::encode(TAG_OCTET_STRING, &[]),                  // No privacy parameters
+    ].concat());
+    let scoped = ber::encode(TAG_SEQUENCE, &[
+        ber::encode(TAG_OCTET_STRING, &engine.id),
+        ber::encode(TAG_OCTET_STRING, &[]),
+        encode_pdu(GET_REQUEST, message_id, 0, 0, oids),
+    ].concat());
+    ber::encode(TAG_SEQUENCE, &[
+        ber::encode_integer(3),
+        global,
+        ber::encode(TAG_OCTET_STRING, &security),
+        scoped,
+    ].concat())
+}
+
+fn parse_community_message(data: &[u8]) -> Result<Pdu, Box<dyn Error>> {
+    let mut message = BerReader::new(BerReader::new(data).expect(TAG_SEQUENCE)?);
+    let _version = message.integer()?;
+    let _community = message.expect(TAG_OCTET_STRING)?;
+    parse_pdu(&mut message)
+}
+
+fn parse_v3_message(data: &[u8]) -> Result<(i64, EngineInfo, Pdu), Box<dyn Error>> {
+    let mut message = BerReader::new(BerReader::new(data).expect(TAG_SEQUENCE)?);
+    if message.integer()? != 3 {
+        return Err("Not an SNMPv3 message".into());
+    }
+    let message_id = BerReader::new(message.expect(TAG_SEQUENCE)?).integer()?;
+    let mut security = BerReader::new(BerReader::new(message.expect(TAG_OCTET_STRING)?).expect(TAG_SEQUENCE)?);
+    let engine = EngineInfo {
+        id: security.expect(TAG_OCTET_STRING)?.to_vec(),
+        boots: security.integer()?,
+        time: security.integer()?,
+    };
+    let mut scoped = BerReader::new(message.expect(TAG_SEQUENCE)?);
+    let _context_engine = scoped.expect(TAG_OCTET_STRING)?;
+    let _context_name = scoped.expect(TAG_OCTET_STRING)?;
+    Ok((message_id, engine, parse_pdu(&mut scoped)?))
+}
+
+fn parse_pdu(reader: &mut BerReader) -> Result<Pdu, Box<dyn Error>> {
+    let (kind, content) = reader.read()?;
+    if kind != RESPONSE && kind != REPORT {
+        return Err(format!("Unexpected SNMP PDU 0x{:02X}", kind).into());
+    }
+    let mut pdu = BerReader::new(content);
+    let request_id = pdu.integer()?;
+    let error_status = pdu.integer()?;
+    let _error_index = pdu.integer()?;
+
+    // v1 agents signal the end of the MIB with noSuchName instead of endOfMibView
+    let mut varbinds = Vec::new();
+    let mut list = BerReader::new(pdu.expect(TAG_SEQUENCE)?);
+    while !list.is_empty() {
+        let mut binding = BerReader::new(list.expect(TAG_SEQUENCE)?);
+        let oid = ber::decode_oid(binding.expect(TAG_OID)?);
+        let (tag, value) = binding.read()?;
+        varbinds.push((oid, decode_value(tag, value)));
+    }
+    if kind == RESPONSE && error_status != 0 {
+        varbinds.clear();
+    }
+    Ok(Pdu { kind, request_id, varbinds })
+}
+
+fn decode_value(tag: u8, content: &[u8]) -> SnmpValue {
+    match tag {
+        TAG_INTEGER => SnmpValue::Integer(ber::decode_integer(content)),
+        TAG_OCTET_STRING => SnmpValue::String(content.to_vec()),
+        TAG_OID => SnmpValue::Oid(ber::decode_oid(content)),
+        IP_ADDRESS => content.try_into().map(SnmpValue::IpAddress).unwrap_or(SnmpValue::Null),
+        COUNTER32 | GAUGE32 | COUNTER64 => SnmpValue::Counter(ber::decode_unsigned(content)),
+        TIME_TICKS => SnmpValue::TimeTicks(ber::decode_unsigned(content)),
+        OPAQUE => SnmpValue::Opaque(content.to_vec()),
+        NO_SUCH_OBJECT | NO_SUCH_INSTANCE | END_OF_MIB_VIEW => SnmpValue::EndOfView,
+        _ => SnmpValue::Null,
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    // A v2c GetResponse from net-snmp (request 0x1001) with a long-form PDU length and one value of every common type
+    const GET_RESPONSE: &str = "3081c502010104067075626c6963a281b7020210010201000201003081aa301b06082b06010201010100040f4c696e757820677720352e31302e30\
+        301606082b06010201010200060a2b06010401bf0803020a300f06082b0601020101030043038954723014060a2b0601020102020106020406001122334455\
+        3013060a2b060102010202010a02410500ffffffff3016060e2b060102010414010181400002014004c0000201300f060a2b060102010202010302020106\
+        300e060a2b0601020102020103038200";
+
+    // The SNMPv3 discovery Report (message 0x1002) carrying the engine and usmStatsUnknownEngineIDs.0 = 7
+    const DISCOVERY_REPORT: &str = "3067020103300f02021002020300ffe3040100020103041f301d040d80001f8880e9630000d61ff449020103020301e2400400040004003030040d\
+        80001f8880e9630000d61ff4490400a81d020210020201000201003011300f060a2b060106030f01010400410107";
+
+    const ENGINE_ID: &str = "80001f8880e9630000d61ff449";
+
+    fn bytes(hex: &str) -> Vec<u8> {
+        (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect()
+    }
+
+    fn response_pdu(kind: u8, request_id: i64, error_status: i64, varbinds: &[(Vec<u32>, Vec<u8>)]) -> Vec<u8> {
+        let bindings: Vec<u8> = varbinds.iter()
+            .flat_map(|(oid, value)| ber::encode(TAG_SEQUENCE, &[ber::encode_oid(oid), value.clone()].concat()))
+            .collect();
+        ber::encode(kind, &[
+            ber::encode_integer(request_id),
+            ber::encode_integer(error_status),
+            ber::encode_integer(if error_status == 0 { 0 } else { 1 }),
+            ber::encode(TAG_SEQUENCE, &bindings),
+        ].concat())
+    }
+
+    // What the stand-in agent holds, in OID order: the system group and the first interface column
+    fn mib() -> Vec<(Vec<u32>, Vec<u8>)> {
+        vec![
+            (SYS_DESCR.to_vec(), ber::encode(TAG_OCTET_STRING, b"Linux gw 5.10.0")),
+            (vec![1, 3, 6, 1, 2, 1, 1, 3, 0], ber::encode(TIME_TICKS, &[0x01, 0x00])),
+            (vec![1, 3, 6, 1, 2, 1, 1, 5, 0], ber::encode(TAG_OCTET_STRING, b"gw")),
+            (vec![1, 3, 6, 1, 2, 1, 2, 2, 1, 1, 1], ber::encode_integer(1)),
+        ]
+    }
+
+    // UDP SNMP stand-in: community "public" (v1 and v2c), and SNMPv3 users "admin" (noAuthNoPriv) and "operator" (authPriv only)
+    fn serve() -> SocketAddr {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = socket.local_addr().unwrap();
+        thread::spawn(move || {
+            let mut buf = [0u8; 65535];
+            while let Ok((len, peer)) = socket.recv_from(&mut buf) {
+                let mut message = BerReader::new(BerReader::new(&buf[..len]).expect(TAG_SEQUENCE).unwrap());
+                let version = message.integer().unwrap();
+                let reply = if version == 3 {
+                    let message_id = BerReader::new(message.expect(TAG_SEQUENCE).unwrap()).integer().unwrap();
+                    let mut security = BerReader::new(BerReader::new(message.expect(TAG_OCTET_STRING).unwrap()).expect(TAG_SEQUENCE).unwrap());
+                    let (engine_id, _boots, _time) = (security.expect(TAG_OCTET_STRING).unwrap(), security.integer().unwrap(), security.integer().unwrap());
+                    let user = security.expect(TAG_OCTET_STRING).unwrap();
+                    let counter = ber::encode(COUNTER32, &[1]);
+                    let pdu = match (engine_id.is_empty(), user) {
+                        (true, _) => response_pdu(REPORT, message_id, 0, &[(vec![1, 3, 6, 1, 6, 3, 15, 1, 1, 4, 0], counter)]),
+                        (false, b"admin") => response_pdu(RESPONSE, message_id, 0, &mib()[..1]),
+                        (false, b"operator") => response_pdu(REPORT, message_id, 0, &[(vec![1, 3, 6, 1, 6, 3, 15, 1, 1, 1, 0], counter)]),
+                        (false, _) => response_pdu(REPORT, message_id, 0, &[([&USM_UNKNOWN_USER_NAMES[..], &[0]].concat(), counter)]),
+                    };
+                    let engine = bytes(ENGINE_ID);
+                    let security = ber::encode(TAG_SEQUENCE, &[
+                        ber::encode(TAG_OCTET_STRING, &engine), ber::encode_integer(3), ber::encode_integer(123456),
+                        ber::encode(TAG_OCTET_STRING, user), ber::encode(TAG_OCTET_STRING, &[]), ber::encode(TAG_OCTET_STRING, &[]),
+                    ].concat());
+                    ber::encode(TAG_SEQUENCE, &[
+                        ber::encode_integer(3),
+                        ber::encode(TAG_SEQUENCE, &[ber::encode_integer(message_id), ber::encode_integer(65507), ber::encode(TAG_OCTET_STRING, &[0]), ber::encode_integer(3)].concat()),
+                        ber::encode(TAG_OCTET_STRING, &security),
+                        ber::encode(TAG_SEQUENCE, &[ber::encode(TAG_OCTET_STRING, &engine), ber::encode(TAG_OCTET_STRING, &[]), pdu].concat()),
+                    ].concat())
+                } else {
+                    // Wrong communities are dropped silently, as real agents do
+                    if message.expect(TAG_OCTET_STRING).unwrap() != b"public" {
+                        continue;
+                    }
+                    let (kind, content) = message.read().unwrap();
+                    let mut request = BerReader::new(content);
+                    let (id, _, repetitions) = (request.integer().unwrap(), request.integer().unwrap(), request.integer().unwrap());
+                    let mut bindings = BerReader::new(request.expect(TAG_SEQUENCE).unwrap());
+                    let oid = ber::decode_oid(BerReader::new(bindings.expect(TAG_SEQUENCE).unwrap()).expect(TAG_OID).unwrap());
+                    let following: Vec<(Vec<u32>, Vec<u8>)> = mib().into_iter().filter(|(o, _)| *o > oid).collect();
+                    let pdu = match kind {
+                        GET_REQUEST => response_pdu(RESPONSE, id, 0, &mib().into_iter().filter(|(o, _)| *o == oid).collect::<Vec<_>>()),
+                        GET_BULK_REQUEST => {
+                            let mut rows: Vec<(Vec<u32>, Vec<u8>)> = following.into_iter().take(repetitions as usize).collect();
+                            if rows.len() < repetitions as usize {
+                                rows.push((oid, ber::encode(END_OF_MIB_VIEW, &[])));
+                            }
+                            response_pdu(RESPONSE, id, 0, &rows)
+                        },
+                        // v1 ends the MIB with noSuchName(2)
+                        _ => match following.first() {
+                            Some(row) => response_pdu(RESPONSE, id, 0, std::slice::from_ref(row)),
+                            None => response_pdu(RESPONSE, id, 2, &[(oid, ber::encode(TAG_NULL, &[]))]),
+                        },
+                    };
+                    ber::encode(TAG_SEQUENCE, &[ber::encode_integer(version), ber::encode(TAG_OCTET_STRING, b"public"), pdu].concat())
+                };
+                let _ = socket.send_to(&reply, peer);
+            }
+        });
+        address
+    }
+
+    #[test]
+    fn canned_get_response_is_decoded() {
+        let pdu = parse_community_message(&bytes(GET_RESPONSE)).unwrap();
+        assert_eq!((pdu.kind, pdu.request_id, pdu.varbinds.len()), (RESPONSE, 0x1001, 8));
+        let value = |index: usize| &pdu.varbinds[index].1;
+        assert_eq!(pdu.varbinds[0].0, SYS_DESCR);
+        assert_eq!(value(0).as_text(), "Linux gw 5.10.0");
+        assert_eq!(value(1), &SnmpValue::Oid(vec![1, 3, 6, 1, 4, 1, 8072, 3, 2, 10]));
+        assert_eq!((value(2), value(2).as_text()), (&SnmpValue::TimeTicks(9000050), "1d 01:00:00".to_string()));
+        assert_eq!(value(3).as_text(), "00:11:22:33:44:55");
+        assert_eq!((value(4), value(4).as_u64()), (&SnmpValue::Counter(4294967295), Some(4294967295)));
+        assert_eq!(pdu.varbinds[5].0, [1, 3, 6, 1, 2, 1, 4, 20, 1, 1, 192, 0, 2, 1]);
+        assert_eq!(value(5).as_text(), "192.0.2.1");
+        assert_eq!(value(6), &SnmpValue::Integer(6));
+        assert_eq!(value(7), &SnmpValue::EndOfView);
+    }
+
+    #[test]
+    fn error_responses_carry_no_bindings() {
+        let message = |kind: u8, error_status: i64| ber::encode(TAG_SEQUENCE, &[
+            ber::encode_integer(0),
+            ber::encode(TAG_OCTET_STRING, b"public"),
+            response_pdu(kind, 7, error_status, &[(SYS_DESCR.to_vec(), ber::encode(TAG_NULL, &[]))]),
+        ].concat());
+        let pdu = parse_community_message(&message(RESPONSE, 2)).unwrap();
+        assert_eq!((pdu.request_id, pdu.varbinds.len()), (7, 0));
+        assert_eq!(parse_community_message(&message(RESPONSE, 0)).unwrap().varbinds, vec![(SYS_DESCR.to_vec(), SnmpValue::Null)]);
+        // Requests echoed back, or anything that is not a response, are ignored
+        assert_eq!(parse_community_message(&message(GET_REQUEST, 0)).err().unwrap().to_string(), "Unexpected SNMP PDU 0xA0");
+        assert!(parse_community_message(&bytes(GET_RESPONSE)[..40]).is_err());
+    }
+
+    #[test]
+    fn discovery_report_reveals_the_engine() {
+        let (message_id, engine, pdu) = parse_v3_message(&bytes(DISCOVERY_REPORT)).unwrap();
+        assert_eq!((message_id, pdu.kind, pdu.request_id), (0x1002, REPORT, 0x1002));
+        assert_eq!((hex(&engine.id).replace(':', ""), engine.boots, engine.time), (ENGINE_ID.to_string(), 3, 123456));
+        assert_eq!(pdu.varbinds, vec![(vec![1, 3, 6, 1, 6, 3, 15, 1, 1, 4, 0], SnmpValue::Counter(7))]);
+        assert_eq!(parse_v3_message(&bytes(GET_RESPONSE)).err().unwrap().to_string(), "Not an SNMPv3 message");
+    }
+
+    #[test]
+    fn requests_round_trip_through_the_parser_layout() {
+        let request = community_message(SnmpVersion::V2c, "private", GET_BULK_REQUEST, 0x1234, 0, BULK_REPETITIONS, &[SYS_DESCR.to_vec()]);
+        let mut message = BerReader::new(BerReader::new(&request).expect(TAG_SEQUENCE).unwrap());
+        assert_eq!(message.integer().unwrap(), 1);
+        assert_eq!(message.expect(TAG_OCTET_STRING).unwrap(), b"private");
+        let mut pdu = BerReader::new(message.expect(GET_BULK_REQUEST).unwrap());
+        assert_eq!((pdu.integer().unwrap(), pdu.integer().unwrap(), pdu.integer().unwrap()), (0x1234, 0, BULK_REPETITIONS));
+    }
+
+    #[test]
+    fn communities_users_and_walks_against_a_stand_in_agent() {
+        let mut client = SnmpClient::new(serve(), Duration::from_millis(200), 1).unwrap();
+        let communities: Vec<String> = ["private", "public", "cisco"].iter().map(|c| c.to_string()).collect();
+        assert_eq!(client.probe_communities(SnmpVersion::V2c, &communities).unwrap(), vec!["public"]);
+        assert_eq!(client.probe_communities(SnmpVersion::V1, &communities[..1]).unwrap(), Vec::<String>::new());
+
+        // GETBULK stops at the end of the subtree, GETNEXT at noSuchName
+        let system = [1, 3, 6, 1, 2, 1, 1];
+        let oids = |rows: Vec<VarBind>| rows.into_iter().map(|(oid, _)| ber::oid_to_string(&oid)).collect::<Vec<_>>();
+        let expected = vec!["1.3.6.1.2.1.1.1.0", "1.3.6.1.2.1.1.3.0", "1.3.6.1.2.1.1.5.0"];
+        assert_eq!(oids(client.walk(SnmpVersion::V2c, "public", &system).unwrap()), expected);
+        assert_eq!(oids(client.walk(SnmpVersion::V1, "public", &system).unwrap()), expected);
+        assert_eq!(oids(client.walk(SnmpVersion::V2c, "public", &[1, 3, 6, 1, 2, 1, 2]).unwrap()), vec!["1.3.6.1.2.1.2.2.1.1.1"]);
+        assert!(client.walk(SnmpVersion::V1, "public", &[1, 3, 6, 1, 2, 1, 25]).unwrap().is_empty());
+        assert_eq!(client.walk(SnmpVersion::V2c, "private", &system).err().unwrap().to_string(), "No response from SNMP agent");
+
+        let engine = client.discover_engine().unwrap().unwrap();
+        assert_eq!((hex(&engine.id).replace(':', ""), engine.boots, engine.time), (ENGINE_ID.to_string(), 3, 123456));
+        let probe = |client: &mut SnmpClient, user: &str| client.probe_user(&engine, user).unwrap();
+        assert_eq!(probe(&mut client, "admin"), UserProbe::NoAuth);
+        assert_eq!(probe(&mut client, "operator"), UserProbe::RequiresAuth);
+        assert_eq!(probe(&mut client, "guest"), UserProbe::Unknown);
+    }
+}
@@ -0,0 +1,431 @@
+use console::style;
+use indicatif::ProgressBar;
+use socket2::{Domain, Protocol, Socket, Type};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpStream, UdpSocket};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::models::{Host, Port};
+use crate::utils::OUI_DATABASE;
+use crate::utils::net::local_networks;
+
+// How long to wait for replies to a probe
+const PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+// ARP requests are triggered in batches small enough for the kernel neighbour table
+const ARP_BATCH: usize = 256;
+// Full OUI list shipped with nmap, used in addition to the bundled subset when installed
+const NMAP_MAC_PREFIXES: &str = "/usr/share/nmap/nmap-mac-prefixes";
+
+// Ways of finding live hosts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscoveryMethod {
+    Arp,                             // Local segment only: kernel ARP resolution read back from /proc/net/arp
+    Icmp,                            // ICMP echo (ping socket, or raw socket as root)
+    Tcp,                             // TCP connect to common ports: SYN/ACK or RST both prove the host is up
+}
+
+impl fmt::Display for DiscoveryMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            DiscoveryMethod::Arp => "arp",
+            DiscoveryMethod::Icmp => "icmp",
+            DiscoveryMethod::Tcp => "tcp",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+// Settings for a discovery sweep
+pub struct DiscoveryOptions {
+    pub methods: Vec<DiscoveryMethod>,
+    pub tcp_ports: Vec<u16>,
+    pub threads: usize,              // Concurrent TCP ping workers
+    pub oui_file: Option<String>,    // Extra MAC vendor database
+}
+
+// Parse a comma separated list of discovery methods (arp,icmp,tcp)
+pub fn parse_discovery_methods(value: &str) -> Result<Vec<DiscoveryMethod>, Box<dyn Error>> {
+    let mut methods = Vec::new();
+    for name in value.split(',').map(|m| m.trim().to_lowercase()).filter(|m| !m.is_empty()) {
+        let method = match name.as_str() {
+            "arp" => DiscoveryMethod::Arp,
+            "icmp" | "ping" => DiscoveryMethod::Icmp,
+            "tcp" => DiscoveryMethod::Tcp,
+            _ => return Err(format!("Unknown discovery method: {} (expected arp, icmp or tcp)", name).into()),
+        };
+        if !methods.contains(&method) {
+            methods.push(method);
+        }
+    }
+    if methods.is_empty() {
+        return Err("No discovery methods selected".into());
+    }
+    Ok(methods)
+}
+
+// Find live hosts with the selected methods and return them with MAC vendors where known
+pub fn perform_discovery(spinner: &ProgressBar, targets: &[IpAddr], options: &DiscoveryOptions, base_filename: &str, scan_dir: &str) -> Result<Vec<Host>, Box<dyn Error>> {
+    let output_file = format!("{}/{}_discovery.txt", scan_dir, base_filename);
+    let ipv4: Vec<Ipv4Addr> = targets.iter()
+        .filter_map(|ip| match ip { IpAddr::V4(ip) => Some(*ip), IpAddr::V6(_) => None })
+        .collect();
+
+    let mut hosts: BTreeMap<IpAddr, Host> = BTreeMap::new();
+    let mut notes: Vec<String> = Vec::new();
+    let mut failures = 0;
+
+    for method in &options.methods {
+        spinner.set_message(format!("Discovering hosts with {} on {} addresses ({} up so far)...",
+            style(method).cyan(), targets.len(), style(hosts.len()).green()));
+
+        match method {
+            DiscoveryMethod::Arp => {
+                // ARP only reaches addresses on a directly connected network
+                let networks = local_networks();
+                let on_link: Vec<Ipv4Addr> = ipv4.iter().copied()
+                    .filter(|ip| networks.iter().any(|n| n.contains(*ip)))
+                    .collect();
+                if on_link.is_empty() {
+                    notes.push("ARP: no targets on a directly connected network".to_string());
+                    continue;
+                }
+                let interfaces: BTreeSet<&str> = networks.iter()
+                    .filter(|n| on_link.iter().any(|ip| n.contains(*ip)))
+                    .map(|n| n.interface.as_str())
+                    .collect();
+                notes.push(format!("ARP: {} targets on {}", on_link.len(), interfaces.into_iter().collect::<Vec<_>>().join(", ")));
+                match arp_sweep(&on_link) {
+                    Ok(found) => {
+                        for (ip, mac) in found {
+                            let host = mark_up(&mut hosts, IpAddr::V4(ip), "arp-response");
+                            host.mac = Some(mac);
+                        }
+                    },
+                    Err(e) => {
+                        failures += 1;
+                        notes.push(format!("ARP sweep failed: {}", e));
+                    }
+                }
+            },
+            DiscoveryMethod::Icmp => {
+                if ipv4.is_empty() {
+                    notes.push("ICMP: only IPv4 targets are pinged".to_string());
+                    continue;
+                }
+                match icmp_sweep(&ipv4) {
+                    Ok(found) => {
+                        for ip in found {
+                            mark_up(&mut hosts, IpAddr::V4(ip), "echo-reply");
+                        }
+                    },
+                    Err(e) => {
+                        failures += 1;
+                        notes.push(format!("ICMP sweep failed (needs root or net.ipv4.ping_group_range): {}", e));
+                    }
+                }
+            },
+            DiscoveryMethod::Tcp => {
+                for (ip, (reason, open)) in tcp_sweep(spinner, targets, &options.tcp_ports, options.threads) {
+                    let host = mark_up(&mut hosts, ip, reason);
+                    for port in open {
+                        host.ports.retain(|p| !(p.port == port && p.protocol == "tcp"));
+                        host.ports.push(Port {
+                            protocol: "tcp".to_string(),
+                            port,
+                            state: "open".to_string(),
+                            service: None,
+                            source: "Discovery".to_string(),
+                            last_seen: None,
+                        });
+                    }
+                }
+            },
+        }
+    }
+
+    if failures == options.methods.len() {
+        return Err(notes.join("; ").into());
+    }
+
+    // Vendor from the MAC prefix
+    let oui = load_oui_database(options.oui_file.as_deref())?;
+    for host in hosts.values_mut() {
+        host.vendor = host.mac.as_deref().and_then(|mac| lookup_vendor(&oui, mac));
+    }
+
+    // Save the raw results
+    let mut file = File::create(output_file)?;
+    let names: Vec<String> = options.methods.iter().map(|m| m.to_string()).collect();
+    writeln!(file, "# Host discovery of {} addresses ({}): {} up", targets.len(), names.join(", "), hosts.len())?;
+    for note in &notes {
+        writeln!(file, "# {}", note)?;
+    }
+    for host in hosts.values() {
+        let ports: Vec<String> = host.open_ports().map(|p| p.port.to_string()).collect();
+        writeln!(file, "{}\t{}\t{}\t{}\t{}",
+            host.address,
+            host.reason.as_deref().unwrap_or("-"),
+            host.mac.as_deref().unwrap_or("-"),
+            host.vendor.as_deref().unwrap_or("-"),
+            if ports.is_empty() { "-".to_string() } else { ports.join(",") })?;
+    }
+
+    Ok(hosts.into_values().collect())
+}
+
+// Record a host as up, keeping the reason of the first method that found it
+fn mark_up<'a>(hosts: &'a mut BTreeMap<IpAddr, Host>, ip: IpAddr, reason: &str) -> &'a mut Host {
+    let host = hosts.entry(ip).or_insert_with(|| Host::new(&ip.to_string()));
+    host.status = Some("up".to_string());
+    if host.reason.is_none() {
+        host.reason = Some(reason.to_string());
+    }
+    host
+}
+
+// Make the kernel ARP for every address (a UDP datagram to the discard port is enough),
+// then read the resolved entries back from the neighbour table
+fn arp_sweep(targets: &[Ipv4Addr]) -> Result<BTreeMap<Ipv4Addr, String>, Box<dyn Error>> {
+    let socket = UdpSocket::bind("0.0.0.0:0")?;
+    let mut found = BTreeMap::new();
+
+    for batch in targets.chunks(ARP_BATCH) {
+        for ip in batch {
+            let _ = socket.send_to(&[0], (*ip, 9));
+        }
+        thread::sleep(PROBE_TIMEOUT);
+
+        let table = read_arp_table()?;
+        for ip in batch {
+            if let Some(mac) = table.get(ip) {
+                found.insert(*ip, mac.clone());
+            }
+        }
+    }
+    Ok(found)
+}
+
+// Completed entries of the kernel ARP table
+fn read_arp_table() -> Result<HashMap<Ipv4Addr, String>, Box<dyn Error>> {
+    let table = fs::read_to_string("/proc/net/arp").map_err(|e| format!("cannot read /proc/net/arp ({})", e))?;
+    Ok(parse_arp_table(&table))
+}
+
+fn parse_arp_table(table: &str) -> HashMap<Ipv4Addr, String> {
+    let mut entries = HashMap::new();
+    for line in table.lines().skip(1) {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 4 {
+            continue;
+        }
+        // Flag 0x2 is ATF_COM: the hardware address has been resolved
+        let complete = u32::from_str_radix(fields[2].trim_start_matches("0x"), 16).is_ok_and(|f| f & 0x2 != 0);
+        if let Ok(ip) = fields[0].parse::<Ipv4Addr>() && complete && fields[3] != "00:00:00:00:00:00" {
+            entries.insert(ip, fields[3].to_lowercase());
+        }
+    }
+    entries
+}
+
+// Send one ICMP echo request to every address and collect the ones that reply
+fn icmp_sweep(targets: &[Ipv4Addr]) -> Result<BTreeSet<Ipv4Addr>, Box<dyn Error>> {
+    // Unprivileged ping sockets strip the IP header and filter replies by identifier; raw sockets do neither
+    let (socket, raw) = match Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::ICMPV4)) {
+        Ok(socket) => (socket, false),
+        Err(_) => (Socket::new(Domain::IPV4, Type::RAW, Some(Protocol::ICMPV4))?, true),
+    };
+    // std's datagram API works on any AF_INET datagram/raw descriptor
+    let socket = UdpSocket::from(socket);
+    socket.set_read_timeout(Some(Duration::from_millis(200)))?;
+
+    let identifier = std::process::id() as u16;
+    let wanted: HashSet<Ipv4Addr> = targets.iter().copied().collect();
+    let sending = AtomicBool::new(true);
+
+    let alive = thread::scope(|scope| {
+        let receiver = scope.spawn(|| {
+            let mut alive = BTreeSet::new();
+            let mut buffer = [0u8; 1500];
+            let mut deadline = None;
+            loop {
+                if !sending.load(Ordering::Relaxed) {
+                    let deadline = *deadline.get_or_insert_with(|| Instant::now() + PROBE_TIMEOUT);
+                    if Instant::now() >= deadline {
+                        break;
+                    }
+                }
+                let Ok((len, SocketAddr::V4(from))) = socket.recv_from(&mut buffer) else {
+                    continue;
+                };
+                let offset = if raw { (buffer[0] & 0x0f) as usize * 4 } else { 0 };
+                let Some(reply) = buffer.get(offset..len) else { continue };
+                // Echo reply (type 0), with our identifier when the kernel doesn't filter for us
+                if reply.len() >= 8 && reply[0] == 0 && (!raw || reply[4..6] == identifier.to_be_bytes()) && wanted.contains(from.ip()) {
+                    alive.insert(*from.ip());
+                }
+            }
+            alive
+        });
+
+        for (sequence, ip) in targets.iter().enumerate() {
+            let _ = socket.send_to(&echo_request(identifier, sequence as u16), (*ip, 0));
+            // Pace the sweep so replies aren't dropped from the receive buffer
+            if sequence % 64 == 63 {
+                thread::sleep(Duration::from_millis(10));
+            }
+        }
+        sending.store(false, Ordering::Relaxed);
+        receiver.join().unwrap_or_default()
+    });
+
+    Ok(alive)
+}
+
+// ICMP echo request with the internet checksum filled in
+fn echo_request(identifier: u16, sequence: u16) -> Vec<u8> {
+    let mut packet = vec![8, 0, 0, 0];
+    packet.extend_from_slice(&identifier.to_be_bytes());
+    packet.extend_from_slice(&sequence.to_be_bytes());
+    packet.extend_from_slice(b"rust_recon discovery");
+
+    let mut sum: u32 = packet.chunks(2)
+        .map(|pair| u16::from_be_bytes([pair[0], *pair.get(1).unwrap_or(&0)]) as u32)
+        .sum();
+    while sum >> 16 != 0 {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    packet[2..4].copy_from_slice(&(!(sum as u16)).to_be_bytes());
+    packet
+}
+
+// Connect to each port in turn until the host answers; a refused connection (RST) counts as up
+fn tcp_sweep(spinner: &ProgressBar, targets: &[IpAddr], ports: &[u16], threads: usize) -> BTreeMap<IpAddr, (&'static str, Vec<u16>)> {
+    let found: Mutex<BTreeMap<IpAddr, (&'static str, Vec<u16>)>> = Mutex::new(BTreeMap::new());
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(|| {
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(ip) = targets.get(index) else { break };
+
+                    for port in ports {
+                        let reply = match TcpStream::connect_timeout(&SocketAddr::new(*ip, *port), PROBE_TIMEOUT) {
+                            Ok(_) => Some(("syn-ack", Some(*port))),
+                            Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => Some(("reset", None)),
+                            Err(_) => None,
+                        };
+                        if let Some((reason, open)) = reply {
+                            let mut found = found.lock().unwrap_or_else(|e| e.into_inner());
+                            found.insert(*ip, (reason, open.into_iter().collect()));
+                            break;
+                        }
+                    }
+
+                    let completed = done.fetch_add(1, Ordering::Relaxed) + 1;
+                    if completed.is_multiple_of(25) {
+                        let hits = found.lock().map(|f| f.len()).unwrap_or(0);
+                        spinner.set_message(format!("TCP ping {}/{} addresses ({} up)...", completed, targets.len(), style(hits).green()));
+                    }
+                }
+            });
+        }
+    });
+
+    found.into_inner().unwrap_or_else(|e| e.into_inner())
+}
+
+// MAC prefix -> vendor from the bundled list, nmap's list when installed, and an optional user file
+fn load_oui_database(oui_file: Option<&str>) -> Result<HashMap<String, String>, Box<dyn Error>> {
+    let mut database = HashMap::new();
+    parse_oui_database(OUI_DATABASE, &mut database);
+    if let Ok(contents) = fs::read_to_string(NMAP_MAC_PREFIXES) {
+        parse_oui_database(&contents, &mut database);
+    }
+    if let Some(path) = oui_file {
+        let contents = fs::read_to_string(path).map_err(|e| format!("Cannot read OUI database {}: {}", path, e))?;
+        parse_oui_database(&contents, &mut database);
+    }
+    Ok(database)
+}
+
+// nmap-mac-prefixes format: six hex digits, whitespace, vendor
+fn parse_oui_database(contents: &str, database: &mut HashMap<String, String>) {
+    for line in contents.lines().map(|l| l.trim()).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+        if let Some((prefix, vendor)) = line.split_once(char::is_whitespace)
+            && prefix.len() == 6 && prefix.chars().all(|c| c.is_ascii_hexdigit()) {
+            database.insert(prefix.to_uppercase(), vendor.trim().to_string());
+        }
+    }
+}
+
+fn lookup_vendor(database: &HashMap<String, String>, mac: &str) -> Option<String> {
+    let prefix: String = mac.chars().filter(|c| c.is_ascii_hexdigit()).take(6).collect();
+    database.get(&prefix.to_uppercase()).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discovery_methods_are_parsed() {
+        let methods = parse_discovery_methods("ARP, ping,tcp,icmp,").unwrap();
+        assert_eq!(methods, vec![DiscoveryMethod::Arp, DiscoveryMethod::Icmp, DiscoveryMethod::Tcp]);
+        assert!(parse_discovery_methods("arp,udp").is_err());
+        assert!(parse_discovery_methods(" , ").is_err());
+    }
+
+    #[test]
+    fn echo_request_checksum_verifies() {
+        let packet = echo_request(0x1234, 7);
+        assert_eq!(&packet[..2], &[8, 0]);
+        assert_eq!(&packet[4..8], &[0x12, 0x34, 0x00, 0x07]);
+
+        // Summing a packet that carries its checksum gives 0xffff
+        let mut sum: u32 = packet.chunks(2)
+            .map(|pair| u16::from_be_bytes([pair[0], *pair.get(1).unwrap_or(&0)]) as u32)
+            .sum();
+        while sum >> 16 != 0 {
+            sum = (sum & 0xffff) + (sum >> 16);
+        }
+        assert_eq!(sum, 0xffff);
+    }
+
+    #[test]
+    fn arp_table_keeps_resolved_entries() {
+        let table = "IP address       HW type     Flags       HW address            Mask     Device
+192.168.1.1      0x1         0x2         AA:BB:CC:00:11:22     *        eth0
+192.168.1.7      0x1         0x0         00:00:00:00:00:00     *        eth0
+192.168.1.8      0x1         0x6         00:0c:29:ab:cd:ef     *        eth0
+192.168.1.9      0x1         0x2         00:00:00:00:00:00     *        eth0
+192.168.1.10     0x1         0x4         52:54:00:12:34:56     *        eth0
+";
+        let entries = parse_arp_table(table);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[&Ipv4Addr::new(192, 168, 1, 1)], "aa:bb:cc:00:11:22");
+        assert_eq!(entries[&Ipv4Addr::new(192, 168, 1, 8)], "00:0c:29:ab:cd:ef");
+    }
+
+    #[test]
+    fn vendors_are_looked_up_by_prefix() {
+        let mut database = HashMap::new();
+        parse_oui_database(OUI_DATABASE, &mut database);
+        parse_oui_database("# comment\n\n000c29   Example VM Vendor\nZZ0000 Not hex\n12345 Too short\n", &mut database);
+
+        assert_eq!(lookup_vendor(&database, "00:0C:29:ab:cd:ef").as_deref(), Some("Example VM Vendor"));
+        assert_eq!(lookup_vendor(&database, "b8-27-eb-01-02-03").as_deref(), Some("Raspberry Pi Foundation"));
+        assert_eq!(lookup_vendor(&database, "005056.aabb.ccdd").as_deref(), Some("VMware"));
+        assert_eq!(lookup_vendor(&database, "fe:ff:ff:00:00:01"), None);
+        assert!(!database.contains_key("ZZ0000") && !database.contains_key("12345"));
+    }
+}
@@ -1,5 +1,7 @@
 pub mod traceroute;
 pub mod netdiscover;
+pub mod discovery;
 
 pub use traceroute::*;
-pub use netdiscover::*;
\ No newline at end of file
+pub use netdiscover::*;
+pub use discovery::*;
\ No newline at end of file
@@ -4,9 +4,12 @@ use std::process::Command;
 use std::error::Error;
 use std::fs::File;
 use std::io::Write;
+use std::net::IpAddr;
+
+use crate::models::Host;
 
 // Perform network discovery using netdiscover with spinner
-pub fn perform_netdiscover(spinner: &ProgressBar, target_network: &str, base_filename: &str, scan_dir: &str) -> Result<(), Box<dyn Error>> {
+pub fn perform_netdiscover(spinner: &ProgressBar, target_network: &str, base_filename: &str, scan_dir: &str) -> Result<Vec<Host>, Box<dyn Error>> {
     let output_file = format!("{}/{}_netdiscover.txt", scan_dir, base_filename);
     
     // Update spinner message
@@ -21,6 +24,30 @@ pub fn perform_netdiscover(spinner: &ProgressBar, target_network: &str, base_fil
     
     let mut file = File::create(output_file)?;
     file.write_all(&output.stdout)?;
+    file.write_all(&output.stderr)?;
+    
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("netdiscover exited with {}: {}", output.status, stderr.trim()).into());
+    }
     
-    Ok(())
+    Ok(parse_netdiscover(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// Parse the host table: IP, MAC, packet count, length, vendor
+fn parse_netdiscover(output: &str) -> Vec<Host> {
+    let mut hosts = Vec::new();
+    for line in output.lines() {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.len() < 2 || tokens[0].parse::<IpAddr>().is_err() || tokens[1].matches(':').count() != 5 {
+            continue;
+        }
+        let mut host = Host::new(tokens[0]);
+        host.status = Some("up".to_string());
+        host.reason = Some("arp-response".to_string());
+        host.mac = Some(tokens[1].to_lowercase());
+        host.vendor = Some(tokens.get(4..).unwrap_or_default().join(" ")).filter(|v| !v.is_empty());
+        hosts.push(host);
+    }
+    hosts
 }
\ No newline at end of file
@@ -4,15 +4,19 @@ use std::process::Command;
 use std::error::Error;
 use std::fs::File;
 use std::io::Write;
+use std::net::IpAddr;
+use crate::models::{Hop, Trace};
 
 // Perform Traceroute network path discovery with spinner
-pub fn perform_traceroute(spinner: &ProgressBar, target: &str, base_filename: &str, scan_dir: &str) -> Result<(), Box<dyn Error>> {
+pub fn perform_traceroute(spinner: &ProgressBar, target: &str, base_filename: &str, scan_dir: &str) -> Result<Trace, Box<dyn Error>> {
     let output_file = format!("{}/{}_traceroute.txt", scan_dir, base_filename);
     
     // Update spinner message
     spinner.set_message(format!("Tracing network path to {}...", style(target).cyan()));
     
+    // -A adds the origin AS of every hop
     let output = Command::new("traceroute")
+        .arg("-A")
         .arg(target)
         .output()?;
     
@@ -21,5 +25,151 @@ pub fn perform_traceroute(spinner: &ProgressBar, target: &str, base_filename: &s
     let mut file = File::create(output_file)?;
     file.write_all(&output.stdout)?;
     
-    Ok(())
-}
\ No newline at end of file
+    Ok(parse_traceroute(target, &String::from_utf8_lossy(&output.stdout)))
+}
+
+// Parse traceroute output into hop records
+pub fn parse_traceroute(target: &str, output: &str) -> Trace {
+    let mut trace = Trace {
+        target: target.to_string(),
+        destination: None,
+        reached: false,
+        hops: Vec::new(),
+    };
+
+    for line in output.lines() {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+
+        // Header: traceroute to example.com (93.184.216.34), 30 hops max, 60 byte packets
+        if tokens.first() == Some(&"traceroute") {
+            trace.destination = tokens.iter()
+                .find(|t| t.starts_with('('))
+                .map(|t| t.trim_matches(|c| c == '(' || c == ')' || c == ',').to_string());
+            continue;
+        }
+
+        let Some(ttl) = tokens.first().and_then(|t| t.parse::<u8>().ok()) else {
+            continue;
+        };
+
+        let mut line_hops: Vec<Hop> = Vec::new();
+        let mut current: Option<usize> = None;
+        let mut i = 1;
+        while i < tokens.len() {
+            let token = tokens[i];
+            i += 1;
+
+            if token == "*" || token.starts_with('!') {
+                continue;
+            }
+
+            // [AS15169] or [*] when the AS is unknown
+            if token.starts_with('[') {
+                let asn = token.trim_matches(|c| c == '[' || c == ']');
+                if let Some(index) = current && asn != "*" {
+                    line_hops[index].asn = Some(asn.to_string());
+                }
+                continue;
+            }
+
+            // RTT, printed as "0.512 ms"
+            let rtt = match token.strip_suffix("ms") {
+                Some(value) if !value.is_empty() => value.parse::<f64>().ok(),
+                _ if tokens.get(i) == Some(&"ms") => token.parse::<f64>().ok(),
+                _ => None,
+            };
+            if let Some(rtt) = rtt {
+                if tokens.get(i) == Some(&"ms") {
+                    i += 1;
+                }
+                if let Some(index) = current {
+                    line_hops[index].rtts.push(rtt);
+                }
+                continue;
+            }
+
+            // A responder: "name (ip)" or a bare address with -n
+            let (hostname, address) = match tokens.get(i).filter(|t| t.starts_with('(')) {
+                Some(next) => {
+                    i += 1;
+                    let address = next.trim_matches(|c| c == '(' || c == ')').to_string();
+                    let hostname = (token != address).then(|| token.to_string());
+                    (hostname, address)
+                }
+                None if token.parse::<IpAddr>().is_ok() => (None, token.to_string()),
+                None => continue,
+            };
+
+            current = match line_hops.iter().position(|h| h.address.as_deref() == Some(address.as_str())) {
+                Some(index) => Some(index),
+                None => {
+                    line_hops.push(Hop {
+                        ttl,
+                        address: Some(address),
+                        hostname,
+                        asn: None,
+                        rtts: Vec::new(),
+                        geo: None,
+                    });
+                    Some(line_hops.len() - 1)
+                }
+            };
+        }
+
+        // Every probe timed out
+        if line_hops.is_empty() {
+            line_hops.push(Hop { ttl, address: None, hostname: None, asn: None, rtts: Vec::new(), geo: None });
+        }
+
+        trace.hops.extend(line_hops);
+    }
+
+    let destination = trace.destination.clone().unwrap_or_else(|| target.to_string());
+    trace.reached = trace.hops.iter().any(|h| h.address.as_deref() == Some(destination.as_str()));
+
+    trace
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = "traceroute to example.com (93.184.216.34), 30 hops max, 60 byte packets
+ 1  _gateway (192.168.1.1) [*]  0.512 ms  0.480 ms  0.470 ms
+ 2  * * *
+ 3  ae1.r01.example.net (198.51.100.1) [AS64500]  5.101 ms ae2.r02.example.net (198.51.100.2) [AS64500]  5.230 ms  5.300 ms
+ 4  10.9.9.9 (10.9.9.9) [*]  7.001 ms !H  * *
+ 5  93.184.216.34 (93.184.216.34) [AS15133]  10.2 ms  10.1 ms  10.3 ms
+";
+
+    fn hop_line(hop: &Hop) -> String {
+        format!("{} {} {} {} {:?}", hop.ttl, hop.address.as_deref().unwrap_or("*"), hop.hostname.as_deref().unwrap_or("-"),
+            hop.asn.as_deref().unwrap_or("-"), hop.rtts)
+    }
+
+    #[test]
+    fn hops_timeouts_load_balancing_and_as_numbers() {
+        let trace = parse_traceroute("example.com", OUTPUT);
+        assert_eq!(trace.destination.as_deref(), Some("93.184.216.34"));
+        assert!(trace.reached);
+        assert_eq!(trace.hops.iter().map(hop_line).collect::<Vec<_>>(), vec![
+            "1 192.168.1.1 _gateway - [0.512, 0.48, 0.47]",
+            "2 * - - []",
+            "3 198.51.100.1 ae1.r01.example.net AS64500 [5.101]",
+            "3 198.51.100.2 ae2.r02.example.net AS64500 [5.23, 5.3]",
+            "4 10.9.9.9 - - [7.001]",
+            "5 93.184.216.34 - AS15133 [10.2, 10.1, 10.3]",
+        ]);
+    }
+
+    #[test]
+    fn numeric_output_and_unreached_targets() {
+        let output = "traceroute to 203.0.113.9 (203.0.113.9), 30 hops max, 60 byte packets
+ 1  192.168.1.1  0.4 ms  0.3 ms  0.3 ms
+ 2  * * *
+";
+        let trace = parse_traceroute("203.0.113.9", output);
+        assert!(!trace.reached);
+        assert_eq!(trace.hops.iter().map(hop_line).collect::<Vec<_>>(), vec!["1 192.168.1.1 - - [0.4, 0.3, 0.3]", "2 * - - []"]);
+    }
+}
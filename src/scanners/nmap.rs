@@ -1,15 +1,19 @@
 use clap::ArgMatches;
 use console::style;
 use indicatif::MultiProgress;
+use quick_xml::events::Event;
+use quick_xml::reader::Reader;
+use std::collections::HashMap;
 use std::path::Path;
 use std::process::{Command, Output, Stdio};
 use std::error::Error;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::{BufRead, BufReader, Write};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Instant;
 
+use crate::models::{parse_port_ranges, Host, Port, ScanCoverage, Service, UnlistedPorts};
 use crate::ui::progress::create_spinner;
 
 // Run NMAP scan with specified options and animated progress with status updates
@@ -123,9 +127,8 @@ pub fn run_nmap_scan(mp: &MultiProgress, matches: &ArgMatches, target: &str, bas
     // Create a thread to handle stdout and update progress
     let stdout_thread = thread::spawn(move || {
         let mut progress_percent = 0;
-        let mut current_phase = String::new();
         
-        for line in stdout_reader.lines().flatten() {
+        for line in stdout_reader.lines().map_while(Result::ok) {
             // Write line to the output file
             if let Ok(mut file) = stdout_file_clone.lock() {
                 let _ = writeln!(file, "{}", line);
@@ -164,7 +167,7 @@ pub fn run_nmap_scan(mp: &MultiProgress, matches: &ArgMatches, target: &str, bas
             
             // Detect current task
             if line.contains("Initiating") {
-                current_phase = extract_current_task(&line);
+                let current_phase = extract_current_task(&line);
                 spinner_clone.set_message(format!(
                     "NMAP {} scan: {} | {}%", 
                     style(scan_type).yellow(),
@@ -229,10 +232,9 @@ pub fn run_nmap_scan(mp: &MultiProgress, matches: &ArgMatches, target: &str, bas
 
 // Helper function to extract percentage from NMAP output
 fn extract_percentage(line: &str) -> Option<u32> {
-    if let Some(pct_str) = line.split("About ").nth(1) {
-        if let Some(pct_num) = pct_str.split('%').next() {
-            return pct_num.trim().parse::<u32>().ok();
-        }
+    if let Some(pct_str) = line.split("About ").nth(1)
+        && let Some(pct_num) = pct_str.split('%').next() {
+        return pct_num.trim().parse::<u32>().ok();
     }
     None
 }
@@ -332,4 +334,164 @@ pub fn convert_nmap_to_html(base_filename: &str, scan_dir: &str) -> Result<(), B
     }
     
     Ok(())
-}
\ No newline at end of file
+}
+// Parse NMAP XML output (-oX) into hosts with their ports and services
+pub fn parse_nmap_xml(xml_file: &str) -> Result<Vec<Host>, Box<dyn Error>> {
+    let xml = fs::read_to_string(xml_file)?;
+    let mut reader = Reader::from_str(&xml);
+    reader.config_mut().trim_text(true);
+
+    let mut hosts = Vec::new();
+    let mut current: Option<Host> = None;
+    let mut port: Option<Port> = None;
+    // Ports probed per protocol (one <scaninfo> per scan type), and the current host's <extraports>
+    let mut scanned: Vec<(String, Vec<(u16, u16)>)> = Vec::new();
+    let mut unlisted: Vec<(Option<String>, UnlistedPorts)> = Vec::new();
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) => {
+                let mut attrs: HashMap<String, String> = HashMap::new();
+                for attr in e.attributes().flatten() {
+                    attrs.insert(String::from_utf8_lossy(attr.key.as_ref()).to_string(), attr.unescape_value()?.to_string());
+                }
+                let attr = |key: &str| attrs.get(key).filter(|v| !v.is_empty()).cloned();
+
+                match (e.name().as_ref(), current.as_mut()) {
+                    (b"scaninfo", None) => {
+                        let protocol = attr("protocol").unwrap_or_else(|| "tcp".to_string());
+                        let ranges = parse_port_ranges(&attr("services").unwrap_or_default());
+                        match scanned.iter_mut().find(|(p, _)| *p == protocol) {
+                            Some((_, known)) => known.extend(ranges),
+                            None => scanned.push((protocol, ranges)),
+                        }
+                    },
+                    (b"host", _) => {
+                        current = Some(Host::default());
+                        unlisted.clear();
+                    },
+                    (b"extraports", Some(_)) => {
+                        unlisted.push((None, UnlistedPorts {
+                            state: attr("state").unwrap_or_default(),
+                            count: attr("count").and_then(|c| c.parse().ok()).unwrap_or(0),
+                            ranges: Vec::new(),
+                        }));
+                    },
+                    // Nmap 7.80+ names the summarised ports and their protocol
+                    (b"extrareasons", Some(_)) => {
+                        if let Some((protocol, extra)) = unlisted.last_mut() {
+                            extra.ranges.extend(parse_port_ranges(&attr("ports").unwrap_or_default()));
+                            *protocol = attr("proto").or(protocol.take());
+                        }
+                    },
+                    (b"status", Some(host)) => {
+                        host.status = attr("state");
+                        host.reason = attr("reason");
+                    },
+                    (b"address", Some(host)) => {
+                        if attr("addrtype").as_deref() == Some("mac") {
+                            host.mac = attr("addr");
+                            host.vendor = attr("vendor");
+                        } else if host.address.is_empty() {
+                            host.address = attr("addr").unwrap_or_default();
+                        }
+                    },
+                    (b"hostname", Some(host)) => {
+                        if let Some(name) = attr("name") {
+                            host.hostnames.insert(name.to_lowercase());
+                        }
+                    },
+                    (b"osmatch", Some(host)) if host.os.is_none() => host.os = attr("name"),
+                    (b"port", Some(_)) => {
+                        port = Some(Port {
+                            protocol: attr("protocol").unwrap_or_else(|| "tcp".to_string()),
+                            port: attr("portid").and_then(|p| p.parse().ok()).unwrap_or(0),
+                            state: String::new(),
+                            service: None,
+                            source: "Nmap".to_string(),
+                            last_seen: None,
+                        });
+                    },
+                    (b"state", Some(_)) => {
+                        if let Some(port) = port.as_mut() {
+                            port.state = attr("state").unwrap_or_default();
+                        }
+                    },
+                    (b"service", Some(_)) => {
+                        if let Some(port) = port.as_mut() {
+                            port.service = Some(Service {
+                                name: attr("name").unwrap_or_default(),
+                                product: attr("product"),
+                                version: attr("version"),
+                                extra_info: attr("extrainfo"),
+                                ..Default::default()
+                            });
+                        }
+                    },
+                    _ => {}
+                }
+            },
+            Event::End(e) => match e.name().as_ref() {
+                b"port" => {
+                    if let (Some(port), Some(host)) = (port.take(), current.as_mut()) {
+                        host.ports.push(port);
+                    }
+                },
+                b"host" => {
+                    if let Some(mut host) = current.take()
+                        && !host.address.is_empty() {
+                        host.coverage = scanned.iter().map(|(protocol, ranges)| ScanCoverage {
+                            source: "Nmap".to_string(),
+                            protocol: protocol.clone(),
+                            ranges: ranges.clone(),
+                            // Without a protocol the summary applies to a single-protocol scan
+                            unlisted: unlisted.iter()
+                                .filter(|(p, _)| p.as_deref().is_none_or(|p| p == protocol))
+                                .map(|(_, extra)| extra.clone())
+                                .collect(),
+                        }).collect();
+                        hosts.push(host);
+                    }
+                },
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(hosts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn records_scan_coverage_and_extraports() {
+        let xml = r#"<?xml version="1.0"?>
+<nmaprun scanner="nmap" version="7.94">
+<scaninfo type="syn" protocol="tcp" numservices="5" services="21-23,80,443"/>
+<host><status state="up" reason="syn-ack"/><address addr="192.0.2.1" addrtype="ipv4"/>
+<ports>
+<extraports state="closed" count="3"><extrareasons reason="reset" count="3" proto="tcp" ports="21,23,443"/></extraports>
+<port protocol="tcp" portid="22"><state state="open" reason="syn-ack"/><service name="ssh"/></port>
+<port protocol="tcp" portid="80"><state state="filtered" reason="no-response"/></port>
+</ports></host>
+</nmaprun>"#;
+        let path = env::temp_dir().join(format!("rust_recon_nmap_{}.xml", std::process::id()));
+        fs::write(&path, xml).unwrap();
+        let hosts = parse_nmap_xml(path.to_str().unwrap()).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(hosts.len(), 1);
+        let coverage = &hosts[0].coverage;
+        assert_eq!(coverage.len(), 1);
+        assert_eq!(coverage[0].protocol, "tcp");
+        assert_eq!(coverage[0].ranges, vec![(21, 23), (80, 80), (443, 443)]);
+        assert!(coverage[0].covers(443) && !coverage[0].covers(8080));
+        assert_eq!(coverage[0].unlisted_state(21).as_deref(), Some("closed"));
+        assert_eq!(hosts[0].ports.iter().map(|p| (p.port, p.state.as_str())).collect::<Vec<_>>(), vec![(22, "open"), (80, "filtered")]);
+    }
+}
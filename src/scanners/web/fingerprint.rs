@@ -0,0 +1,565 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use console::style;
+use indicatif::ProgressBar;
+use regex::Regex;
+use reqwest::Url;
+use reqwest::blocking::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::Write;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::models::Technology;
+use crate::utils::fs::ResourceManager;
+
+// Name of the signature file looked up through the ResourceManager
+const SIGNATURE_FILE: &str = "web_fingerprints.json";
+
+// Identify web technologies natively (no WhatWeb) and return the technology inventory
+pub fn perform_fingerprint(spinner: &ProgressBar, target: &str, signature_file: Option<&str>, base_filename: &str, scan_dir: &str) -> Result<Vec<Technology>, Box<dyn Error>> {
+    let output_file = format!("{}/{}_fingerprint.json", scan_dir, base_filename);
+
+    // Prepare target
+    let target_url = if target.starts_with("http://") || target.starts_with("https://") {
+        target.to_string()
+    } else {
+        format!("http://{}", target)
+    };
+
+    spinner.set_message(format!("Loading web fingerprint signatures for {}...", style(&target_url).cyan()));
+    let signatures = shared_signatures(signature_file)?;
+
+    spinner.set_message(format!("Fetching {} for fingerprinting...", style(&target_url).cyan()));
+    let client = Client::builder()
+        .timeout(Duration::from_secs(15))
+        .danger_accept_invalid_certs(true)
+        .user_agent("Mozilla/5.0 (X11; Linux x86_64) RustRecon")
+        .build()?;
+    let page = fetch_page(&client, &target_url)?;
+
+    spinner.set_message(format!("Matching {} signatures against {}...", signatures.technologies.len(), style(&page.url).cyan()));
+    let technologies = signatures.identify(&page);
+
+    let mut file = File::create(output_file)?;
+    file.write_all(serde_json::to_string_pretty(&technologies)?.as_bytes())?;
+
+    Ok(technologies)
+}
+
+// Everything collected from a page that signatures can match against
+pub struct FetchedPage {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub cookies: Vec<(String, String)>,
+    pub meta: Vec<(String, String)>,
+    pub scripts: Vec<String>,
+    pub html: String,
+    pub favicon_hash: Option<i32>,
+}
+
+// Fetch a page (following redirects) plus its favicon
+pub fn fetch_page(client: &Client, url: &str) -> Result<FetchedPage, Box<dyn Error>> {
+    let response = client.get(url).send()?;
+    let final_url = response.url().clone();
+
+    let mut headers = Vec::new();
+    let mut cookies = Vec::new();
+    for (name, value) in response.headers() {
+        let value = String::from_utf8_lossy(value.as_bytes()).to_string();
+        if name.as_str() == "set-cookie"
+            && let Some((cookie_name, rest)) = value.split_once('=') {
+            let cookie_value = rest.split(';').next().unwrap_or("").to_string();
+            cookies.push((cookie_name.trim().to_string(), cookie_value));
+        }
+        headers.push((name.as_str().to_string(), value));
+    }
+    let html = response.text().unwrap_or_default();
+
+    let meta = extract_meta_tags(&html);
+    let scripts = extract_script_sources(&html);
+
+    // Prefer the icon declared in the page, fall back to /favicon.ico
+    let favicon_url = extract_favicon_link(&html)
+        .and_then(|href| final_url.join(&href).ok())
+        .or_else(|| final_url.join("/favicon.ico").ok());
+    let favicon_hash = favicon_url.and_then(|icon| fetch_favicon_hash(client, &icon));
+
+    Ok(FetchedPage {
+        url: final_url.to_string(),
+        headers,
+        cookies,
+        meta,
+        scripts,
+        html,
+        favicon_hash,
+    })
+}
+
+// Download a favicon and compute its Shodan-style hash
+fn fetch_favicon_hash(client: &Client, url: &Url) -> Option<i32> {
+    let response = client.get(url.clone()).send().ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let bytes = response.bytes().ok()?;
+    if bytes.is_empty() {
+        return None;
+    }
+    Some(favicon_hash(&bytes))
+}
+
+// Shodan's http.favicon.hash: MurmurHash3 (x86, 32 bit) of the MIME-style base64 of the icon
+pub fn favicon_hash(data: &[u8]) -> i32 {
+    let encoded = BASE64.encode(data);
+    let mut wrapped = String::with_capacity(encoded.len() + encoded.len() / 76 + 1);
+    for chunk in encoded.as_bytes().chunks(76) {
+        wrapped.push_str(&String::from_utf8_lossy(chunk));
+        wrapped.push('\n');
+    }
+    murmur3_32(wrapped.as_bytes(), 0) as i32
+}
+
+// MurmurHash3 x86_32
+fn murmur3_32(data: &[u8], seed: u32) -> u32 {
+    const C1: u32 = 0xcc9e2d51;
+    const C2: u32 = 0x1b873593;
+    let mut hash = seed;
+
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        let mut k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        hash ^= k;
+        hash = hash.rotate_left(13).wrapping_mul(5).wrapping_add(0xe6546b64);
+    }
+
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        let mut k: u32 = 0;
+        for (i, byte) in tail.iter().enumerate() {
+            k |= (*byte as u32) << (8 * i);
+        }
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        hash ^= k;
+    }
+
+    hash ^= data.len() as u32;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85ebca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2ae35);
+    hash ^= hash >> 16;
+    hash
+}
+
+// Compile a page-parsing regex on first use
+fn cached_regex(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).unwrap())
+}
+
+// Collect (name, content) pairs from <meta> tags (name, property and http-equiv keys)
+fn extract_meta_tags(html: &str) -> Vec<(String, String)> {
+    static TAG: OnceLock<Regex> = OnceLock::new();
+    static KEY: OnceLock<Regex> = OnceLock::new();
+    static CONTENT: OnceLock<Regex> = OnceLock::new();
+    let tag_re = cached_regex(&TAG, r"(?is)<meta\s[^>]*>");
+    // Attribute names must follow whitespace so data-name= and similar don't match
+    let key_re = cached_regex(&KEY, r#"(?is)\s(?:name|property|http-equiv)\s*=\s*["']([^"']+)["']"#);
+    let content_re = cached_regex(&CONTENT, r#"(?is)\scontent\s*=\s*["']([^"']*)["']"#);
+
+    tag_re.find_iter(html)
+        .filter_map(|tag| {
+            let key = key_re.captures(tag.as_str())?.get(1)?.as_str().to_lowercase();
+            let content = content_re.captures(tag.as_str())?.get(1)?.as_str().to_string();
+            Some((key, content))
+        })
+        .collect()
+}
+
+// Collect the src attribute of every <script> tag
+fn extract_script_sources(html: &str) -> Vec<String> {
+    static SCRIPT: OnceLock<Regex> = OnceLock::new();
+    let script_re = cached_regex(&SCRIPT, r#"(?is)<script[^>]+src\s*=\s*["']([^"']+)["']"#);
+    script_re.captures_iter(html)
+        .filter_map(|c| c.get(1).map(|m| m.as_str().to_string()))
+        .collect()
+}
+
+// Find the href of the first <link rel="...icon..."> tag
+fn extract_favicon_link(html: &str) -> Option<String> {
+    static LINK: OnceLock<Regex> = OnceLock::new();
+    static HREF: OnceLock<Regex> = OnceLock::new();
+    let link_re = cached_regex(&LINK, r#"(?is)<link[^>]+rel\s*=\s*["'][^"']*icon[^"']*["'][^>]*>"#);
+    let href_re = cached_regex(&HREF, r#"(?is)\shref\s*=\s*["']([^"']+)["']"#);
+    let tag = link_re.find(html)?;
+    Some(href_re.captures(tag.as_str())?.get(1)?.as_str().to_string())
+}
+
+// A single Wappalyzer-style pattern: a regex plus an optional version template (e.g. "\1")
+struct Pattern {
+    regex: Regex,
+    version: Option<String>,
+}
+
+impl Pattern {
+    // Parse "regex\;version:\1\;confidence:50"; patterns Rust's regex engine can't compile are skipped
+    fn parse(raw: &str) -> Option<Pattern> {
+        let mut parts = raw.split("\\;");
+        let expression = parts.next().unwrap_or("");
+        let version = parts
+            .filter_map(|part| part.strip_prefix("version:"))
+            .map(|v| v.to_string())
+            .next();
+        let regex = Regex::new(&format!("(?i){}", expression)).ok()?;
+        Some(Pattern { regex, version })
+    }
+
+    // Match the pattern, returning the resolved version (if any) on success
+    fn matches(&self, value: &str) -> Option<Option<String>> {
+        let captures = self.regex.captures(value)?;
+        let version = self.version.as_ref().map(|template| {
+            let mut version = template.clone();
+            for i in (1..captures.len()).rev() {
+                let group = captures.get(i).map(|m| m.as_str()).unwrap_or("");
+                version = version.replace(&format!("\\{}", i), group);
+            }
+            version
+        }).filter(|v| !v.is_empty());
+        Some(version)
+    }
+}
+
+// Signatures for one technology
+struct Signature {
+    name: String,
+    category: String,
+    headers: Vec<(String, Pattern)>,
+    cookies: Vec<(String, Pattern)>,
+    meta: Vec<(String, Pattern)>,
+    script_src: Vec<Pattern>,
+    html: Vec<Pattern>,
+    favicon: Vec<i32>,
+    implies: Vec<String>,
+}
+
+// The full signature database
+pub struct SignatureSet {
+    technologies: Vec<Signature>,
+}
+
+// Load signatures from an explicit file, an installed/updated copy, or the bundled default
+pub fn load_signatures(signature_file: Option<&str>) -> Result<SignatureSet, Box<dyn Error>> {
+    let content = match signature_file {
+        Some(path) => fs::read_to_string(path)?,
+        None => match ResourceManager::get_resource_path(SIGNATURE_FILE) {
+            Some(path) => fs::read_to_string(path)?,
+            None => crate::utils::WEB_FINGERPRINTS.to_string(),
+        },
+    };
+    SignatureSet::from_json(&content)
+}
+
+// Loaded once per process, so pipeline scans reuse the compiled patterns for every host
+pub fn shared_signatures(signature_file: Option<&str>) -> Result<&'static SignatureSet, Box<dyn Error>> {
+    static SIGNATURES: OnceLock<Result<SignatureSet, String>> = OnceLock::new();
+    SIGNATURES.get_or_init(|| load_signatures(signature_file).map_err(|e| e.to_string()))
+        .as_ref()
+        .map_err(|e| e.clone().into())
+}
+
+impl SignatureSet {
+    // Build the signature set from Wappalyzer-style JSON ({"categories": {...}, "technologies": {...}})
+    pub fn from_json(content: &str) -> Result<SignatureSet, Box<dyn Error>> {
+        let json: Value = serde_json::from_str(content)?;
+        let categories: HashMap<String, String> = json.get("categories")
+            .and_then(Value::as_object)
+            .map(|cats| cats.iter()
+                .filter_map(|(id, cat)| Some((id.clone(), cat.get("name")?.as_str()?.to_string())))
+                .collect())
+            .unwrap_or_default();
+        let technologies = json.get("technologies")
+            .and_then(Value::as_object)
+            .ok_or("Signature file has no \"technologies\" object")?;
+
+        let mut signatures = Vec::new();
+        for (name, tech) in technologies {
+            let category = tech.get("cats")
+                .and_then(Value::as_array)
+                .and_then(|cats| cats.first())
+                .map(|id| id.to_string())
+                .and_then(|id| categories.get(&id).cloned())
+                .unwrap_or_else(|| "Other".to_string());
+
+            signatures.push(Signature {
+                name: name.clone(),
+                category,
+                headers: keyed_patterns(tech.get("headers")),
+                cookies: keyed_patterns(tech.get("cookies")),
+                meta: keyed_patterns(tech.get("meta")),
+                script_src: patterns(tech.get("scriptSrc")),
+                html: patterns(tech.get("html")),
+                favicon: tech.get("favicon")
+                    .and_then(Value::as_array)
+                    .map(|hashes| hashes.iter().filter_map(|h| h.as_i64()).map(|h| h as i32).collect())
+                    .unwrap_or_default(),
+                implies: strings(tech.get("implies")),
+            });
+        }
+
+        Ok(SignatureSet { technologies: signatures })
+    }
+
+    // Match every signature against a fetched page, then add implied technologies
+    pub fn identify(&self, page: &FetchedPage) -> Vec<Technology> {
+        let mut found: Vec<Technology> = Vec::new();
+
+        for signature in &self.technologies {
+            let mut evidence = Vec::new();
+            let mut version: Option<String> = None;
+            let mut record = |hit: Option<Option<String>>, what: String| {
+                if let Some(matched_version) = hit {
+                    evidence.push(what);
+                    if version.is_none() {
+                        version = matched_version;
+                    }
+                }
+            };
+
+            for (name, pattern) in &signature.headers {
+                for (header, value) in page.headers.iter().filter(|(h, _)| h.eq_ignore_ascii_case(name)) {
+                    record(pattern.matches(value), format!("header {}: {}", header, value));
+                }
+            }
+            for (name, pattern) in &signature.cookies {
+                // Cookie names may be prefixes (e.g. "NSC_")
+                for (cookie, value) in page.cookies.iter().filter(|(c, _)| c.eq_ignore_ascii_case(name) || (name.ends_with('_') && c.starts_with(name.as_str()))) {
+                    record(pattern.matches(value), format!("cookie {}", cookie));
+                }
+            }
+            for (name, pattern) in &signature.meta {
+                for (meta, content) in page.meta.iter().filter(|(m, _)| m.eq_ignore_ascii_case(name)) {
+                    record(pattern.matches(content), format!("meta {}: {}", meta, content));
+                }
+            }
+            for pattern in &signature.script_src {
+                for script in &page.scripts {
+                    record(pattern.matches(script), format!("script {}", script));
+                }
+            }
+            for pattern in &signature.html {
+                if let Some(hit) = pattern.matches(&page.html) {
+                    let snippet: String = pattern.regex.find(&page.html).map(|m| m.as_str().chars().take(80).collect()).unwrap_or_default();
+                    record(Some(hit), format!("html {}", snippet));
+                }
+            }
+            if let Some(hash) = page.favicon_hash
+                && signature.favicon.contains(&hash) {
+                record(Some(None), format!("favicon hash {}", hash));
+            }
+
+            if !evidence.is_empty() {
+                found.push(Technology {
+                    source: "Fingerprint".to_string(),
+                    url: page.url.clone(),
+                    product: signature.name.clone(),
+                    version,
+                    category: signature.category.clone(),
+                    evidence,
+                });
+            }
+        }
+
+        // Resolve "implies" transitively (e.g. WordPress -> PHP)
+        let mut index = 0;
+        while index < found.len() {
+            let implied: Vec<String> = self.technologies.iter()
+                .find(|s| s.name == found[index].product)
+                .map(|s| s.implies.clone())
+                .unwrap_or_default();
+            for name in implied {
+                if found.iter().any(|t| t.product == name) {
+                    continue;
+                }
+                let category = self.technologies.iter()
+                    .find(|s| s.name == name)
+                    .map(|s| s.category.clone())
+                    .unwrap_or_else(|| "Other".to_string());
+                let evidence = vec![format!("implied by {}", found[index].product)];
+                found.push(Technology {
+                    source: "Fingerprint".to_string(),
+                    url: page.url.clone(),
+                    product: name,
+                    version: None,
+                    category,
+                    evidence,
+                });
+            }
+            index += 1;
+        }
+
+        found
+    }
+}
+
+// Patterns given as a string or an array of strings
+fn patterns(value: Option<&Value>) -> Vec<Pattern> {
+    strings(value).iter().filter_map(|p| Pattern::parse(p)).collect()
+}
+
+// Patterns keyed by header/cookie/meta name
+fn keyed_patterns(value: Option<&Value>) -> Vec<(String, Pattern)> {
+    let mut keyed = Vec::new();
+    if let Some(map) = value.and_then(Value::as_object) {
+        for (key, raw) in map {
+            for pattern in patterns(Some(raw)) {
+                keyed.push((key.clone(), pattern));
+            }
+        }
+    }
+    keyed
+}
+
+// A string or an array of strings
+fn strings(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::String(s)) => vec![s.clone()],
+        Some(Value::Array(values)) => values.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIGNATURES: &str = r#"{
+        "categories": {"1": {"name": "CMS"}, "12": {"name": "JavaScript frameworks"}, "22": {"name": "Web servers"},
+            "27": {"name": "Programming languages"}, "34": {"name": "Databases"}, "62": {"name": "PaaS"}},
+        "technologies": {
+            "WordPress": {"cats": [1], "meta": {"generator": "^WordPress(?: ([\\d.]+))?\\;version:\\1"},
+                "html": "<link rel=[\"']stylesheet[\"'] [^>]+/wp-(?:content|includes)/", "implies": ["PHP", "MySQL"]},
+            "PHP": {"cats": [27], "headers": {"X-Powered-By": "^php/?([\\d.]+)?\\;version:\\1"}, "cookies": {"PHPSESSID": ""}},
+            "MySQL": {"cats": [34]},
+            "jQuery": {"cats": [12], "scriptSrc": ["jquery(?:-|\\.min\\.)?([\\d.]+)?(?:\\.min)?\\.js\\;version:\\1\\;confidence:50"]},
+            "Nginx": {"cats": [22], "headers": {"Server": "nginx(?:/([\\d.]+))?\\;version:\\1"}},
+            "Citrix ADC": {"cats": [62], "cookies": {"NSC_": ""}},
+            "Jenkins": {"cats": [1], "favicon": [81586312], "implies": "Java"},
+            "Lookahead": {"cats": [1], "html": "(?=unsupported)"}
+        }
+    }"#;
+
+    fn page(headers: &[(&str, &str)], cookies: &[(&str, &str)], html: &str, favicon_hash: Option<i32>) -> FetchedPage {
+        let pairs = |values: &[(&str, &str)]| values.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        FetchedPage {
+            url: "https://www.example.com/".to_string(),
+            headers: pairs(headers),
+            cookies: pairs(cookies),
+            meta: extract_meta_tags(html),
+            scripts: extract_script_sources(html),
+            html: html.to_string(),
+            favicon_hash,
+        }
+    }
+
+    fn found(technologies: &[Technology]) -> Vec<String> {
+        technologies.iter()
+            .map(|t| format!("{} {} [{}]", t.product, t.version.as_deref().unwrap_or("-"), t.category))
+            .collect()
+    }
+
+    #[test]
+    fn murmur3_matches_reference_vectors() {
+        // Reference values of MurmurHash3_x86_32, as used by Python's mmh3 (mmh3.hash("foo") == -156908512)
+        assert_eq!(murmur3_32(b"", 0), 0);
+        assert_eq!(murmur3_32(b"", 1), 0x514e28b7);
+        assert_eq!(murmur3_32(&[0, 0, 0, 0], 0), 0x2362f9de);
+        assert_eq!(murmur3_32(b"a", 0x9747b28c), 0x7fa09ea6);
+        assert_eq!(murmur3_32(b"Hello, world!", 0x9747b28c), 0x24884cba);
+        assert_eq!(murmur3_32(b"The quick brown fox jumps over the lazy dog", 0x9747b28c), 0x2fa826cd);
+        assert_eq!(murmur3_32(b"foo", 0) as i32, -156908512);
+    }
+
+    #[test]
+    fn favicon_hash_uses_mime_base64() {
+        // Shodan hashes base64.encodebytes(icon): 76 characters per line, each ending in a newline
+        let icon: Vec<u8> = (0..100).collect();
+        let encoded = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4\n\
+            OTo7PD0+P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5fYGFiYw==\n";
+        assert_eq!(favicon_hash(&icon), murmur3_32(encoded.as_bytes(), 0) as i32);
+        assert_ne!(favicon_hash(&icon), murmur3_32(BASE64.encode(&icon).as_bytes(), 0) as i32);
+    }
+
+    #[test]
+    fn patterns_resolve_version_templates() {
+        let jquery = Pattern::parse(r"jquery(?:-|\.min\.)?([\d.]+)?(?:\.min)?\.js\;version:\1\;confidence:50").unwrap();
+        assert_eq!(jquery.matches("/static/JQuery-3.6.0.min.js"), Some(Some("3.6.0".to_string())));
+        // A template whose group did not participate yields no version, but still a match
+        assert_eq!(jquery.matches("/static/jquery.js"), Some(None));
+        assert_eq!(jquery.matches("/static/app.js"), None);
+
+        let swapped = Pattern::parse(r"(\d+)\.(\d+)\;version:\2.\1").unwrap();
+        assert_eq!(swapped.matches("v4.12"), Some(Some("12.4".to_string())));
+        let fixed = Pattern::parse(r"X-Generator\;version:2").unwrap();
+        assert_eq!(fixed.matches("x-generator"), Some(Some("2".to_string())));
+
+        // Look-around is not supported by the regex crate; such patterns are dropped
+        assert!(Pattern::parse("(?=unsupported)").is_none());
+    }
+
+    #[test]
+    fn meta_keys_need_an_attribute_boundary() {
+        let html = r#"<meta data-name="generator" content="Hugo"><meta property="og:site_name" data-content="x" content="Example">
+            <META NAME='Generator' CONTENT='WordPress 6.4.2'>"#;
+        assert_eq!(extract_meta_tags(html), vec![
+            ("og:site_name".to_string(), "Example".to_string()),
+            ("generator".to_string(), "WordPress 6.4.2".to_string()),
+        ]);
+        let link = r#"<link data-href="/x.png" rel="shortcut icon" href="/static/favicon.png">"#;
+        assert_eq!(extract_favicon_link(link).as_deref(), Some("/static/favicon.png"));
+    }
+
+    #[test]
+    fn canned_page_is_identified() {
+        let signatures = SignatureSet::from_json(SIGNATURES).unwrap();
+        let html = r#"<html><head>
+            <meta name="generator" content="WordPress 6.4.2">
+            <link rel='stylesheet' href='https://www.example.com/wp-content/themes/site/style.css'>
+            <script src="/wp-includes/js/jquery/jquery.min.js?ver=3.7.1"></script>
+            <script src="https://code.jquery.com/jquery-3.7.1.min.js"></script>
+            </head></html>"#;
+        let fetched = page(&[("server", "nginx/1.24.0"), ("x-powered-by", "PHP/8.2.13")], &[("NSC_TMAS", "ffffffff0951"), ("PHPSESSID", "abc")], html, None);
+        let technologies = signatures.identify(&fetched);
+
+        assert_eq!(found(&technologies), vec![
+            "Citrix ADC - [PaaS]",
+            "Nginx 1.24.0 [Web servers]",
+            "PHP 8.2.13 [Programming languages]",
+            "WordPress 6.4.2 [CMS]",
+            "jQuery 3.7.1 [JavaScript frameworks]",
+            "MySQL - [Databases]",
+        ]);
+        let evidence = |product: &str| technologies.iter().find(|t| t.product == product).unwrap().evidence.clone();
+        assert_eq!(evidence("WordPress")[0], "meta generator: WordPress 6.4.2");
+        assert!(evidence("WordPress")[1].starts_with("html <link rel='stylesheet' href='https://www.example.com/wp-content/"));
+        assert_eq!(evidence("PHP"), vec!["header x-powered-by: PHP/8.2.13", "cookie PHPSESSID"]);
+        assert_eq!(evidence("MySQL"), vec!["implied by WordPress"]);
+        assert!(technologies.iter().all(|t| t.source == "Fingerprint" && t.url == "https://www.example.com/"));
+    }
+
+    #[test]
+    fn favicon_matches_and_unknown_implied_technologies() {
+        let signatures = SignatureSet::from_json(SIGNATURES).unwrap();
+        let technologies = signatures.identify(&page(&[], &[], "<html></html>", Some(81586312)));
+        assert_eq!(found(&technologies), vec!["Jenkins - [CMS]", "Java - [Other]"]);
+        assert_eq!(technologies[0].evidence, vec!["favicon hash 81586312"]);
+
+        assert!(signatures.identify(&page(&[], &[], "<html></html>", Some(1))).is_empty());
+        assert!(SignatureSet::from_json(r#"{"categories": {}}"#).is_err());
+        // The bundled database loads
+        assert!(!SignatureSet::from_json(crate::utils::WEB_FINGERPRINTS).unwrap().technologies.is_empty());
+    }
+}
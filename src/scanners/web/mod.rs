@@ -2,8 +2,10 @@ pub mod gobuster;
 pub mod nikto;
 pub mod whatweb;
 pub mod ssl;
+pub mod fingerprint;
 
 pub use gobuster::*;
 pub use nikto::*;
 pub use whatweb::*;
-pub use ssl::*;
\ No newline at end of file
+pub use ssl::*;
+pub use fingerprint::*;
\ No newline at end of file
@@ -1,31 +1,41 @@
 use console::style;
 use indicatif::ProgressBar;
+use quick_xml::events::Event;
+use quick_xml::reader::Reader;
 use std::process::Command;
 use std::error::Error;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::Write;
 
-// Perform Nikto web scan with spinner
-pub fn perform_nikto(spinner: &ProgressBar, target: &str, base_filename: &str, scan_dir: &str) -> Result<(), Box<dyn Error>> {
-    let output_file = format!("{}/{}_nikto.txt", scan_dir, base_filename);
-    
+use crate::models::{Finding, Severity};
+
+// Perform Nikto web scan with spinner and return the normalised findings
+pub fn perform_nikto(spinner: &ProgressBar, target: &str, base_filename: &str, scan_dir: &str) -> Result<Vec<Finding>, Box<dyn Error>> {
+    let output_file = format!("{}/{}_nikto.xml", scan_dir, base_filename);
+    let text_file = format!("{}/{}_nikto.txt", scan_dir, base_filename);
+
     // Update spinner with status
     spinner.set_message(format!("Preparing Nikto scan for {}...", style(target).cyan()));
-    
+
     // Prepare target
     let target_url = if target.starts_with("http://") || target.starts_with("https://") {
         target.to_string()
     } else {
         format!("http://{}", target)
     };
-    
+
     spinner.set_message(format!("Running comprehensive Nikto vulnerability scan on {}...", style(&target_url).cyan()));
-    
+
     let output = Command::new("nikto")
         .arg("-h").arg(&target_url)
         .arg("-o").arg(&output_file)
+        .arg("-Format").arg("xml")
         .output()?;
-    
+
+    // Keep the human readable console output next to the XML
+    let mut file = File::create(text_file)?;
+    file.write_all(&output.stdout)?;
+
     // If there was an error, save stderr
     if !output.status.success() {
         let error_file = format!("{}/{}_nikto_error.txt", scan_dir, base_filename);
@@ -33,6 +43,221 @@ pub fn perform_nikto(spinner: &ProgressBar, target: &str, base_filename: &str, s
         file.write_all(&output.stderr)?;
         return Err(format!("Nikto errors - see {} for details", error_file).into());
     }
-    
-    Ok(())
-}
\ No newline at end of file
+
+    spinner.set_message(format!("Parsing Nikto findings for {}...", style(&target_url).cyan()));
+    let xml = fs::read_to_string(&output_file)?;
+    parse_nikto_xml(&xml)
+}
+
+// Parse Nikto XML output (-Format xml) into normalised findings
+pub fn parse_nikto_xml(xml: &str) -> Result<Vec<Finding>, Box<dyn Error>> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut findings = Vec::new();
+    let mut current: Option<NiktoItem> = None;
+    let mut field = String::new();
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => {
+                let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                if name == "item" {
+                    let mut item = NiktoItem::default();
+                    for attr in e.attributes().flatten() {
+                        let value = attr.unescape_value()?.to_string();
+                        match attr.key.as_ref() {
+                            b"id" => item.id = value,
+                            b"osvdbid" => item.osvdb = value,
+                            b"method" => item.method = value,
+                            _ => {}
+                        }
+                    }
+                    current = Some(item);
+                } else if current.is_some() {
+                    field = name;
+                    text.clear();
+                }
+            },
+            Event::Text(e) if current.is_some() => {
+                text.push_str(&e.unescape()?);
+            },
+            Event::CData(e) if current.is_some() => {
+                text.push_str(&String::from_utf8_lossy(&e));
+            },
+            Event::End(e) => {
+                if e.name().as_ref() == b"item" {
+                    if let Some(item) = current.take() {
+                        findings.push(item.into_finding());
+                    }
+                } else if let Some(item) = current.as_mut() {
+                    let value = text.trim().to_string();
+                    match field.as_str() {
+                        "description" => item.description = value,
+                        "uri" => item.uri = value,
+                        "namelink" => item.namelink = value,
+                        "references" => item.references = value,
+                        _ => {}
+                    }
+                    field.clear();
+                    text.clear();
+                }
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(findings)
+}
+
+// Raw values of a single <item> element
+#[derive(Default)]
+struct NiktoItem {
+    id: String,
+    osvdb: String,
+    method: String,
+    description: String,
+    uri: String,
+    namelink: String,
+    references: String,
+}
+
+impl NiktoItem {
+    fn into_finding(self) -> Finding {
+        let mut references = Vec::new();
+        if !self.osvdb.is_empty() && self.osvdb != "0" {
+            references.push(format!("OSVDB-{}", self.osvdb));
+        }
+        for cve in extract_cves(&format!("{} {}", self.description, self.references)) {
+            if !references.contains(&cve) {
+                references.push(cve);
+            }
+        }
+        for link in self.references.split_whitespace().map(|l| l.trim_end_matches(',')) {
+            if link.starts_with("http") && !references.iter().any(|r| r == link) {
+                references.push(link.to_string());
+            }
+        }
+
+        let severity = nikto_severity(&self.description, &references);
+        let location = if !self.namelink.is_empty() { self.namelink } else { self.uri };
+
+        Finding {
+            source: "Nikto".to_string(),
+            id: if self.id.is_empty() { None } else { Some(self.id) },
+            severity,
+            location,
+            method: if self.method.is_empty() { None } else { Some(self.method) },
+            description: self.description,
+            references,
+        }
+    }
+}
+
+// Map a Nikto finding onto a severity using its description and references
+fn nikto_severity(description: &str, references: &[String]) -> Severity {
+    let desc = description.to_lowercase();
+    let matches_any = |keywords: &[&str]| keywords.iter().any(|k| desc.contains(k));
+
+    if matches_any(&["remote command execution", "remote code execution", "command injection", "arbitrary command", "backdoor", "shellshock"]) {
+        Severity::Critical
+    } else if references.iter().any(|r| r.starts_with("CVE-"))
+        || matches_any(&["sql injection", "directory traversal", "arbitrary file", "file inclusion", "default account", "default password", "password file"]) {
+        Severity::High
+    } else if matches_any(&["cross-site scripting", "xss", "directory indexing", "backup", "phpinfo", "admin", "config", "http trace", "http track", "put method", "outdated", "webdav"]) {
+        Severity::Medium
+    } else if matches_any(&["header is not present", "header is not defined", "anti-clickjacking", "x-content-type-options", "httponly", "secure flag", "uncommon header", "inode", "allowed http methods"]) {
+        Severity::Low
+    } else {
+        Severity::Info
+    }
+}
+
+// Pull CVE identifiers (CVE-YYYY-NNNN) out of free text
+pub fn extract_cves(text: &str) -> Vec<String> {
+    let mut cves = Vec::new();
+    let upper = text.to_uppercase();
+    for (idx, _) in upper.match_indices("CVE-") {
+        let rest = &upper[idx + 4..];
+        let year: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+        if year.len() != 4 || !rest[4..].starts_with('-') {
+            continue;
+        }
+        let number: String = rest[5..].chars().take_while(|c| c.is_ascii_digit()).collect();
+        if number.len() >= 4 {
+            let cve = format!("CVE-{}-{}", year, number);
+            if !cves.contains(&cve) {
+                cves.push(cve);
+            }
+        }
+    }
+    cves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPORT: &str = r#"<?xml version="1.0" ?>
+<niktoscan>
+<scandetails targetip="192.0.2.5" targethostname="www.example.com" targetport="80">
+<item id="999986" osvdbid="0" osvdblink="" method="GET">
+<description><![CDATA[/: The anti-clickjacking X-Frame-Options header is not present.]]></description>
+<uri><![CDATA[/]]></uri>
+<namelink><![CDATA[http://www.example.com:80/]]></namelink>
+<iplink><![CDATA[http://192.0.2.5:80/]]></iplink>
+<references><![CDATA[https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/X-Frame-Options]]></references>
+</item>
+<item id="000428" osvdbid="3233" method="GET">
+<description>/cgi-bin/test.cgi: Site appears vulnerable to the 'shellshock' vulnerability (CVE-2014-6271, cve-2014-6278).</description>
+<uri>/cgi-bin/test.cgi</uri>
+<namelink></namelink>
+<references>https://nvd.nist.gov/vuln/detail/CVE-2014-6271, https://nvd.nist.gov/vuln/detail/CVE-2014-6271</references>
+</item>
+<item id="" osvdbid="" method="">
+<description>Server banner has changed from &apos;Apache&apos; to &apos;nginx&apos;.</description>
+<uri></uri>
+</item>
+</scandetails>
+</niktoscan>"#;
+
+    #[test]
+    fn items_become_findings() {
+        let findings = parse_nikto_xml(REPORT).unwrap();
+        assert_eq!(findings.len(), 3);
+
+        let header = &findings[0];
+        assert_eq!(header.id.as_deref(), Some("999986"));
+        assert_eq!(header.method.as_deref(), Some("GET"));
+        assert_eq!(header.location, "http://www.example.com:80/");
+        assert_eq!(header.severity, Severity::Low);
+        assert_eq!(header.references, vec!["https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/X-Frame-Options"]);
+
+        // Without a namelink the URI is the location; CVEs and links are collected once each
+        let shellshock = &findings[1];
+        assert_eq!(shellshock.location, "/cgi-bin/test.cgi");
+        assert_eq!(shellshock.severity, Severity::Critical);
+        assert_eq!(shellshock.references, vec![
+            "OSVDB-3233", "CVE-2014-6271", "CVE-2014-6278", "https://nvd.nist.gov/vuln/detail/CVE-2014-6271",
+        ]);
+
+        let banner = &findings[2];
+        assert_eq!((banner.id.as_deref(), banner.method.as_deref()), (None, None));
+        assert_eq!(banner.description, "Server banner has changed from 'Apache' to 'nginx'.");
+        assert_eq!(banner.severity, Severity::Info);
+    }
+
+    #[test]
+    fn malformed_xml_is_an_error() {
+        assert!(parse_nikto_xml("<niktoscan><item id=\"1\"><description>x</uri></item>").is_err());
+        assert!(parse_nikto_xml("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn cves_need_a_year_and_number() {
+        assert_eq!(extract_cves("see cve-2021-44228 and CVE-2021-44228, not CVE-21-1 or CVE-2020-12"), vec!["CVE-2021-44228"]);
+        assert!(extract_cves("CVE-").is_empty());
+    }
+}
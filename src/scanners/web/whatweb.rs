@@ -1,33 +1,45 @@
 use console::style;
 use indicatif::ProgressBar;
+use serde_json::Value;
 use std::process::Command;
 use std::error::Error;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::Write;
 
-// Perform WhatWeb technology identification with spinner
-pub fn perform_whatweb(spinner: &ProgressBar, target: &str, base_filename: &str, scan_dir: &str) -> Result<(), Box<dyn Error>> {
+use crate::models::Technology;
+
+// WhatWeb plugins that describe the response rather than a technology
+const NON_TECHNOLOGY_PLUGINS: &[&str] = &[
+    "Country", "IP", "Title", "Email", "RedirectLocation", "UncommonHeaders",
+    "HTML5", "Script", "Frame", "PasswordField", "Meta-Author", "Meta-Refresh-Redirect",
+    "Allow", "Via-Proxy", "Object", "Access-Control-Allow-Methods",
+];
+
+// Perform WhatWeb technology identification with spinner and return the technology inventory
+pub fn perform_whatweb(spinner: &ProgressBar, target: &str, base_filename: &str, scan_dir: &str) -> Result<Vec<Technology>, Box<dyn Error>> {
     let output_file = format!("{}/{}_whatweb.txt", scan_dir, base_filename);
-    
+    let json_file = format!("{}/{}_whatweb.json", scan_dir, base_filename);
+
     // Update spinner message
     spinner.set_message(format!("Setting up WhatWeb for {}...", style(target).cyan()));
-    
+
     // Prepare target
     let target_url = if target.starts_with("http://") || target.starts_with("https://") {
         target.to_string()
     } else {
         format!("http://{}", target)
     };
-    
+
     spinner.set_message(format!("Identifying web technologies on {}...", style(&target_url).cyan()));
-    
+
     let output = Command::new("whatweb")
         .arg("-v")  // Verbose
         .arg("-a3")  // Aggression level
-        .arg("--log-verbose").arg(&output_file)
+        .arg(format!("--log-verbose={}", output_file))
+        .arg(format!("--log-json={}", json_file))
         .arg(&target_url)
         .output()?;
-    
+
     // If there was an error, save stderr
     if !output.status.success() {
         let error_file = format!("{}/{}_whatweb_error.txt", scan_dir, base_filename);
@@ -35,6 +47,204 @@ pub fn perform_whatweb(spinner: &ProgressBar, target: &str, base_filename: &str,
         file.write_all(&output.stderr)?;
         return Err(format!("WhatWeb errors - see {} for details", error_file).into());
     }
-    
-    Ok(())
-}
\ No newline at end of file
+
+    spinner.set_message(format!("Parsing WhatWeb plugins for {}...", style(&target_url).cyan()));
+    let json = fs::read_to_string(&json_file)?;
+    parse_whatweb_json(&json)
+}
+
+// Parse WhatWeb --log-json output into a technology inventory
+pub fn parse_whatweb_json(json: &str) -> Result<Vec<Technology>, Box<dyn Error>> {
+    // Older WhatWeb releases write one object per line instead of a valid array
+    let entries: Vec<Value> = match serde_json::from_str::<Value>(json) {
+        Ok(Value::Array(entries)) => entries,
+        Ok(entry) => vec![entry],
+        Err(_) => json.lines()
+            .map(|line| line.trim().trim_start_matches('[').trim_end_matches(']').trim_end_matches(','))
+            .filter(|line| !line.is_empty())
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect(),
+    };
+
+    let mut technologies = Vec::new();
+    let mut parsed = 0;
+    for entry in &entries {
+        let url = match entry.get("target").and_then(Value::as_str) {
+            Some(url) => url,
+            None => continue,
+        };
+        let plugins = match entry.get("plugins").and_then(Value::as_object) {
+            Some(plugins) => plugins,
+            None => continue,
+        };
+        parsed += 1;
+
+        // The HTTPServer plugin only carries the Server banner (e.g. "Apache/2.4.49 (Ubuntu)")
+        let server_banners = plugins.get("HTTPServer")
+            .map(|data| string_values(data, "string"))
+            .unwrap_or_default();
+
+        for (name, data) in plugins {
+            // Response headers (cookies, security headers, ...) are not technologies either
+            if NON_TECHNOLOGY_PLUGINS.contains(&name.as_str()) || whatweb_category(name) == "HTTP Header" {
+                continue;
+            }
+
+            let mut versions = string_values(data, "version");
+            let mut evidence = string_values(data, "string");
+            evidence.extend(string_values(data, "os").into_iter().map(|os| format!("OS: {}", os)));
+            evidence.extend(string_values(data, "module").into_iter().map(|m| format!("Module: {}", m)));
+
+            let product = if name == "HTTPServer" {
+                // Only report the banner as its own product when no dedicated plugin matched it
+                let banner = match evidence.first() {
+                    Some(banner) => banner.clone(),
+                    None => continue,
+                };
+                let (product, version) = split_server_banner(&banner);
+                if plugins.keys().any(|k| k.eq_ignore_ascii_case(&product)) {
+                    continue;
+                }
+                versions.extend(version);
+                product
+            } else {
+                // Attach the Server banner as evidence for the plugin it describes
+                for banner in &server_banners {
+                    if banner.to_lowercase().contains(&name.to_lowercase()) && !evidence.contains(banner) {
+                        evidence.push(banner.clone());
+                    }
+                }
+                name.clone()
+            };
+            let category = if name == "HTTPServer" { "Web Server" } else { whatweb_category(name) }.to_string();
+
+            // One inventory entry per reported version (or a single unversioned entry)
+            let versions: Vec<Option<String>> = if versions.is_empty() {
+                vec![None]
+            } else {
+                versions.into_iter().map(Some).collect()
+            };
+            for version in versions {
+                technologies.push(Technology {
+                    source: "WhatWeb".to_string(),
+                    url: url.to_string(),
+                    product: product.clone(),
+                    version,
+                    category: category.clone(),
+                    evidence: evidence.clone(),
+                });
+            }
+        }
+    }
+
+    if parsed == 0 {
+        return Err("No WhatWeb results found in the JSON log".into());
+    }
+    Ok(technologies)
+}
+
+// Split a Server banner such as "Apache/2.4.49 (Ubuntu)" into product and version
+fn split_server_banner(banner: &str) -> (String, Option<String>) {
+    let first = banner.split_whitespace().next().unwrap_or(banner);
+    match first.split_once('/') {
+        Some((product, version)) if !version.is_empty() => (product.to_string(), Some(version.to_string())),
+        _ => (first.to_string(), None),
+    }
+}
+
+// Collect the string values of a WhatWeb plugin field (fields are arrays of strings or numbers)
+fn string_values(data: &Value, field: &str) -> Vec<String> {
+    match data.get(field) {
+        Some(Value::Array(values)) => values.iter()
+            .map(|v| match v {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+            .collect(),
+        Some(Value::String(s)) => vec![s.clone()],
+        _ => Vec::new(),
+    }
+}
+
+// WhatWeb does not export plugin categories, so classify the common plugins here
+fn whatweb_category(plugin: &str) -> &'static str {
+    match plugin {
+        "Apache" | "nginx" | "Microsoft-IIS" | "lighttpd" | "LiteSpeed" | "Tomcat" | "Jetty" | "Kestrel"
+        | "OpenResty" | "Caddy" | "Apache-Tomcat" | "Oracle-HTTP-Server" | "Lotus-Domino" => "Web Server",
+        "PHP" | "ASP_NET" | "Python" | "Perl" | "Ruby" | "Java" | "Mod_Perl" | "Phusion_Passenger" => "Programming Language",
+        "WordPress" | "Drupal" | "Joomla" | "Magento" | "TYPO3" | "Django" | "Ruby-on-Rails" | "Laravel"
+        | "Confluence" | "Jenkins" | "phpMyAdmin" | "MediaWiki" | "Moodle" | "SharePoint" => "CMS / Application",
+        "JQuery" | "Bootstrap" | "Modernizr" | "AngularJS" | "React" | "Vue" | "Prototype" | "MooTools" => "JavaScript Library",
+        "Cloudflare" | "Akamai-Global-Host" | "Varnish" | "Squid" | "X-Cache" | "CloudFront" => "CDN / Proxy",
+        "Cookies" | "HttpOnly" | "Strict-Transport-Security" | "X-Frame-Options" | "X-XSS-Protection"
+        | "Content-Security-Policy" | "X-UA-Compatible" | "X-Powered-By" | "Access-Control-Allow-Origin" => "HTTP Header",
+        "OpenSSL" | "mod_ssl" | "Mod_Security" | "mod_fastcgi" => "Server Module",
+        "Google-Analytics" | "Google-Tag-Manager" => "Analytics",
+        _ => "Other",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(technologies: &[Technology]) -> Vec<(String, Option<String>, String)> {
+        technologies.iter().map(|t| (t.product.clone(), t.version.clone(), t.category.clone())).collect()
+    }
+
+    #[test]
+    fn plugins_become_technologies() {
+        let json = r#"[
+{"target":"http://www.example.com","http_status":200,"plugins":{
+  "Apache":{"version":["2.4.49"]},
+  "HTTPServer":{"os":["Ubuntu Linux"],"string":["Apache/2.4.49 (Ubuntu)"]},
+  "PHP":{"version":["7.4.3","8.1.2"]},
+  "JQuery":{"version":[3]},
+  "Title":{"string":["Welcome"]},
+  "Cookies":{"string":["PHPSESSID"]},
+  "HttpOnly":{"string":["PHPSESSID"]},
+  "X-Frame-Options":{"string":["SAMEORIGIN"]},
+  "Country":{"string":["RESERVED"],"module":["ZZ"]}}},
+{"target":"http://api.example.com","plugins":{"HTTPServer":{"string":["gunicorn/20.1.0"]}}},
+{"http_status":0,"plugins":{"Apache":{}}}
+]"#;
+        let technologies = parse_whatweb_json(json).unwrap();
+        assert_eq!(summary(&technologies), vec![
+            ("Apache".to_string(), Some("2.4.49".to_string()), "Web Server".to_string()),
+            ("JQuery".to_string(), Some("3".to_string()), "JavaScript Library".to_string()),
+            ("PHP".to_string(), Some("7.4.3".to_string()), "Programming Language".to_string()),
+            ("PHP".to_string(), Some("8.1.2".to_string()), "Programming Language".to_string()),
+            ("gunicorn".to_string(), Some("20.1.0".to_string()), "Web Server".to_string()),
+        ]);
+        // The Server banner is kept as evidence for the plugin it names, not reported twice
+        assert_eq!(technologies[0].evidence, vec!["Apache/2.4.49 (Ubuntu)"]);
+        assert_eq!(technologies[4].url, "http://api.example.com");
+    }
+
+    #[test]
+    fn line_per_object_logs_are_read() {
+        let json = "[\n{\"target\":\"http://a.example.com\",\"plugins\":{\"nginx\":{}}},\n{\"target\":\"http://b.example.com\",\"plugins\":{\"WordPress\":{\"version\":[\"6.2\"]}}}\n";
+        let technologies = parse_whatweb_json(json).unwrap();
+        assert_eq!(summary(&technologies), vec![
+            ("nginx".to_string(), None, "Web Server".to_string()),
+            ("WordPress".to_string(), Some("6.2".to_string()), "CMS / Application".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn server_banners_split_into_product_and_version() {
+        assert_eq!(split_server_banner("Apache/2.4.49 (Ubuntu)"), ("Apache".to_string(), Some("2.4.49".to_string())));
+        assert_eq!(split_server_banner("cloudflare"), ("cloudflare".to_string(), None));
+        assert_eq!(split_server_banner("Server/"), ("Server/".to_string(), None));
+    }
+
+    #[test]
+    fn logs_without_results_are_errors() {
+        for json in ["", "[\n]\n", "not json\n{\"truncated\":", r#"[{"http_status":0,"plugins":{}}]"#] {
+            let error = parse_whatweb_json(json).unwrap_err();
+            assert_eq!(error.to_string(), "No WhatWeb results found in the JSON log", "{:?}", json);
+        }
+        // A target without any matching plugin is a valid, empty result
+        assert!(parse_whatweb_json(r#"[{"target":"http://www.example.com","plugins":{}}]"#).unwrap().is_empty());
+    }
+}
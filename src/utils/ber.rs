@@ -0,0 +1,223 @@
+use std::error::Error;
+
+// Basic Encoding Rules (X.690) as used by SNMP and SPNEGO: definite lengths only
+
+pub const TAG_INTEGER: u8 = 0x02;
+pub const TAG_OCTET_STRING: u8 = 0x04;
+pub const TAG_NULL: u8 = 0x05;
+pub const TAG_OID: u8 = 0x06;
+pub const TAG_SEQUENCE: u8 = 0x30;
+
+// Tag, length and content
+pub fn encode(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut encoded = vec![tag];
+    let length = content.len();
+    if length < 0x80 {
+        encoded.push(length as u8);
+    } else {
+        let bytes: Vec<u8> = length.to_be_bytes().into_iter().skip_while(|b| *b == 0).collect();
+        encoded.push(0x80 | bytes.len() as u8);
+        encoded.extend_from_slice(&bytes);
+    }
+    encoded.extend_from_slice(content);
+    encoded
+}
+
+// Shortest two's complement form
+pub fn encode_integer(value: i64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let mut start = 0;
+    while start < 7 && ((bytes[start] == 0 && bytes[start + 1] & 0x80 == 0) || (bytes[start] == 0xFF && bytes[start + 1] & 0x80 != 0)) {
+        start += 1;
+    }
+    encode(TAG_INTEGER, &bytes[start..])
+}
+
+pub fn encode_oid(oid: &[u32]) -> Vec<u8> {
+    let mut content = Vec::new();
+    if oid.len() >= 2 {
+        push_base128(&mut content, oid[0] * 40 + oid[1]);
+        for arc in &oid[2..] {
+            push_base128(&mut content, *arc);
+        }
+    }
+    encode(TAG_OID, &content)
+}
+
+fn push_base128(buffer: &mut Vec<u8>, value: u32) {
+    let mut groups = vec![(value & 0x7F) as u8];
+    let mut rest = value >> 7;
+    while rest > 0 {
+        groups.push(0x80 | (rest & 0x7F) as u8);
+        rest >>= 7;
+    }
+    buffer.extend(groups.into_iter().rev());
+}
+
+// Dotted form of a parsed OID
+pub fn oid_to_string(oid: &[u32]) -> String {
+    oid.iter().map(|arc| arc.to_string()).collect::<Vec<_>>().join(".")
+}
+
+// Sequential reader over concatenated TLVs
+pub struct BerReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BerReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        BerReader { data, position: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.position >= self.data.len()
+    }
+
+    // Next element as (tag, content)
+    pub fn read(&mut self) -> Result<(u8, &'a [u8]), Box<dyn Error>> {
+        let tag = *self.data.get(self.position).ok_or("Truncated BER element")?;
+        let first = *self.data.get(self.position + 1).ok_or("Truncated BER length")?;
+        self.position += 2;
+        let length = if first & 0x80 == 0 {
+            first as usize
+        } else {
+            let count = (first & 0x7F) as usize;
+            if count == 0 || count > 4 {
+                return Err("Unsupported BER length".into());
+            }
+            let bytes = self.data.get(self.position..self.position + count).ok_or("Truncated BER length")?;
+            self.position += count;
+            bytes.iter().fold(0usize, |length, b| (length << 8) | *b as usize)
+        };
+        let content = self.data.get(self.position..self.position + length).ok_or("Truncated BER content")?;
+        self.position += length;
+        Ok((tag, content))
+    }
+
+    // Next element, which must carry the given tag
+    pub fn expect(&mut self, tag: u8) -> Result<&'a [u8], Box<dyn Error>> {
+        let (found, content) = self.read()?;
+        if found != tag {
+            return Err(format!("Expected BER tag 0x{:02X}, found 0x{:02X}", tag, found).into());
+        }
+        Ok(content)
+    }
+
+    pub fn integer(&mut self) -> Result<i64, Box<dyn Error>> {
+        Ok(decode_integer(self.expect(TAG_INTEGER)?))
+    }
+}
+
+pub fn decode_integer(content: &[u8]) -> i64 {
+    let negative = content.first().is_some_and(|b| b & 0x80 != 0);
+    content.iter().fold(if negative { -1 } else { 0 }, |value, b| (value << 8) | *b as i64)
+}
+
+// Unsigned application types (Counter32, Gauge32, TimeTicks, Counter64) may use the full width
+pub fn decode_unsigned(content: &[u8]) -> u64 {
+    content.iter().fold(0u64, |value, b| (value << 8) | *b as u64)
+}
+
+pub fn decode_oid(content: &[u8]) -> Vec<u32> {
+    let mut arcs = Vec::new();
+    let mut value: u32 = 0;
+    for byte in content {
+        value = (value << 7) | (byte & 0x7F) as u32;
+        if byte & 0x80 == 0 {
+            if arcs.is_empty() {
+                let first = (value / 40).min(2);
+                arcs.push(first);
+                arcs.push(value - first * 40);
+            } else {
+                arcs.push(value);
+            }
+            value = 0;
+        }
+    }
+    arcs
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn integer_content(value: i64) -> Vec<u8> {
+        let encoded = encode_integer(value);
+        assert_eq!(encoded[..2], [TAG_INTEGER, encoded.len() as u8 - 2]);
+        encoded[2..].to_vec()
+    }
+
+    #[test]
+    fn integers_use_the_shortest_twos_complement_form() {
+        assert_eq!(integer_content(0), [0x00]);
+        assert_eq!(integer_content(127), [0x7F]);
+        assert_eq!(integer_content(128), [0x00, 0x80]);
+        assert_eq!(integer_content(256), [0x01, 0x00]);
+        assert_eq!(integer_content(-1), [0xFF]);
+        assert_eq!(integer_content(-128), [0x80]);
+        assert_eq!(integer_content(-129), [0xFF, 0x7F]);
+        assert_eq!(integer_content(i64::MIN), [0x80, 0, 0, 0, 0, 0, 0, 0]);
+
+        for value in [0, 1, 127, 128, 255, 256, 65535, 65507, -1, -128, -129, -65536, i64::from(i32::MAX), i64::MAX, i64::MIN] {
+            assert_eq!(decode_integer(&integer_content(value)), value, "{}", value);
+        }
+        // Unsigned application types keep the high bit as magnitude
+        assert_eq!(decode_unsigned(&[0x00, 0xFF, 0xFF, 0xFF, 0xFF]), 4294967295);
+        assert_eq!(decode_unsigned(&[0xFF, 0xFF, 0xFF, 0xFF]), 4294967295);
+    }
+
+    #[test]
+    fn oids_round_trip_including_multi_byte_arcs() {
+        assert_eq!(encode_oid(&[1, 3, 6, 1, 2, 1, 1, 1, 0]), [TAG_OID, 8, 0x2B, 0x06, 0x01, 0x02, 0x01, 0x01, 0x01, 0x00]);
+        // 2.999 shares its first byte group with the second arc: 2 * 40 + 999 = 1079
+        assert_eq!(encode_oid(&[2, 999, 3]), [TAG_OID, 3, 0x88, 0x37, 0x03]);
+
+        for oid in [
+            vec![1, 3, 6, 1, 2, 1, 1, 3, 0],
+            vec![1, 3, 6, 1, 4, 1, 311, 21, 20],
+            vec![1, 3, 6, 1, 4, 1, 8072, 3, 2, 10],
+            vec![1, 3, 6, 1, 2, 1, 4, 20, 1, 1, 192, 0, 2, 255],
+            vec![0, 39],
+            vec![2, 999, 3],
+            vec![1, 2, u32::MAX >> 4],
+        ] {
+            let encoded = encode_oid(&oid);
+            let content = BerReader::new(&encoded).expect(TAG_OID).unwrap();
+            assert_eq!(decode_oid(content), oid, "{}", oid_to_string(&oid));
+        }
+    }
+
+    #[test]
+    fn short_and_long_form_lengths_are_read() {
+        let short = encode(TAG_OCTET_STRING, b"public");
+        assert_eq!(short[..2], [TAG_OCTET_STRING, 6]);
+        let one_byte = encode(TAG_OCTET_STRING, &[0xAA; 200]);
+        assert_eq!(one_byte[..3], [TAG_OCTET_STRING, 0x81, 200]);
+        let two_bytes = encode(TAG_SEQUENCE, &[0x55; 300]);
+        assert_eq!(two_bytes[..4], [TAG_SEQUENCE, 0x82, 0x01, 0x2C]);
+
+        let data = [short, one_byte, two_bytes, encode(TAG_NULL, &[])].concat();
+        let mut reader = BerReader::new(&data);
+        assert_eq!(reader.expect(TAG_OCTET_STRING).unwrap(), b"public");
+        assert_eq!(reader.read().unwrap(), (TAG_OCTET_STRING, &[0xAA; 200][..]));
+        assert_eq!(reader.expect(TAG_SEQUENCE).unwrap().len(), 300);
+        assert_eq!(reader.expect(TAG_NULL).unwrap(), b"");
+        assert!(reader.is_empty());
+        assert_eq!(reader.read().unwrap_err().to_string(), "Truncated BER element");
+    }
+
+    #[test]
+    fn malformed_lengths_are_errors() {
+        let error = |data: &[u8]| BerReader::new(data).read().unwrap_err().to_string();
+        assert_eq!(error(&[TAG_SEQUENCE]), "Truncated BER length");
+        assert_eq!(error(&[TAG_SEQUENCE, 0x82, 0x01]), "Truncated BER length");
+        assert_eq!(error(&[TAG_OCTET_STRING, 0x05, b'a', b'b']), "Truncated BER content");
+        assert_eq!(error(&[TAG_OCTET_STRING, 0x81, 0x80, 0x00]), "Truncated BER content");
+        // Indefinite lengths and lengths wider than four bytes are not supported
+        assert_eq!(error(&[TAG_SEQUENCE, 0x80, 0x00, 0x00]), "Unsupported BER length");
+        assert_eq!(error(&[TAG_SEQUENCE, 0x85, 0, 0, 0, 0, 1, 0]), "Unsupported BER length");
+
+        let mut reader = BerReader::new(&[TAG_OCTET_STRING, 0x00]);
+        assert_eq!(reader.integer().unwrap_err().to_string(), "Expected BER tag 0x02, found 0x04");
+    }
+}
@@ -0,0 +1,204 @@
+use maxminddb::{geoip2, Reader};
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::models::GeoInfo;
+
+// Where geoipupdate and distribution packages put the databases, searched when none are given
+const DATABASE_DIRS: &[&str] = &["/usr/share/GeoIP", "/var/lib/GeoIP", "/usr/local/share/GeoIP"];
+const DATABASE_FILES: &[&str] = &[
+    "GeoLite2-City.mmdb", "GeoIP2-City.mmdb", "GeoLite2-Country.mmdb", "GeoLite2-ASN.mmdb",
+    "ip2asn-combined.tsv", "ip2asn-v4.tsv", "ip2asn-v6.tsv",
+];
+
+// One line of an IP2ASN TSV file: range_start range_end AS_number country_code AS_description
+struct AsnRange {
+    start: IpAddr,
+    end: IpAddr,
+    asn: u32,
+    country_code: Option<String>,
+    organization: Option<String>,
+}
+
+// Every database loaded for offline lookups; nothing here touches the network
+pub struct GeoDatabase {
+    readers: Vec<(String, Reader<Vec<u8>>)>,  // MaxMind database type -> reader
+    ranges: Vec<AsnRange>,                     // IP2ASN ranges sorted by start address
+    pub files: Vec<PathBuf>,
+}
+
+impl GeoDatabase {
+    // Load the given MMDB/TSV files, or whatever is found in the usual GeoIP directories
+    pub fn open(files: &[PathBuf]) -> Result<Self, Box<dyn Error>> {
+        let files = if files.is_empty() { find_databases() } else { files.to_vec() };
+        if files.is_empty() {
+            return Err(format!("No GeoIP/ASN databases found in ~/.local/share/GeoIP or {}; pass them with --geoip-db", DATABASE_DIRS.join(", ")).into());
+        }
+
+        let mut database = GeoDatabase { readers: Vec::new(), ranges: Vec::new(), files: files.clone() };
+        for file in &files {
+            let name = file.display();
+            match file.extension().and_then(|e| e.to_str()) {
+                Some("mmdb") => {
+                    let reader = Reader::open_readfile(file).map_err(|e| format!("Error reading {}: {}", name, e))?;
+                    database.readers.push((reader.metadata.database_type.clone(), reader));
+                },
+                Some("gz") => return Err(format!("{} is compressed; decompress it with gunzip first", name).into()),
+                _ => {
+                    let text = fs::read_to_string(file).map_err(|e| format!("Error reading {}: {}", name, e))?;
+                    database.ranges.extend(text.lines().filter_map(parse_range));
+                },
+            }
+        }
+        database.ranges.sort_by_key(|range| range.start);
+        Ok(database)
+    }
+
+    // Loaded once per process, so pipeline scans reuse the databases for every host
+    pub fn shared(files: &[PathBuf]) -> Result<&'static GeoDatabase, Box<dyn Error>> {
+        static DATABASE: OnceLock<Result<GeoDatabase, String>> = OnceLock::new();
+        DATABASE.get_or_init(|| GeoDatabase::open(files).map_err(|e| e.to_string()))
+            .as_ref()
+            .map_err(|e| e.clone().into())
+    }
+
+    // Everything the loaded databases know about an address; None when none of them lists it
+    pub fn lookup(&self, address: IpAddr) -> Option<GeoInfo> {
+        let mut geo = GeoInfo::default();
+        for (database_type, reader) in &self.readers {
+            if database_type.contains("ASN") || database_type.contains("ISP") {
+                let Ok(asn) = reader.lookup::<geoip2::Asn>(address) else { continue };
+                if geo.asn.is_none() && asn.autonomous_system_number.is_some() {
+                    geo.asn = asn.autonomous_system_number;
+                    geo.organization = asn.autonomous_system_organization.map(|o| o.to_string());
+                    geo.sources.push(database_type.clone());
+                }
+            } else {
+                let Ok(city) = reader.lookup::<geoip2::City>(address) else { continue };
+                let english = |names: Option<BTreeMap<&str, &str>>| names.and_then(|n| n.get("en").map(|s| s.to_string()));
+                let country = city.country.or(city.registered_country);
+                if geo.country_code.is_none()
+                    && let Some(country) = country {
+                    geo.country_code = country.iso_code.map(|c| c.to_string());
+                    geo.country = english(country.names);
+                    geo.city = city.city.and_then(|c| english(c.names));
+                    geo.sources.push(database_type.clone());
+                }
+            }
+        }
+
+        // IP2ASN fills in whatever the MaxMind databases left out
+        let index = self.ranges.partition_point(|range| range.start <= address);
+        if let Some(range) = index.checked_sub(1).map(|i| &self.ranges[i]).filter(|range| address <= range.end) {
+            let missing = geo.asn.is_none() || (geo.country_code.is_none() && range.country_code.is_some());
+            if geo.asn.is_none() {
+                geo.asn = Some(range.asn);
+                geo.organization = range.organization.clone();
+                geo.network = Some(format!("{}-{}", range.start, range.end));
+            }
+            if geo.country_code.is_none() {
+                geo.country_code = range.country_code.clone();
+            }
+            if missing {
+                geo.sources.push("IP2ASN".to_string());
+            }
+        }
+
+        (!geo.sources.is_empty()).then_some(geo)
+    }
+}
+
+fn parse_range(line: &str) -> Option<AsnRange> {
+    let fields: Vec<&str> = line.split('\t').collect();
+    let asn: u32 = fields.get(2)?.trim().parse().ok()?;
+    // AS0 marks unrouted space
+    if asn == 0 {
+        return None;
+    }
+    let text = |index: usize| fields.get(index).map(|f| f.trim()).filter(|f| !f.is_empty() && *f != "None" && *f != "Unknown").map(|f| f.to_string());
+    Some(AsnRange {
+        start: fields.first()?.trim().parse().ok()?,
+        end: fields.get(1)?.trim().parse().ok()?,
+        asn,
+        country_code: text(3),
+        organization: text(4),
+    })
+}
+
+// Known database files in ~/.local/share/GeoIP and the system GeoIP directories
+fn find_databases() -> Vec<PathBuf> {
+    let home = env::var("HOME").ok().map(|home| Path::new(&home).join(".local/share/GeoIP"));
+    let dirs = home.into_iter().chain(DATABASE_DIRS.iter().map(PathBuf::from));
+    let mut files: Vec<PathBuf> = Vec::new();
+    for dir in dirs {
+        for name in DATABASE_FILES {
+            // The first copy of each database wins
+            if files.iter().any(|f| f.file_name().is_some_and(|n| n == *name)) {
+                continue;
+            }
+            let path = dir.join(name);
+            if path.is_file() {
+                files.push(path);
+            }
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An IP2ASN excerpt, out of order, with unrouted space and a v6 range without country
+    const IP2ASN: &str = "2001:db8::\t2001:db8:ffff:ffff:ffff:ffff:ffff:ffff\t64500\tUnknown\tEXAMPLE-V6\n\
+        1.0.4.0\t1.0.7.255\t38803\tAU\tGTELECOM-AUSTRALIA Gtelecom Pty Ltd\n\
+        1.0.0.0\t1.0.0.255\t13335\tUS\tCLOUDFLARENET\n\
+        1.0.1.0\t1.0.3.255\t0\tNone\tNot routed\n\
+        2c0f:ffd8::\t2c0f:ffd8:ffff:ffff:ffff:ffff:ffff:ffff\t33762\tZA\tNone\n\
+        not a range\n";
+
+    #[test]
+    fn ranges_are_parsed_from_tsv_lines() {
+        let range = parse_range("1.0.4.0\t1.0.7.255\t38803\tAU\tGTELECOM-AUSTRALIA Gtelecom Pty Ltd").unwrap();
+        assert_eq!((range.start, range.end), ("1.0.4.0".parse().unwrap(), "1.0.7.255".parse().unwrap()));
+        assert_eq!((range.asn, range.country_code.as_deref()), (38803, Some("AU")));
+        assert_eq!(range.organization.as_deref(), Some("GTELECOM-AUSTRALIA Gtelecom Pty Ltd"));
+
+        let anonymous = parse_range("2001:db8::\t2001:db8::ffff\t64500\tUnknown\tNone").unwrap();
+        assert_eq!((anonymous.country_code, anonymous.organization), (None, None));
+
+        assert!(parse_range("1.0.1.0\t1.0.3.255\t0\tNone\tNot routed").is_none());
+        assert!(parse_range("1.0.1.0\t1.0.3.255").is_none());
+        assert!(parse_range("1.0.1.0\tnot an address\t64500\tUS\tEXAMPLE").is_none());
+        assert!(parse_range("").is_none());
+    }
+
+    #[test]
+    fn addresses_are_found_in_the_covering_range() {
+        let dir = std::env::temp_dir().join(format!("rust_recon_geoip_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("ip2asn-combined.tsv");
+        fs::write(&file, IP2ASN).unwrap();
+        let database = GeoDatabase::open(std::slice::from_ref(&file)).unwrap();
+        let asn = |address: &str| database.lookup(address.parse().unwrap()).and_then(|geo| geo.asn);
+
+        // Range boundaries are inclusive
+        assert_eq!((asn("1.0.0.0"), asn("1.0.0.255")), (Some(13335), Some(13335)));
+        assert_eq!((asn("1.0.4.0"), asn("1.0.7.255")), (Some(38803), Some(38803)));
+        // Before the first range, in unrouted (AS0) space and past the last v4 range
+        assert_eq!((asn("0.255.255.255"), asn("1.0.1.0"), asn("1.0.3.255"), asn("1.0.8.0")), (None, None, None, None));
+        assert_eq!((asn("2001:db8::1"), asn("2001:db9::"), asn("2c0f:ffd8::1"), asn("::1")), (Some(64500), None, Some(33762), None));
+
+        let geo = database.lookup("1.0.0.1".parse().unwrap()).unwrap();
+        assert_eq!((geo.country_code.as_deref(), geo.organization.as_deref()), (Some("US"), Some("CLOUDFLARENET")));
+        assert_eq!((geo.network.as_deref(), geo.sources), (Some("1.0.0.0-1.0.0.255"), vec!["IP2ASN".to_string()]));
+        let geo = database.lookup("2001:db8::1".parse().unwrap()).unwrap();
+        assert_eq!((geo.country_code, geo.organization.as_deref()), (None, Some("EXAMPLE-V6")));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,4 +1,11 @@
+pub mod ber;
 pub mod fs;
+pub mod geoip;
+pub mod net;
 
 // Embedded resources
-pub const ROSE_PINE_XSL: &str = include_str!("rose_pine_nmap.xsl");
\ No newline at end of file
+pub const ROSE_PINE_XSL: &str = include_str!("rose_pine_nmap.xsl");
+pub const WEB_FINGERPRINTS: &str = include_str!("web_fingerprints.json");
+pub const SUBDOMAIN_WORDLIST: &str = include_str!("subdomains.txt");
+// oui.txt plus every assignment in the registry named by RUST_RECON_OUI at build time (see build.rs)
+pub const OUI_DATABASE: &str = include_str!(concat!(env!("OUT_DIR"), "/oui.txt"));
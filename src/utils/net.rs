@@ -0,0 +1,82 @@
+use std::error::Error;
+use std::net::{IpAddr, Ipv4Addr, ToSocketAddrs};
+
+// Largest IPv4 range expanded into individual addresses (/16)
+const MAX_EXPANDED_HOSTS: u32 = 65536;
+
+// Expand a target (IP, hostname or IPv4 CIDR) into the addresses it covers
+pub fn expand_targets(target: &str) -> Result<Vec<IpAddr>, Box<dyn Error>> {
+    let target = target.trim();
+    if let Some((network, prefix)) = target.split_once('/') {
+        let network: Ipv4Addr = network.parse().map_err(|_| format!("Only IPv4 CIDR ranges can be expanded: {}", target))?;
+        let prefix: u32 = prefix.parse().map_err(|_| format!("Invalid CIDR prefix: {}", target))?;
+        if prefix > 32 {
+            return Err(format!("Invalid CIDR prefix: {}", target).into());
+        }
+        let size = 1u64 << (32 - prefix);
+        if size > MAX_EXPANDED_HOSTS as u64 {
+            return Err(format!("{} is too large to expand (maximum /16)", target).into());
+        }
+
+        let mask = if prefix == 0 { 0 } else { u32::MAX << (32 - prefix) };
+        let first = u32::from(network) & mask;
+        let last = first + (size as u32 - 1);
+        // Network and broadcast addresses are skipped except for /31 and /32
+        let (start, end) = if prefix >= 31 { (first, last) } else { (first + 1, last - 1) };
+        return Ok((start..=end).map(|ip| IpAddr::V4(Ipv4Addr::from(ip))).collect());
+    }
+
+    if let Ok(ip) = target.parse::<IpAddr>() {
+        return Ok(vec![ip]);
+    }
+
+    // Hostname: use whatever the system resolver returns
+    let mut addresses: Vec<IpAddr> = (target, 0).to_socket_addrs()?.map(|a| a.ip()).collect();
+    addresses.sort();
+    addresses.dedup();
+    Ok(addresses)
+}
+
+// A directly connected IPv4 network from the routing table
+pub struct LocalNetwork {
+    pub interface: String,
+    pub network: Ipv4Addr,
+    pub prefix: u32,
+}
+
+impl LocalNetwork {
+    pub fn contains(&self, ip: Ipv4Addr) -> bool {
+        let mask = if self.prefix == 0 { 0 } else { u32::MAX << (32 - self.prefix) };
+        u32::from(ip) & mask == u32::from(self.network)
+    }
+}
+
+// Directly connected (gateway-less) IPv4 networks, read from /proc/net/route on Linux
+pub fn local_networks() -> Vec<LocalNetwork> {
+    let Ok(routes) = std::fs::read_to_string("/proc/net/route") else {
+        return Vec::new();
+    };
+
+    let mut networks = Vec::new();
+    for line in routes.lines().skip(1) {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 8 {
+            continue;
+        }
+        // Addresses are little-endian hex; flag 0x1 is RTF_UP
+        let parse = |value: &str| u32::from_str_radix(value, 16).map(u32::from_be);
+        let (Ok(destination), Ok(gateway), Ok(flags), Ok(mask)) =
+            (parse(fields[1]), parse(fields[2]), u32::from_str_radix(fields[3], 16), parse(fields[7])) else {
+            continue;
+        };
+        if gateway != 0 || destination == 0 || flags & 0x1 == 0 {
+            continue;
+        }
+        networks.push(LocalNetwork {
+            interface: fields[0].to_string(),
+            network: Ipv4Addr::from(destination),
+            prefix: mask.count_ones(),
+        });
+    }
+    networks
+}